@@ -23,9 +23,13 @@ prelude! {}
 
 base::implement! {
     impl Display for Loc {
-        |&self, fmt| write!(fmt,
-            "`{}`:{}:{}-{}", self.file, self.line, self.span.lbound, self.span.ubound
-        )
+        |&self, fmt| if self.is_truncation_marker() {
+            fmt.write_str(Self::TRUNCATION_MARKER_FILE)
+        } else {
+            write!(fmt,
+                "`{}`:{}:{}-{}", self.file, self.line, self.span.lbound, self.span.ubound
+            )
+        }
     }
 
     impl Display for CLoc {
@@ -81,6 +81,23 @@ pub type Span = base::Range<usize>;
 /// assert_eq! { loc.line, 325 }
 /// assert_eq! { loc.span, (7, 38).into() }
 /// ```
+///
+/// The column span can be a single column, or be omitted altogether.
+///
+/// ```rust
+/// # alloc_data::prelude! {}
+/// let s = "`blah/stuff/file.ml`:325:7";
+/// let loc = Loc::parse(s).unwrap();
+/// assert_eq! { format!("{}", loc), "`blah/stuff/file.ml`:325:7-7" }
+/// assert_eq! { loc.line, 325 }
+/// assert_eq! { loc.span, (7, 7).into() }
+///
+/// let s = "`blah/stuff/file.ml`:325";
+/// let loc = Loc::parse(s).unwrap();
+/// assert_eq! { format!("{}", loc), "`blah/stuff/file.ml`:325:0-0" }
+/// assert_eq! { loc.line, 325 }
+/// assert_eq! { loc.span, (0, 0).into() }
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Loc {
     /// File the location is for.
@@ -91,6 +108,9 @@ pub struct Loc {
     pub span: Span,
 }
 impl Loc {
+    /// File of the location marking truncated traces, see [`Self::is_truncation_marker`].
+    pub const TRUNCATION_MARKER_FILE: &'static str = "… (truncated)";
+
     /// Constructor.
     pub fn new(file: Str, line: usize, span: impl Into<Span>) -> Self {
         Self {
@@ -99,6 +119,29 @@ impl Loc {
             span: span.into(),
         }
     }
+
+    /// True if the location is the marker of a truncated trace.
+    ///
+    /// Truncated traces start with this location, in place of the frames that were dropped. See
+    /// [`mem::Factory::register_truncation_marker`]. Reads the string factory, the caller must not
+    /// hold a [`mem::Factory`].
+    ///
+    /// ```rust
+    /// # alloc_data::prelude! {}
+    /// let mut factory = mem::Factory::new(false);
+    /// let marker = factory.register_truncation_marker();
+    /// let loc = factory.register_loc("file.ml", 0, (0, 0));
+    /// drop(factory);
+    /// assert!(marker.is_truncation_marker());
+    /// assert!(!loc.is_truncation_marker());
+    /// assert_eq!(marker.to_string(), "… (truncated)");
+    /// ```
+    pub fn is_truncation_marker(&self) -> bool {
+        self.line == 0
+            && self
+                .file
+                .str_do(|file| file == Self::TRUNCATION_MARKER_FILE)
+    }
 }
 
 /// A counted location.
@@ -117,18 +160,33 @@ impl Loc {
 /// assert_eq! { loc.line, 325 }
 /// assert_eq! { loc.span, (7, 38).into() }
 /// assert_eq! { cnt, 5 }
+///
+/// let s = "`blah/stuff/file.ml`:325:7#5";
+/// let CLoc { loc, cnt } = CLoc::parse(s).unwrap();
+/// assert_eq! { format!("{}", loc), "`blah/stuff/file.ml`:325:7-7" }
+/// assert_eq! { cnt, 5 }
+///
+/// let s = "`blah/stuff/file.ml`:325#5";
+/// let CLoc { loc, cnt } = CLoc::parse(s).unwrap();
+/// assert_eq! { format!("{}", loc), "`blah/stuff/file.ml`:325:0-0" }
+/// assert_eq! { cnt, 5 }
 /// ```
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CLoc {
-    /// Location.
-    pub loc: Loc,
+    /// Location, shared with all the counted locations that mention it when it is interned.
+    ///
+    /// See [`mem::Factory::register_loc`].
+    pub loc: Arc<Loc>,
     /// Number of times the location is repeated.
     pub cnt: usize,
 }
 impl CLoc {
     /// Constructor.
-    pub fn new(loc: Loc, cnt: usize) -> Self {
-        Self { loc, cnt }
+    pub fn new(loc: impl Into<Arc<Loc>>, cnt: usize) -> Self {
+        Self {
+            loc: loc.into(),
+            cnt,
+        }
     }
 }
 
@@ -149,7 +207,7 @@ impl CLoc {
 ///     assert_eq! { kind, *exp }
 /// }
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum AllocKind {
     /// Minor allocation.
     Minor,
@@ -164,6 +222,12 @@ pub enum AllocKind {
 }
 
 impl AllocKind {
+    /// All the allocation kinds.
+    pub fn all() -> &'static [AllocKind] {
+        use AllocKind::*;
+        &[Minor, Major, MajorPostponed, Serialized, Unknown]
+    }
+
     /// String representation of an allocation kind.
     pub fn as_str(&self) -> &'static str {
         use AllocKind::*;
@@ -184,7 +248,7 @@ pub struct Builder {
     pub uid_hint: Option<uid::Alloc>,
     /// Allocation kind.
     pub kind: AllocKind,
-    /// Size of the allocation.
+    /// Size of the allocation, in bytes once parsed, see [`Alloc::size`].
     pub size: u32,
     /// Sample count.
     pub nsamples: u32,
@@ -196,6 +260,8 @@ pub struct Builder {
     pub toc: time::SinceStart,
     /// Time of death.
     pub tod: Option<time::SinceStart>,
+    /// PID of the process that made the allocation, if known.
+    pub pid: Option<u64>,
 }
 impl Builder {
     /// Constructor.
@@ -217,6 +283,7 @@ impl Builder {
             labels,
             toc,
             tod,
+            pid: None,
         }
     }
 
@@ -226,6 +293,25 @@ impl Builder {
         self
     }
 
+    /// Sets the PID of the process that made the allocation.
+    pub fn pid(mut self, pid: u64) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Converts the size of the allocation from machine words to bytes.
+    ///
+    /// The number of samples is left untouched.
+    pub fn size_words_to_bytes(mut self, init: &Init) -> Res<Self> {
+        self.size = init.words_to_bytes(self.size)?;
+        Ok(self)
+    }
+
+    /// Trace accessor.
+    pub fn trace(&self) -> Arc<Vec<CLoc>> {
+        self.trace.get()
+    }
+
     /// Builds an `Alloc`.
     pub fn build(self, sample_rate: &SampleRate, uid: uid::Alloc) -> Res<Alloc> {
         let Self {
@@ -237,6 +323,7 @@ impl Builder {
             labels,
             toc,
             tod,
+            pid,
         } = self;
         let real_size = sample_rate.real_size_of(nsamples);
         match uid_hint {
@@ -258,21 +345,33 @@ impl Builder {
             labels,
             toc,
             tod,
+            pid,
         })
     }
 }
 
 /// Some allocation information.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// The [UID](Self::uid) is the canonical identity of an allocation, see [`Self::ident_key`]. In
+/// particular, hashing only considers the UID so that the hash of an allocation does not change
+/// over its life, *e.g.* when [its time of death is set](Self::set_tod). Equality still compares
+/// all the fields, which is consistent with hashing since equal allocations have the same UID.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Alloc {
     /// Uid of the allocation.
     pub uid: uid::Alloc,
     /// Allocation kind.
     pub kind: AllocKind,
-    /// Size of the allocation.
+    /// Size of the allocation, **in bytes**.
+    ///
+    /// Dumps store sizes in machine words, parsers convert them to bytes using the word size of
+    /// the run, see [`Init::words_to_bytes`]. Use [`Self::size_words`] to retrieve the size in
+    /// machine words.
     pub size: u32,
-    /// Real size of the allocation.
-    pub real_size: u32,
+    /// Real size of the allocation, in bytes.
+    ///
+    /// Computed from the number of samples, see [`SampleRate::real_size_of`].
+    pub real_size: u64,
     /// Sample count.
     pub nsamples: u32,
     /// Allocation-site callstack.
@@ -283,10 +382,25 @@ pub struct Alloc {
     pub toc: time::SinceStart,
     /// Time of death.
     pub tod: Option<time::SinceStart>,
+    /// PID of the process that made the allocation, if known.
+    ///
+    /// Memtrace CTF dumps store a PID in the header of each packet, which is attached to the
+    /// allocations of the packet. In the usual single-process dumps, all allocations have the same
+    /// PID. Allocations from the older dump-directory format do not have one.
+    pub pid: Option<u64>,
+}
+
+impl std::hash::Hash for Alloc {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.ident_key().hash(state)
+    }
 }
 
 impl Alloc {
     /// Constructor.
+    ///
+    /// The size is in bytes, see [`Self::size`]. The number of samples is the size in machine
+    /// words, using the word size of the sample rate.
     pub fn new(
         sample_rate: &base::SampleRate,
         uid: impl Into<uid::Alloc>,
@@ -298,21 +412,48 @@ impl Alloc {
         tod: Option<time::SinceStart>,
     ) -> Self {
         let uid = uid.into();
-        let nsamples = size;
+        let nsamples = size / std::cmp::max(sample_rate.word_size_bytes, 1);
         let real_size = sample_rate.real_size_of(nsamples);
         Self {
             uid,
             kind,
             size,
             real_size,
-            nsamples: size,
+            nsamples,
             trace,
             labels,
             toc,
             tod,
+            pid: None,
         }
     }
 
+    /// Parses an allocation in the textual diff format, using the default [`Init`].
+    ///
+    /// The UID of the allocation is the one from the text. Use [`Parseable::parse_with`] to
+    /// provide a custom init, *e.g.* to use a different sampling rate.
+    ///
+    /// ```rust
+    /// # use alloc_data::prelude::*;
+    /// let alloc = Alloc::parse(
+    ///     "18446744073709551615: Major 7 [ `src/main.ml`:3:2-9#1 ] [ `label` ] 0.25 1.5",
+    /// ).unwrap();
+    /// assert_eq!(alloc.uid, uid::Alloc::from(18_446_744_073_709_551_615_u64));
+    /// assert_eq!(alloc.kind, AllocKind::Major);
+    /// assert_eq!(alloc.size, 7);
+    /// assert_eq!(alloc.toc, time::SinceStart::from_nano_timestamp(0, 250_000_000));
+    /// assert_eq!(alloc.tod, Some(time::SinceStart::from_nano_timestamp(1, 500_000_000)));
+    /// assert_eq!(alloc.labels().len(), 1);
+    ///
+    /// let err = Alloc::parse("18446744073709551616: Minor 7 [ ] [ ] 0.25 _").unwrap_err();
+    /// assert!(err.to_pretty().contains("UID fitting in a usize"), "{}", err.to_pretty());
+    /// ```
+    ///
+    /// [`Parseable::parse_with`]: crate::parser::Parseable::parse_with
+    pub fn parse(text: impl AsRef<str>) -> Res<Self> {
+        Self::parse_with(text, &Init::default())
+    }
+
     /// Sets the number of samples.
     pub fn nsamples(mut self, nsamples: u32) -> Self {
         self.nsamples = nsamples;
@@ -344,19 +485,34 @@ impl Alloc {
     pub fn uid(&self) -> &uid::Alloc {
         &self.uid
     }
+    /// Stable identity of the allocation event, to use as a key for deduplication/memoization.
+    ///
+    /// This is the UID of the allocation, which unlike fields such as the time of death never
+    /// changes over the life of the allocation.
+    pub fn ident_key(&self) -> &uid::Alloc {
+        &self.uid
+    }
     /// Kind accessor.
     pub fn kind(&self) -> &AllocKind {
         &self.kind
     }
-    /// Size accessor (in machine words).
+    /// Size accessor, in bytes.
     pub fn size(&self) -> u32 {
         self.size
     }
+    /// Size of the allocation in machine words, see [`Init::word_size_bytes`].
+    pub fn size_words(&self, init: &Init) -> u32 {
+        self.size / init.word_size_bytes()
+    }
 
     /// Trace accessor.
     pub fn trace(&self) -> Arc<Vec<CLoc>> {
         self.trace.get()
     }
+    /// UID of the trace of the allocation, allocations with the same trace have the same UID.
+    pub fn trace_uid(&self) -> Trace {
+        self.trace
+    }
     /// Allocation-site of the allocation.
     pub fn alloc_site_do<Res>(&self, action: impl FnOnce(Option<&CLoc>) -> Res) -> Res {
         let trace = self.trace();
@@ -367,6 +523,38 @@ impl Alloc {
     pub fn labels(&self) -> Arc<Vec<Str>> {
         self.labels.get()
     }
+    /// UID of the labels of the allocation, allocations with the same labels have the same UID.
+    pub fn labels_uid(&self) -> Labels {
+        self.labels
+    }
+    /// Number of labels of the allocation.
+    pub fn label_count(&self) -> usize {
+        self.labels.len()
+    }
+    /// Label at some index, if any.
+    pub fn label(&self, index: usize) -> Option<Str> {
+        self.labels.label(index)
+    }
+    /// True if the allocation has label `label`.
+    ///
+    /// ```rust
+    /// # use alloc_data::prelude::*;
+    /// let alloc = Alloc::parse("0: Minor 1 [ ] [ `a` `b` ] 0.1 _").unwrap();
+    /// assert_eq!(alloc.label_count(), 2);
+    /// assert!(alloc.has_label("b"));
+    /// assert!(!alloc.has_label("c"));
+    /// assert_eq!(alloc.label(0).map(|l| l.to_string()), Some("a".into()));
+    /// assert_eq!(alloc.label(2), None);
+    /// let labels: Vec<_> = alloc.iter_labels().map(|l| l.to_string()).collect();
+    /// assert_eq!(labels, vec!["a", "b"]);
+    /// ```
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels.contains(label)
+    }
+    /// Iterator over the labels of the allocation.
+    pub fn iter_labels(&self) -> mem::labels::Iter {
+        self.labels.iter()
+    }
     /// Time of creation accessor.
     pub fn toc(&self) -> time::SinceStart {
         self.toc
@@ -375,6 +563,41 @@ impl Alloc {
     pub fn tod(&self) -> Option<time::SinceStart> {
         self.tod
     }
+
+    /// True if the allocation is live at some time.
+    ///
+    /// An allocation is live at its time of creation, but not at its time of death.
+    pub fn is_live_at(&self, time: time::SinceStart) -> bool {
+        self.toc <= time && self.tod.map(|tod| time < tod).unwrap_or(true)
+    }
+
+    /// Age of the allocation at some time, normalized between `0` and `1`.
+    ///
+    /// This is the time elapsed since the allocation was created divided by `now`, *i.e.* `0` for
+    /// an allocation created at `now` and `1` for an allocation created at the start of the run.
+    /// Allocations created after `now` have age `0`, and so does everything when `now` is zero.
+    /// The time of death is ignored.
+    ///
+    /// ```rust
+    /// use alloc_data::prelude::*;
+    /// let mut factory = alloc_data::mem::Factory::new(false);
+    /// let (trace, labels) = (factory.register_trace(vec![]), factory.empty_labels());
+    /// drop(factory);
+    /// let secs = time::SinceStart::from_secs;
+    /// let alloc = Builder::new(None, AllocKind::Minor, 1, trace, labels, secs(1), None)
+    ///     .build(&SampleRate::new(1.0, 64), uid::Alloc::from(0usize))
+    ///     .unwrap();
+    /// assert_eq!(alloc.age_at(secs(0)), 0.0);
+    /// assert_eq!(alloc.age_at(secs(1)), 0.0);
+    /// assert_eq!(alloc.age_at(secs(4)), 0.75);
+    /// ```
+    pub fn age_at(&self, now: time::SinceStart) -> f64 {
+        if now <= self.toc {
+            return 0.0;
+        }
+        let age = (now - self.toc).as_secs_f64() / now.as_secs_f64();
+        age.min(1.0)
+    }
 }
 
 /// A diff.
@@ -399,6 +622,55 @@ impl Diff {
     ) -> Self {
         Self { time, new, dead }
     }
+
+    /// Parses a diff in the textual diff format, using the default [`Init`].
+    ///
+    /// Use [`Parseable::parse_with`] to provide a custom init, *e.g.* for callstacks going from
+    /// the allocation site to `main`.
+    ///
+    /// ```rust
+    /// # use alloc_data::prelude::*;
+    /// let diff = Diff::parse(r#"
+    ///     0.5
+    ///     new {
+    ///         3: Minor 4 [ `src/main.ml`:3:2-9#1 ] [ ] 0.25 _
+    ///         4: Major 12 [ `src/main.ml`:7:0-9#2 ] [ `label` ] 0.3 0.45
+    ///     }
+    ///     dead {
+    ///         1: 0.4
+    ///     }
+    /// "#).unwrap();
+    /// assert_eq!(diff.time, time::SinceStart::from_nano_timestamp(0, 500_000_000));
+    /// assert_eq!(diff.new.len(), 2);
+    /// assert_eq!(diff.new[1].uid_hint, Some(uid::Alloc::from(4usize)));
+    /// assert_eq!(diff.new[1].tod, Some(time::SinceStart::from_nano_timestamp(0, 450_000_000)));
+    /// assert_eq!(
+    ///     diff.dead,
+    ///     vec![(uid::Alloc::from(1usize), time::SinceStart::from_nano_timestamp(0, 400_000_000))],
+    /// );
+    /// ```
+    ///
+    /// [`Parseable::parse_with`]: crate::parser::Parseable::parse_with
+    pub fn parse(text: impl AsRef<str>) -> Res<Self> {
+        Self::parse_with(text, &Init::default())
+    }
+}
+
+/// Information about the profiled executable.
+///
+/// Memtrace CTF dumps store it in their trace info, dump directories do not have it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExeInfo {
+    /// Name of the executable.
+    pub name: String,
+    /// Name of the host system.
+    pub host_name: String,
+    /// Parameters of the executable.
+    pub params: String,
+    /// PID of the process.
+    pub pid: u64,
+    /// Context string, only present in the dumps of the recent memtrace formats.
+    pub context: Option<String>,
 }
 
 /// Data from a memthol init file.
@@ -408,12 +680,18 @@ pub struct Init {
     pub start_time: time::Date,
     /// Optional end time.
     pub end_time: Option<time::SinceStart>,
-    /// Size of machine words in bytes.
+    /// Size of machine words in bits.
     pub word_size: usize,
     /// True if the callstack go from `main` to allocation site, called *reversed order*.
     pub callstack_is_rev: bool,
     /// Sampling rate.
     pub sample_rate: base::SampleRate,
+    /// Unique identifier of the run, randomly generated when the init is created.
+    ///
+    /// Used to detect that a new run started, which is not reliable using `start_time`.
+    pub run_id: u64,
+    /// Information about the profiled executable, if known.
+    pub exe_info: Option<ExeInfo>,
 }
 
 impl Default for Init {
@@ -424,6 +702,8 @@ impl Default for Init {
             word_size: 8,
             callstack_is_rev: false,
             sample_rate: SampleRate::new(1.0, 8),
+            run_id: base::rand::random(),
+            exe_info: None,
         }
     }
 }
@@ -442,9 +722,50 @@ impl Init {
             word_size,
             callstack_is_rev,
             sample_rate: SampleRate::new(1.0, convert(word_size, "Init::new, word_size")),
+            run_id: base::rand::random(),
+            exe_info: None,
         }
     }
 
+    /// Size of machine words in bytes, at least `1`.
+    ///
+    /// ```rust
+    /// # use alloc_data::prelude::*;
+    /// let init = Init::new(time::Date::from_timestamp(0, 0), None, 64, false);
+    /// assert_eq!(init.word_size_bytes(), 8);
+    /// assert_eq!(Init::default().word_size_bytes(), 1);
+    /// ```
+    pub fn word_size_bytes(&self) -> u32 {
+        std::cmp::max(convert(self.word_size / 8, "Init::word_size_bytes"), 1)
+    }
+
+    /// Converts a size in machine words to bytes.
+    ///
+    /// Fails if the size in bytes does not fit in a `u32`.
+    ///
+    /// ```rust
+    /// # use alloc_data::prelude::*;
+    /// let init = Init::new(time::Date::from_timestamp(0, 0), None, 32, false);
+    /// assert_eq!(init.words_to_bytes(7).unwrap(), 28);
+    /// assert!(init.words_to_bytes(u32::MAX).is_err());
+    /// ```
+    pub fn words_to_bytes(&self, words: u32) -> Res<u32> {
+        words.checked_mul(self.word_size_bytes()).ok_or_else(|| {
+            format!(
+                "size of {} words of {} bytes overflows u32 when converted to bytes",
+                words,
+                self.word_size_bytes()
+            )
+            .into()
+        })
+    }
+
+    /// Sets the information about the profiled executable.
+    pub fn exe_info(mut self, exe_info: ExeInfo) -> Self {
+        self.exe_info = Some(exe_info);
+        self
+    }
+
     /// Sets the sampling rate.
     pub fn sample_rate(mut self, rate: f64) -> Self {
         self.sample_rate = SampleRate::new(
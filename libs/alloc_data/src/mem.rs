@@ -86,6 +86,14 @@ macro_rules! new {
                 pub fn get_elm(&self, uid: $uid) -> Arc<$ty> {
                     self.mem.get_elm(uid.uid)
                 }
+                /// Number of elements in the factory.
+                pub fn len(&self) -> usize {
+                    self.mem.len()
+                }
+                /// True if the factory is empty.
+                pub fn is_empty(&self) -> bool {
+                    self.mem.is_empty()
+                }
             }
 
             $crate::prelude::lazy_static! {
@@ -118,6 +126,7 @@ macro_rules! new {
 }
 
 pub mod labels;
+pub mod loc;
 pub mod str;
 pub mod trace;
 
@@ -129,12 +138,20 @@ pub struct Factory<'a> {
     labels: labels::AsWrite<'a>,
     /// Write-access to the trace factory.
     trace: trace::AsWrite<'a>,
+    /// Write-access to the location factory.
+    loc: loc::AsWrite<'a>,
     /// Indicates whether the callstacks are in reverse order.
     ///
     /// If true, callstacks must be reversed when registering them.
     callstack_is_rev: bool,
     /// The empty list of labels.
     empty_labels: Labels,
+    /// Trace interning statistics.
+    trace_stats: TraceStats,
+    /// Location interning statistics.
+    loc_stats: LocStats,
+    /// String interning statistics.
+    str_stats: StrStats,
 }
 impl<'a> Factory<'a> {
     /// Constructor.
@@ -145,15 +162,80 @@ impl<'a> Factory<'a> {
             str: Str::factory_mut(),
             labels,
             trace: Trace::factory_mut(),
+            loc: loc::LocUid::factory_mut(),
             callstack_is_rev,
             empty_labels,
+            trace_stats: TraceStats::default(),
+            loc_stats: LocStats::default(),
+            str_stats: StrStats::default(),
         }
     }
 
+    /// True if callstacks are reversed when registering them, see [`Self::register_trace`].
+    pub fn callstack_is_rev(&self) -> bool {
+        self.callstack_is_rev
+    }
+
+    /// Trace interning statistics accessor.
+    pub fn trace_stats(&self) -> TraceStats {
+        self.trace_stats
+    }
+    /// Registers a trace cache hit.
+    ///
+    /// Called by parsers when they reuse a trace they already registered, without going through
+    /// [`Self::register_trace`].
+    #[inline]
+    pub fn trace_cache_hit(&mut self) {
+        self.trace_stats.cache_hits += 1
+    }
+
+    /// Location interning statistics accessor.
+    pub fn loc_stats(&self) -> LocStats {
+        self.loc_stats
+    }
+
+    /// String interning statistics accessor.
+    pub fn str_stats(&self) -> StrStats {
+        self.str_stats
+    }
+
+    /// Registers a location in the location factory.
+    ///
+    /// Identical locations, *i.e.* same file, line and span, share the same storage.
+    #[inline]
+    pub fn register_loc(&mut self, file: &str, line: usize, span: impl Into<Span>) -> Arc<Loc> {
+        let file = self.register_str(file);
+        let count = self.loc.len();
+        let uid = self.loc.get_uid(Loc::new(file, line, span));
+        if self.loc.len() > count {
+            self.loc_stats.distinct += 1
+        } else {
+            self.loc_stats.hits += 1
+        }
+        self.loc.get_elm(uid)
+    }
+
+    /// Registers the location marking truncated traces.
+    ///
+    /// See [`Loc::is_truncation_marker`].
+    #[inline]
+    pub fn register_truncation_marker(&mut self) -> Arc<Loc> {
+        self.register_loc(Loc::TRUNCATION_MARKER_FILE, 0, (0, 0))
+    }
+
     /// Registers a string in the string factory.
+    ///
+    /// Updates the [string interning statistics](Self::str_stats).
     #[inline]
     pub fn register_str(&mut self, s: &str) -> Str {
-        self.str.get_uid(s)
+        let count = self.str.len();
+        let res = self.str.get_uid(s);
+        if self.str.len() > count {
+            self.str_stats.distinct += 1
+        } else {
+            self.str_stats.hits += 1
+        }
+        res
     }
     /// Registers a label in the label factory.
     #[inline]
@@ -171,7 +253,112 @@ impl<'a> Factory<'a> {
         if self.callstack_is_rev {
             trace.reverse()
         }
-        self.trace.get_uid(trace)
+        let count = self.trace.len();
+        let trace = self.trace.get_uid(trace);
+        self.trace_stats.cache_misses += 1;
+        if self.trace.len() > count {
+            self.trace_stats.distinct += 1
+        }
+        trace
+    }
+}
+
+/// Trace interning statistics of a [`Factory`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraceStats {
+    /// Number of traces reused from a parser-side cache, without any lookup in the factory.
+    pub cache_hits: usize,
+    /// Number of traces looked up in the factory.
+    pub cache_misses: usize,
+    /// Number of distinct traces the factory interned, *i.e.* looked up traces that were new.
+    pub distinct: usize,
+}
+
+impl TraceStats {
+    /// Ratio of cache hits over all trace registrations, `None` if there were none.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        if total == 0 {
+            None
+        } else {
+            Some(self.cache_hits as f64 / total as f64)
+        }
+    }
+}
+
+impl fmt::Display for TraceStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{} cache hit(s), {} cache miss(es), {} distinct trace(s)",
+            self.cache_hits, self.cache_misses, self.distinct
+        )
+    }
+}
+
+/// Location interning statistics of a [`Factory`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LocStats {
+    /// Number of registered locations that were already known.
+    pub hits: usize,
+    /// Number of distinct locations the factory interned.
+    pub distinct: usize,
+}
+
+impl LocStats {
+    /// Ratio of hits over all location registrations, `None` if there were none.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.hits + self.distinct;
+        if total == 0 {
+            None
+        } else {
+            Some(self.hits as f64 / total as f64)
+        }
+    }
+}
+
+impl fmt::Display for LocStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{} hit(s), {} distinct location(s)",
+            self.hits, self.distinct
+        )
+    }
+}
+
+/// String interning statistics of a [`Factory`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StrStats {
+    /// Number of registered strings that were already known.
+    pub hits: usize,
+    /// Number of distinct strings the factory interned.
+    pub distinct: usize,
+}
+
+impl StrStats {
+    /// Ratio of hits over all string registrations, `None` if there were none.
+    pub fn dedup_ratio(&self) -> Option<f64> {
+        let total = self.hits + self.distinct;
+        if total == 0 {
+            None
+        } else {
+            Some(self.hits as f64 / total as f64)
+        }
+    }
+}
+
+impl fmt::Display for StrStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{} hit(s), {} distinct string(s)",
+            self.hits, self.distinct
+        )?;
+        if let Some(ratio) = self.dedup_ratio() {
+            write!(fmt, ", {:.2}% deduplicated", ratio * 100.)?
+        }
+        Ok(())
     }
 }
 
@@ -201,6 +388,15 @@ impl<Elm: ?Sized + Ord> Memory<Elm> {
     pub fn get_elm(&self, uid: usize) -> Arc<Elm> {
         self.vec[uid].clone()
     }
+
+    /// Number of elements in the memory.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    /// True if the memory is empty.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
 }
 
 impl<Elm> Memory<Elm>
@@ -39,3 +39,60 @@ pub fn get(uid: Labels) -> std::sync::Arc<Vec<Str>> {
     let mem = mem::read();
     mem.get_elm(uid)
 }
+
+impl Labels {
+    /// Number of labels in the list.
+    pub fn len(self) -> usize {
+        self.get().len()
+    }
+
+    /// True if the list of labels is empty.
+    pub fn is_empty(self) -> bool {
+        self.get().is_empty()
+    }
+
+    /// Label at some index, if any.
+    ///
+    /// Not called `get` as [`Labels::get`] already retrieves the whole list.
+    pub fn label(self, index: usize) -> Option<Str> {
+        self.get().get(index).cloned()
+    }
+
+    /// True if one of the labels is `label`.
+    pub fn contains(self, label: &str) -> bool {
+        self.get().iter().any(|l| l == label)
+    }
+
+    /// Iterator over the labels.
+    pub fn iter(self) -> Iter {
+        Iter {
+            labels: self.get(),
+            next: 0,
+        }
+    }
+}
+
+/// Iterator over some labels, see [`Labels::iter`].
+pub struct Iter {
+    /// Labels to iterate over.
+    labels: std::sync::Arc<Vec<Str>>,
+    /// Index of the next label.
+    next: usize,
+}
+
+impl Iterator for Iter {
+    type Item = Str;
+    fn next(&mut self) -> Option<Str> {
+        let res = self.labels.get(self.next).cloned();
+        if res.is_some() {
+            self.next += 1
+        }
+        res
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.labels.len().saturating_sub(self.next);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
@@ -143,6 +143,14 @@ impl<'a> AsWrite<'a> {
             uid: self.mem.get_uid(s),
         }
     }
+    /// Number of strings in the factory.
+    pub fn len(&self) -> usize {
+        self.mem.len()
+    }
+    /// True if the factory is empty.
+    pub fn is_empty(&self) -> bool {
+        self.mem.is_empty()
+    }
 }
 
 crate::prelude::lazy_static! {
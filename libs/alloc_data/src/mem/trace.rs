@@ -19,7 +19,9 @@
 
 //! Handles the internals of trace sharing.
 
-use crate::CLoc;
+use std::{fmt, sync::Arc};
+
+use crate::{CLoc, Loc};
 
 // Macro defined in `crate::mem`.
 new! {
@@ -49,3 +51,146 @@ pub fn get(uid: Trace) -> std::sync::Arc<Vec<CLoc>> {
     let mem = mem::read();
     mem.get_elm(uid)
 }
+
+impl Trace {
+    /// True if the trace was truncated when it was built.
+    ///
+    /// Truncated traces start with a [truncation marker](Loc::is_truncation_marker).
+    pub fn is_truncated(self) -> bool {
+        self.get()
+            .first()
+            .map(|cloc| cloc.loc.is_truncation_marker())
+            .unwrap_or(false)
+    }
+
+    /// Iterator over the frames of the trace, each location being repeated as many times as its
+    /// count.
+    pub fn frames_expanded(self) -> Frames {
+        Frames::new(self.get())
+    }
+
+    /// Number of frames of the trace, counting repetitions.
+    pub fn depth(self) -> usize {
+        self.get().iter().map(|cloc| cloc.cnt).sum()
+    }
+
+    /// Human-readable rendering of the trace, one frame per line.
+    ///
+    /// Adjacent occurrences of the same location are collapsed as `loc (xN)`. If there are more
+    /// than `max_depth` frames after collapsing, the middle of the trace is replaced by an
+    /// ellipsis.
+    pub fn display_grouped(self, max_depth: usize) -> Grouped {
+        Grouped {
+            trace: self.get(),
+            max_depth,
+        }
+    }
+}
+
+/// Iterator over the expanded frames of a trace, see [`Trace::frames_expanded`].
+pub struct Frames {
+    /// Locations of the trace.
+    trace: Arc<Vec<CLoc>>,
+    /// Index of the current location.
+    index: usize,
+    /// Number of times the current location was yielded.
+    yielded: usize,
+}
+
+impl Frames {
+    /// Constructor.
+    pub fn new(trace: Arc<Vec<CLoc>>) -> Self {
+        Self {
+            trace,
+            index: 0,
+            yielded: 0,
+        }
+    }
+}
+
+impl Iterator for Frames {
+    type Item = Arc<Loc>;
+    fn next(&mut self) -> Option<Arc<Loc>> {
+        loop {
+            let cloc = self.trace.get(self.index)?;
+            if self.yielded < cloc.cnt {
+                self.yielded += 1;
+                return Some(cloc.loc.clone());
+            }
+            self.index += 1;
+            self.yielded = 0
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self
+            .trace
+            .get(self.index..)
+            .map(|clocs| clocs.iter().map(|cloc| cloc.cnt).sum::<usize>() - self.yielded)
+            .unwrap_or(0);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Frames {}
+
+/// Grouped rendering of a trace, see [`Trace::display_grouped`].
+pub struct Grouped {
+    /// Locations of the trace.
+    trace: Arc<Vec<CLoc>>,
+    /// Maximum number of frames displayed.
+    max_depth: usize,
+}
+
+impl fmt::Display for Grouped {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut frames: Vec<(Arc<Loc>, usize)> = Vec::with_capacity(self.trace.len());
+        for loc in Frames::new(self.trace.clone()) {
+            match frames.last_mut() {
+                Some((last, cnt)) if *last == loc => *cnt += 1,
+                _ => frames.push((loc, 1)),
+            }
+        }
+
+        let (head, tail) = if frames.len() > self.max_depth {
+            let tail = self.max_depth / 2;
+            (self.max_depth - tail, tail)
+        } else {
+            (frames.len(), 0)
+        };
+        let omitted = frames.len() - head - tail;
+
+        let mut first = true;
+        let mut sep = |fmt: &mut fmt::Formatter| {
+            if first {
+                first = false;
+                Ok(())
+            } else {
+                writeln!(fmt)
+            }
+        };
+
+        for (loc, cnt) in &frames[..head] {
+            sep(fmt)?;
+            write_frame(fmt, loc, *cnt)?
+        }
+        if omitted > 0 {
+            sep(fmt)?;
+            write!(fmt, "... ({} frames omitted)", omitted)?
+        }
+        for (loc, cnt) in &frames[frames.len() - tail..] {
+            sep(fmt)?;
+            write_frame(fmt, loc, *cnt)?
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a single frame of a grouped trace.
+fn write_frame(fmt: &mut fmt::Formatter, loc: &Loc, cnt: usize) -> fmt::Result {
+    if cnt > 1 {
+        write!(fmt, "{} (x{})", loc, cnt)
+    } else {
+        write!(fmt, "{}", loc)
+    }
+}
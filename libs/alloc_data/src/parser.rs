@@ -100,16 +100,25 @@ peg::parser! {
         / expected!("list of whitespace-separated, backquote-delimited strings")
 
         /// Parses a location.
+        ///
+        /// The column span is optional, and so is its upper bound. A missing upper bound defaults
+        /// to the lower bound, a missing span defaults to `0-0`.
         pub rule loc(f: &mut Factory) -> Loc
         = file: str(f)
             _ ":"
             _ line: usize()
-            _ ":"
-            _ col_start: usize()
-            _ "-"
-            _ col_end: usize()
+            span: (
+                _ ":"
+                _ col_start: usize()
+                col_end: (
+                    _ "-"
+                    _ col_end: usize() { col_end }
+                )? {
+                    (col_start, col_end.unwrap_or(col_start))
+                }
+            )?
         {
-            Loc::new(file, line, (col_start, col_end))
+            Loc::new(file, line, span.unwrap_or((0, 0)))
         }
         / expected!("file location")
 
@@ -209,10 +218,12 @@ peg::parser! {
 
         /// Parses a uid.
         pub rule uid() -> uid::Alloc
-        = quiet! {
-            uid: usize() { uid.into() }
+        = n: quiet! { integer() } {?
+            n.parse::<usize>()
+                .map(uid::Alloc::from)
+                .map_err(|_| "UID fitting in a usize on this target")
         }
-        / expected!("UID (big uint)")
+        / expected!("UID (usize)")
 
         /// Parses an allocation kind.
         pub rule alloc_kind() -> AllocKind
@@ -300,6 +311,65 @@ peg::parser! {
     }
 }
 
+/// Maximum number of characters shown on each side of the position of a parse error.
+const ERROR_CONTEXT_LEN: usize = 30;
+
+/// Turns a `peg` parse error into an error that shows where parsing failed.
+///
+/// The error is chained with the line, column and byte offset of the error, as well as a snippet
+/// of the line of `text` the error is on.
+///
+/// ```rust
+/// # use alloc_data::prelude::*;
+/// let err = uid::Alloc::parse("7_").unwrap_err();
+/// let pretty = err.to_pretty();
+/// assert!(pretty.contains("line 1, column 2 (byte 1)"), "{}", pretty);
+/// assert!(pretty.contains("| 7_\n|  ^"), "{}", pretty);
+///
+/// let err = Init::parse("start: 0.0\nword_size: 64\ncallstacks: nowhere").unwrap_err();
+/// let pretty = err.to_pretty();
+/// assert!(pretty.contains("line 3, column 13 (byte 37)"), "{}", pretty);
+/// assert!(pretty.contains("| callstacks: nowhere\n|             ^"), "{}", pretty);
+/// ```
+pub fn parse_error(text: &str, e: peg::error::ParseError<peg::str::LineCol>) -> err::Error {
+    let offset = e.location.offset;
+    let (line, column) = (e.location.line, e.location.column);
+
+    // Start/end of the line the error is on.
+    let line_start = text[..offset].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    let line_end = text[offset..]
+        .find('\n')
+        .map(|idx| offset + idx)
+        .unwrap_or_else(|| text.len());
+
+    // Only keep `ERROR_CONTEXT_LEN` characters on each side of the error.
+    let before: String = {
+        let mut before: Vec<char> = text[line_start..offset]
+            .chars()
+            .rev()
+            .take(ERROR_CONTEXT_LEN)
+            .collect();
+        before.reverse();
+        before.into_iter().collect()
+    };
+    let after: String = text[offset..line_end]
+        .chars()
+        .take(ERROR_CONTEXT_LEN)
+        .collect();
+
+    let context = format!(
+        "parse error at line {}, column {} (byte {})\n| {}{}\n| {:>width$}",
+        line,
+        column,
+        offset,
+        before,
+        after,
+        "^",
+        width = before.chars().count() + 1,
+    );
+    err::Error::from(e).chain_err(|| context)
+}
+
 /// Trait for types that can be parsed.
 pub trait Parseable: Sized {
     /// Information used during parsing.
@@ -352,7 +422,7 @@ macro_rules! implement {
             type Info = $info_ty;
             fn parse_with(text: impl AsRef<str>, $info: &Self::Info) -> Res<Self> {
                 let $txt = text.as_ref();
-                let res = $def?;
+                let res = $def.map_err(|e| parse_error($txt, e))?;
                 Ok(res)
             }
         }
@@ -383,9 +453,42 @@ implement! {
         Loc => loc(text, &mut Factory::new(false)),
         CLoc => counted_loc(text, &mut Factory::new(false)),
     }
+}
+
+/// Sizes are converted from machine words to bytes, see [`Alloc::size`].
+impl Parseable for Builder {
+    type Info = Init;
+    fn parse_with(text: impl AsRef<str>, init: &Self::Info) -> Res<Self> {
+        let text = text.as_ref();
+        let builder = new_alloc(text, &mut Factory::new(init.callstack_is_rev))
+            .map_err(|e| parse_error(text, e))?;
+        builder.size_words_to_bytes(init)
+    }
+}
+
+/// Sizes are converted from machine words to bytes, see [`Alloc::size`].
+impl Parseable for Diff {
+    type Info = Init;
+    fn parse_with(text: impl AsRef<str>, init: &Self::Info) -> Res<Self> {
+        let text = text.as_ref();
+        let mut res = diff(text, &mut Factory::new(init.callstack_is_rev))
+            .map_err(|e| parse_error(text, e))?;
+        res.new = res
+            .new
+            .into_iter()
+            .map(|builder| builder.size_words_to_bytes(init))
+            .collect::<Res<_>>()?;
+        Ok(res)
+    }
+}
 
-    Parseable<Init>(text, init) for {
-        Builder => new_alloc(text, &mut Factory::new(init.callstack_is_rev)),
-        Diff => diff(text, &mut Factory::new(init.callstack_is_rev)),
+impl Parseable for Alloc {
+    type Info = Init;
+    fn parse_with(text: impl AsRef<str>, init: &Self::Info) -> Res<Self> {
+        let builder = Builder::parse_with(text, init)?;
+        let uid = builder
+            .uid_hint
+            .ok_or("cannot build an allocation without a UID")?;
+        builder.build(&init.sample_rate, uid)
     }
 }
@@ -232,3 +232,251 @@ fn diff_0() {
     assert_eq! { diff.new.len(), 164 }
     assert_eq! { diff.dead.len(), 21 }
 }
+
+#[test]
+fn sizes_in_bytes() {
+    // Diffs store sizes in machine words, parsing converts them to bytes.
+    let init = Init::new(time::Date::from_timestamp(0, 0), None, 64, false);
+    let diff = unwrap!(Diff::parse_with(DIFF_0, &init));
+    let builder = diff.new[0].clone();
+    assert_eq! { builder.uid_hint, Some(uid::Alloc::from(212usize)) }
+    assert_eq! { builder.size, 182 * 8 }
+    assert_eq! { builder.nsamples, 182 }
+
+    let alloc = unwrap!(builder.build(&init.sample_rate, uid::Alloc::from(212usize)));
+    assert_eq! { alloc.size(), 182 * 8 }
+    assert_eq! { alloc.size_words(&init), 182 }
+    assert_eq! { alloc.real_size, 182 * 8 }
+
+    // 32-bit words.
+    let init = Init::new(time::Date::from_timestamp(0, 0), None, 32, false);
+    let alloc = unwrap!(Alloc::parse_with("7: Minor 5 [ ] [ ] 0.25 _", &init));
+    assert_eq! { alloc.size, 20 }
+    assert_eq! { alloc.size_words(&init), 5 }
+}
+
+#[test]
+fn new_alloc_samples() {
+    let mut factory = mem::Factory::new(false);
+    let trace = factory.register_trace(vec![]);
+    let labels = factory.empty_labels();
+    // Releases the locks on the memories.
+    drop(factory);
+
+    // 32-bit words, one sample out of four.
+    let rate = SampleRate::new(0.25, 32);
+    let alloc = Alloc::new(
+        &rate,
+        7usize,
+        AllocKind::Minor,
+        20,
+        trace,
+        labels,
+        time::SinceStart::zero(),
+        None,
+    );
+    assert_eq! { alloc.size, 20 }
+    assert_eq! { alloc.nsamples, 5 }
+    assert_eq! { alloc.real_size, 80 }
+}
+
+#[test]
+fn trace_stats() {
+    let mut factory = mem::Factory::new(false);
+    let file = factory.register_str("trace_stats_test.ml");
+    let trace = |line| {
+        vec![CLoc::new(
+            Loc::new(
+                file,
+                line,
+                Span {
+                    lbound: 0,
+                    ubound: 7,
+                },
+            ),
+            1,
+        )]
+    };
+
+    let _ = factory.register_trace(trace(1));
+    let _ = factory.register_trace(trace(2));
+    let _ = factory.register_trace(trace(1));
+    factory.trace_cache_hit();
+
+    let stats = factory.trace_stats();
+    assert_eq! { stats.cache_hits, 1 }
+    assert_eq! { stats.cache_misses, 3 }
+    assert_eq! { stats.distinct, 2 }
+    assert_eq! { stats.hit_ratio(), Some(0.25) }
+}
+
+#[test]
+fn uid_bounds() {
+    let max = unwrap!(uid::Alloc::parse(usize::MAX.to_string()));
+    assert_eq! { max, uid::Alloc::from(usize::MAX) }
+}
+
+#[test]
+fn expanded_trace() {
+    let mut factory = mem::Factory::new(false);
+    let file = factory.register_str("expanded_trace_test.ml");
+    let cloc = |line, cnt| CLoc::new(Loc::new(file, line, (0, 7)), cnt);
+    let trace = factory.register_trace(vec![cloc(1, 1), cloc(2, 3), cloc(3, 0), cloc(4, 2)]);
+    // Releases the locks on the memories.
+    drop(factory);
+
+    assert_eq! { trace.depth(), 6 }
+    let frames = trace.frames_expanded();
+    assert_eq! { frames.len(), 6 }
+    let lines: Vec<usize> = frames.map(|loc| loc.line).collect();
+    assert_eq! { lines, vec![1, 2, 2, 2, 4, 4] }
+}
+
+#[test]
+fn grouped_trace() {
+    let mut factory = mem::Factory::new(false);
+    let file = factory.register_str("grouped_trace_test.ml");
+    let cloc = |line, cnt| CLoc::new(Loc::new(file, line, (0, 7)), cnt);
+    let trace = factory.register_trace(vec![
+        cloc(1, 1),
+        cloc(2, 3),
+        cloc(2, 2),
+        cloc(3, 1),
+        cloc(4, 1),
+        cloc(5, 1),
+    ]);
+    // Releases the locks on the memories.
+    drop(factory);
+
+    let full = format!("{}", trace.display_grouped(10));
+    assert_eq! {
+        full,
+        "`grouped_trace_test.ml`:1:0-7\n\
+        `grouped_trace_test.ml`:2:0-7 (x5)\n\
+        `grouped_trace_test.ml`:3:0-7\n\
+        `grouped_trace_test.ml`:4:0-7\n\
+        `grouped_trace_test.ml`:5:0-7"
+    }
+
+    let truncated = format!("{}", trace.display_grouped(3));
+    assert_eq! {
+        truncated,
+        "`grouped_trace_test.ml`:1:0-7\n\
+        `grouped_trace_test.ml`:2:0-7 (x5)\n\
+        ... (2 frames omitted)\n\
+        `grouped_trace_test.ml`:5:0-7"
+    }
+}
+
+#[test]
+fn loc_interning() {
+    let mut factory = mem::Factory::new(false);
+    let loc_1 = factory.register_loc("loc_interning_test.ml", 3, (2, 9));
+    let loc_2 = factory.register_loc("loc_interning_test.ml", 3, (2, 9));
+    let loc_3 = factory.register_loc("loc_interning_test.ml", 4, (2, 9));
+
+    assert! { Arc::ptr_eq(&loc_1, &loc_2) }
+    assert_eq! { loc_1, loc_2 }
+    assert_ne! { loc_1, loc_3 }
+
+    let stats = factory.loc_stats();
+    assert_eq! { stats.hits, 1 }
+    assert_eq! { stats.distinct, 2 }
+
+    // Traces built from interned locations still compare structurally.
+    let trace_1 = factory.register_trace(vec![CLoc::new(loc_1.clone(), 2)]);
+    let trace_2 = factory.register_trace(vec![CLoc::new(loc_2.clone(), 2)]);
+    let trace_3 = factory.register_trace(vec![CLoc::new(loc_3.clone(), 2)]);
+    assert_eq! { trace_1, trace_2 }
+    assert_ne! { trace_1, trace_3 }
+
+    // Counted locations share the interned location.
+    drop(factory);
+    assert! { Arc::ptr_eq(&trace_1.get()[0].loc, &loc_1) }
+}
+
+#[test]
+fn str_interning() {
+    let mut factory = mem::Factory::new(false);
+    let input = String::from("str_interning_test.ml str_interning_test.ml str_interning_other.ml");
+    let mut words = input.split(' ');
+    let file_1 = factory.register_str(words.next().unwrap());
+    let file_2 = factory.register_str(words.next().unwrap());
+    let other = factory.register_str(words.next().unwrap());
+
+    assert_eq! { file_1, file_2 }
+    assert_ne! { file_1, other }
+
+    // Locations register their file path too.
+    factory.register_loc("str_interning_test.ml", 7, (0, 3));
+
+    let stats = factory.str_stats();
+    assert_eq! { stats.hits, 2 }
+    assert_eq! { stats.distinct, 2 }
+    assert_eq! { stats.dedup_ratio(), Some(0.5) }
+
+    // Release the write lock before reading the strings back.
+    drop(factory);
+    assert! { Arc::ptr_eq(&file_1.get(), &file_2.get()) }
+}
+
+#[test]
+fn real_size_overflow() {
+    let mut factory = mem::Factory::new(false);
+    let trace = factory.register_trace(vec![]);
+    let labels = factory.empty_labels();
+    // Releases the locks on the memories.
+    drop(factory);
+
+    // One sample every 2^20 words of 8 bytes.
+    let rate = SampleRate::new(1. / (1 << 20) as f64, 64);
+    let nsamples = 1 << 12;
+    let alloc = Builder::new(
+        None,
+        AllocKind::Major,
+        nsamples,
+        trace,
+        labels,
+        time::SinceStart::zero(),
+        None,
+    )
+    .nsamples(nsamples);
+    let alloc = unwrap!(alloc.build(&rate, 0usize.into()));
+    assert_eq! { alloc.real_size, 1 << 35 }
+    assert! { alloc.real_size > u32::MAX as u64 }
+}
+
+#[test]
+fn alloc_hash_ignores_tod() {
+    use std::hash::{Hash, Hasher};
+    let hash = |alloc: &Alloc| {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        alloc.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    let mut factory = mem::Factory::new(false);
+    let trace = factory.register_trace(vec![]);
+    let labels = factory.empty_labels();
+    // Releases the locks on the memories.
+    drop(factory);
+
+    let rate = SampleRate::new(1.0, 64);
+    let toc = time::SinceStart::zero();
+    let mut alloc = Alloc::new(
+        &rate,
+        7usize,
+        AllocKind::Minor,
+        100,
+        trace,
+        labels,
+        toc,
+        None,
+    );
+    let (before, key) = (alloc.clone(), alloc.ident_key().clone());
+
+    unwrap!(alloc.set_tod(time::SinceStart::from_secs(1)));
+    assert_ne! { alloc, before }
+    assert_eq! { hash(&alloc), hash(&before) }
+    assert_eq! { alloc.ident_key(), &key }
+}
@@ -20,6 +20,16 @@
 //! Errors for memthol, handled by `error_chain`.
 //!
 //! This module also features a global list of errors.
+//!
+//! # Severity
+//!
+//! Errors are either *recoverable* or *fatal*, see [`Severity`] and [`Error::severity`]. A
+//! recoverable error only concerns some piece of the input, which can be reported and skipped
+//! while the rest of the input is still processed. A fatal error means memthol cannot go on.
+//!
+//! - recoverable: malformed input ([`ErrorKind::Parse`] and the `peg`, integer and
+//!   (de)serialization errors), and diffs that cannot be applied ([`ErrorKind::Diff`]);
+//! - fatal: configuration errors ([`ErrorKind::Config`]), I/O errors, and all the other errors.
 
 pub use error_chain::bail;
 
@@ -44,10 +54,108 @@ error_chain::error_chain! {
     }
 
     links {}
-    errors {}
+    errors {
+        /// Malformed input, recoverable.
+        Parse(msg: String) {
+            description("parse error")
+            display("{}", msg)
+        }
+        /// Diff that cannot be applied, recoverable.
+        Diff(msg: String) {
+            description("illegal diff")
+            display("{}", msg)
+        }
+        /// Configuration error, fatal.
+        Config(msg: String) {
+            description("configuration error")
+            display("{}", msg)
+        }
+    }
+}
+
+/// Severity of an error, see the [module-level documentation].
+///
+/// [module-level documentation]: crate::err (module-level documentation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Only concerns a piece of the input, which can be skipped.
+    Recoverable,
+    /// Memthol cannot go on.
+    Fatal,
+}
+
+impl ErrorKind {
+    /// Severity of an error kind, `None` for plain messages, which inherit the severity of the
+    /// error they wrap.
+    fn severity(&self) -> Option<Severity> {
+        match self {
+            Self::Parse(_) | Self::Diff(_) | Self::Peg(_) | Self::ParseInt(_) | Self::Serde(_) => {
+                Some(Severity::Recoverable)
+            }
+            Self::Config(_) | Self::Io(_) => Some(Severity::Fatal),
+            Self::Msg(_) => None,
+            // `error_chain` adds a hidden variant.
+            #[allow(unreachable_patterns)]
+            _ => Some(Severity::Fatal),
+        }
+    }
 }
 
 impl Error {
+    /// Severity of an error, see the [module-level documentation].
+    ///
+    /// The severity is the one of the outermost error of the chain that is not a plain message.
+    /// Wrapping an error with [`ResExt::chain_err`] and a message does not change its severity,
+    /// while wrapping it with a kind does. Plain messages that do not wrap anything are fatal.
+    ///
+    /// ```rust
+    /// use base::prelude::{*, err::{ErrorKind, Severity}};
+    /// let parse: Res<()> = Err(ErrorKind::Parse("unexpected token".into()).into());
+    /// let parse = parse.chain_err(|| "while reading a diff").unwrap_err();
+    /// assert_eq!(parse.severity(), Severity::Recoverable);
+    ///
+    /// let io: Res<()> = Err(std::io::Error::from(std::io::ErrorKind::NotFound).into());
+    /// let io = io.chain_err(|| "while reading a diff").unwrap_err();
+    /// assert!(io.is_fatal());
+    ///
+    /// // Reclassified by the outermost kind.
+    /// let config: Res<()> = Err(parse);
+    /// let config = config
+    ///     .chain_err(|| ErrorKind::Config("illegal init file".into()))
+    ///     .unwrap_err();
+    /// assert!(config.is_fatal());
+    ///
+    /// // Plain messages are fatal.
+    /// let msg: err::Error = "something went wrong".into();
+    /// assert!(msg.is_fatal());
+    /// ```
+    pub fn severity(&self) -> Severity {
+        use std::error::Error;
+        // Foreign errors, chained with `chain_err`, are classified by type.
+        let mut current: Option<&(dyn Error + 'static)> = Some(self);
+        while let Some(e) = current {
+            if let Some(e) = e.downcast_ref::<Self>() {
+                if let Some(severity) = e.kind().severity() {
+                    return severity;
+                }
+            } else if e.is::<std::io::Error>() {
+                return Severity::Fatal;
+            } else if e.is::<std::num::ParseIntError>() || e.is::<bincode::Error>() {
+                return Severity::Recoverable;
+            }
+            current = e.source();
+        }
+        Severity::Fatal
+    }
+    /// True if the error is fatal, see [`Self::severity`].
+    pub fn is_fatal(&self) -> bool {
+        self.severity() == Severity::Fatal
+    }
+    /// True if the error is recoverable, see [`Self::severity`].
+    pub fn is_recoverable(&self) -> bool {
+        self.severity() == Severity::Recoverable
+    }
+
     /// Multi-line representation of a trace of errors.
     ///
     /// See the [module-level documentation] for more.
@@ -160,7 +268,7 @@ pub struct ErrorCxt {
 }
 
 pub use list::{
-    register, register_fatal, register_non_fatal, unwrap_register, unwrap_register_fatal,
+    count, register, register_fatal, register_non_fatal, unwrap_register, unwrap_register_fatal,
     unwrap_register_non_fatal,
 };
 
@@ -219,6 +327,20 @@ mod list {
         register(e, true)
     }
 
+    /// Number of errors registered so far, and how many of them are fatal.
+    ///
+    /// ```rust
+    /// let (count, fatal) = base::err::count();
+    /// base::err::register_non_fatal("some error");
+    /// base::err::register_fatal("some fatal error");
+    /// assert_eq!(base::err::count(), (count + 2, fatal + 1));
+    /// ```
+    pub fn count() -> (usize, usize) {
+        let errors = ERRORS.read().expect("global error list was poisoned");
+        let fatal = errors.iter().filter(|(_, is_fatal)| *is_fatal).count();
+        (errors.len(), fatal)
+    }
+
     impl ErrorCxt {
         /// Constructor.
         pub fn new() -> Self {
@@ -48,6 +48,8 @@ pub mod err;
 use prelude::serde::*;
 
 /// Used to convert between integer representations.
+///
+/// Panics if the conversion fails, see [`try_convert`] for a version that does not.
 #[inline]
 pub fn convert<In, Out>(n: In, from: &'static str) -> Out
 where
@@ -60,6 +62,29 @@ where
     }
 }
 
+/// Used to convert between integer representations, fails if the conversion fails.
+///
+/// Unlike [`convert`], this function yields an error instead of panicking.
+///
+/// ```rust
+/// let n: u8 = base::try_convert(7u64, "test").unwrap();
+/// assert_eq!(n, 7);
+/// let err = base::try_convert::<u64, u8>(300, "test").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "while converting 300 (test): out of range integral type conversion attempted",
+/// );
+/// ```
+#[inline]
+pub fn try_convert<In, Out>(n: In, from: &'static str) -> err::Res<Out>
+where
+    In: std::convert::TryInto<Out> + std::fmt::Display + Copy,
+    In::Error: std::fmt::Display,
+{
+    n.try_into()
+        .map_err(|e| format!("while converting {} ({}): {}", n, from, e).into())
+}
+
 /// Returns what it's given.
 pub fn identity<T>(t: T) -> T {
     t
@@ -150,8 +175,49 @@ impl SampleRate {
     }
 
     /// Computes the real size of an allocation from its number of samples.
-    pub fn real_size_of(&self, nsamples: u32) -> u32 {
-        nsamples * self.word_size_bytes * self.factor
+    ///
+    /// The computation is done in `u64`, which cannot overflow since all three operands are `u32`s.
+    ///
+    /// ```rust
+    /// # use base::SampleRate;
+    /// let rate = SampleRate::new(0.25, 64);
+    /// assert_eq!(rate.factor, 4);
+    /// assert_eq!(rate.real_size_of(3), 96);
+    /// // Overflows `u32`.
+    /// assert_eq!(rate.real_size_of(1 << 30), 1 << 35);
+    /// assert_eq!(rate.real_size_of(u32::MAX), (u32::MAX as u64) * 32);
+    /// ```
+    pub fn real_size_of(&self, nsamples: u32) -> u64 {
+        (nsamples as u64) * (self.word_size_bytes as u64) * (self.factor as u64)
+    }
+
+    /// Computes the number of samples corresponding to a real size, inverse of
+    /// [`Self::real_size_of`].
+    ///
+    /// ```rust
+    /// # use base::SampleRate;
+    /// let rate = SampleRate::new(0.25, 64);
+    /// assert_eq!(rate.samples_of(96), 3);
+    /// assert_eq!(rate.samples_of(rate.real_size_of(u32::MAX)), u32::MAX as u64);
+    /// ```
+    pub fn samples_of(&self, size: u64) -> u64 {
+        let sample_size = (self.word_size_bytes as u64) * (self.factor as u64);
+        if sample_size == 0 {
+            0
+        } else {
+            size / sample_size
+        }
+    }
+
+    /// True if the sample rate is not `1`, *i.e.* sizes are estimates.
+    ///
+    /// ```rust
+    /// # use base::SampleRate;
+    /// assert!(!SampleRate::new(1.0, 64).is_sampled());
+    /// assert!(SampleRate::new(0.25, 64).is_sampled());
+    /// ```
+    pub fn is_sampled(&self) -> bool {
+        self.sample_rate != 1.0
     }
 }
 
@@ -320,15 +386,68 @@ where
         self.lbound > self.ubound
     }
 
-    /// Intersection of two ranges.
-    pub fn intersection(self, other: Self) -> Self
+    /// Intersection of two ranges, `None` if the ranges are disjoint.
+    ///
+    /// Since ranges are inclusive, ranges that touch have a singleton intersection.
+    ///
+    /// ```rust
+    /// use base::Range;
+    /// // Disjoint.
+    /// assert_eq!(Range::new(0, 3).intersection(Range::new(5, 7)), None);
+    /// // Touching.
+    /// assert_eq!(Range::new(0, 5).intersection(Range::new(5, 7)), Some(Range::new(5, 5)));
+    /// // Overlapping.
+    /// assert_eq!(Range::new(0, 6).intersection(Range::new(5, 7)), Some(Range::new(5, 6)));
+    /// // Nested.
+    /// assert_eq!(Range::new(0, 9).intersection(Range::new(5, 7)), Some(Range::new(5, 7)));
+    /// // Empty.
+    /// assert_eq!(Range::new(3, 1).intersection(Range::new(0, 7)), None);
+    /// ```
+    pub fn intersection(self, other: Self) -> Option<Self>
     where
         T: Ord,
     {
-        Self::new(
+        let res = Self::new(
             std::cmp::max(self.lbound, other.lbound),
             std::cmp::min(self.ubound, other.ubound),
-        )
+        );
+        if res.is_empty() {
+            None
+        } else {
+            Some(res)
+        }
+    }
+
+    /// Union of two ranges, *i.e.* the smallest range containing both of them.
+    ///
+    /// Empty ranges are ignored: the union of a range with an empty range is the former.
+    ///
+    /// ```rust
+    /// use base::Range;
+    /// // Disjoint.
+    /// assert_eq!(Range::new(0, 3).union(Range::new(5, 7)), Range::new(0, 7));
+    /// // Touching.
+    /// assert_eq!(Range::new(0, 5).union(Range::new(5, 7)), Range::new(0, 7));
+    /// // Nested.
+    /// assert_eq!(Range::new(0, 9).union(Range::new(5, 7)), Range::new(0, 9));
+    /// // Empty.
+    /// assert_eq!(Range::new(3, 1).union(Range::new(5, 7)), Range::new(5, 7));
+    /// assert_eq!(Range::new(5, 7).union(Range::new(3, 1)), Range::new(5, 7));
+    /// ```
+    pub fn union(self, other: Self) -> Self
+    where
+        T: Ord,
+    {
+        if self.is_empty() {
+            other
+        } else if other.is_empty() {
+            self
+        } else {
+            Self::new(
+                std::cmp::min(self.lbound, other.lbound),
+                std::cmp::max(self.ubound, other.ubound),
+            )
+        }
     }
 }
 
@@ -34,6 +34,7 @@ pub mod chrono {
 
 pub mod duration;
 mod lifetime;
+pub mod repr;
 mod since_start;
 
 // Re-exporting sub-module stuff.
@@ -119,6 +120,86 @@ impl Date {
         (self.date.timestamp(), self.date.timestamp_subsec_nanos())
     }
 
+    /// Amount of nanoseconds since the unix epoch.
+    ///
+    /// Fails if the amount does not fit in an `i64`, *i.e.* for dates before 1677 or after 2262.
+    ///
+    /// ```rust
+    /// use base::prelude::time::*;
+    /// let date = Date::from_timestamp(1_566_489_242, 7_000_572);
+    /// let nanos = date.to_epoch_nanos().unwrap();
+    /// assert_eq!(nanos, 1_566_489_242_007_000_572);
+    /// assert_eq!(Date::from_epoch_nanos(nanos), date);
+    ///
+    /// // Before the epoch.
+    /// let date = Date::from_timestamp(-2, 250_000_000);
+    /// let nanos = date.to_epoch_nanos().unwrap();
+    /// assert_eq!(nanos, -1_750_000_000);
+    /// assert_eq!(Date::from_epoch_nanos(nanos), date);
+    /// ```
+    pub fn to_epoch_nanos(&self) -> Res<i64> {
+        let (secs, nanos) = self.timestamp();
+        secs.checked_mul(1_000_000_000)
+            .and_then(|secs| secs.checked_add(nanos.into()))
+            .ok_or_else(|| {
+                format!(
+                    "date {} cannot be represented as nanoseconds since the unix epoch",
+                    self
+                )
+                .into()
+            })
+    }
+    /// Constructor from an amount of nanoseconds since the unix epoch.
+    ///
+    /// See [`Self::to_epoch_nanos`] for examples.
+    pub fn from_epoch_nanos(nanos: i64) -> Self {
+        let (secs, nanos) = (
+            nanos.div_euclid(1_000_000_000),
+            nanos.rem_euclid(1_000_000_000),
+        );
+        Self::from_timestamp(secs, convert(nanos, "from_epoch_nanos: nanos"))
+    }
+
+    /// ISO-8601 representation of a date, in UTC with nanosecond precision.
+    ///
+    /// The result follows RFC 3339, a profile of ISO-8601. The sub-second part has zero, three,
+    /// six or nine digits depending on the precision needed.
+    ///
+    /// ```rust
+    /// use base::prelude::time::*;
+    /// let date = Date::from_timestamp(1_566_489_242, 7_000_572);
+    /// let iso = date.to_iso8601();
+    /// assert_eq!(iso, "2019-08-22T15:54:02.007000572Z");
+    /// assert_eq!(Date::parse_iso8601(&iso).unwrap(), date);
+    /// let date = Date::from_timestamp(1_566_489_242, 0);
+    /// assert_eq!(date.to_iso8601(), "2019-08-22T15:54:02Z");
+    ///
+    /// // Any offset is accepted.
+    /// let date = Date::parse_iso8601("2019-08-22T17:54:02.007000572+02:00").unwrap();
+    /// assert_eq!(date.timestamp(), (1_566_489_242, 7_000_572));
+    ///
+    /// // Round-trips across the formats.
+    /// let nanos = date.to_epoch_nanos().unwrap();
+    /// let date = Date::parse_iso8601(&Date::from_epoch_nanos(nanos).to_iso8601()).unwrap();
+    /// assert_eq!(date.to_epoch_nanos().unwrap(), nanos);
+    ///
+    /// assert!(Date::parse_iso8601("2019-08-22").is_err());
+    /// ```
+    pub fn to_iso8601(&self) -> String {
+        self.date
+            .with_timezone(&chrono::Utc)
+            .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+    }
+    /// Parses an ISO-8601 date, see [`Self::to_iso8601`].
+    ///
+    /// Only the RFC 3339 profile of ISO-8601 is supported: a full date and time, with an offset.
+    pub fn parse_iso8601(s: &str) -> Res<Self> {
+        let date = chrono::DateTime::parse_from_rfc3339(s).map_err(|e| {
+            crate::err::ErrorKind::Parse(format!("illegal ISO-8601 date `{}`: {}", s, e))
+        })?;
+        Ok(date.with_timezone(&chrono::offset::Local).into())
+    }
+
     /// The hours/minutes/seconds/millis of a date.
     ///
     /// This is currently used only for debugging purposes.
@@ -43,6 +43,23 @@ pub trait DurationExt: From<Duration> {
         std::time::Duration::new(secs, convert(micros, "duration_from_micros: micros")).into()
     }
 
+    /// Creates a duration from an amount of milliseconds.
+    ///
+    /// ```rust
+    /// use base::prelude::time::{DurationExt, SinceStart};
+    /// let time = SinceStart::from_millis(83_250);
+    /// assert_eq!(time, SinceStart::from_nano_timestamp(83, 250_000_000));
+    /// assert_eq!(time.as_secs_f64(), 83.25);
+    /// ```
+    fn from_millis(ms: u64) -> Self {
+        std::time::Duration::from_millis(ms).into()
+    }
+
+    /// Amount of seconds, as a float.
+    fn as_secs_f64(&self) -> f64 {
+        self.as_duration().as_secs_f64()
+    }
+
     /// Duration parser from an amount of seconds, seen as a float.
     ///
     /// # Examples
@@ -145,6 +162,31 @@ pub trait DurationExt: From<Duration> {
     fn display_nanos<'me>(&'me self) -> DurationDisplay<'me, Self, Nanos> {
         self.into()
     }
+    /// Human-readable version of a duration, see [`Human`].
+    ///
+    /// ```rust
+    /// use base::prelude::time::{Duration, DurationExt};
+    /// let human = |secs, nanos| Duration::new(secs, nanos).display_human().to_string();
+    /// assert_eq!(human(0, 0), "0s");
+    /// // Sub-millisecond.
+    /// assert_eq!(human(0, 1), "1ns");
+    /// assert_eq!(human(0, 999), "999ns");
+    /// assert_eq!(human(0, 1_000), "1.0µs");
+    /// assert_eq!(human(0, 999_949), "999.9µs");
+    /// // Rounding to the next unit.
+    /// assert_eq!(human(0, 999_950), "1.0ms");
+    /// assert_eq!(human(0, 1_500_000), "1.5ms");
+    /// assert_eq!(human(0, 999_960_000), "1.0s");
+    /// assert_eq!(human(59, 960_000_000), "1m00.0s");
+    /// assert_eq!(human(3_599, 960_000_000), "1h00m00.0s");
+    /// // Minutes and hours.
+    /// assert_eq!(human(83, 400_000_000), "1m23.4s");
+    /// assert_eq!(human(3 * 3_600 + 25 * 60 + 7, 250_000_000), "3h25m07.3s");
+    /// assert_eq!(human(100 * 3_600, 0), "100h00m00.0s");
+    /// ```
+    fn display_human<'me>(&'me self) -> DurationDisplay<'me, Self, Human> {
+        self.into()
+    }
 }
 
 impl DurationExt for Duration {
@@ -201,6 +243,44 @@ impl TimePrecision for Millis {
     }
 }
 
+/// Human-readable precision, the unit depends on the duration.
+///
+/// Zero is `0s`. Durations below a microsecond are displayed in nanoseconds, *e.g.* `999ns`. Longer
+/// durations are rounded to one decimal, in microseconds (`12.3µs`), milliseconds (`4.5ms`),
+/// seconds (`6.7s`), minutes (`1m23.4s`) or hours (`2h03m04.5s`).
+pub struct Human;
+impl TimePrecision for Human {
+    fn duration_fmt(duration: &Duration, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let nanos = duration.as_nanos();
+        // Number of tenths of `unit` nanoseconds, rounded to the nearest.
+        let tenths = |unit: u128| (nanos + unit / 20) / (unit / 10);
+        // Bounds are such that rounding never yields `1000.0` of a unit.
+        if nanos == 0 {
+            write!(fmt, "0s")
+        } else if nanos < 1_000 {
+            write!(fmt, "{}ns", nanos)
+        } else if nanos < 999_950 {
+            let micros = tenths(1_000);
+            write!(fmt, "{}.{}µs", micros / 10, micros % 10)
+        } else if nanos < 999_950_000 {
+            let millis = tenths(1_000_000);
+            write!(fmt, "{}.{}ms", millis / 10, millis % 10)
+        } else {
+            let secs = tenths(1_000_000_000);
+            let (secs, tenth) = (secs / 10, secs % 10);
+            let (mins, secs) = (secs / 60, secs % 60);
+            let (hours, mins) = (mins / 60, mins % 60);
+            if hours > 0 {
+                write!(fmt, "{}h{:0>2}m{:0>2}.{}s", hours, mins, secs, tenth)
+            } else if mins > 0 {
+                write!(fmt, "{}m{:0>2}.{}s", mins, secs, tenth)
+            } else {
+                write!(fmt, "{}.{}s", secs, tenth)
+            }
+        }
+    }
+}
+
 /// Thin wrapper around a reference to a duration.
 pub struct DurationDisplay<'a, T: DurationExt + ?Sized, Precision: TimePrecision> {
     /// The actual duration.
@@ -233,6 +313,14 @@ impl<'a, T: DurationExt + ?Sized> From<&'a T> for DurationDisplay<'a, T, Millis>
         }
     }
 }
+impl<'a, T: DurationExt + ?Sized> From<&'a T> for DurationDisplay<'a, T, Human> {
+    fn from(duration: &'a T) -> Self {
+        Self {
+            duration,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
 impl<T: DurationExt, Precision: TimePrecision> fmt::Display for DurationDisplay<'_, T, Precision> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         Precision::duration_fmt(self.duration.as_duration(), fmt)
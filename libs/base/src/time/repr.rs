@@ -0,0 +1,155 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Alternative representations for dates and durations, for interop with external tools.
+//!
+//! The default serde representation of [`Date`] and [`SinceStart`] is the one derived by serde.
+//! The [`iso8601`] and [`epoch_nanos`] modules provide alternative representations, to use with
+//! serde's `with` attribute.
+//!
+//! ```rust
+//! use base::prelude::{serde::*, time::*};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Point {
+//!     #[serde(with = "base::time::repr::iso8601")]
+//!     date: Date,
+//!     #[serde(with = "base::time::repr::epoch_nanos")]
+//!     time: SinceStart,
+//! }
+//!
+//! let point = Point {
+//!     date: Date::from_timestamp(1_566_489_242, 7_000_572),
+//!     time: SinceStart::from_nano_timestamp(83, 250_000_000),
+//! };
+//! let bytes = base::bincode::serialize(&point).unwrap();
+//! let res: Point = base::bincode::deserialize(&bytes).unwrap();
+//! assert_eq!(res, point);
+//! ```
+
+prelude! { time::* }
+
+use crate::err::Res;
+
+/// Types that can be represented as ISO-8601 strings.
+pub trait Iso8601: Sized {
+    /// ISO-8601 representation.
+    fn to_iso8601(&self) -> String;
+    /// Parses an ISO-8601 representation.
+    fn parse_iso8601(s: &str) -> Res<Self>;
+}
+
+impl Iso8601 for Date {
+    fn to_iso8601(&self) -> String {
+        Date::to_iso8601(self)
+    }
+    fn parse_iso8601(s: &str) -> Res<Self> {
+        Date::parse_iso8601(s)
+    }
+}
+impl Iso8601 for SinceStart {
+    fn to_iso8601(&self) -> String {
+        SinceStart::to_iso8601(self)
+    }
+    fn parse_iso8601(s: &str) -> Res<Self> {
+        SinceStart::parse_iso8601(s)
+    }
+}
+
+/// Types that can be represented as an amount of nanoseconds since some epoch.
+///
+/// The epoch is the unix epoch for [`Date`], and the start of the run for [`SinceStart`].
+pub trait EpochNanos: Sized {
+    /// Amount of nanoseconds since the epoch.
+    fn to_epoch_nanos(&self) -> Res<i64>;
+    /// Constructor from an amount of nanoseconds since the epoch.
+    fn from_epoch_nanos(nanos: i64) -> Res<Self>;
+}
+
+impl EpochNanos for Date {
+    fn to_epoch_nanos(&self) -> Res<i64> {
+        Date::to_epoch_nanos(self)
+    }
+    fn from_epoch_nanos(nanos: i64) -> Res<Self> {
+        Ok(Date::from_epoch_nanos(nanos))
+    }
+}
+impl EpochNanos for SinceStart {
+    fn to_epoch_nanos(&self) -> Res<i64> {
+        self.to_nanos()
+    }
+    fn from_epoch_nanos(nanos: i64) -> Res<Self> {
+        SinceStart::from_nanos(nanos)
+    }
+}
+
+/// Serde representation as ISO-8601 strings, see [`Iso8601`].
+pub mod iso8601 {
+    use super::Iso8601;
+    use ::serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    /// Serializes a value as an ISO-8601 string.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Iso8601,
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.to_iso8601())
+    }
+
+    /// Deserializes a value from an ISO-8601 string.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Iso8601,
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        T::parse_iso8601(&s).map_err(|e| D::Error::custom(e.to_pretty()))
+    }
+}
+
+/// Serde representation as an amount of nanoseconds since an epoch, see [`EpochNanos`].
+pub mod epoch_nanos {
+    use super::EpochNanos;
+    use ::serde::{
+        de::Error as DeError, ser::Error as SerError, Deserialize, Deserializer, Serializer,
+    };
+
+    /// Serializes a value as an amount of nanoseconds.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: EpochNanos,
+        S: Serializer,
+    {
+        let nanos = value
+            .to_epoch_nanos()
+            .map_err(|e| S::Error::custom(e.to_pretty()))?;
+        serializer.serialize_i64(nanos)
+    }
+
+    /// Deserializes a value from an amount of nanoseconds.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: EpochNanos,
+        D: Deserializer<'de>,
+    {
+        let nanos = i64::deserialize(deserializer)?;
+        T::from_epoch_nanos(nanos).map_err(|e| D::Error::custom(e.to_pretty()))
+    }
+}
@@ -21,6 +21,8 @@
 
 prelude! { time::* }
 
+use crate::err::Res;
+
 /// Wrapper around a duration.
 ///
 /// This type represents a point in time **relative to** the start time of the run of the program
@@ -62,6 +64,140 @@ impl SinceStart {
         self.duration.subsec_nanos() == 0 && self.duration.as_secs() == 0
     }
 
+    /// Difference between two points in time, zero if `other` is after `self`.
+    ///
+    /// ```rust
+    /// use base::prelude::time::SinceStart;
+    /// let (one, two) = (SinceStart::from_secs(1), SinceStart::from_secs(2));
+    /// assert_eq!(two.saturating_sub(one), one);
+    /// assert_eq!(one.saturating_sub(two), SinceStart::zero());
+    /// ```
+    pub fn saturating_sub(self, other: Self) -> Self {
+        self.checked_sub(other).unwrap_or_else(Self::zero)
+    }
+    /// Difference between two points in time, `None` if `other` is after `self`.
+    ///
+    /// ```rust
+    /// use base::prelude::time::SinceStart;
+    /// let (one, two) = (SinceStart::from_secs(1), SinceStart::from_secs(2));
+    /// assert_eq!(two.checked_sub(one), Some(one));
+    /// assert_eq!(one.checked_sub(two), None);
+    /// ```
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.duration.checked_sub(other.duration).map(Self::from)
+    }
+
+    /// Amount of nanoseconds since the start of the run.
+    ///
+    /// Fails if the amount does not fit in an `i64`, *i.e.* after about 292 years.
+    ///
+    /// ```rust
+    /// use base::prelude::time::SinceStart;
+    /// let time = SinceStart::from_nano_timestamp(83, 250_000_000);
+    /// let nanos = time.to_nanos().unwrap();
+    /// assert_eq!(nanos, 83_250_000_000);
+    /// assert_eq!(SinceStart::from_nanos(nanos).unwrap(), time);
+    /// assert!(SinceStart::from_nanos(-1).is_err());
+    /// ```
+    pub fn to_nanos(&self) -> Res<i64> {
+        crate::try_convert(self.duration.as_nanos(), "nanoseconds since start")
+    }
+    /// Constructor from an amount of nanoseconds since the start of the run.
+    ///
+    /// Fails on negative amounts.
+    pub fn from_nanos(nanos: i64) -> Res<Self> {
+        let nanos: u64 = crate::try_convert(nanos, "nanoseconds since start")?;
+        Ok(Duration::from_nanos(nanos).into())
+    }
+
+    /// ISO-8601 duration representation, *e.g.* `PT83.25S`.
+    ///
+    /// Only uses seconds, with nanosecond precision and without trailing zeros.
+    ///
+    /// ```rust
+    /// use base::prelude::time::SinceStart;
+    /// let time = SinceStart::from_nano_timestamp(83, 250_000_000);
+    /// assert_eq!(time.to_iso8601(), "PT83.25S");
+    /// assert_eq!(SinceStart::parse_iso8601("PT83.25S").unwrap(), time);
+    /// assert_eq!(SinceStart::parse_iso8601("PT1M23.25S").unwrap(), time);
+    /// assert_eq!(SinceStart::zero().to_iso8601(), "PT0S");
+    /// assert_eq!(SinceStart::parse_iso8601("PT0S").unwrap(), SinceStart::zero());
+    ///
+    /// let time = SinceStart::from_nano_timestamp(3_725, 1);
+    /// assert_eq!(time.to_iso8601(), "PT3725.000000001S");
+    /// assert_eq!(SinceStart::parse_iso8601("PT1H2M5.000000001S").unwrap(), time);
+    ///
+    /// // Round-trips across the formats.
+    /// let nanos = time.to_nanos().unwrap();
+    /// let iso = SinceStart::from_nanos(nanos).unwrap().to_iso8601();
+    /// assert_eq!(SinceStart::parse_iso8601(&iso).unwrap().to_nanos().unwrap(), nanos);
+    ///
+    /// assert!(SinceStart::parse_iso8601("83.25").is_err());
+    /// assert!(SinceStart::parse_iso8601("P1DT2S").is_err());
+    /// assert!(SinceStart::parse_iso8601("PT2S1M").is_err());
+    /// assert!(SinceStart::parse_iso8601("PT5124095576030431H59M").is_err());
+    /// // Each component fits, their sum does not.
+    /// assert!(SinceStart::parse_iso8601("PT5124095576030431H5124095576030431M").is_err());
+    /// ```
+    pub fn to_iso8601(&self) -> String {
+        let mut secs = self.display_nanos().to_string();
+        while secs.ends_with('0') {
+            secs.pop();
+        }
+        if secs.ends_with('.') {
+            secs.pop();
+        }
+        format!("PT{}S", secs)
+    }
+    /// Parses an ISO-8601 duration, see [`Self::to_iso8601`].
+    ///
+    /// Only the time part of ISO-8601 durations is supported, *i.e.* `PT[<h>H][<m>M][<s>S]` where
+    /// `<h>` and `<m>` are integers and `<s>` is an amount of seconds with at most nanosecond
+    /// precision.
+    pub fn parse_iso8601(s: &str) -> Res<Self> {
+        let illegal = |reason: &str| -> crate::err::Error {
+            crate::err::ErrorKind::Parse(format!("illegal ISO-8601 duration `{}`: {}", s, reason))
+                .into()
+        };
+        let mut tail = s
+            .strip_prefix("PT")
+            .ok_or_else(|| illegal("expected a `PT` prefix"))?;
+        if tail.is_empty() {
+            return Err(illegal("expected at least one component"));
+        }
+
+        let mut res = Duration::new(0, 0);
+        // Components must appear in this order, each at most once.
+        let mut designators = ['H', 'M', 'S'].iter();
+        while !tail.is_empty() {
+            let end = tail
+                .find(|c: char| c.is_ascii_alphabetic())
+                .ok_or_else(|| illegal("expected a `H`, `M` or `S` designator"))?;
+            let (value, designator) = (&tail[..end], tail[end..].chars().next());
+            tail = &tail[end + 1..];
+            let designator = designator.expect("non-empty suffix");
+            if !designators.any(|d| *d == designator) {
+                return Err(illegal(&format!("unexpected designator `{}`", designator)));
+            }
+            let value = if designator == 'S' {
+                Duration::parse_secs(value)?
+            } else {
+                let value: u64 = value
+                    .parse()
+                    .map_err(|_| illegal(&format!("illegal integer `{}`", value)))?;
+                let secs_per_unit = if designator == 'H' { 3_600 } else { 60 };
+                let secs = value
+                    .checked_mul(secs_per_unit)
+                    .ok_or_else(|| illegal("amount of seconds overflow"))?;
+                Duration::from_secs(secs)
+            };
+            res = res
+                .checked_add(value)
+                .ok_or_else(|| illegal("amount of seconds overflow"))?;
+        }
+        Ok(res.into())
+    }
+
     /// Turns itself in a lifetime.
     pub fn to_lifetime(self) -> Lifetime {
         Lifetime::from(self.duration)
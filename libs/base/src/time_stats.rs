@@ -243,6 +243,24 @@ macro_rules! new_time_stats {
                 _: impl FnMut(&'static str, &$crate::time_stats::FakeStopwatch)
             ) {
             }
+
+            /// Description and elapsed time of all stopwatches.
+            ///
+            /// Stopwatches that were never started are ignored.
+            #[cfg(any(test, feature = "time_stats"))]
+            pub fn durations(&self) -> Vec<(&'static str, std::time::Duration)> {
+                let mut res = vec![];
+                self.all_do(|| (), |desc, sw| res.push((desc, sw.elapsed())));
+                res
+            }
+            /// Description and elapsed time of all stopwatches.
+            ///
+            /// Always empty when not profiling.
+            #[cfg(not(any(test, feature = "time_stats")))]
+            #[inline]
+            pub fn durations(&self) -> Vec<(&'static str, std::time::Duration)> {
+                vec![]
+            }
         }
         impl std::fmt::Display for $ty_name {
             fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -129,6 +129,11 @@ macro_rules! new_uids {
 new_uids! {
     mod alloc_uid {
         /// Allocation UID.
+        ///
+        /// Backed by a `usize`, whose width depends on the target: the monotonic `u64` UIDs
+        /// generated by the CTF parser fit on 64-bit servers, but the client runs on `wasm32`
+        /// where a `usize` only has 32 bits. Allocation UIDs index the dense [`AllocMap`], which
+        /// is why they are not wider.
         Alloc,
         /// Map from allocation UIDs to something.
         map: AllocMap with iter: AllocIter,
@@ -26,6 +26,7 @@ use axis::{XAxis, YAxis};
 pub mod spec;
 
 pub mod axis;
+pub mod histogram;
 pub mod settings;
 pub mod time;
 pub use spec::ChartSpec;
@@ -35,6 +36,10 @@ pub use spec::ChartSpec;
 pub enum RawChart {
     /// A time chart.
     Time(time::TimeChart),
+    /// An allocation size histogram.
+    Histogram(histogram::SizeHistogram),
+    /// An allocation lifetime histogram.
+    LifetimeDist(histogram::LifetimeHistogram),
 }
 
 #[cfg(any(test, feature = "server"))]
@@ -44,12 +49,53 @@ impl RawChart {
         &mut self,
         filters: &mut Filters,
         init: bool,
+        settings: &settings::Chart,
         resolution: settings::Resolution,
         time_windopt: &TimeWindopt,
     ) -> Res<Option<Points>> {
         match self {
             Self::Time(time_chart) => {
-                time_chart.new_points(filters, init, resolution, time_windopt)
+                let changed = time_chart.configure(settings, time_windopt);
+                time_chart.new_points(filters, init || changed, resolution, time_windopt)
+            }
+            Self::Histogram(histogram) => {
+                let changed = histogram.configure(settings, time_windopt);
+                if init || changed || histogram.has_new_stuff()? {
+                    histogram.new_points(filters, init).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+            Self::LifetimeDist(histogram) => {
+                let changed = histogram.configure(settings, time_windopt);
+                if init || changed || histogram.has_new_stuff()? {
+                    histogram.new_points(filters, init).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    /// Computes all the points from scratch, at full resolution.
+    fn full_points(
+        &mut self,
+        filters: &mut Filters,
+        settings: &settings::Chart,
+        time_windopt: &TimeWindopt,
+    ) -> Res<Points> {
+        match self {
+            Self::Time(time_chart) => {
+                time_chart.configure(settings, time_windopt);
+                time_chart.full_points(filters, time_windopt)
+            }
+            Self::Histogram(histogram) => {
+                histogram.configure(settings, time_windopt);
+                histogram.new_points(filters, true)
+            }
+            Self::LifetimeDist(histogram) => {
+                histogram.configure(settings, time_windopt);
+                histogram.new_points(filters, true)
             }
         }
     }
@@ -58,6 +104,8 @@ impl RawChart {
     fn reset(&mut self, filters: &filter::Filters) {
         match self {
             Self::Time(chart) => chart.reset(filters),
+            Self::Histogram(chart) => chart.reset(filters),
+            Self::LifetimeDist(chart) => chart.reset(filters),
         }
     }
 }
@@ -65,11 +113,17 @@ impl RawChart {
 #[cfg(any(test, feature = "server"))]
 impl RawChart {
     /// Constructor.
-    pub fn new(filters: &filter::Filters, x_axis: XAxis, y_axis: YAxis) -> Res<Self> {
+    pub fn new(filters: &filter::Filters, spec: &ChartSpec) -> Res<Self> {
+        let (x_axis, y_axis, origin) = (*spec.x_axis(), *spec.y_axis(), spec.origin());
         let chart = match x_axis {
             XAxis::Time => Self::Time(match y_axis {
-                YAxis::TotalSize => time::TimeChart::new_total_size(filters),
+                YAxis::TotalSize => time::TimeChart::new_total_size(filters, origin),
+                YAxis::AllocRate => time::TimeChart::new_alloc_rate(origin),
+                YAxis::FreeRate => time::TimeChart::new_free_rate(origin),
+                YAxis::AllocCount => bail!("illegal chart: {} over {}", y_axis, x_axis),
             }),
+            XAxis::SizeBucket => Self::Histogram(histogram::SizeHistogram::new(y_axis)?),
+            XAxis::Lifetime => Self::LifetimeDist(histogram::LifetimeHistogram::new(y_axis)?),
         };
         Ok(chart)
     }
@@ -102,7 +156,7 @@ impl Chart {
     ) -> Res<Self> {
         let spec = ChartSpec::new(x_axis, y_axis, active);
         let settings = settings::Chart::from_axes(spec.desc(), x_axis, y_axis);
-        let chart = RawChart::new(filters, x_axis, y_axis)?;
+        let chart = RawChart::new(filters, &spec)?;
         let slf = Self {
             spec,
             settings,
@@ -113,13 +167,22 @@ impl Chart {
     }
 
     /// Constructor.
-    pub fn from_spec(title: Option<String>, filters: &Filters, spec: ChartSpec) -> Res<Self> {
+    ///
+    /// The chart's title is `title` if any, otherwise the spec's [display title].
+    ///
+    /// [display title]: ChartSpec::display_title
+    pub fn from_spec(title: Option<String>, filters: &Filters, mut spec: ChartSpec) -> Res<Self> {
+        if title.is_some() {
+            spec.set_title(title)?
+        } else {
+            spec.check()?
+        }
         let settings = settings::Chart::from_axes(
-            title.unwrap_or_else(|| spec.desc()),
+            spec.display_title(),
             spec.x_axis().clone(),
             spec.y_axis().clone(),
         );
-        let chart = RawChart::new(filters, spec.x_axis().clone(), spec.y_axis().clone())?;
+        let chart = RawChart::new(filters, &spec)?;
         Ok(Self {
             spec,
             settings,
@@ -128,11 +191,18 @@ impl Chart {
         })
     }
 
-    /// Applies an update to its settings.
-    pub fn update(&mut self, msg: msg::to_server::ChartMsg) -> bool {
+    /// Applies an update to its spec or settings.
+    ///
+    /// Returns `true` if the points of the chart must be reloaded.
+    pub fn update(&mut self, msg: msg::to_server::ChartMsg) -> Res<bool> {
         use msg::to_server::ChartMsg::*;
         match msg {
-            SettingsUpdate(msg) => self.settings.update(msg),
+            SettingsUpdate(msg) => Ok(self.settings.update(msg)),
+            SetTitle(title) => {
+                self.spec.set_title(title)?;
+                self.settings.set_title(self.spec.display_title());
+                Ok(false)
+            }
         }
     }
 
@@ -141,6 +211,11 @@ impl Chart {
     pub fn spec(&self) -> &ChartSpec {
         &self.spec
     }
+    /// Spec mutable accessor.
+    #[inline]
+    pub fn spec_mut(&mut self) -> &mut ChartSpec {
+        &mut self.spec
+    }
 
     /// Settings accessor.
     #[inline]
@@ -171,9 +246,13 @@ impl Chart {
     ) -> Res<Option<Points>> {
         self.still_init = self.still_init || init;
         if let Some(resolution) = self.settings.resolution() {
-            let res = self
-                .chart
-                .new_points(filters, self.still_init, resolution, time_windopt);
+            let res = self.chart.new_points(
+                filters,
+                self.still_init,
+                &self.settings,
+                resolution,
+                time_windopt,
+            );
             self.still_init = false;
             res
         } else {
@@ -185,4 +264,66 @@ impl Chart {
     pub fn reset(&mut self, filters: &filter::Filters) {
         self.chart.reset(filters)
     }
+
+    /// Computes all the points of the chart from scratch, at full resolution.
+    ///
+    /// The points sent to the client depend on the chart's [resolution](settings::Resolution):
+    /// time charts merge the events that are too close to be told apart on screen. This is only a
+    /// view transformation, the data itself is kept server-side. The full-resolution points are
+    /// recomputed from this data, on a fresh chart and a copy of `filters`: neither the chart nor
+    /// the filters are modified.
+    pub fn full_points(&self, filters: &Filters, time_windopt: &TimeWindopt) -> Res<Points> {
+        let mut filters = filters.clone();
+        filters.reset(filter::Reset::Hard);
+        let mut chart = RawChart::new(&filters, &self.spec)?;
+        chart.full_points(&mut filters, &self.settings, time_windopt)
+    }
+
+    /// Exports the full-resolution points of the chart as CSV.
+    ///
+    /// See [`Self::full_points`] and [`Points::to_csv`]. Only the lines that are active, and
+    /// visible in this chart, are exported.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     chart::{axis::*, settings::Resolution, Chart},
+    ///     filter::Filters,
+    ///     prelude::*,
+    /// };
+    /// charts::data::start("../ctf/rsc/synthetic.ctf", Default::default()).unwrap();
+    /// # while charts::data::progress::get().unwrap().is_some()
+    /// #     || charts::data::alloc_count().unwrap() == 0
+    /// # {
+    /// #     std::thread::sleep(std::time::Duration::from_millis(50))
+    /// # }
+    /// let mut filters = Filters::new();
+    /// let window = TimeWindopt::new(None, None);
+    /// let mut chart = Chart::new(
+    ///     &filters,
+    ///     XAxis::Time,
+    ///     YAxis::TotalSize,
+    ///     filters.uid_map(true),
+    /// ).unwrap();
+    ///
+    /// // Very low resolution, the rendered points are capped.
+    /// chart.settings_mut().set_resolution(Resolution { width: 10, height: 10 });
+    /// let rendered = chart.new_points(true, &mut filters, &window).unwrap().unwrap();
+    ///
+    /// let csv = chart.export_csv(&filters, &window).unwrap();
+    /// let rows = csv.lines().count() - 1;
+    /// assert!(rendered.len() < 10);
+    /// assert!(rows > 100 * rendered.len());
+    /// // Full resolution does not depend on the resolution of the chart.
+    /// chart.settings_mut().set_resolution(Resolution { width: 1000, height: 10 });
+    /// assert_eq!(chart.export_csv(&filters, &window).unwrap(), csv);
+    /// ```
+    pub fn export_csv(&self, filters: &Filters, time_windopt: &TimeWindopt) -> Res<String> {
+        let points = self.full_points(filters, time_windopt)?;
+        let active = self.spec.active();
+        Ok(points.to_csv(
+            filters.specs().filter(|spec| {
+                spec.is_active() && active.get(&spec.uid()).cloned().unwrap_or(false)
+            }),
+        ))
+    }
 }
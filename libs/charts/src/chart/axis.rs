@@ -26,19 +26,36 @@ prelude! {}
 pub enum XAxis {
     /// Time.
     Time,
+    /// Allocation size buckets, for histograms.
+    SizeBucket,
+    /// Allocation lifetime buckets, for histograms.
+    ///
+    /// Only considers dead allocations, since the lifetime of live allocations is not known yet.
+    Lifetime,
 }
 impl XAxis {
     /// Description of a x-axis.
     pub fn desc(&self) -> &'static str {
         match self {
             Self::Time => "time",
+            Self::SizeBucket => "allocation size",
+            Self::Lifetime => "allocation lifetime",
+        }
+    }
+
+    /// True if charts over this x-axis are histograms.
+    pub fn is_histogram(&self) -> bool {
+        match self {
+            Self::Time => false,
+            Self::SizeBucket | Self::Lifetime => true,
         }
     }
 
     /// The legal y-axes that can be combined with this x-axis.
     pub fn y_axes(&self) -> Vec<YAxis> {
         match self {
-            Self::Time => vec![YAxis::TotalSize],
+            Self::Time => vec![YAxis::TotalSize, YAxis::AllocRate, YAxis::FreeRate],
+            Self::SizeBucket | Self::Lifetime => vec![YAxis::AllocCount, YAxis::TotalSize],
         }
     }
 
@@ -66,6 +83,12 @@ impl Default for XAxis {
 pub enum YAxis {
     /// Total size.
     TotalSize,
+    /// Number of allocations.
+    AllocCount,
+    /// Allocation rate, in bytes per second.
+    AllocRate,
+    /// Deallocation rate, in bytes per second.
+    FreeRate,
     // /// Highest lifetime.
     // MaxLifetime,
 }
@@ -74,6 +97,9 @@ impl YAxis {
     pub fn desc(self) -> &'static str {
         match self {
             Self::TotalSize => "total size",
+            Self::AllocCount => "allocation count",
+            Self::AllocRate => "allocation rate",
+            Self::FreeRate => "deallocation rate",
             // Self::MaxLifetime => "highest lifetime",
         }
     }
@@ -82,6 +108,15 @@ impl YAxis {
     pub fn can_stack_area(self) -> bool {
         match self {
             Self::TotalSize => true,
+            Self::AllocCount | Self::AllocRate | Self::FreeRate => false,
+        }
+    }
+
+    /// True if `self` is a rate, computed over time windows.
+    pub fn is_rate(self) -> bool {
+        match self {
+            Self::AllocRate | Self::FreeRate => true,
+            Self::TotalSize | Self::AllocCount => false,
         }
     }
 }
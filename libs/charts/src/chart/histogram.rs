@@ -0,0 +1,330 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Allocation size and lifetime histogram charts.
+
+prelude! {}
+
+#[cfg(any(test, feature = "server"))]
+use point::{BucketPoints, HistogramPoints};
+
+/// Default number of buckets of a histogram.
+pub const DEFAULT_BUCKET_COUNT: usize = 20;
+
+/// Size buckets of a histogram.
+///
+/// Buckets are non-empty, contiguous and sorted.
+#[derive(Debug, Clone)]
+pub struct Buckets {
+    /// Inclusive size range of each bucket.
+    bounds: Vec<Range<u64>>,
+}
+
+impl Buckets {
+    /// Splits `[min, max]` in at most `count` buckets.
+    ///
+    /// If `log` is true, the bounds of the buckets grow geometrically, otherwise all buckets have
+    /// roughly the same width. Buckets that would be empty are dropped.
+    ///
+    /// ```rust
+    /// use charts::chart::histogram::Buckets;
+    /// let buckets = Buckets::new(1, 100, 4, false);
+    /// assert_eq!(buckets.len(), 4);
+    /// assert_eq!(buckets.index_of(1), Some(0));
+    /// assert_eq!(buckets.index_of(100), Some(3));
+    /// assert_eq!(buckets.index_of(101), None);
+    ///
+    /// let buckets = Buckets::new(1, 1000, 3, true);
+    /// assert_eq!(buckets.index_of(9), Some(0));
+    /// assert_eq!(buckets.index_of(10), Some(0));
+    /// assert_eq!(buckets.index_of(11), Some(1));
+    /// assert_eq!(buckets.index_of(1000), Some(2));
+    ///
+    /// let buckets = Buckets::new(7, 9, 10, false);
+    /// assert_eq!(buckets.len(), 3);
+    /// ```
+    pub fn new(min: u64, max: u64, count: usize, log: bool) -> Self {
+        let max = max.max(min);
+        let count = count.max(1) as u64;
+        let (log_min, log_max) = (min.max(1) as f64, max.max(1) as f64);
+
+        let mut bounds = Vec::with_capacity(count as usize);
+        let mut lbound = min;
+        for i in 1..=count {
+            let ubound = if i == count {
+                max
+            } else if log {
+                (log_min * (log_max / log_min).powf(i as f64 / count as f64)).round() as u64
+            } else {
+                min + (((max - min) as u128 * i as u128) / count as u128) as u64
+            };
+            if ubound >= lbound {
+                bounds.push(Range::new(lbound, ubound));
+                lbound = ubound + 1;
+            }
+        }
+
+        Self { bounds }
+    }
+
+    /// Number of buckets.
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+    /// True if there are no buckets.
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    /// Index of the bucket containing `size`, if any.
+    pub fn index_of(&self, size: u64) -> Option<usize> {
+        let idx = self.bounds.partition_point(|range| range.ubound < size);
+        match self.bounds.get(idx) {
+            Some(range) if range.lbound <= size => Some(idx),
+            _ => None,
+        }
+    }
+
+    /// Iterator over the size range of each bucket.
+    pub fn iter(&self) -> impl Iterator<Item = &Range<u64>> {
+        self.bounds.iter()
+    }
+}
+
+/// Histogram configuration, common to all histograms.
+#[cfg(any(test, feature = "server"))]
+#[derive(Debug)]
+struct Conf {
+    /// True if the histogram counts allocations, false if it sums their sizes.
+    count: bool,
+    /// Maximum number of buckets.
+    bucket_count: usize,
+    /// True if the buckets are log-scale.
+    log_buckets: bool,
+    /// Time at which the histogram is computed.
+    ///
+    /// `None` for the current time.
+    at_time: Option<time::SinceStart>,
+    /// UID of the last allocation, and timestamp of the last deallocation, during the last point
+    /// generation.
+    last: Option<(uid::Alloc, time::SinceStart)>,
+}
+
+#[cfg(any(test, feature = "server"))]
+impl Conf {
+    /// Constructor.
+    ///
+    /// Fails on rate y-axes, which only make sense over time.
+    fn new(y_axis: chart::axis::YAxis) -> Res<Self> {
+        use chart::axis::YAxis;
+        let count = match y_axis {
+            YAxis::AllocCount => true,
+            YAxis::TotalSize => false,
+            YAxis::AllocRate | YAxis::FreeRate => bail!("illegal histogram y-axis: {}", y_axis),
+        };
+        Ok(Self {
+            count,
+            bucket_count: DEFAULT_BUCKET_COUNT,
+            log_buckets: true,
+            at_time: None,
+            last: None,
+        })
+    }
+
+    /// Updates the configuration, returns `true` if it changed.
+    fn configure(&mut self, settings: &settings::Chart, time_windopt: &TimeWindopt) -> bool {
+        let conf = (
+            settings.bucket_count(),
+            settings.log_buckets(),
+            time_windopt.ubound,
+        );
+        let changed = conf != (self.bucket_count, self.log_buckets, self.at_time);
+        self.bucket_count = conf.0;
+        self.log_buckets = conf.1;
+        self.at_time = conf.2;
+        changed
+    }
+
+    /// True if the histogram should be recomputed.
+    fn has_new_stuff(&self) -> Res<bool> {
+        Ok(data::get()?.has_new_stuff_since(self.last))
+    }
+
+    /// Buckets some `(line, key, size)` triples.
+    ///
+    /// The buckets range over the keys, the value of a bucket is the number of triples in this
+    /// bucket if `self.count`, and the sum of their sizes otherwise.
+    fn bucket_points(
+        &self,
+        filters: &Filters,
+        entries: Vec<(uid::Line, u64, u64)>,
+    ) -> BucketPoints {
+        let mut points = BucketPoints::new();
+
+        let keys = entries.iter().map(|(_, key, _)| *key);
+        if let (Some(min), Some(max)) = (keys.clone().min(), keys.max()) {
+            let buckets = Buckets::new(min, max, self.bucket_count, self.log_buckets);
+            points.extend(
+                buckets
+                    .iter()
+                    .map(|range| Point::new(*range, PointVal::new(0, filters))),
+            );
+            for (line, key, size) in entries {
+                if let Some(idx) = buckets.index_of(key) {
+                    let val = if self.count { 1 } else { size };
+                    let vals = &mut points[idx].vals;
+                    *vals.get_mut_or(line, 0) += val;
+                    *vals.get_mut_or(uid::Line::Everything, 0) += val;
+                }
+            }
+        }
+
+        points
+    }
+}
+
+/// Histogram of the sizes of the live allocations.
+#[cfg(any(test, feature = "server"))]
+#[derive(Debug)]
+pub struct SizeHistogram {
+    /// Histogram configuration.
+    conf: Conf,
+}
+
+#[cfg(any(test, feature = "server"))]
+impl SizeHistogram {
+    /// Constructor.
+    pub fn new(y_axis: chart::axis::YAxis) -> Res<Self> {
+        Ok(Self {
+            conf: Conf::new(y_axis)?,
+        })
+    }
+
+    /// Updates the histogram's configuration, returns `true` if it changed.
+    pub fn configure(&mut self, settings: &settings::Chart, time_windopt: &TimeWindopt) -> bool {
+        self.conf.configure(settings, time_windopt)
+    }
+
+    /// True if the histogram should be recomputed.
+    pub fn has_new_stuff(&self) -> Res<bool> {
+        self.conf.has_new_stuff()
+    }
+}
+
+#[cfg(any(test, feature = "server"))]
+impl ChartExt for SizeHistogram {
+    fn new_points(&mut self, filters: &mut Filters, _init: bool) -> Res<Points> {
+        let data = data::get()?;
+        let time = self.conf.at_time.unwrap_or(*data.current_time());
+
+        let live: Vec<(uid::Line, u64, u64)> = data
+            .iter_allocs()
+            .filter(|alloc| alloc.is_live_at(time))
+            .map(|alloc| {
+                let line = filters
+                    .first_match(&time, alloc)
+                    .map(|filter| uid::Line::Filter(filter.uid()))
+                    .unwrap_or(uid::Line::CatchAll);
+                (line, alloc.real_size, alloc.real_size)
+            })
+            .collect();
+
+        let points = self.conf.bucket_points(filters, live);
+
+        self.conf.last = data.last_events();
+
+        let points = if self.conf.count {
+            HistogramPoints::Count(points)
+        } else {
+            HistogramPoints::Size(points)
+        };
+        Ok(points.into())
+    }
+
+    fn reset(&mut self, _filters: &Filters) {
+        self.conf.last = None
+    }
+}
+
+/// Histogram of the lifetimes of the dead allocations.
+///
+/// Lifetimes are bucketed in microseconds. Allocations that are still live (at the end of the time
+/// window, if any) have no lifetime yet and do not appear in the histogram.
+#[cfg(any(test, feature = "server"))]
+#[derive(Debug)]
+pub struct LifetimeHistogram {
+    /// Histogram configuration.
+    conf: Conf,
+}
+
+#[cfg(any(test, feature = "server"))]
+impl LifetimeHistogram {
+    /// Constructor.
+    pub fn new(y_axis: chart::axis::YAxis) -> Res<Self> {
+        Ok(Self {
+            conf: Conf::new(y_axis)?,
+        })
+    }
+
+    /// Updates the histogram's configuration, returns `true` if it changed.
+    pub fn configure(&mut self, settings: &settings::Chart, time_windopt: &TimeWindopt) -> bool {
+        self.conf.configure(settings, time_windopt)
+    }
+
+    /// True if the histogram should be recomputed.
+    pub fn has_new_stuff(&self) -> Res<bool> {
+        self.conf.has_new_stuff()
+    }
+}
+
+#[cfg(any(test, feature = "server"))]
+impl ChartExt for LifetimeHistogram {
+    fn new_points(&mut self, filters: &mut Filters, _init: bool) -> Res<Points> {
+        let data = data::get()?;
+        let time = self.conf.at_time.unwrap_or(*data.current_time());
+
+        let dead: Vec<(uid::Line, u64, u64)> = data
+            .iter_allocs()
+            .filter_map(|alloc| {
+                let tod = alloc.tod().filter(|tod| *tod <= time)?;
+                let lifetime = u64::try_from((tod - alloc.toc()).as_micros()).unwrap_or(u64::MAX);
+                let line = filters
+                    .first_match(&time, alloc)
+                    .map(|filter| uid::Line::Filter(filter.uid()))
+                    .unwrap_or(uid::Line::CatchAll);
+                Some((line, lifetime, alloc.real_size))
+            })
+            .collect();
+
+        let points = self.conf.bucket_points(filters, dead);
+
+        self.conf.last = data.last_events();
+
+        let points = if self.conf.count {
+            HistogramPoints::LifetimeCount(points)
+        } else {
+            HistogramPoints::LifetimeSize(points)
+        };
+        Ok(points.into())
+    }
+
+    fn reset(&mut self, _filters: &Filters) {
+        self.conf.last = None
+    }
+}
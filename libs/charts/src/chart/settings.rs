@@ -22,6 +22,13 @@
 prelude! {}
 
 /// A chart's display mode.
+///
+/// This is a presentation hint only, the points of a chart do not depend on its display mode. It
+/// is part of the [`Chart`] settings sent to the client, and can be changed live with
+/// [`ChartSettingsMsg::SetDisplayMode`].
+///
+/// [`ChartSettingsMsg::SetDisplayMode`]: crate::msg::ChartSettingsMsg::SetDisplayMode
+/// (SetDisplayMode message)
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum DisplayMode {
     /// Normal mode.
@@ -120,6 +127,150 @@ base::implement! {
     }
 }
 
+/// Origin of the time values displayed on time axes.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum TimeOrigin {
+    /// Time elapsed since the start of the run.
+    SinceStart,
+    /// Wall-clock time, *i.e.* start date of the run plus time elapsed since the start.
+    WallClock,
+}
+
+/// Unit of the time values displayed on time axes.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum TimeUnit {
+    /// Milliseconds.
+    Millis,
+    /// Seconds, with millisecond precision.
+    Secs,
+    /// Minutes and seconds, with millisecond precision.
+    Mins,
+    /// Human-readable, the unit depends on the value, see [`time::DurationExt::display_human`].
+    Human,
+}
+
+/// Format of the time values displayed on time axes.
+///
+/// Relative times are always computed from the start date of the run, *i.e.* `Init::start_time`,
+/// so that both origins agree.
+///
+/// ```rust
+/// # use base::prelude::time;
+/// # use charts::chart::settings::*;
+/// let time = time::SinceStart::from_nano_timestamp(83, 250_000_000);
+///
+/// let format = TimeFormat::default();
+/// assert_eq!(format.format(time, None), "1m23.3s");
+/// let format = TimeFormat::new(TimeOrigin::SinceStart, TimeUnit::Secs);
+/// assert_eq!(format.format(time, None), "83.25s");
+/// let format = TimeFormat::new(TimeOrigin::SinceStart, TimeUnit::Millis);
+/// assert_eq!(format.format(time, None), "83250ms");
+/// let format = TimeFormat::new(TimeOrigin::SinceStart, TimeUnit::Mins);
+/// assert_eq!(format.format(time, None), "1m23.25s");
+/// let format = TimeFormat::new(TimeOrigin::SinceStart, TimeUnit::Human);
+/// assert_eq!(format.format(time::SinceStart::from_nano_timestamp(0, 12_345), None), "12.3µs");
+///
+/// // Wall-clock time falls back to relative time when the start date is unknown.
+/// let format = TimeFormat::new(TimeOrigin::WallClock, TimeUnit::Secs);
+/// assert_eq!(format.format(time, None), "83.25s");
+/// let start = time::Date::from_timestamp(1566489242, 0);
+/// let (h, m, _, _) = start.time_info();
+/// assert_eq!(format.format(time, Some(&start)), format!("{:0>2}:{:0>2}:25", h, m + 1));
+/// ```
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TimeFormat {
+    /// Origin of the time values.
+    pub origin: TimeOrigin,
+    /// Unit of the time values.
+    pub unit: TimeUnit,
+}
+impl Default for TimeFormat {
+    fn default() -> Self {
+        Self::new(TimeOrigin::SinceStart, TimeUnit::Human)
+    }
+}
+impl TimeFormat {
+    /// Constructor.
+    pub fn new(origin: TimeOrigin, unit: TimeUnit) -> Self {
+        Self { origin, unit }
+    }
+
+    /// List of all the time formats.
+    ///
+    /// Wall-clock times are not human-readable durations, so there is no wall-clock human format.
+    pub fn all() -> Vec<Self> {
+        let mut res = Vec::with_capacity(7);
+        for origin in &[TimeOrigin::SinceStart, TimeOrigin::WallClock] {
+            for unit in &[TimeUnit::Millis, TimeUnit::Secs, TimeUnit::Mins] {
+                res.push(Self::new(*origin, *unit))
+            }
+        }
+        res.push(Self::new(TimeOrigin::SinceStart, TimeUnit::Human));
+        res
+    }
+
+    /// Formats a point in time.
+    ///
+    /// Falls back to relative time if the origin is wall-clock time and `start` is `None`. Human
+    /// wall-clock times use the [`TimeUnit::Secs`] format.
+    pub fn format(&self, time: time::SinceStart, start: Option<&time::Date>) -> String {
+        use time::DurationExt;
+        let millis = time.as_duration().as_millis();
+        match (self.origin, start) {
+            (TimeOrigin::WallClock, Some(start)) => {
+                let (h, m, s, mi) = (start + time).time_info();
+                match self.unit {
+                    TimeUnit::Millis => format!("{:0>2}:{:0>2}:{:0>2}.{:0>3}", h, m, s, mi),
+                    TimeUnit::Secs | TimeUnit::Human => format!("{:0>2}:{:0>2}:{:0>2}", h, m, s),
+                    TimeUnit::Mins => format!("{:0>2}:{:0>2}", h, m),
+                }
+            }
+            (TimeOrigin::SinceStart, _) | (TimeOrigin::WallClock, None) => match self.unit {
+                TimeUnit::Millis => format!("{}ms", millis),
+                TimeUnit::Secs => format!("{}s", Self::secs_of_millis(millis)),
+                TimeUnit::Mins => {
+                    let (mins, millis) = (millis / 60_000, millis % 60_000);
+                    if mins == 0 {
+                        format!("{}s", Self::secs_of_millis(millis))
+                    } else {
+                        format!("{}m{}s", mins, Self::secs_of_millis(millis))
+                    }
+                }
+                TimeUnit::Human => time.display_human().to_string(),
+            },
+        }
+    }
+
+    /// Formats some milliseconds as seconds, without trailing zeros.
+    fn secs_of_millis(millis: u128) -> String {
+        let (secs, millis) = (millis / 1_000, millis % 1_000);
+        if millis == 0 {
+            secs.to_string()
+        } else {
+            let mut res = format!("{}.{:0>3}", secs, millis);
+            while res.ends_with('0') {
+                res.pop();
+            }
+            res
+        }
+    }
+}
+impl fmt::Display for TimeFormat {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let origin = match self.origin {
+            TimeOrigin::SinceStart => "since start",
+            TimeOrigin::WallClock => "wall clock",
+        };
+        let unit = match self.unit {
+            TimeUnit::Millis => "ms",
+            TimeUnit::Secs => "s",
+            TimeUnit::Mins => "min",
+            TimeUnit::Human => "auto",
+        };
+        write!(fmt, "{} ({})", origin, unit)
+    }
+}
+
 /// Settings for a chart.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Chart {
@@ -137,6 +288,18 @@ pub struct Chart {
     y_log: bool,
     /// Resolution of the chart, if it is known.
     resolution: Option<Resolution>,
+    /// Number of buckets, for histograms, or of time windows, for rate charts.
+    bucket_count: usize,
+    /// True if the buckets are log-scale, for histograms.
+    log_buckets: bool,
+    /// Format of the time values on the x-axis, for time charts.
+    time_format: TimeFormat,
+    /// Start date of the run, used for wall-clock time formatting.
+    start_date: Option<time::Date>,
+    /// True if the lines are split by allocation kind, for time charts.
+    split_by_kind: bool,
+    /// Time cursor shared by all the charts, drawn as a vertical guide by time charts.
+    cursor: Option<time::SinceStart>,
 }
 impl Chart {
     /// Constructor.
@@ -149,16 +312,22 @@ impl Chart {
             x_log: false,
             y_log: false,
             resolution: None,
+            bucket_count: chart::histogram::DEFAULT_BUCKET_COUNT,
+            log_buckets: true,
+            time_format: TimeFormat::default(),
+            start_date: None,
+            split_by_kind: false,
+            cursor: None,
         }
     }
 
     /// Constructor from a pair of axes.
     pub fn from_axes(
         title: impl Into<String>,
-        _x: chart::axis::XAxis,
+        x: chart::axis::XAxis,
         y: chart::axis::YAxis,
     ) -> Self {
-        Self::new(title, y.can_stack_area())
+        Self::new(title, !x.is_histogram() && y.can_stack_area())
     }
 
     /// Applies an update.
@@ -181,6 +350,22 @@ impl Chart {
                 self.set_resolution(resolution);
                 true
             }
+            SetBucketCount(count) => {
+                self.set_bucket_count(count);
+                true
+            }
+            SetLogBuckets(log) => {
+                self.set_log_buckets(log);
+                true
+            }
+            SetYLog(log) => {
+                self.set_y_log(log);
+                false
+            }
+            SetSplitByKind(split) => {
+                self.set_split_by_kind(split);
+                true
+            }
         }
     }
 
@@ -236,6 +421,19 @@ impl Chart {
     }
 
     /// Sets the display setting.
+    ///
+    /// Stacked-area modes are ignored if the chart does not support them.
+    ///
+    /// ```rust
+    /// use charts::chart::settings::{Chart, DisplayMode};
+    /// let mut settings = Chart::new("total size over time", true);
+    /// settings.set_display_mode(DisplayMode::StackedArea);
+    /// assert_eq!(settings.display_mode(), DisplayMode::StackedArea);
+    ///
+    /// let mut settings = Chart::new("size histogram", false);
+    /// settings.set_display_mode(DisplayMode::StackedArea);
+    /// assert_eq!(settings.display_mode(), DisplayMode::Normal);
+    /// ```
     pub fn set_display_mode(&mut self, setting: DisplayMode) {
         if self.can_stacked_area || !setting.is_stacked_area() {
             self.display_mode = setting
@@ -273,14 +471,100 @@ impl Chart {
         self.resolution
     }
 
+    /// Number of buckets, for histograms, or of time windows, for rate charts.
+    pub fn bucket_count(&self) -> usize {
+        self.bucket_count
+    }
+    /// Sets the number of buckets, for histograms, or of time windows, for rate charts.
+    ///
+    /// A histogram has at least one bucket, a rate chart at least one time window.
+    pub fn set_bucket_count(&mut self, count: usize) {
+        self.bucket_count = count.max(1)
+    }
+    /// True if the buckets are log-scale, for histograms.
+    pub fn log_buckets(&self) -> bool {
+        self.log_buckets
+    }
+    /// Sets the log-scale buckets flag, for histograms.
+    pub fn set_log_buckets(&mut self, log: bool) {
+        self.log_buckets = log
+    }
+
+    /// Format of the time values on the x-axis, for time charts.
+    pub fn time_format(&self) -> TimeFormat {
+        self.time_format
+    }
+    /// Sets the format of the time values on the x-axis.
+    pub fn set_time_format(&mut self, time_format: TimeFormat) {
+        self.time_format = time_format
+    }
+    /// Start date of the run, if known.
+    pub fn start_date(&self) -> Option<&time::Date> {
+        self.start_date.as_ref()
+    }
+    /// Sets the start date of the run.
+    pub fn set_start_date(&mut self, start_date: Option<time::Date>) {
+        self.start_date = start_date
+    }
+    /// Time cursor, if any.
+    pub fn cursor(&self) -> Option<time::SinceStart> {
+        self.cursor
+    }
+    /// Sets the time cursor.
+    pub fn set_cursor(&mut self, cursor: Option<time::SinceStart>) {
+        self.cursor = cursor
+    }
+    /// Formats a point in time w.r.t. the time format and the start date of the run.
+    pub fn format_time(&self, time: time::SinceStart) -> String {
+        self.time_format.format(time, self.start_date.as_ref())
+    }
+
     /// Sets the x-axis-log setting.
     pub fn set_x_log(&mut self, x_log: bool) {
         self.x_log = x_log
     }
     /// Sets the y-axis-log setting.
+    ///
+    /// Only impacts rendering: the points are the same whether the y-axis is log-scale or not.
+    /// Log-scale only applies to the normal [display mode](DisplayMode), and only if the y-axis
+    /// values are integers (sizes, counts...). Since zero has no logarithm, the y-axis starts at
+    /// `1` and zero values are drawn at the bottom of the y-axis.
+    ///
+    /// ```rust
+    /// use charts::{chart::settings::Chart, msg::ChartSettingsMsg};
+    /// let mut settings = Chart::new("total size over time", true);
+    /// assert!(!settings.y_log());
+    /// // No need to regenerate the points.
+    /// assert!(!settings.update(ChartSettingsMsg::SetYLog(true)));
+    /// assert!(settings.y_log());
+    /// ```
     pub fn set_y_log(&mut self, y_log: bool) {
         self.y_log = y_log
     }
+
+    /// True if the lines are split by allocation kind, for time charts.
+    pub fn split_by_kind(&self) -> bool {
+        self.split_by_kind
+    }
+    /// Sets the split-by-allocation-kind setting, for time charts.
+    ///
+    /// When active, each line also has one sub-line per [`AllocKind`] appearing in its
+    /// allocations, called `<filter> (<kind>)`. Sub-lines are only drawn in the normal
+    /// [display mode](DisplayMode). Off by default.
+    ///
+    /// ```rust
+    /// use charts::{chart::settings::Chart, msg::ChartSettingsMsg};
+    /// let mut settings = Chart::new("total size over time", true);
+    /// assert!(!settings.split_by_kind());
+    /// // The points need to be regenerated.
+    /// assert!(settings.update(ChartSettingsMsg::SetSplitByKind(true)));
+    /// assert!(settings.split_by_kind());
+    /// ```
+    ///
+    /// [`AllocKind`]: alloc::AllocKind (AllocKind enum in alloc_data)
+    pub fn set_split_by_kind(&mut self, split_by_kind: bool) {
+        self.split_by_kind = split_by_kind
+    }
 }
 
 /// Settings for all the charts.
@@ -291,6 +575,12 @@ pub struct Charts {
     /// This only impacts actual point generation: only points that are in this window will be
     /// generated.
     time_window: TimeWindopt,
+    /// Palette used to pick the colors of new filters.
+    palette: color::Palette,
+    /// If true, the color of a new filter is derived from its name instead of the palette.
+    name_colors: bool,
+    /// Format of the time values on time axes.
+    time_format: TimeFormat,
 }
 impl Default for Charts {
     fn default() -> Self {
@@ -302,6 +592,9 @@ impl Charts {
     pub fn new() -> Self {
         Self {
             time_window: TimeWindopt::new(None, None),
+            palette: color::Palette::default(),
+            name_colors: false,
+            time_format: TimeFormat::default(),
         }
     }
 
@@ -320,7 +613,12 @@ impl Charts {
         // Exhaustive deconstruction to create errors when new fields are added to `Self`.
         //
         // DO NOT USE `..` here.
-        let Self { time_window } = self;
+        let Self {
+            time_window,
+            palette: _,
+            name_colors: _,
+            time_format: _,
+        } = self;
 
         match time_window {
             Range {
@@ -351,6 +649,52 @@ impl Charts {
         &mut self.time_window
     }
 
+    /// Time window clamped to the end time of the run, if any.
+    ///
+    /// This is the window actually used to generate points. When the user sets a window that goes
+    /// beyond `end_time`, its bounds are brought back to `end_time` so that charts stop at the last
+    /// event of the run. The user's settings are not modified: the window is still displayed and
+    /// saved as the user wrote it.
+    ///
+    /// ```rust
+    /// # use charts::chart::settings::Charts;
+    /// use base::prelude::time::SinceStart;
+    /// let secs = |s| Some(SinceStart::from_secs(s));
+    /// let mut settings = Charts::new();
+    ///
+    /// // Unknown end time, nothing changes.
+    /// assert_eq!(settings.time_windopt_until(None), *settings.time_windopt());
+    ///
+    /// // Unbounded window.
+    /// let window = settings.time_windopt_until(secs(10));
+    /// assert_eq!((window.lbound, window.ubound), (None, secs(10)));
+    ///
+    /// // Window beyond the end time.
+    /// settings.time_windopt_mut().lbound = secs(11);
+    /// settings.time_windopt_mut().ubound = secs(15);
+    /// let window = settings.time_windopt_until(secs(10));
+    /// assert_eq!((window.lbound, window.ubound), (secs(10), secs(10)));
+    ///
+    /// // Window within the run.
+    /// settings.time_windopt_mut().lbound = secs(3);
+    /// settings.time_windopt_mut().ubound = secs(7);
+    /// let window = settings.time_windopt_until(secs(10));
+    /// assert_eq!((window.lbound, window.ubound), (secs(3), secs(7)));
+    /// ```
+    pub fn time_windopt_until(&self, end_time: Option<time::SinceStart>) -> TimeWindopt {
+        let mut time_window = self.time_window.clone();
+        if let Some(end_time) = end_time {
+            time_window.lbound = time_window.lbound.map(|lb| lb.min(end_time));
+            time_window.ubound = Some(
+                time_window
+                    .ubound
+                    .map(|ub| ub.min(end_time))
+                    .unwrap_or(end_time),
+            );
+        }
+        time_window
+    }
+
     /// Time-window accessor.
     pub fn time_window(&self, current_time: time::SinceStart) -> TimeWindow {
         self.time_window
@@ -358,12 +702,62 @@ impl Charts {
             .unwrap_or_else(time::SinceStart::zero, || current_time)
     }
 
+    /// Palette accessor.
+    pub fn palette(&self) -> color::Palette {
+        self.palette
+    }
+    /// Sets the palette.
+    pub fn set_palette(&mut self, palette: color::Palette) {
+        self.palette = palette
+    }
+
+    /// True if filter colors are derived from filter names.
+    pub fn name_colors(&self) -> bool {
+        self.name_colors
+    }
+    /// Sets the name-based filter coloring flag.
+    pub fn set_name_colors(&mut self, name_colors: bool) {
+        self.name_colors = name_colors
+    }
+
+    /// Format of the time values on time axes.
+    pub fn time_format(&self) -> TimeFormat {
+        self.time_format
+    }
+    /// Sets the format of the time values on time axes.
+    pub fn set_time_format(&mut self, time_format: TimeFormat) {
+        self.time_format = time_format
+    }
+
+    /// Color for a new filter called `name`, with `index` the number of filters so far.
+    pub fn new_filter_color(&self, name: &str, index: usize) -> Color {
+        if self.name_colors {
+            Color::from_name(name)
+        } else {
+            Color::next_from_palette(self.palette, index)
+        }
+    }
+
     /// Overwrites itself with a new value.
     ///
     /// Returns `true` if a reload of the points is necessary.
-    pub fn overwrite(&mut self, Self { time_window }: Self) -> bool {
+    pub fn overwrite(
+        &mut self,
+        Self {
+            time_window,
+            palette,
+            name_colors,
+            time_format,
+        }: Self,
+    ) -> bool {
         let mut reload = false;
 
+        // Only impact new filters, no need to reload.
+        self.palette = palette;
+        self.name_colors = name_colors;
+        // Only impacts rendering, no need to reload.
+        self.time_format = time_format;
+
         if self.time_window != time_window {
             self.time_window = time_window;
             reload = true
@@ -32,6 +32,19 @@ pub struct ChartSpec {
     y_axis: YAxis,
     /// Active filters.
     active: BTMap<uid::Line, bool>,
+    /// True if time charts start with an origin point.
+    ///
+    /// The origin point sits at the start of the time window, *i.e.* the start of the run unless
+    /// the time window has a lower bound. Its value is the initial value of the chart: the size of
+    /// the allocations live at that time for cumulative charts such as total size over time, and
+    /// zero for rate charts.
+    origin: bool,
+    /// Custom title, the chart is titled by its [description](Self::desc) if none.
+    #[serde(default)]
+    title: Option<String>,
+    /// Sample rate of the run, see [`Self::sample_rate`].
+    #[serde(default)]
+    sample_rate: Option<SampleRate>,
 }
 impl ChartSpec {
     /// Creates a new chart spec.
@@ -41,6 +54,9 @@ impl ChartSpec {
             x_axis,
             y_axis,
             active,
+            origin: true,
+            title: None,
+            sample_rate: None,
         }
     }
 
@@ -49,6 +65,55 @@ impl ChartSpec {
         format!("{} over {}", self.y_axis.desc(), self.x_axis.desc())
     }
 
+    /// Custom title, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+    /// Title of the chart: its custom title if any, its [description](Self::desc) otherwise.
+    pub fn display_title(&self) -> String {
+        self.title.clone().unwrap_or_else(|| self.desc())
+    }
+    /// Sets the custom title, `None` falls back on the [description](Self::desc).
+    ///
+    /// Fails on empty titles, titles that are only whitespace are considered empty.
+    ///
+    /// ```rust
+    /// use charts::chart::{axis::*, ChartSpec};
+    /// let mut spec = ChartSpec::new(XAxis::Time, YAxis::TotalSize, Default::default());
+    /// assert_eq!(spec.display_title(), spec.desc());
+    ///
+    /// spec.set_title(Some("heap".into())).unwrap();
+    /// assert_eq!(spec.display_title(), "heap");
+    ///
+    /// let err = spec.set_title(Some(" ".into())).unwrap_err();
+    /// assert!(err.to_string().contains("cannot be empty"));
+    /// assert_eq!(spec.title(), Some("heap"));
+    ///
+    /// spec.set_title(None).unwrap();
+    /// assert_eq!(spec.display_title(), spec.desc());
+    /// ```
+    pub fn set_title(&mut self, title: Option<String>) -> Res<()> {
+        if let Some(title) = title.as_ref() {
+            Self::check_title(title)?
+        }
+        self.title = title;
+        Ok(())
+    }
+    /// Checks the custom title, if any, is legal, see [`Self::set_title`].
+    pub fn check(&self) -> Res<()> {
+        match self.title.as_ref() {
+            Some(title) => Self::check_title(title),
+            None => Ok(()),
+        }
+    }
+    /// Fails on empty titles.
+    fn check_title(title: &str) -> Res<()> {
+        if title.trim().is_empty() {
+            bail!("chart titles cannot be empty")
+        }
+        Ok(())
+    }
+
     /// UID accessor.
     pub fn uid(&self) -> uid::Chart {
         self.uid
@@ -72,6 +137,103 @@ impl ChartSpec {
         &mut self.active
     }
 
+    /// True if time charts start with an origin point.
+    ///
+    /// Histograms ignore this flag.
+    pub fn origin(&self) -> bool {
+        self.origin
+    }
+    /// Sets the origin flag, see [`Self::origin`].
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     chart::{axis::*, Chart, ChartSpec},
+    ///     filter::Filters,
+    ///     prelude::*,
+    /// };
+    /// charts::data::start("../ctf/rsc/synthetic.ctf", Default::default()).unwrap();
+    /// # while charts::data::progress::get().unwrap().is_some()
+    /// #     || charts::data::alloc_count().unwrap() == 0
+    /// # {
+    /// #     std::thread::sleep(std::time::Duration::from_millis(50))
+    /// # }
+    /// let filters = Filters::new();
+    /// let window = TimeWindopt::new(None, None);
+    /// let csv = |y_axis, origin| {
+    ///     let mut spec = ChartSpec::new(XAxis::Time, y_axis, filters.uid_map(true));
+    ///     spec.set_origin(origin);
+    ///     let chart = Chart::from_spec(None, &filters, spec).unwrap();
+    ///     let csv = chart.export_csv(&filters, &window).unwrap();
+    ///     csv.lines().skip(1).map(String::from).collect::<Vec<_>>()
+    /// };
+    ///
+    /// for y_axis in &[YAxis::TotalSize, YAxis::AllocRate] {
+    ///     let (with, without) = (csv(*y_axis, true), csv(*y_axis, false));
+    ///     // The origin is at the start of the run, with value zero.
+    ///     assert_eq!(with[0], "0.000000,0,0");
+    ///     assert_eq!(with[1..], without[..]);
+    /// }
+    /// ```
+    pub fn set_origin(&mut self, origin: bool) {
+        self.origin = origin
+    }
+
+    /// Sample rate of the run, `None` if no run is loaded yet.
+    ///
+    /// When the run is sampled, *i.e.* the rate is not `1`, the values of the chart are estimates:
+    /// an allocation of `n` words yields on average `rate * n` samples, each standing for `1 / rate`
+    /// words. Since samples are drawn independently, the relative error of a value aggregating `n`
+    /// words is roughly `1 / sqrt(rate * n)`. It shrinks as values grow, and grows as the rate
+    /// decreases: with a rate of `1e-5`, `100MB` of 64-bit words are about `125` samples, *i.e.* a
+    /// relative error around `9%`, while `10GB` are about `12,500` samples, *i.e.* around `1%`.
+    ///
+    /// ```rust
+    /// use charts::{chart::{axis::*, ChartSpec}, prelude::*};
+    /// let mut spec = ChartSpec::new(XAxis::Time, YAxis::TotalSize, Default::default());
+    /// assert!(!spec.is_estimate());
+    /// assert_eq!(spec.estimate_prefix(), "");
+    /// assert_eq!(spec.raw_samples_of(1024), None);
+    ///
+    /// spec.set_sample_rate(Some(SampleRate::new(1.0, 64)));
+    /// assert!(!spec.is_estimate());
+    /// assert_eq!(spec.raw_samples_of(1024), None);
+    ///
+    /// spec.set_sample_rate(Some(SampleRate::new(0.25, 64)));
+    /// assert!(spec.is_estimate());
+    /// assert_eq!(spec.estimate_prefix(), "≈");
+    /// assert_eq!(spec.raw_samples_of(1024), Some(32));
+    /// ```
+    pub fn sample_rate(&self) -> Option<&SampleRate> {
+        self.sample_rate.as_ref()
+    }
+    /// Sets the sample rate of the run, see [`Self::sample_rate`].
+    pub fn set_sample_rate(&mut self, sample_rate: Option<SampleRate>) {
+        self.sample_rate = sample_rate
+    }
+    /// True if the values of the chart are estimates, see [`Self::sample_rate`].
+    pub fn is_estimate(&self) -> bool {
+        self.sample_rate
+            .as_ref()
+            .map(SampleRate::is_sampled)
+            .unwrap_or(false)
+    }
+    /// Prefix for the values of the chart, `"≈"` if they are [estimates](Self::is_estimate).
+    pub fn estimate_prefix(&self) -> &'static str {
+        if self.is_estimate() {
+            "≈"
+        } else {
+            ""
+        }
+    }
+    /// Number of samples a size in bytes stands for, `None` if the values are not
+    /// [estimates](Self::is_estimate).
+    pub fn raw_samples_of(&self, size: u64) -> Option<u64> {
+        self.sample_rate
+            .as_ref()
+            .filter(|rate| rate.is_sampled())
+            .map(|rate| rate.samples_of(size))
+    }
+
     /// True if the spec has active filters.
     pub fn has_active_filters(&self) -> bool {
         self.active.iter().any(|(_, active)| *active)
@@ -21,8 +21,10 @@
 
 prelude! {}
 
+pub mod rate;
 pub mod size;
 
+pub use rate::TimeRate;
 pub use size::TimeSize;
 
 /// A time chart.
@@ -30,6 +32,8 @@ pub use size::TimeSize;
 pub enum TimeChart {
     /// Total size over time chart.
     Size(TimeSize),
+    /// Allocation or deallocation rate over time chart.
+    Rate(TimeRate),
 }
 
 impl TimeChart {
@@ -53,6 +57,37 @@ impl TimeChart {
             Self::Size(time_size_chart) => {
                 time_size_chart.new_points(filters, init, resolution, time_windopt)
             }
+            Self::Rate(rate_chart) => {
+                if init || rate_chart.has_new_stuff()? {
+                    rate_chart.new_points(filters, init).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    /// Computes all the points from scratch, at full resolution.
+    pub fn full_points(
+        &mut self,
+        filters: &mut Filters,
+        time_windopt: &TimeWindopt,
+    ) -> Res<Points> {
+        match self {
+            Self::Size(time_size_chart) => time_size_chart.full_points(filters, time_windopt),
+            Self::Rate(rate_chart) => rate_chart.full_points(filters, time_windopt),
+        }
+    }
+
+    /// Updates the chart's configuration, returns `true` if it changed.
+    pub fn configure(
+        &mut self,
+        settings: &chart::settings::Chart,
+        time_windopt: &TimeWindopt,
+    ) -> bool {
+        match self {
+            Self::Size(chart) => chart.configure(settings),
+            Self::Rate(chart) => chart.configure(settings, time_windopt),
         }
     }
 
@@ -60,13 +95,27 @@ impl TimeChart {
     pub fn reset(&mut self, filters: &Filters) {
         match self {
             Self::Size(chart) => chart.reset(filters),
+            Self::Rate(chart) => chart.reset(filters),
         }
     }
 }
 
 impl TimeChart {
     /// Total size over time constructor.
-    pub fn new_total_size(filters: &Filters) -> Self {
-        Self::Size(TimeSize::new(filters))
+    ///
+    /// See [`ChartSpec::origin`](chart::ChartSpec::origin) for `origin`, same for the other
+    /// constructors.
+    pub fn new_total_size(filters: &Filters, origin: bool) -> Self {
+        Self::Size(TimeSize::new(filters, origin))
+    }
+
+    /// Allocation rate over time constructor.
+    pub fn new_alloc_rate(origin: bool) -> Self {
+        Self::Rate(TimeRate::new(rate::RateKind::Alloc, origin))
+    }
+
+    /// Deallocation rate over time constructor.
+    pub fn new_free_rate(origin: bool) -> Self {
+        Self::Rate(TimeRate::new(rate::RateKind::Free, origin))
     }
 }
@@ -0,0 +1,366 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Rate over time charts.
+
+prelude! {}
+
+#[cfg(any(test, feature = "server"))]
+use point::{Rate, SubLine, TimeRatePoints};
+
+/// Splits a time window in contiguous windows of (roughly) the same duration.
+#[derive(Debug, Clone)]
+pub struct Windows {
+    /// Bounds of the windows, one more than there are windows.
+    ///
+    /// Window `i` ranges from `bounds[i]` (inclusive) to `bounds[i + 1]` (exclusive), except for
+    /// the last window which includes its upper bound.
+    bounds: Vec<time::SinceStart>,
+}
+
+impl Windows {
+    /// Splits `time_window` in `count` windows.
+    ///
+    /// There is always at least one window. Windows have the same duration, up to a nanosecond.
+    ///
+    /// ```rust
+    /// use charts::{chart::time::rate::Windows, prelude::*};
+    /// let secs = time::SinceStart::from_secs;
+    /// let windows = Windows::new(&Range::new(secs(2), secs(6)), 4);
+    /// assert_eq!(windows.len(), 4);
+    /// assert_eq!(windows.index_of(secs(1)), None);
+    /// assert_eq!(windows.index_of(secs(2)), Some(0));
+    /// assert_eq!(windows.index_of(secs(3)), Some(1));
+    /// assert_eq!(windows.index_of(secs(6)), Some(3));
+    /// assert_eq!(windows.index_of(secs(7)), None);
+    /// assert_eq!(windows.window(1), Range::new(secs(3), secs(4)));
+    /// ```
+    pub fn new(time_window: &TimeWindow, count: usize) -> Self {
+        let count = count.max(1);
+        let lbound = time_window.lbound;
+        let span = if time_window.lbound < time_window.ubound {
+            (time_window.ubound - time_window.lbound).as_nanos()
+        } else {
+            0
+        };
+        let bounds = (0..=count)
+            .map(|i| {
+                let offset = u64::try_from(span * i as u128 / count as u128).unwrap_or(u64::MAX);
+                lbound + time::SinceStart::from(std::time::Duration::from_nanos(offset))
+            })
+            .collect();
+        Self { bounds }
+    }
+
+    /// Number of windows.
+    pub fn len(&self) -> usize {
+        self.bounds.len() - 1
+    }
+    /// True if there are no windows, which never happens.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Start of the first window.
+    pub fn lbound(&self) -> time::SinceStart {
+        self.bounds[0]
+    }
+    /// End of the last window.
+    pub fn ubound(&self) -> time::SinceStart {
+        self.bounds[self.len()]
+    }
+
+    /// Bounds of a window.
+    pub fn window(&self, idx: usize) -> TimeWindow {
+        Range::new(self.bounds[idx], self.bounds[idx + 1])
+    }
+
+    /// Index of the window containing some time, if any.
+    pub fn index_of(&self, time: time::SinceStart) -> Option<usize> {
+        if time < self.lbound() || self.ubound() < time {
+            return None;
+        }
+        let idx = self.bounds.partition_point(|bound| *bound <= time);
+        Some((idx - 1).min(self.len() - 1))
+    }
+}
+
+/// Events a rate chart measures the rate of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RateKind {
+    /// Allocations, by time of creation.
+    Alloc,
+    /// Deallocations, by time of death.
+    Free,
+}
+
+/// (De)allocation rate over time chart.
+///
+/// Splits the time window in [`bucket_count`] windows of the same duration. The value of a line in
+/// a window is the total size of the allocations of this line created (or that died, depending on
+/// the [`RateKind`]) in the window, divided by the duration of the window. The events before the
+/// time window are ignored: an allocation created before the time window is only counted by a
+/// deallocation rate chart, if it dies in the time window.
+///
+/// The first point is the *origin*: it sits at the start of the time window, and its rates are
+/// zero since no window ends there. Then comes one point per window, at the end of the window. In
+/// particular, the first window starts at the origin and the events exactly at the origin belong
+/// to it. The origin point is omitted if the [spec](chart::ChartSpec::origin) of the chart says
+/// so, the windows do not change.
+///
+/// [`bucket_count`]: chart::settings::Chart::bucket_count (bucket_count method of Chart)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeRate {
+    /// Events the chart measures the rate of.
+    kind: RateKind,
+    /// Number of windows.
+    window_count: usize,
+    /// True if the lines are split by allocation kind.
+    split_by_kind: bool,
+    /// Time window, with optional bounds.
+    time_windopt: TimeWindopt,
+    /// UID of the last allocation, and timestamp of the last deallocation, during the last point
+    /// generation.
+    last: Option<(uid::Alloc, time::SinceStart)>,
+    /// End of the last window during the last point generation.
+    end: Option<time::SinceStart>,
+    /// True if the points start with an origin point.
+    origin: bool,
+}
+
+impl TimeRate {
+    /// Constructor.
+    pub fn new(kind: RateKind, origin: bool) -> Self {
+        Self {
+            kind,
+            window_count: chart::histogram::DEFAULT_BUCKET_COUNT,
+            split_by_kind: false,
+            time_windopt: Range::new(None, None),
+            last: None,
+            end: None,
+            origin,
+        }
+    }
+
+    /// Events the chart measures the rate of.
+    pub fn kind(&self) -> RateKind {
+        self.kind
+    }
+}
+
+#[cfg(any(test, feature = "server"))]
+impl TimeRate {
+    /// Updates the configuration, returns `true` if it changed.
+    pub fn configure(
+        &mut self,
+        settings: &chart::settings::Chart,
+        time_windopt: &TimeWindopt,
+    ) -> bool {
+        let conf = (
+            settings.bucket_count(),
+            settings.split_by_kind(),
+            *time_windopt,
+        );
+        let changed = conf != (self.window_count, self.split_by_kind, self.time_windopt);
+        self.window_count = conf.0;
+        self.split_by_kind = conf.1;
+        self.time_windopt = conf.2;
+        changed
+    }
+
+    /// Computes all the points from scratch, over some time window.
+    ///
+    /// The time window becomes the one of the chart. Rates do not depend on the resolution of the
+    /// chart, so these are the points [`ChartExt::new_points`] yields.
+    pub fn full_points(
+        &mut self,
+        filters: &mut Filters,
+        time_windopt: &TimeWindopt,
+    ) -> Res<Points> {
+        self.time_windopt = *time_windopt;
+        self.new_points(filters, true)
+    }
+
+    /// True if the chart should be recomputed.
+    ///
+    /// This is the case when there are new events, or when the time window moved because time
+    /// passed.
+    pub fn has_new_stuff(&self) -> Res<bool> {
+        let data = data::get()?;
+        let end = self
+            .time_windopt
+            .to_time_window(|| *data.current_time())
+            .ubound;
+        Ok(data.has_new_stuff_since(self.last) || self.end != Some(end))
+    }
+}
+
+#[cfg(any(test, feature = "server"))]
+impl TimeRate {
+    /// Computes the points of the chart for some data.
+    ///
+    /// A new allocation goes to the first filter that matches it, and is remembered by the
+    /// filters. A death goes to the filter that owned the allocation, see
+    /// [`Filters::find_dead_match`]. Allocations the filters do not remember, because no chart
+    /// registered them yet, go to the first filter that matches them.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     chart::{
+    ///         settings,
+    ///         time::rate::{RateKind, TimeRate},
+    ///     },
+    ///     data::Data,
+    ///     filter::{ord::Cmp, *},
+    ///     fixture::*,
+    ///     point::Rate,
+    ///     prelude::{alloc::Diff, *},
+    /// };
+    /// let mut data = data(vec![
+    ///     Diff::new(secs(1), vec![builder(0, 10, 0), builder(1, 6, 1)], vec![]),
+    ///     Diff::new(secs(4), vec![builder(2, 8, 3), builder(3, 4, 4)], vec![]),
+    /// ]);
+    ///
+    /// let big = size_filter(Cmp::Ge, 8);
+    /// let big_line = uid::Line::Filter(big.uid());
+    /// let mut filters = Filters::new_with(vec![big]);
+    ///
+    /// let mut settings = settings::Chart::new("rate", false);
+    /// settings.set_bucket_count(2);
+    /// let mut chart = |kind, data: &Data, filters: &mut Filters, line| {
+    ///     let mut chart = TimeRate::new(kind, true);
+    ///     chart.configure(&settings, &Range::new(None, None));
+    ///     let points = chart.rate_points(data, filters);
+    ///     points
+    ///         .iter()
+    ///         .map(|point| (point.key, *point.vals.get(line).unwrap()))
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// // Windows `[0, 2[` and `[2, 4]`, after the origin.
+    /// let rates = |r1: u64, r2: u64| {
+    ///     vec![
+    ///         (secs(0), Rate::new(0u64)),
+    ///         (secs(2), Rate::new(r1)),
+    ///         (secs(4), Rate::new(r2)),
+    ///     ]
+    /// };
+    /// let everything = uid::Line::Everything;
+    /// assert_eq!(chart(RateKind::Alloc, &data, &mut filters, everything), rates(8, 6));
+    /// assert_eq!(chart(RateKind::Alloc, &data, &mut filters, big_line), rates(5, 4));
+    ///
+    /// // Two deaths in the second window.
+    /// let dead = vec![(uid(0), secs(3)), (uid(1), secs(4))];
+    /// data.add_diff(Diff::new(secs(4), vec![], dead)).unwrap();
+    /// assert_eq!(chart(RateKind::Free, &data, &mut filters, everything), rates(0, 8));
+    /// assert_eq!(chart(RateKind::Free, &data, &mut filters, big_line), rates(0, 5));
+    /// assert_eq!(chart(RateKind::Free, &data, &mut filters, uid::Line::CatchAll), rates(0, 3));
+    /// ```
+    pub fn rate_points(&mut self, data: &data::Data, filters: &mut Filters) -> TimeRatePoints {
+        let time_window = self.time_windopt.to_time_window(|| *data.current_time());
+        let windows = Windows::new(&time_window, self.window_count);
+
+        let mut sizes: Vec<PointVal<u64>> = (0..windows.len())
+            .map(|_| PointVal::new(0, filters))
+            .collect();
+        let split_by_kind = self.split_by_kind;
+        let mut add = |idx: usize, line: Option<uid::Filter>, alloc: &Alloc| {
+            let line = line.map(uid::Line::Filter).unwrap_or(uid::Line::CatchAll);
+            let vals = &mut sizes[idx];
+            for line in &[line, uid::Line::Everything] {
+                *vals.get_mut_or(*line, 0) += alloc.real_size;
+                if split_by_kind {
+                    *vals
+                        .kinds
+                        .entry(SubLine::new(*line, *alloc.kind()))
+                        .or_insert(0) += alloc.real_size;
+                }
+            }
+        };
+
+        match self.kind {
+            RateKind::Alloc => {
+                for alloc in data.iter_new_since(windows.lbound()) {
+                    let idx = match windows.index_of(alloc.toc) {
+                        Some(idx) => idx,
+                        None => break,
+                    };
+                    add(idx, filters.find_match(data.current_time(), alloc), alloc)
+                }
+            }
+            RateKind::Free => {
+                for (tod, alloc) in data.iter_dead_since(windows.lbound()) {
+                    let idx = match windows.index_of(tod) {
+                        Some(idx) => idx,
+                        None => break,
+                    };
+                    let line = filters.find_dead_match(alloc.uid()).or_else(|| {
+                        filters
+                            .first_match(data.current_time(), alloc)
+                            .map(Filter::uid)
+                    });
+                    add(idx, line, alloc)
+                }
+            }
+        }
+
+        let mut points = TimeRatePoints::with_capacity(windows.len() + 1);
+        if self.origin {
+            points.push(Point::new(
+                windows.lbound(),
+                PointVal::new(Rate::new(0u64), filters),
+            ));
+        }
+        for (idx, sizes) in sizes.into_iter().enumerate() {
+            let window = windows.window(idx);
+            let duration = window.ubound - window.lbound;
+            let rates = PointVal {
+                map: sizes
+                    .map
+                    .into_iter()
+                    .map(|(line, size)| (line, Rate::of(size, duration)))
+                    .collect(),
+                kinds: sizes
+                    .kinds
+                    .into_iter()
+                    .map(|(sub_line, size)| (sub_line, Rate::of(size, duration)))
+                    .collect(),
+            };
+            points.push(Point::new(window.ubound, rates))
+        }
+
+        self.last = data.last_events();
+        self.end = Some(windows.ubound());
+
+        points
+    }
+}
+
+#[cfg(any(test, feature = "server"))]
+impl ChartExt for TimeRate {
+    fn new_points(&mut self, filters: &mut Filters, _init: bool) -> Res<Points> {
+        let data = data::get()?;
+        Ok(self.rate_points(&data, filters).into())
+    }
+
+    fn reset(&mut self, _filters: &Filters) {
+        self.last = None;
+        self.end = None;
+    }
+}
@@ -21,6 +21,8 @@
 
 prelude! {}
 
+#[cfg(any(test, feature = "server"))]
+use point::SubLine;
 use point::{Size, TimeSizePoints};
 
 /// Initial size value.
@@ -37,6 +39,12 @@ pub struct TimeSize {
     last_time_stamp: Option<time::SinceStart>,
     /// Points.
     points: TimeSizePoints,
+    /// True if the lines are split by allocation kind.
+    split_by_kind: bool,
+    /// True if the points start with an origin point, see [`chart::ChartSpec::origin`].
+    ///
+    /// Otherwise, the points start with the first event after the origin.
+    origin: bool,
 }
 
 impl TimeSize {
@@ -47,6 +55,8 @@ impl TimeSize {
             size: Self::init_size_point(filters),
             last_time_stamp: None,
             points: TimeSizePoints::with_capacity(32),
+            split_by_kind: false,
+            origin: true,
         }
     }
 }
@@ -61,10 +71,30 @@ impl TimeSize {
         resolution: chart::settings::Resolution,
         time_windopt: &TimeWindopt,
     ) -> Res<Option<Points>> {
-        self.do_it(filters, init, resolution, time_windopt)
+        self.do_it(filters, init, Some(resolution), time_windopt)
             .map(|opt| opt.map(Points::from))
     }
 
+    /// Computes all the points from scratch, at full resolution.
+    ///
+    /// Unlike [`Self::new_points`], events are never merged because they are too close to be told
+    /// apart at the chart's resolution: there is one point per event timestamp.
+    pub fn full_points(
+        &mut self,
+        filters: &mut Filters,
+        time_windopt: &TimeWindopt,
+    ) -> Res<Points> {
+        self.do_it(filters, true, None, time_windopt)
+            .map(|opt| opt.unwrap_or_default().into())
+    }
+
+    /// Updates the configuration, returns `true` if it changed.
+    pub fn configure(&mut self, settings: &chart::settings::Chart) -> bool {
+        let changed = self.split_by_kind != settings.split_by_kind();
+        self.split_by_kind = settings.split_by_kind();
+        changed
+    }
+
     /// Resets (drops) all its points and re-initializes itself for `filters`.
     pub fn reset(&mut self, filters: &filter::Filters) {
         self.last = None;
@@ -75,13 +105,15 @@ impl TimeSize {
 
 impl TimeSize {
     /// Constructor.
-    pub fn new(filters: &filter::Filters) -> Self {
+    pub fn new(filters: &filter::Filters, origin: bool) -> Self {
         let size = PointVal::new(INIT_SIZE_VALUE.into(), filters);
         Self {
             last: None,
             size,
             last_time_stamp: None,
             points: TimeSizePoints::with_capacity(32),
+            split_by_kind: false,
+            origin,
         }
     }
 
@@ -94,11 +126,74 @@ impl TimeSize {
 /// # Helpers for point generation
 #[cfg(any(test, feature = "server"))]
 impl TimeSize {
+    /// Skips the events strictly before `lbound`, the lower bound of the time window.
+    ///
+    /// Only called on (re)initialization, with the first point already generated. The events
+    /// before the time window only update the first point, which only depends on the allocations
+    /// that are live at `lbound`. Without custom filters and without splitting lines by allocation
+    /// kind, the data's [`LiveHistory`](data::LiveHistory) yields the live size directly. Otherwise
+    /// only these live allocations go through the filters, which also remembers their lines for
+    /// their deaths.
+    fn skip_below(&mut self, data: &data::Data, filters: &mut Filters, lbound: time::SinceStart) {
+        let (since, last_event) = match data.events_before(lbound) {
+            Some(res) => res,
+            None => return,
+        };
+
+        let split_by_kind = self.split_by_kind;
+        let first = &mut self.points[0].vals;
+        let last_size = &mut self.size;
+        let mut add = |line: uid::Line, kind: Option<alloc::AllocKind>, size: u64| {
+            for vals in [&mut *first, &mut *last_size].iter_mut() {
+                for line in &[line, uid::Line::Everything] {
+                    vals.map
+                        .entry(*line)
+                        .or_insert_with(|| INIT_SIZE_VALUE.into())
+                        .size += size;
+                    if let Some(kind) = kind {
+                        vals.kinds
+                            .entry(SubLine::new(*line, kind))
+                            .or_insert_with(|| INIT_SIZE_VALUE.into())
+                            .size += size
+                    }
+                }
+            }
+        };
+
+        if filters.filters().is_empty() && !split_by_kind {
+            add(uid::Line::CatchAll, None, data.live_before(lbound).1)
+        } else {
+            for alloc in data.iter_live_before(lbound) {
+                let line = filters
+                    .find_match(data.current_time(), alloc)
+                    .map(uid::Line::Filter)
+                    .unwrap_or(uid::Line::CatchAll);
+                let kind = if split_by_kind {
+                    Some(*alloc.kind())
+                } else {
+                    None
+                };
+                add(line, kind, alloc.real_size)
+            }
+        }
+
+        self.last_time_stamp = Some(last_event);
+        self.last = Some(since);
+    }
+
+    /// Generates the new points.
+    ///
+    /// Events closer than a fifth of the `resolution`'s width are merged in the same point. All
+    /// events are kept if `resolution` is `None`.
+    ///
+    /// On (re)initialization, the first point is the origin: it sits at the start of the time
+    /// window, and its value is the size of the allocations live at that time. It is dropped if
+    /// the chart has no origin.
     fn do_it(
         &mut self,
         filters: &mut Filters,
         init: bool,
-        resolution: chart::settings::Resolution,
+        resolution: Option<chart::settings::Resolution>,
         time_windopt: &TimeWindopt,
     ) -> Res<Option<TimeSizePoints>> {
         let data = data::get()?;
@@ -108,7 +203,9 @@ impl TimeSize {
         }
 
         let time_window = time_windopt.to_time_window(|| *data.current_time());
-        let min_time_spacing = data.current_time().clone() / (resolution.width / 5);
+        let min_time_spacing = resolution
+            .map(|resolution| data.current_time().clone() / (resolution.width / 5))
+            .unwrap_or_else(time::SinceStart::zero);
 
         debug_assert!(self.points.is_empty());
         if init {
@@ -125,13 +222,17 @@ impl TimeSize {
             }),
             self.size.clone(),
         ));
+        if init {
+            self.skip_below(&data, filters, time_window.lbound);
+        }
+        let split_by_kind = self.split_by_kind;
         let points = &mut self.points;
 
         let (last_time_stamp, last_size, last) =
             (&mut self.last_time_stamp, &mut self.size, self.last.clone());
 
         macro_rules! update {
-            ($f_uid:expr, _, last_size => |ref mut $val:pat| $action:expr) => {{
+            ($f_uid:expr, $kind:expr, _, last_size => |ref mut $val:pat| $action:expr) => {{
                 let $val = last_size
                     .map
                     .entry($f_uid)
@@ -142,20 +243,43 @@ impl TimeSize {
                     .entry(uid::Line::Everything)
                     .or_insert_with(|| INIT_SIZE_VALUE.into());
                 $action;
+                if let Some(kind) = $kind {
+                    for line in &[$f_uid, uid::Line::Everything] {
+                        let $val = last_size
+                            .kinds
+                            .entry(SubLine::new(*line, kind))
+                            .or_insert_with(|| INIT_SIZE_VALUE.into());
+                        $action;
+                    }
+                }
             }};
-            ($f_uid:expr, $map:expr => |ref mut $val:pat| $action:expr) => {{
-                let $val = $map.entry($f_uid).or_insert_with(|| update!(@last size $f_uid));
+            ($f_uid:expr, $kind:expr, $vals:expr => |ref mut $val:pat| $action:expr) => {{
+                let $val = $vals.map.entry($f_uid).or_insert_with(|| update!(@last size $f_uid));
                 $action;
-                let $val = $map
+                let $val = $vals
+                    .map
                     .entry(uid::Line::Everything)
                     .or_insert_with(|| update!(@last size $f_uid));
                 $action;
+                if let Some(kind) = $kind {
+                    for line in &[$f_uid, uid::Line::Everything] {
+                        let sub_line = SubLine::new(*line, kind);
+                        let $val = $vals
+                            .kinds
+                            .entry(sub_line)
+                            .or_insert_with(|| update!(@last size sub_line));
+                        $action;
+                    }
+                }
             }};
-            ($f_uid:expr, $map:expr, last_size => |ref mut $val:pat| $action:expr) => {{
-                update!($f_uid, $map => |ref mut $val| $action);
-                update!($f_uid, _, last_size => |ref mut $val| $action);
+            ($f_uid:expr, $kind:expr, $vals:expr, last_size => |ref mut $val:pat| $action:expr) => {{
+                update!($f_uid, $kind, $vals => |ref mut $val| $action);
+                update!($f_uid, $kind, _, last_size => |ref mut $val| $action);
             }};
 
+            (@last size $sub_line:ident) => {
+                *last_size.kinds.entry($sub_line).or_insert_with(|| INIT_SIZE_VALUE.into())
+            };
             (@last size $f_uid:expr) => {
                 *last_size.map.entry($f_uid).or_insert_with(|| INIT_SIZE_VALUE.into())
             };
@@ -199,11 +323,22 @@ impl TimeSize {
                 |alloc| (alloc.toc, alloc.real_size, true, alloc),
                 |(tod, alloc)| (*tod, alloc.real_size, false, alloc),
             );
-            let f_uid = if let Some(f_uid) = filters.find_match(data.current_time(), alloc) {
+            // Deaths go to the line that registered the allocation in the first place.
+            let f_uid = if add {
+                filters.find_match(data.current_time(), alloc)
+            } else {
+                filters.find_dead_match(alloc.uid())
+            };
+            let f_uid = if let Some(f_uid) = f_uid {
                 uid::Line::Filter(f_uid)
             } else {
                 uid::Line::CatchAll
             };
+            let kind = if split_by_kind {
+                Some(*alloc.kind())
+            } else {
+                None
+            };
 
             match time_window.cmp(timestamp) {
                 // Below the time-window, update the first point if any.
@@ -211,26 +346,26 @@ impl TimeSize {
                     debug_assert!(points.len() <= 1);
 
                     *last_time_stamp = Some(timestamp);
-                    let last_map = if let Some(last) = points.last_mut() {
-                        &mut last.vals.map
+                    let last_vals = if let Some(last) = points.last_mut() {
+                        &mut last.vals
                     } else {
                         points.push(Point::new(timestamp, last_size.clone()));
                         let last = points
                             .last_mut()
                             .expect("`last_mut` after `push` cannot fail");
-                        &mut last.vals.map
+                        &mut last.vals
                     };
 
                     let mut underflow = false;
 
                     update!(
-                        f_uid, last_map, last_size => |ref mut val| if add {
-                            val.size += size as u64
+                        f_uid, kind, last_vals, last_size => |ref mut val| if add {
+                            val.size += size
                         } else {
-                            if val.size < size as u64 {
+                            if val.size < size {
                                 underflow = true;
                             }
-                            val.size -= size as u64
+                            val.size = val.size.saturating_sub(size)
                         }
                     );
 
@@ -258,9 +393,11 @@ impl TimeSize {
                         timestamp
                     };
 
+                    // Events never go in the origin point, which only holds the initial value.
+                    let at_origin = init && points.len() == 1;
                     let (vals, repeat_previous) = if let Some(last) = points.last_mut() {
-                        if last.key == adjusted_timestamp {
-                            (&mut last.vals.map, true)
+                        if last.key == adjusted_timestamp && !at_origin {
+                            (&mut last.vals, true)
                         } else {
                             let mut repeat = Point::new(adjusted_timestamp, PointVal::empty());
                             let (last_val, last_everything_val) = (
@@ -282,6 +419,18 @@ impl TimeSize {
                                 .map
                                 .insert(uid::Line::Everything, last_everything_val);
                             debug_assert_eq!(prev, None);
+                            if let Some(kind) = kind {
+                                for line in &[f_uid, uid::Line::Everything] {
+                                    let sub_line = SubLine::new(*line, kind);
+                                    let last_val = last_size
+                                        .kinds
+                                        .get(&sub_line)
+                                        .cloned()
+                                        .unwrap_or_else(|| INIT_SIZE_VALUE.into());
+                                    let prev = repeat.vals.kinds.insert(sub_line, last_val);
+                                    debug_assert_eq!(prev, None);
+                                }
+                            }
 
                             let new = repeat.clone();
 
@@ -291,26 +440,26 @@ impl TimeSize {
                             let last = points
                                 .last_mut()
                                 .expect("`last_mut` after `push` cannot fail");
-                            (&mut last.vals.map, true)
+                            (&mut last.vals, true)
                         }
                     } else {
                         points.push(Point::new(adjusted_timestamp, last_size.clone()));
                         let last = points
                             .last_mut()
                             .expect("`last_mut` after `push` cannot fail");
-                        (&mut last.vals.map, false)
+                        (&mut last.vals, false)
                     };
 
                     let mut underflow = None;
 
                     update! {
-                        f_uid, vals => |ref mut val| if add {
-                            val.size += size as u64
+                        f_uid, kind, vals => |ref mut val| if add {
+                            val.size += size
                         } else {
-                            if val.size < size as u64 {
+                            if val.size < size {
                                 underflow = Some(val.size)
                             }
-                            val.size -= size as u64
+                            val.size = val.size.saturating_sub(size)
                         }
                     }
 
@@ -332,18 +481,31 @@ impl TimeSize {
                             );
                             debug_assert_eq!(prev, None)
                         }
+                        if let Some(kind) = kind {
+                            for line in &[f_uid, uid::Line::Everything] {
+                                let sub_line = SubLine::new(*line, kind);
+                                if points[penultimate].vals.kinds.get(&sub_line).is_none() {
+                                    let last_val = last_size
+                                        .kinds
+                                        .get(&sub_line)
+                                        .cloned()
+                                        .unwrap_or_else(|| INIT_SIZE_VALUE.into());
+                                    points[penultimate].vals.kinds.insert(sub_line, last_val);
+                                }
+                            }
+                        }
                     }
 
                     let mut underflow = None;
 
                     update! {
-                        f_uid, _, last_size => |ref mut val| if add {
-                            val.size += size as u64
+                        f_uid, kind, _, last_size => |ref mut val| if add {
+                            val.size += size
                         } else {
-                            if val.size < size as u64 {
+                            if val.size < size {
                                 underflow = Some(val.size)
                             }
-                            val.size -= size as u64
+                            val.size = val.size.saturating_sub(size)
                         }
                     }
 
@@ -380,6 +542,10 @@ impl TimeSize {
 
         self.last = data.last_events();
 
+        if init && !self.origin && points.len() > 1 {
+            points.remove(0);
+        }
+
         debug_assert!(!points.is_empty());
         // println!();
         // println!("points {{");
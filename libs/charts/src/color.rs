@@ -40,6 +40,275 @@ macro_rules! rng {
     };
 }
 
+/// A color palette, used to pick the colors of new filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    /// Random colors.
+    Random,
+    /// Hues spread on the color wheel with the golden angle.
+    Default,
+    /// The *viridis* colormap.
+    Viridis,
+    /// Okabe-Ito colorblind-safe palette.
+    OkabeIto,
+}
+impl Palette {
+    /// Short description of the palette.
+    pub fn desc(self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::Default => "default",
+            Self::Viridis => "viridis",
+            Self::OkabeIto => "colorblind-safe (Okabe-Ito)",
+        }
+    }
+
+    /// List of all the palettes.
+    pub fn all() -> Vec<Self> {
+        base::debug_do! {
+            // If you get an error here, it means the definition of `Palette` changed. You need to
+            // update the following `match` statement, as well as the list returned by this function
+            // (below).
+            match Self::Random {
+                Self::Random
+                | Self::Default
+                | Self::Viridis
+                | Self::OkabeIto => (),
+            }
+        }
+        vec![Self::Random, Self::Default, Self::Viridis, Self::OkabeIto]
+    }
+
+    /// Fixed list of colors of the palette, if any.
+    ///
+    /// Returns `None` for palettes that generate their colors on the fly.
+    fn colors(self) -> Option<&'static [Color]> {
+        match self {
+            Self::Random | Self::Default => None,
+            Self::Viridis => Some(VIRIDIS),
+            Self::OkabeIto => Some(OKABE_ITO),
+        }
+    }
+}
+impl Default for Palette {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+impl fmt::Display for Palette {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.desc().fmt(fmt)
+    }
+}
+
+/// Samples of the *viridis* colormap.
+///
+/// The darkest end of the colormap is left out as it is barely visible on a dark background.
+const VIRIDIS: &[Color] = &[
+    Color::new(0x3b, 0x52, 0x8b),
+    Color::new(0x21, 0x90, 0x8c),
+    Color::new(0x5d, 0xc8, 0x63),
+    Color::new(0xfd, 0xe7, 0x25),
+    Color::new(0x46, 0x33, 0x7e),
+    Color::new(0x2c, 0x72, 0x8e),
+    Color::new(0x27, 0xad, 0x81),
+    Color::new(0xaa, 0xdc, 0x32),
+];
+
+/// Okabe-Ito colorblind-safe palette.
+///
+/// Black is left out as it is not visible on a dark background.
+const OKABE_ITO: &[Color] = &[
+    Color::new(0xe6, 0x9f, 0x00),
+    Color::new(0x56, 0xb4, 0xe9),
+    Color::new(0x00, 0x9e, 0x73),
+    Color::new(0xf0, 0xe4, 0x42),
+    Color::new(0x00, 0x72, 0xb2),
+    Color::new(0xd5, 0x5e, 0x00),
+    Color::new(0xcc, 0x79, 0xa7),
+];
+
+/// CSS named colors, sorted by name.
+const CSS_NAMED: &[(&str, Color)] = &[
+    ("aliceblue", Color::new(0xf0, 0xf8, 0xff)),
+    ("antiquewhite", Color::new(0xfa, 0xeb, 0xd7)),
+    ("aqua", Color::new(0x00, 0xff, 0xff)),
+    ("aquamarine", Color::new(0x7f, 0xff, 0xd4)),
+    ("azure", Color::new(0xf0, 0xff, 0xff)),
+    ("beige", Color::new(0xf5, 0xf5, 0xdc)),
+    ("bisque", Color::new(0xff, 0xe4, 0xc4)),
+    ("black", Color::new(0x00, 0x00, 0x00)),
+    ("blanchedalmond", Color::new(0xff, 0xeb, 0xcd)),
+    ("blue", Color::new(0x00, 0x00, 0xff)),
+    ("blueviolet", Color::new(0x8a, 0x2b, 0xe2)),
+    ("brown", Color::new(0xa5, 0x2a, 0x2a)),
+    ("burlywood", Color::new(0xde, 0xb8, 0x87)),
+    ("cadetblue", Color::new(0x5f, 0x9e, 0xa0)),
+    ("chartreuse", Color::new(0x7f, 0xff, 0x00)),
+    ("chocolate", Color::new(0xd2, 0x69, 0x1e)),
+    ("coral", Color::new(0xff, 0x7f, 0x50)),
+    ("cornflowerblue", Color::new(0x64, 0x95, 0xed)),
+    ("cornsilk", Color::new(0xff, 0xf8, 0xdc)),
+    ("crimson", Color::new(0xdc, 0x14, 0x3c)),
+    ("cyan", Color::new(0x00, 0xff, 0xff)),
+    ("darkblue", Color::new(0x00, 0x00, 0x8b)),
+    ("darkcyan", Color::new(0x00, 0x8b, 0x8b)),
+    ("darkgoldenrod", Color::new(0xb8, 0x86, 0x0b)),
+    ("darkgray", Color::new(0xa9, 0xa9, 0xa9)),
+    ("darkgreen", Color::new(0x00, 0x64, 0x00)),
+    ("darkgrey", Color::new(0xa9, 0xa9, 0xa9)),
+    ("darkkhaki", Color::new(0xbd, 0xb7, 0x6b)),
+    ("darkmagenta", Color::new(0x8b, 0x00, 0x8b)),
+    ("darkolivegreen", Color::new(0x55, 0x6b, 0x2f)),
+    ("darkorange", Color::new(0xff, 0x8c, 0x00)),
+    ("darkorchid", Color::new(0x99, 0x32, 0xcc)),
+    ("darkred", Color::new(0x8b, 0x00, 0x00)),
+    ("darksalmon", Color::new(0xe9, 0x96, 0x7a)),
+    ("darkseagreen", Color::new(0x8f, 0xbc, 0x8f)),
+    ("darkslateblue", Color::new(0x48, 0x3d, 0x8b)),
+    ("darkslategray", Color::new(0x2f, 0x4f, 0x4f)),
+    ("darkslategrey", Color::new(0x2f, 0x4f, 0x4f)),
+    ("darkturquoise", Color::new(0x00, 0xce, 0xd1)),
+    ("darkviolet", Color::new(0x94, 0x00, 0xd3)),
+    ("deeppink", Color::new(0xff, 0x14, 0x93)),
+    ("deepskyblue", Color::new(0x00, 0xbf, 0xff)),
+    ("dimgray", Color::new(0x69, 0x69, 0x69)),
+    ("dimgrey", Color::new(0x69, 0x69, 0x69)),
+    ("dodgerblue", Color::new(0x1e, 0x90, 0xff)),
+    ("firebrick", Color::new(0xb2, 0x22, 0x22)),
+    ("floralwhite", Color::new(0xff, 0xfa, 0xf0)),
+    ("forestgreen", Color::new(0x22, 0x8b, 0x22)),
+    ("fuchsia", Color::new(0xff, 0x00, 0xff)),
+    ("gainsboro", Color::new(0xdc, 0xdc, 0xdc)),
+    ("ghostwhite", Color::new(0xf8, 0xf8, 0xff)),
+    ("gold", Color::new(0xff, 0xd7, 0x00)),
+    ("goldenrod", Color::new(0xda, 0xa5, 0x20)),
+    ("gray", Color::new(0x80, 0x80, 0x80)),
+    ("green", Color::new(0x00, 0x80, 0x00)),
+    ("greenyellow", Color::new(0xad, 0xff, 0x2f)),
+    ("grey", Color::new(0x80, 0x80, 0x80)),
+    ("honeydew", Color::new(0xf0, 0xff, 0xf0)),
+    ("hotpink", Color::new(0xff, 0x69, 0xb4)),
+    ("indianred", Color::new(0xcd, 0x5c, 0x5c)),
+    ("indigo", Color::new(0x4b, 0x00, 0x82)),
+    ("ivory", Color::new(0xff, 0xff, 0xf0)),
+    ("khaki", Color::new(0xf0, 0xe6, 0x8c)),
+    ("lavender", Color::new(0xe6, 0xe6, 0xfa)),
+    ("lavenderblush", Color::new(0xff, 0xf0, 0xf5)),
+    ("lawngreen", Color::new(0x7c, 0xfc, 0x00)),
+    ("lemonchiffon", Color::new(0xff, 0xfa, 0xcd)),
+    ("lightblue", Color::new(0xad, 0xd8, 0xe6)),
+    ("lightcoral", Color::new(0xf0, 0x80, 0x80)),
+    ("lightcyan", Color::new(0xe0, 0xff, 0xff)),
+    ("lightgoldenrodyellow", Color::new(0xfa, 0xfa, 0xd2)),
+    ("lightgray", Color::new(0xd3, 0xd3, 0xd3)),
+    ("lightgreen", Color::new(0x90, 0xee, 0x90)),
+    ("lightgrey", Color::new(0xd3, 0xd3, 0xd3)),
+    ("lightpink", Color::new(0xff, 0xb6, 0xc1)),
+    ("lightsalmon", Color::new(0xff, 0xa0, 0x7a)),
+    ("lightseagreen", Color::new(0x20, 0xb2, 0xaa)),
+    ("lightskyblue", Color::new(0x87, 0xce, 0xfa)),
+    ("lightslategray", Color::new(0x77, 0x88, 0x99)),
+    ("lightslategrey", Color::new(0x77, 0x88, 0x99)),
+    ("lightsteelblue", Color::new(0xb0, 0xc4, 0xde)),
+    ("lightyellow", Color::new(0xff, 0xff, 0xe0)),
+    ("lime", Color::new(0x00, 0xff, 0x00)),
+    ("limegreen", Color::new(0x32, 0xcd, 0x32)),
+    ("linen", Color::new(0xfa, 0xf0, 0xe6)),
+    ("magenta", Color::new(0xff, 0x00, 0xff)),
+    ("maroon", Color::new(0x80, 0x00, 0x00)),
+    ("mediumaquamarine", Color::new(0x66, 0xcd, 0xaa)),
+    ("mediumblue", Color::new(0x00, 0x00, 0xcd)),
+    ("mediumorchid", Color::new(0xba, 0x55, 0xd3)),
+    ("mediumpurple", Color::new(0x93, 0x70, 0xdb)),
+    ("mediumseagreen", Color::new(0x3c, 0xb3, 0x71)),
+    ("mediumslateblue", Color::new(0x7b, 0x68, 0xee)),
+    ("mediumspringgreen", Color::new(0x00, 0xfa, 0x9a)),
+    ("mediumturquoise", Color::new(0x48, 0xd1, 0xcc)),
+    ("mediumvioletred", Color::new(0xc7, 0x15, 0x85)),
+    ("midnightblue", Color::new(0x19, 0x19, 0x70)),
+    ("mintcream", Color::new(0xf5, 0xff, 0xfa)),
+    ("mistyrose", Color::new(0xff, 0xe4, 0xe1)),
+    ("moccasin", Color::new(0xff, 0xe4, 0xb5)),
+    ("navajowhite", Color::new(0xff, 0xde, 0xad)),
+    ("navy", Color::new(0x00, 0x00, 0x80)),
+    ("oldlace", Color::new(0xfd, 0xf5, 0xe6)),
+    ("olive", Color::new(0x80, 0x80, 0x00)),
+    ("olivedrab", Color::new(0x6b, 0x8e, 0x23)),
+    ("orange", Color::new(0xff, 0xa5, 0x00)),
+    ("orangered", Color::new(0xff, 0x45, 0x00)),
+    ("orchid", Color::new(0xda, 0x70, 0xd6)),
+    ("palegoldenrod", Color::new(0xee, 0xe8, 0xaa)),
+    ("palegreen", Color::new(0x98, 0xfb, 0x98)),
+    ("paleturquoise", Color::new(0xaf, 0xee, 0xee)),
+    ("palevioletred", Color::new(0xdb, 0x70, 0x93)),
+    ("papayawhip", Color::new(0xff, 0xef, 0xd5)),
+    ("peachpuff", Color::new(0xff, 0xda, 0xb9)),
+    ("peru", Color::new(0xcd, 0x85, 0x3f)),
+    ("pink", Color::new(0xff, 0xc0, 0xcb)),
+    ("plum", Color::new(0xdd, 0xa0, 0xdd)),
+    ("powderblue", Color::new(0xb0, 0xe0, 0xe6)),
+    ("purple", Color::new(0x80, 0x00, 0x80)),
+    ("rebeccapurple", Color::new(0x66, 0x33, 0x99)),
+    ("red", Color::new(0xff, 0x00, 0x00)),
+    ("rosybrown", Color::new(0xbc, 0x8f, 0x8f)),
+    ("royalblue", Color::new(0x41, 0x69, 0xe1)),
+    ("saddlebrown", Color::new(0x8b, 0x45, 0x13)),
+    ("salmon", Color::new(0xfa, 0x80, 0x72)),
+    ("sandybrown", Color::new(0xf4, 0xa4, 0x60)),
+    ("seagreen", Color::new(0x2e, 0x8b, 0x57)),
+    ("seashell", Color::new(0xff, 0xf5, 0xee)),
+    ("sienna", Color::new(0xa0, 0x52, 0x2d)),
+    ("silver", Color::new(0xc0, 0xc0, 0xc0)),
+    ("skyblue", Color::new(0x87, 0xce, 0xeb)),
+    ("slateblue", Color::new(0x6a, 0x5a, 0xcd)),
+    ("slategray", Color::new(0x70, 0x80, 0x90)),
+    ("slategrey", Color::new(0x70, 0x80, 0x90)),
+    ("snow", Color::new(0xff, 0xfa, 0xfa)),
+    ("springgreen", Color::new(0x00, 0xff, 0x7f)),
+    ("steelblue", Color::new(0x46, 0x82, 0xb4)),
+    ("tan", Color::new(0xd2, 0xb4, 0x8c)),
+    ("teal", Color::new(0x00, 0x80, 0x80)),
+    ("thistle", Color::new(0xd8, 0xbf, 0xd8)),
+    ("tomato", Color::new(0xff, 0x63, 0x47)),
+    ("turquoise", Color::new(0x40, 0xe0, 0xd0)),
+    ("violet", Color::new(0xee, 0x82, 0xee)),
+    ("wheat", Color::new(0xf5, 0xde, 0xb3)),
+    ("white", Color::new(0xff, 0xff, 0xff)),
+    ("whitesmoke", Color::new(0xf5, 0xf5, 0xf5)),
+    ("yellow", Color::new(0xff, 0xff, 0x00)),
+    ("yellowgreen", Color::new(0x9a, 0xcd, 0x32)),
+];
+
+/// Parses the arguments of a CSS functional notation such as `rgb(...)`, if `text` uses it.
+///
+/// Arguments are separated by commas or whitespaces, the optional alpha value comes last and can be
+/// separated by a `/`. Returns `None` if `text` is not `<name>(...)`.
+fn css_args<'a>(text: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let inner = text.strip_prefix(name)?.trim_start();
+    let inner = inner.strip_prefix('(')?.strip_suffix(')')?;
+    Some(
+        inner
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect(),
+    )
+}
+
+/// Parses a CSS number or percentage, percentages are scaled so that `100%` is `max`.
+fn css_number(arg: &str, max: f32) -> Option<f32> {
+    let res = if let Some(pct) = arg.strip_suffix('%') {
+        pct.parse::<f32>().ok()? * max / 100.0
+    } else {
+        arg.parse::<f32>().ok()?
+    };
+    if res.is_finite() {
+        Some(res)
+    } else {
+        None
+    }
+}
+
 /// RGBA color.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub struct Color {
@@ -83,7 +352,7 @@ impl Color {
     /// let color = Color::new(0xff, 0x00, 0x00);
     /// assert_eq!(&color.to_string(), "#ff0000")
     /// ```
-    pub fn new(r: u8, g: u8, b: u8) -> Self {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
 
@@ -92,18 +361,108 @@ impl Color {
 
     /// Constructs a color from a string.
     ///
-    /// - only accepts RGB strings starting with `#`.
+    /// Accepts the following CSS notations, case-insensitive:
+    ///
+    /// - hexadecimal `#rrggbb` and `#rgb`;
+    /// - [named colors](https://www.w3.org/TR/css-color-4/#named-colors) such as `rebeccapurple`;
+    /// - `rgb(r, g, b)` and `rgba(r, g, b, a)`, where channels are numbers between `0` and `255` or
+    ///   percentages;
+    /// - `hsl(h, s%, l%)` and `hsla(h, s%, l%, a)`, where `h` is in degrees (optional `deg` suffix).
+    ///
+    /// Functional notations also accept whitespace-separated arguments, with an optional alpha
+    /// value after a `/`: `rgb(255 0 0 / 50%)`. Alpha values are checked but ignored since colors
+    /// are opaque. As in CSS, out-of-range values are clamped: `rgb(300, -5, 0)` is `#ff0000`. Values
+    /// that are not numbers are errors.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use charts::color::Color;
     /// let color = Color::from_str("#ff0000").unwrap();
-    /// assert_eq!(&color.to_string(), "#ff0000")
+    /// assert_eq!(&color.to_string(), "#ff0000");
+    ///
+    /// let parse = |s: &str| Color::from_str(s).unwrap().to_string();
+    /// assert_eq!(parse("#F00"), "#ff0000");
+    /// assert_eq!(parse("RebeccaPurple"), "#663399");
+    /// assert_eq!(parse("rgb(102, 51, 153)"), "#663399");
+    /// assert_eq!(parse("rgba(102, 51, 153, 0.5)"), "#663399");
+    /// assert_eq!(parse("rgb(40% 20% 60% / 50%)"), "#663399");
+    /// assert_eq!(parse("hsl(0, 100%, 50%)"), "#ff0000");
+    /// assert_eq!(parse("hsla(240deg 100% 50% / 1)"), "#0000ff");
+    ///
+    /// // Out-of-range values are clamped.
+    /// assert_eq!(parse("rgb(300, -5, 0)"), "#ff0000");
+    /// assert_eq!(parse("hsl(-120, 150%, 50%)"), "#0000ff");
+    ///
+    /// // Errors.
+    /// assert!(Color::from_str("not a color").is_err());
+    /// assert!(Color::from_str("rgb(1, 2)").is_err());
+    /// assert!(Color::from_str("rgb(1, 2, three)").is_err());
+    /// assert!(Color::from_str("#12345").is_err());
+    ///
+    /// // Round-trip through the hexadecimal representation.
+    /// for text in &["#000000", "#0a0b0c", "#ffffff", "#663399"] {
+    ///     assert_eq!(&parse(text), text);
+    ///     assert_eq!(parse(&parse(text)), *text);
+    /// }
+    /// for text in &["tomato", "rgb(1, 2, 3)", "hsl(200, 50%, 40%)"] {
+    ///     let color = Color::from_str(text).unwrap();
+    ///     assert_eq!(Color::from_str(color.to_string()).unwrap(), color);
+    /// }
     /// ```
     pub fn from_str<Str: AsRef<str>>(text: Str) -> Res<Self> {
         let text = text.as_ref();
+        let lower = text.trim().to_ascii_lowercase();
+
+        if lower.starts_with('#') {
+            Self::from_hex_str(&lower)
+        } else if let Some(args) = css_args(&lower, "rgba").or_else(|| css_args(&lower, "rgb")) {
+            Self::from_rgb_args(&args)
+                .ok_or_else(|| format!("illegal CSS rgb color `{}`", text).into())
+        } else if let Some(args) = css_args(&lower, "hsla").or_else(|| css_args(&lower, "hsl")) {
+            Self::from_hsl_args(&args)
+                .ok_or_else(|| format!("illegal CSS hsl color `{}`", text).into())
+        } else if let Ok(idx) = CSS_NAMED.binary_search_by(|(name, _)| (*name).cmp(&lower)) {
+            Ok(CSS_NAMED[idx].1)
+        } else {
+            bail!("illegal color string `{}`", text)
+        }
+    }
+
+    /// Checks the optional alpha argument of a CSS color, at index `3`.
+    fn check_css_alpha(args: &[&str]) -> Option<()> {
+        match args.len() {
+            3 => Some(()),
+            4 => css_number(args[3], 1.0).map(|_| ()),
+            _ => None,
+        }
+    }
+
+    /// Constructor from the arguments of a CSS `rgb(...)` color.
+    fn from_rgb_args(args: &[&str]) -> Option<Self> {
+        Self::check_css_alpha(args)?;
+        let channel = |arg| css_number(arg, 255.0).map(|val| val.max(0.0).min(255.0).round() as u8);
+        Some(Self::new(
+            channel(args[0])?,
+            channel(args[1])?,
+            channel(args[2])?,
+        ))
+    }
+
+    /// Constructor from the arguments of a CSS `hsl(...)` color.
+    fn from_hsl_args(args: &[&str]) -> Option<Self> {
+        Self::check_css_alpha(args)?;
+        let hue = css_number(args[0].strip_suffix("deg").unwrap_or(args[0]), 360.0)?;
+        let ratio = |arg| css_number(arg, 100.0).map(|val| val.max(0.0).min(100.0) / 100.0);
+        Some(Self::from_hue(
+            hue.rem_euclid(360.0),
+            ratio(args[1])?,
+            ratio(args[2])?,
+        ))
+    }
 
+    /// Constructs a color from a `#rrggbb` or `#rgb` string.
+    fn from_hex_str(text: &str) -> Res<Self> {
         macro_rules! fail {
             () => {
                 bail!("illegal RGB color string `{}`", text)
@@ -117,6 +476,10 @@ impl Color {
             };
         }
 
+        if text.len() == 4 && &text[0..1] == "#" {
+            let short = |idx: usize| u8::from_str_radix(&text[idx..idx + 1], 16).map(|c| c * 17);
+            return Ok(Self::new(fail!(short(1)), fail!(short(2)), fail!(short(3))));
+        }
         if text.len() != 7 || &text[0..1] != "#" {
             fail!()
         }
@@ -214,6 +577,85 @@ impl Color {
         Self::from_hue(rng!().gen::<f32>() * 360f32, 1.0, 0.5)
     }
 
+    /// Color of index `index` in a palette.
+    ///
+    /// Except for [`Palette::Random`], the result only depends on `palette` and `index`.
+    /// Consecutive indices yield colors that are far apart.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use charts::color::{Color, Palette};
+    /// let color = Color::next_from_palette(Palette::OkabeIto, 0);
+    /// assert_eq!(&color.to_string(), "#e69f00");
+    /// // Palettes with a fixed number of colors wrap around.
+    /// assert_eq!(color, Color::next_from_palette(Palette::OkabeIto, 7));
+    ///
+    /// let (fst, snd) = (
+    ///     Color::next_from_palette(Palette::Default, 0),
+    ///     Color::next_from_palette(Palette::Default, 1),
+    /// );
+    /// assert_eq!(fst, Color::next_from_palette(Palette::Default, 0));
+    /// assert!(!fst.is_similar_to(&snd));
+    /// ```
+    pub fn next_from_palette(palette: Palette, index: usize) -> Self {
+        /// Golden angle, in degrees.
+        const GOLDEN_ANGLE: f32 = 137.507_76;
+        match palette {
+            Palette::Random => Self::random(),
+            Palette::Default => Self::from_hue(((index as f32) * GOLDEN_ANGLE) % 360., 1.0, 0.5),
+            Palette::Viridis | Palette::OkabeIto => {
+                let colors = palette
+                    .colors()
+                    .expect("palettes with a fixed list of colors");
+                colors[index % colors.len()]
+            }
+        }
+    }
+
+    /// Deterministic constructor from a seed.
+    ///
+    /// Unlike [`Color::random`], the result only depends on `seed`, and is stable across sessions
+    /// and platforms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use charts::color::Color;
+    /// assert_eq!(Color::from_seed(7), Color::from_seed(7));
+    /// assert_ne!(Color::from_seed(7), Color::from_seed(8));
+    /// ```
+    pub fn from_seed(seed: u64) -> Self {
+        // One round of splitmix64, so that close seeds yield unrelated hues.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        let hue = ((z % 3600) as f32) / 10.;
+        Self::from_hue(hue, 1.0, 0.5)
+    }
+
+    /// Deterministic constructor from a (filter) name.
+    ///
+    /// Hashes `name` (FNV-1a) and feeds the result to [`Color::from_seed`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use charts::color::Color;
+    /// let color = Color::from_name("my filter");
+    /// assert_eq!(color, Color::from_name("my filter"));
+    /// assert_ne!(color, Color::from_name("my other filter"));
+    /// ```
+    pub fn from_name(name: &str) -> Self {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in name.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        Self::from_seed(hash)
+    }
+
     /// Keeps on constructing colors until the input predicate is true.
     pub fn random_until(pred: impl Fn(&Color) -> bool) -> Self {
         let mut color = Self::random();
@@ -238,4 +680,24 @@ impl Color {
         }
         check!(self.r, other.r) && check!(self.g, other.g) && check!(self.b, other.b)
     }
+    /// Lighter shade of this color.
+    ///
+    /// Shade `index` out of `count` mixes the color with white, from `30%` white for the first shade
+    /// to `60%` white for the last one.
+    ///
+    /// ```rust
+    /// # use charts::color::Color;
+    /// let color = Color::new(0x00, 0x80, 0xff);
+    /// assert_eq!(&color.shade(0, 1).to_string(), "#4ca6ff");
+    /// assert_eq!(&color.shade(4, 5).to_string(), "#99ccff");
+    /// ```
+    pub fn shade(&self, index: usize, count: usize) -> Self {
+        let white = if count <= 1 {
+            0.3
+        } else {
+            0.3 + 0.3 * (index.min(count - 1) as f32) / ((count - 1) as f32)
+        };
+        let mix = |c: u8| (c as f32 + (255 - c) as f32 * white) as u8;
+        Self::new(mix(self.r), mix(self.g), mix(self.b))
+    }
 }
@@ -21,9 +21,16 @@
 
 prelude! {}
 
+pub mod errors;
+pub mod flamegraph;
+pub mod live;
+pub mod snapshot;
 mod watcher;
 
-pub use watcher::Watcher;
+pub use errors::ErrorLog;
+pub use flamegraph::Flamegraph;
+pub use live::LiveHistory;
+pub use watcher::{MalformedPolicy, Watcher, WatcherConfig};
 
 /// Factory used when parsing dump-data.
 ///
@@ -83,19 +90,115 @@ impl<'a> FullFactory<'a> {
 
 /// Starts global data handling.
 ///
-/// - runs the file watcher daemon.
-pub fn start(target: impl AsRef<std::path::Path>) -> Res<()> {
-    Watcher::spawn(target, false);
+/// - runs the file watcher daemon with some configuration, see [`WatcherConfig`]; dump directories
+///   are watched until the server stops, memtrace CTF files are loaded once.
+pub fn start(target: impl AsRef<std::path::Path>, config: WatcherConfig) -> Res<()> {
+    Watcher::spawn(target, true, config);
     Ok(())
 }
 
+/// Re-parses the data from scratch, see [`Watcher::rescan_all`].
+///
+/// Returns the cancellation flag of the new load.
+pub fn rescan() -> Res<Arc<std::sync::atomic::AtomicBool>> {
+    Watcher::rescan_all()
+}
+
+/// True if the watcher thread is running, see [`Watcher::is_running`].
+pub fn watcher_is_running() -> bool {
+    Watcher::is_running()
+}
+
+/// Word size override in bits, `0` if none.
+static WORD_SIZE_OVERRIDE: std::sync::atomic::AtomicU8 = std::sync::atomic::AtomicU8::new(0);
+
+/// Forces the word size (in bits) used when loading memtrace CTF dumps.
+///
+/// Must be called before [`start`] to have any effect. See the `word_size_override` option of
+/// [`ctf::ParseOptions`] for details.
+///
+/// [`start`]: crate::data::start (start function in module data)
+/// [`ctf::ParseOptions`]: ctf::ParseOptions (ParseOptions struct in crate ctf)
+pub fn set_word_size_override(word_size: Option<u8>) -> Res<()> {
+    if word_size == Some(0) {
+        bail!("illegal word size override `0`")
+    }
+    WORD_SIZE_OVERRIDE.store(word_size.unwrap_or(0), std::sync::atomic::Ordering::SeqCst);
+    Ok(())
+}
+
+/// Word size override in bits, if any, see [`set_word_size_override`].
+pub fn word_size_override() -> Option<u8> {
+    match WORD_SIZE_OVERRIDE.load(std::sync::atomic::Ordering::SeqCst) {
+        0 => None,
+        word_size => Some(word_size),
+    }
+}
+
 lazy_static! {
     /// Progress indicator, used during loading.
     static ref PROG: sync::RwLock<Option<LoadInfo>> = sync::RwLock::new(Some(LoadInfo::unknown()));
+    /// Cancellation flag of the current load, see [`progress::new_load`].
+    static ref LOAD_CANCELLED: sync::RwLock<Arc<std::sync::atomic::AtomicBool>> =
+        sync::RwLock::new(Arc::new(std::sync::atomic::AtomicBool::new(false)));
     /// Global state.
     static ref DATA: sync::RwLock<Data> = sync::RwLock::new(Data::new());
-    /// Errors.
-    static ref ERRORS: sync::RwLock<Vec<String>> = sync::RwLock::new(vec![]);
+    /// Snapshot file for memtrace CTF files, see [`set_snapshot_path`].
+    static ref SNAPSHOT_PATH: sync::RwLock<Option<std::path::PathBuf>> = sync::RwLock::new(None);
+    /// Statistics of the last memtrace CTF file parsed, see [`parse_stats`].
+    static ref PARSE_STATS: sync::RwLock<Option<ctf::ParseStats>> = sync::RwLock::new(None);
+}
+
+/// Statistics of the last parse of a memtrace CTF file, if any.
+///
+/// `None` until a memtrace CTF file is parsed, and for dump directories. Loading from a snapshot
+/// does not parse anything, and thus does not produce statistics either.
+pub fn parse_stats() -> Res<Option<ctf::ParseStats>> {
+    PARSE_STATS
+        .read()
+        .map(|stats| stats.clone())
+        .map_err(|e| err::Error::from(e.to_string()))
+        .chain_err(|| "while reading the parse statistics")
+}
+
+/// Sets the statistics of the last parse of a memtrace CTF file, see [`parse_stats`].
+fn set_parse_stats(stats: Option<ctf::ParseStats>) -> Res<()> {
+    *PARSE_STATS
+        .write()
+        .map_err(|e| err::Error::from(e.to_string()))
+        .chain_err(|| "while setting the parse statistics")? = stats;
+    Ok(())
+}
+
+/// Sets the snapshot file used when loading memtrace CTF files.
+///
+/// Must be called before [`start`] to have any effect. When loading a memtrace CTF file, the data
+/// comes from the snapshot if there is a valid one at this path, see the [`snapshot`] module for
+/// the invalidation rules. Otherwise the file is parsed and the snapshot is written afterwards.
+/// Dump directories ignore this setting.
+///
+/// [`start`]: crate::data::start (start function in module data)
+pub fn set_snapshot_path(path: Option<std::path::PathBuf>) -> Res<()> {
+    *SNAPSHOT_PATH
+        .write()
+        .map_err(|e| {
+            let e: err::Error = e.to_string().into();
+            e
+        })
+        .chain_err(|| "while setting the snapshot path")? = path;
+    Ok(())
+}
+
+/// Snapshot file used when loading memtrace CTF files, see [`set_snapshot_path`].
+pub fn snapshot_path() -> Res<Option<std::path::PathBuf>> {
+    SNAPSHOT_PATH
+        .read()
+        .map(|path| path.clone())
+        .map_err(|e| {
+            let e: err::Error = e.to_string().into();
+            e
+        })
+        .chain_err(|| "while reading the snapshot path")
 }
 
 /// Handles progress information.
@@ -128,6 +231,49 @@ pub mod progress {
         write().map(|mut prog| *prog = Some(LoadInfo::unknown()))
     }
 
+    /// Starts a new load, returns its cancellation flag.
+    ///
+    /// Each load owns its flag, raised by [`cancel`] until the next load starts. Loaders poll it
+    /// and stop as soon as possible once it is raised.
+    ///
+    /// ```rust
+    /// use charts::data::progress;
+    /// use std::sync::atomic::Ordering::SeqCst;
+    /// let load = progress::new_load().unwrap();
+    /// assert!(!load.load(SeqCst));
+    /// progress::cancel().unwrap();
+    /// assert!(load.load(SeqCst));
+    ///
+    /// // Cancelling a load does not cancel the next one.
+    /// let next = progress::new_load().unwrap();
+    /// assert!(!next.load(SeqCst));
+    /// assert!(load.load(SeqCst));
+    /// ```
+    ///
+    /// [`cancel`]: crate::data::progress::cancel (cancel function)
+    pub fn new_load() -> Res<Arc<std::sync::atomic::AtomicBool>> {
+        let flag = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        *LOAD_CANCELLED
+            .write()
+            .map_err(|e| err::Error::from(e.to_string()))
+            .chain_err(|| "while starting a new load")? = flag.clone();
+        Ok(flag)
+    }
+
+    /// Requests the current load to stop as soon as possible, see [`new_load`].
+    ///
+    /// Everything loaded so far is kept.
+    ///
+    /// [`new_load`]: crate::data::progress::new_load (new_load function)
+    pub fn cancel() -> Res<()> {
+        LOAD_CANCELLED
+            .read()
+            .map_err(|e| err::Error::from(e.to_string()))
+            .chain_err(|| "while cancelling the current load")?
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        Ok(())
+    }
+
     /// Removes the progress, meaning loading is over.
     pub fn set_done() -> Res<()> {
         *write()? = None;
@@ -151,6 +297,13 @@ pub mod progress {
         Ok(())
     }
 
+    /// Sets the number of dumps loaded and the total number of dumps.
+    pub fn set_progress(loaded: usize, total: usize) -> Res<()> {
+        let mut prog = write()?;
+        *prog = Some(LoadInfo { total, loaded });
+        Ok(())
+    }
+
     /// Increments the number of dumps loaded.
     pub fn inc_loaded() -> Res<()> {
         if let Some(mut prog) = write()?.as_mut() {
@@ -182,13 +335,20 @@ pub fn get<'a>() -> Res<sync::RwLockReadGuard<'a, Data>> {
         .chain_err(|| "while reading the global state")
 }
 
+/// True if a thread panicked while holding the global data lock.
+///
+/// Does not block, unlike [`get`] which fails in this case.
+pub fn is_poisoned() -> bool {
+    DATA.is_poisoned()
+}
+
 /// Total number of allocations.
 pub fn alloc_count() -> Res<usize> {
     get().map(|data| data.uid_map.len())
 }
 
 /// Global data write-accessor.
-fn get_mut<'a>() -> Res<sync::RwLockWriteGuard<'a, Data>> {
+pub(crate) fn get_mut<'a>() -> Res<sync::RwLockWriteGuard<'a, Data>> {
     DATA.write()
         .map_err(|e| {
             let e: err::Error = e.to_string().into();
@@ -202,13 +362,37 @@ pub struct Data {
     /// Init state.
     init: Option<alloc::Init>,
     /// Map from allocation UIDs to allocation data.
+    ///
+    /// Sorted by time of creation, see [`invariants::uid_order_is_toc_order`].
     uid_map: uid::AllocMap<Alloc>,
     /// Map from time-of-death to allocation UIDs.
     tod_map: BTMap<time::SinceStart, BTSet<uid::Alloc>>,
+    /// Size of the live set over time.
+    live: LiveHistory,
     /// Time of the latest diff.
     current_time: time::SinceStart,
     /// Statistics.
     stats: Option<AllocStats>,
+    /// If true, allocation UID collisions are fatal.
+    ///
+    /// Otherwise, the most recent allocation is dropped and a warning is issued.
+    strict: bool,
+    /// Number of allocations dropped because of a UID collision whose death is still pending, by
+    /// UID.
+    ///
+    /// Their deaths must be dropped too, otherwise they would kill the existing allocation with the
+    /// same UID. Deaths can come in any later diff, so this spans the whole run.
+    dropped: BTMap<uid::Alloc, usize>,
+    /// If true, the watcher does not register new diffs, see [`is_paused`].
+    ///
+    /// [`is_paused`]: #method.is_paused (is_paused method)
+    paused: bool,
+    /// Number of diffs registered since the last reset.
+    diff_count: usize,
+    /// Recent errors of the run, see [`add_err`].
+    ///
+    /// [`add_err`]: #method.add_err (add_err method)
+    errors: ErrorLog,
 }
 
 impl ops::Index<uid::Alloc> for Data {
@@ -225,11 +409,185 @@ impl Data {
             init: None,
             uid_map: uid::AllocMap::new(),
             tod_map: BTMap::new(),
+            live: LiveHistory::new(),
             current_time: time::SinceStart::zero(),
             stats: None,
+            strict: true,
+            dropped: BTMap::new(),
+            paused: false,
+            diff_count: 0,
+            errors: ErrorLog::default(),
+        }
+    }
+
+    /// Forgets everything but the strict and paused flags, see [`is_strict`] and [`is_paused`].
+    ///
+    /// Also keeps the capacity of the error log, see [`set_error_capacity`].
+    ///
+    /// [`is_strict`]: #method.is_strict (is_strict method)
+    /// [`is_paused`]: #method.is_paused (is_paused method)
+    /// [`set_error_capacity`]: #method.set_error_capacity (set_error_capacity method)
+    pub fn clear(&mut self) {
+        let (strict, paused, error_capacity) = (self.strict, self.paused, self.errors.capacity());
+        *self = Self::new();
+        self.strict = strict;
+        self.paused = paused;
+        self.errors.set_capacity(error_capacity)
+    }
+
+    /// Registers a non-fatal error of the run, see [`ErrorLog`].
+    ///
+    /// The error log is bounded, the oldest errors are dropped when it is full.
+    ///
+    /// ```rust
+    /// use charts::data::Data;
+    /// let mut data = Data::new();
+    /// data.set_error_capacity(2);
+    /// for i in 0..5 {
+    ///     data.add_err(format!("error {}", i))
+    /// }
+    /// let errors = data.errors();
+    /// assert_eq!(errors.iter().collect::<Vec<_>>(), vec!["error 3", "error 4"]);
+    /// assert_eq!(errors.dropped(), 3);
+    ///
+    /// // Clearing the data forgets the errors, but not the capacity.
+    /// data.clear();
+    /// assert!(data.errors().is_empty());
+    /// assert_eq!(data.errors().dropped(), 0);
+    /// assert_eq!(data.errors().capacity(), 2);
+    /// ```
+    pub fn add_err(&mut self, err: impl Into<String>) {
+        self.errors.push(err)
+    }
+
+    /// Recent errors of the run.
+    pub fn errors(&self) -> &ErrorLog {
+        &self.errors
+    }
+
+    /// Sets the capacity of the error log, see [`ErrorLog::set_capacity`].
+    pub fn set_error_capacity(&mut self, capacity: usize) {
+        self.errors.set_capacity(capacity)
+    }
+
+    /// True if the ingestion of new diffs is paused.
+    ///
+    /// While paused, the watcher of a dump directory keeps track of the new diff files but does not
+    /// register them. They are all registered, in order, once ingestion resumes. The profiled
+    /// process is not affected and keeps writing diff files, only the analysis is paused.
+    ///
+    /// Memtrace CTF files are loaded in one go and ignore this flag.
+    ///
+    /// ```rust
+    /// use charts::data::Data;
+    /// let mut data = Data::new();
+    /// assert!(!data.is_paused());
+    /// data.set_paused(true);
+    /// data.clear();
+    /// assert!(data.is_paused());
+    /// ```
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+    /// Sets the paused flag, see [`is_paused`].
+    ///
+    /// [`is_paused`]: #method.is_paused (is_paused method)
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused
+    }
+
+    /// True if allocation UID collisions are fatal.
+    ///
+    /// In strict mode, diffs with an allocation UID collision are rejected, see [`add_diff`].
+    ///
+    /// [`add_diff`]: #method.add_diff (add_diff method)
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+    /// Sets the strict flag, see [`is_strict`].
+    ///
+    /// In lenient mode, allocations dropped because of a UID collision do not appear in the
+    /// statistics, and their deaths are dropped too, even when they come in a later diff. The
+    /// deaths of a UID are dropped first, one per allocation dropped with this UID.
+    ///
+    /// ```rust
+    /// use charts::{fixture::*, prelude::alloc::Diff};
+    /// let mut data = data(vec![Diff::new(secs(1), vec![builder(0, 1, 1)], vec![])]);
+    /// data.set_strict(false);
+    /// let size = data.stats().unwrap().total_size;
+    ///
+    /// // Collides with the first allocation, and so does its death.
+    /// let dead = vec![(uid(0), secs(2))];
+    /// data.add_diff(Diff::new(secs(2), vec![builder(0, 100, 2)], dead)).unwrap();
+    ///
+    /// let stats = data.stats().unwrap();
+    /// assert_eq!(stats.alloc_count, 1);
+    /// assert_eq!(stats.total_size, size);
+    /// assert_eq!(data.iter_allocs().next().unwrap().tod(), None);
+    ///
+    /// // Another collision, whose death comes later.
+    /// data.add_diff(Diff::new(secs(3), vec![builder(0, 100, 3)], vec![])).unwrap();
+    /// data.add_diff(Diff::new(secs(4), vec![], vec![(uid(0), secs(4))])).unwrap();
+    /// assert_eq!(data[uid(0)].tod, None);
+    ///
+    /// // Deaths of the first allocation are not dropped anymore.
+    /// data.add_diff(Diff::new(secs(5), vec![], vec![(uid(0), secs(5))])).unwrap();
+    /// assert_eq!(data[uid(0)].tod, Some(secs(5)));
+    /// ```
+    ///
+    /// [`is_strict`]: #method.is_strict (is_strict method)
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict
+    }
+
+    /// Handles an allocation UID collision.
+    ///
+    /// Fails in strict mode, logs a warning otherwise. Either way, the new allocation (created at
+    /// `toc` with trace `trace`) is not registered.
+    ///
+    /// Returns `false`, *i.e.* the new allocation was not accepted, to ease chaining.
+    fn uid_collision(
+        &mut self,
+        uid: uid::Alloc,
+        toc: time::SinceStart,
+        trace: &[alloc::CLoc],
+    ) -> Res<bool> {
+        let existing = &self.uid_map[uid];
+        let msg = Self::uid_collision_msg(uid, (existing.toc, &existing.trace()[..]), (toc, trace));
+        if self.strict {
+            bail!(msg)
+        } else {
+            log::warn!("{}", msg);
+            log::warn!("dropping the new allocation");
+            self.add_err(format!("{}\ndropped the new allocation", msg));
+            Ok(false)
         }
     }
 
+    /// Describes a UID collision, given the time of creation and trace of both allocations.
+    fn uid_collision_msg(
+        uid: uid::Alloc,
+        (existing_toc, existing_trace): (time::SinceStart, &[alloc::CLoc]),
+        (toc, trace): (time::SinceStart, &[alloc::CLoc]),
+    ) -> String {
+        let site = |trace: &[alloc::CLoc]| {
+            trace
+                .last()
+                .map(|cloc| cloc.to_string())
+                .unwrap_or_else(|| "<empty trace>".into())
+        };
+        format!(
+            "allocation UID collision: two allocations have UID #{}\n\
+            - existing allocation: created at {}, allocation site {}\n\
+            - new allocation: created at {}, allocation site {}",
+            uid,
+            existing_toc,
+            site(existing_trace),
+            toc,
+            site(trace),
+        )
+    }
+
     /// Reserves space for the `Alloc` vector.
     pub fn reserve(&mut self, capa: usize) {
         self.uid_map.reserve(capa)
@@ -251,6 +609,13 @@ impl Data {
         self.init().is_some()
     }
 
+    /// Number of diffs registered since the last reset.
+    ///
+    /// Runs loaded from a memtrace CTF file do not go through diffs, in which case this is zero.
+    pub fn diff_count(&self) -> usize {
+        self.diff_count
+    }
+
     /// Total number of allocations.
     pub fn alloc_count(&self) -> usize {
         self.uid_map.len()
@@ -280,6 +645,20 @@ impl Data {
         }
     }
 
+    /// Time at which the profiling run ended, if known.
+    pub fn end_time(&self) -> Option<time::SinceStart> {
+        self.init.as_ref().and_then(|init| init.end_time)
+    }
+
+    /// Unique identifier of the profiling run.
+    pub fn run_id(&self) -> Res<u64> {
+        if let Some(init) = self.init.as_ref() {
+            Ok(init.run_id)
+        } else {
+            bail!("cannot access run identifier")
+        }
+    }
+
     /// Alloc accessor.
     ///
     /// Fails if the UID is unknown.
@@ -292,6 +671,476 @@ impl Data {
         self.uid_map.iter()
     }
 
+    /// Searches for allocations by UID or by trace.
+    ///
+    /// An allocation matches if its UID is `query`, or if the file of one of the locations of its
+    /// trace contains `query`. UIDs must match exactly, while file matching is case-insensitive.
+    ///
+    /// Yields at most `limit` allocation UIDs, the exact UID match first if any, then the trace
+    /// matches by increasing UID.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     alloc_data::prelude::*,
+    ///     data::Data,
+    ///     prelude::alloc::{Diff, Init},
+    /// };
+    /// let mut factory = alloc_data::mem::Factory::new(false);
+    /// let labels = factory.empty_labels();
+    /// let mut trace = |file| {
+    ///     let loc = factory.register_loc(file, 1, (0, 1));
+    ///     factory.register_trace(vec![CLoc::new(loc, 1)])
+    /// };
+    /// let traces = vec![trace("Search_Foo.ml"), trace("search_bar.ml"), trace("search_foo_2.ml")];
+    /// drop(factory);
+    /// let toc = time::SinceStart::from_secs(1);
+    /// let allocs = traces
+    ///     .into_iter()
+    ///     .enumerate()
+    ///     .map(|(uid, trace)| {
+    ///         let uid = Some(uid::Alloc::from(uid));
+    ///         Builder::new(uid, AllocKind::Minor, 8, trace, labels.clone(), toc, None)
+    ///     })
+    ///     .collect();
+    /// let mut data = Data::new();
+    /// data.reset("dump_dir", Init::default());
+    /// data.add_diff(Diff::new(toc, allocs, vec![])).unwrap();
+    ///
+    /// let uids = |uids: &[usize]| -> Vec<uid::Alloc> {
+    ///     uids.iter().map(|uid| uid::Alloc::from(*uid)).collect()
+    /// };
+    /// // UID hit first, then file hits.
+    /// assert_eq!(data.search("2", 10), uids(&[2]));
+    /// assert_eq!(data.search("1", 10), uids(&[1]));
+    /// // Case-insensitive file hits.
+    /// assert_eq!(data.search("FOO", 10), uids(&[0, 2]));
+    /// assert_eq!(data.search("search_", 10), uids(&[0, 1, 2]));
+    /// // Limit.
+    /// assert_eq!(data.search("search_", 2), uids(&[0, 1]));
+    /// assert_eq!(data.search("search_", 0), uids(&[]));
+    /// ```
+    pub fn search(&self, query: &str, limit: usize) -> Vec<uid::Alloc> {
+        let query = query.trim();
+        if query.is_empty() || limit == 0 {
+            return vec![];
+        }
+        let uid_match = query
+            .parse::<usize>()
+            .ok()
+            .map(uid::Alloc::from)
+            .filter(|uid| self.uid_map.get(*uid).is_some());
+        let query = query.to_lowercase();
+
+        // Whether each distinct file contains `query`, so that files are lowercased only once.
+        let mut file_matches = HMap::new();
+
+        let mut res: Vec<uid::Alloc> = uid_match.iter().cloned().collect();
+        for alloc in self.iter_allocs() {
+            if res.len() >= limit {
+                break;
+            }
+            if Some(alloc.uid()) == uid_match.as_ref() {
+                continue;
+            }
+            let trace_match = alloc.trace().iter().any(|cloc| {
+                let file = cloc.loc.file;
+                *file_matches
+                    .entry(file)
+                    .or_insert_with(|| file.str_do(|file| file.to_lowercase().contains(&query)))
+            });
+            if trace_match {
+                res.push(alloc.uid().clone())
+            }
+        }
+        res
+    }
+
+    /// Time at which live allocations are considered leaked.
+    ///
+    /// This is the end of the run if it is known, and the time of the last event otherwise. In the
+    /// latter case, the run might still be going on and "leaked" allocations might be freed later.
+    pub fn leak_time(&self) -> time::SinceStart {
+        self.end_time().unwrap_or(self.current_time)
+    }
+
+    /// Allocations that were never freed, *i.e.* that are live at the [leak time].
+    ///
+    /// If `filters` contains at least one filter, only the allocations matched by some filter are
+    /// considered. Note that as long as the run is going on (see [`Data::end_time`]), these
+    /// allocations are only *not freed yet*, and might still be freed later.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     filter::Filters,
+    ///     fixture::*,
+    ///     prelude::alloc::{Diff, Init},
+    /// };
+    /// let new = || Diff::new(secs(1), vec![builder(0, 8, 1), builder(1, 8, 1)], vec![]);
+    /// let (first, second) = (uid(0), uid(1));
+    /// let filters = Filters::new();
+    ///
+    /// // Running: the end of the run is unknown.
+    /// let mut data = data(vec![new()]);
+    /// assert_eq!(data.leak_time(), secs(1));
+    /// // Not freed yet...
+    /// assert_eq!(data.leaked(&filters), vec![first.clone(), second.clone()]);
+    /// // ...but freed later on.
+    /// data.add_diff(Diff::new(secs(3), vec![], vec![(first.clone(), secs(3))])).unwrap();
+    /// assert_eq!(data.leaked(&filters), vec![second.clone()]);
+    ///
+    /// // Finished: allocations live at the end of the run were never freed.
+    /// let init = Init { end_time: Some(secs(10)), ..Init::default() };
+    /// let dead = vec![(first.clone(), secs(3))];
+    /// let data = data_with(init, vec![new(), Diff::new(secs(3), vec![], dead)]);
+    /// assert_eq!(data.leak_time(), secs(10));
+    /// assert_eq!(data.leaked(&filters), vec![second]);
+    /// ```
+    ///
+    /// [leak time]: #method.leak_time (leak_time method)
+    pub fn leaked(&self, filters: &Filters) -> Vec<uid::Alloc> {
+        let time = self.leak_time();
+        self.iter_allocs()
+            .filter(|alloc| {
+                alloc.is_live_at(time)
+                    && (filters.filters().is_empty() || filters.first_match(&time, alloc).is_some())
+            })
+            .map(|alloc| alloc.uid().clone())
+            .collect()
+    }
+
+    /// Total size of the allocations live at some time.
+    ///
+    /// If `filters` contains at least one filter, only the allocations matched by some filter are
+    /// considered. See [`live_count_at`] for the complexity.
+    ///
+    /// [`live_count_at`]: #method.live_count_at (live_count_at method)
+    pub fn live_bytes_at(&self, time: time::SinceStart, filters: &Filters) -> u64 {
+        self.live_at(time, filters).1
+    }
+
+    /// Number of allocations live at some time.
+    ///
+    /// An allocation is live at its time of creation, but not at its time of death. If `filters`
+    /// contains at least one filter, only the allocations matched by some filter are considered.
+    ///
+    /// Without filters, this is `O(log n)` where `n` is the number of distinct event times thanks
+    /// to the [`LiveHistory`] maintained by the data. Otherwise, all allocations created before
+    /// `time` must go through the filters: `O(log a + c)` where `a` is the number of allocations
+    /// and `c` the number of allocations created before `time`.
+    ///
+    /// ```rust
+    /// use charts::{chart::settings, filter::Filters, fixture::*, prelude::alloc::Diff};
+    /// let data = data(vec![
+    ///     Diff::new(secs(1), vec![builder(0, 1, 1), builder(1, 2, 1)], vec![]),
+    ///     Diff::new(secs(2), vec![builder(2, 3, 2)], vec![]),
+    ///     Diff::new(secs(3), vec![], vec![(uid(0), secs(3))]),
+    /// ]);
+    ///
+    /// let live_at = |filters: &Filters, time| {
+    ///     let time = secs(time);
+    ///     (data.live_count_at(time, filters), data.live_bytes_at(time, filters))
+    /// };
+    /// let filters = Filters::new();
+    /// assert_eq!(live_at(&filters, 0), (0, 0));
+    /// assert_eq!(live_at(&filters, 1), (2, 3));
+    /// assert_eq!(live_at(&filters, 2), (3, 6));
+    /// assert_eq!(live_at(&filters, 3), (2, 5));
+    ///
+    /// // Only the allocations still live at 5 seconds.
+    /// let mut filters = Filters::new();
+    /// filters.add_leaks(&settings::Charts::new(), secs(5)).unwrap();
+    /// assert_eq!(live_at(&filters, 1), (1, 2));
+    /// assert_eq!(live_at(&filters, 2), (2, 5));
+    /// ```
+    pub fn live_count_at(&self, time: time::SinceStart, filters: &Filters) -> usize {
+        self.live_at(time, filters).0
+    }
+
+    /// Number and total size of the allocations live at some time.
+    ///
+    /// With filters, relies on the allocations being sorted by time of creation to skip the ones
+    /// created after `time`, see [`invariants::uid_order_is_toc_order`]. Diffs breaking this
+    /// invariant are rejected, see [`add_diff`], and memtrace CTF files yield their allocations in
+    /// chronological order.
+    ///
+    /// [`add_diff`]: #method.add_diff (add_diff method)
+    fn live_at(&self, time: time::SinceStart, filters: &Filters) -> (usize, u64) {
+        if filters.filters().is_empty() {
+            return self.live.at(time);
+        }
+        // Allocations are sorted by time of creation.
+        let allocs = &self.uid_map[..];
+        let created = allocs.partition_point(|alloc| alloc.toc <= time);
+        allocs[..created]
+            .iter()
+            .filter(|alloc| alloc.is_live_at(time) && filters.first_match(&time, alloc).is_some())
+            .fold((0, 0), |(count, bytes), alloc| {
+                (count + 1, bytes + alloc.real_size)
+            })
+    }
+
+    /// Size and age of the allocations live at some time, for one of the lines of the filters.
+    ///
+    /// Sizes are real sizes in bytes, ages are normalized between `0` and `1`, see
+    /// [`Alloc::age_at`]. Allocations are attributed to lines the same way the charts do: to the
+    /// first filter matching them, or to the catch-all line if there is none. The everything line
+    /// has all the live allocations. `O(log a + c)` where `a` is the number of allocations and `c`
+    /// the number of allocations created before `time`.
+    ///
+    /// ```rust
+    /// use charts::{chart::settings, filter::Filters, fixture::*, prelude::{alloc::Diff, *}};
+    /// let data = data(vec![
+    ///     Diff::new(secs(0), vec![builder(0, 1, 0)], vec![]),
+    ///     Diff::new(secs(2), vec![builder(1, 1, 2)], vec![]),
+    ///     Diff::new(secs(3), vec![], vec![(uid(1), secs(3))]),
+    /// ]);
+    ///
+    /// let filters = Filters::new();
+    /// let ages = data.live_size_ages(secs(2), uid::Line::Everything, &filters);
+    /// assert_eq!(ages, vec![(1, 1.0), (1, 0.0)]);
+    /// let ages = data.live_size_ages(secs(4), uid::Line::Everything, &filters);
+    /// assert_eq!(ages, vec![(1, 1.0)]);
+    ///
+    /// // Only the allocations still live at 5 seconds, nothing left for the catch-all line.
+    /// let mut filters = Filters::new();
+    /// filters.add_leaks(&settings::Charts::new(), secs(5)).unwrap();
+    /// let leaks = uid::Line::Filter(filters.filters()[0].uid());
+    /// assert_eq!(data.live_size_ages(secs(4), leaks, &filters), vec![(1, 1.0)]);
+    /// assert!(data.live_size_ages(secs(4), uid::Line::CatchAll, &filters).is_empty());
+    /// ```
+    pub fn live_size_ages(
+        &self,
+        time: time::SinceStart,
+        line: uid::Line,
+        filters: &Filters,
+    ) -> Vec<(u64, f64)> {
+        // Allocations are sorted by time of creation.
+        let allocs = &self.uid_map[..];
+        let created = allocs.partition_point(|alloc| alloc.toc <= time);
+        allocs[..created]
+            .iter()
+            .filter(|alloc| alloc.is_live_at(time))
+            .filter(|alloc| match line {
+                uid::Line::Everything => true,
+                uid::Line::CatchAll => filters.first_match(&time, alloc).is_none(),
+                uid::Line::Filter(uid) => filters
+                    .first_match(&time, alloc)
+                    .map(|filter| filter.uid() == uid)
+                    .unwrap_or(false),
+            })
+            .map(|alloc| (alloc.real_size, alloc.age_at(time)))
+            .collect()
+    }
+
+    /// Number and total size of the allocations live right before some time.
+    ///
+    /// Same as [`live_count_at`] without filters, but ignores the events at `time`. `O(log n)`
+    /// where `n` is the number of distinct event times.
+    ///
+    /// [`live_count_at`]: #method.live_count_at (live_count_at method)
+    pub fn live_before(&self, time: time::SinceStart) -> (usize, u64) {
+        self.live.before(time)
+    }
+
+    /// Number and total size of the allocations live at the current time.
+    ///
+    /// `O(log n)` where `n` is the number of distinct event times.
+    pub fn live_now(&self) -> (usize, u64) {
+        self.live.at(self.current_time)
+    }
+
+    /// Allocations created strictly before some time that are still live at that time.
+    ///
+    /// Yields the allocations in UID order, *i.e.* in the order [`iter_new_events`] would. `O(log a
+    /// + c)` where `a` is the number of allocations and `c` the number of allocations created
+    /// before `time`.
+    ///
+    /// [`iter_new_events`]: #method.iter_new_events (iter_new_events method)
+    pub fn iter_live_before(&self, time: time::SinceStart) -> impl Iterator<Item = &Alloc> {
+        // Allocations are sorted by time of creation.
+        let allocs = &self.uid_map[..];
+        let created = allocs.partition_point(|alloc| alloc.toc < time);
+        allocs[..created]
+            .iter()
+            .filter(move |alloc| alloc.tod.map(|tod| time <= tod).unwrap_or(true))
+    }
+
+    /// Allocations created at or after some time.
+    ///
+    /// Yields the allocations in UID order, *i.e.* by increasing time of creation. `O(log a)`
+    /// where `a` is the number of allocations, plus the cost of the iteration itself: callers
+    /// interested in a time window just stop at its upper bound.
+    ///
+    /// ```rust
+    /// use charts::{fixture::*, prelude::alloc::Diff};
+    /// let data = data(vec![
+    ///     Diff::new(secs(1), vec![builder(0, 1, 1), builder(1, 1, 1)], vec![]),
+    ///     Diff::new(secs(3), vec![builder(2, 1, 3)], vec![]),
+    /// ]);
+    ///
+    /// let since = |time| -> Vec<_> {
+    ///     data.iter_new_since(secs(time)).map(|alloc| alloc.uid).collect()
+    /// };
+    /// let uids = |uids: &[usize]| -> Vec<_> { uids.iter().cloned().map(uid).collect() };
+    /// assert_eq!(since(0), uids(&[0, 1, 2]));
+    /// assert_eq!(since(1), uids(&[0, 1, 2]));
+    /// assert_eq!(since(2), uids(&[2]));
+    /// assert!(since(4).is_empty());
+    /// ```
+    pub fn iter_new_since(&self, time: time::SinceStart) -> impl Iterator<Item = &Alloc> {
+        // Allocations are sorted by time of creation.
+        let allocs = &self.uid_map[..];
+        let created = allocs.partition_point(|alloc| alloc.toc < time);
+        allocs[created..].iter()
+    }
+
+    /// Deaths at or after some time.
+    ///
+    /// Yields the time of death and the allocation that died, by increasing time of death.
+    /// Allocations that die at the same time are yielded in UID order.
+    ///
+    /// ```rust
+    /// use charts::{fixture::*, prelude::alloc::Diff};
+    /// let new = vec![builder(0, 1, 1), builder(1, 1, 1), builder(2, 1, 1)];
+    /// let data = data(vec![
+    ///     Diff::new(secs(1), new, vec![]),
+    ///     Diff::new(secs(3), vec![], vec![(uid(1), secs(2)), (uid(0), secs(3))]),
+    /// ]);
+    ///
+    /// let since = |time| -> Vec<_> {
+    ///     data.iter_dead_since(secs(time)).map(|(tod, alloc)| (tod, alloc.uid)).collect()
+    /// };
+    /// assert_eq!(since(0), vec![(secs(2), uid(1)), (secs(3), uid(0))]);
+    /// assert_eq!(since(3), vec![(secs(3), uid(0))]);
+    /// assert!(since(4).is_empty());
+    /// ```
+    pub fn iter_dead_since(
+        &self,
+        time: time::SinceStart,
+    ) -> impl Iterator<Item = (time::SinceStart, &Alloc)> {
+        self.tod_map
+            .range(time..)
+            .flat_map(move |(tod, uids)| uids.iter().map(move |uid| (*tod, &self.uid_map[*uid])))
+    }
+
+    /// Skips the events strictly before some time.
+    ///
+    /// Returns `None` if there are no such events. Otherwise, returns the `since` argument for
+    /// [`iter_new_events`] to only iterate over the events at or after `time`, and the time of the
+    /// last event skipped.
+    ///
+    /// ```rust
+    /// use charts::{fixture::*, prelude::alloc::Diff};
+    /// let data = data(vec![
+    ///     Diff::new(secs(1), vec![builder(0, 1, 1), builder(1, 1, 1)], vec![]),
+    ///     Diff::new(secs(3), vec![builder(2, 1, 3)], vec![(uid(0), secs(3))]),
+    ///     Diff::new(secs(4), vec![], vec![(uid(1), secs(4))]),
+    /// ]);
+    ///
+    /// assert!(data.events_before(secs(1)).is_none());
+    /// let (since, last) = data.events_before(secs(4)).unwrap();
+    /// assert_eq!(last, secs(3));
+    /// let mut events = vec![];
+    /// data.iter_new_events(Some(since), |event| {
+    ///     events.push(event.either(|alloc| (alloc.uid, true), |(_, alloc)| (alloc.uid, false)));
+    ///     Ok(true)
+    /// })
+    /// .unwrap();
+    /// assert_eq!(events, vec![(uid(1), false)]);
+    ///
+    /// let live: Vec<_> = data.iter_live_before(secs(4)).map(|alloc| alloc.uid).collect();
+    /// assert_eq!(live, vec![uid(1), uid(2)]);
+    /// assert_eq!(data.live_before(secs(4)), (2, 2));
+    /// ```
+    ///
+    /// [`iter_new_events`]: #method.iter_new_events (iter_new_events method)
+    pub fn events_before(
+        &self,
+        time: time::SinceStart,
+    ) -> Option<((uid::Alloc, time::SinceStart), time::SinceStart)> {
+        let allocs = &self.uid_map[..];
+        let created = allocs.partition_point(|alloc| alloc.toc < time);
+        // Allocations die after they are created, no allocation means no event at all.
+        let last_alloc = &allocs[created.checked_sub(1)?];
+        let last_dead = self.tod_map.range(..time).next_back().map(|(tod, _)| *tod);
+        let last = last_dead
+            .map(|tod| tod.max(last_alloc.toc))
+            .unwrap_or(last_alloc.toc);
+        // `iter_new_events` only considers deaths strictly after the time in `since`.
+        let since = time - time::SinceStart::from_nano_timestamp(0, 1);
+        Some(((last_alloc.uid, since), last))
+    }
+
+    /// Largest allocation sites among the allocations live at the current time.
+    ///
+    /// Live allocations are grouped by allocation site, *i.e.* the last frame of their trace;
+    /// allocations with an empty trace are ignored. If `filters` contains at least one filter, only
+    /// the allocations matched by some filter are considered.
+    ///
+    /// Yields at most `n` triples `(site, bytes, count)`, sorted by decreasing size, then
+    /// decreasing allocation count, then increasing location. Locations are ordered by file first,
+    /// and files by order of registration since they are interned.
+    ///
+    /// ```rust
+    /// use charts::{filter::Filters, fixture::*, prelude::alloc::Diff};
+    /// let new = vec![
+    ///     traced_builder(0, 8, 1, &["main.ml", "a.ml"]),
+    ///     traced_builder(1, 16, 1, &["d.ml"]),
+    ///     traced_builder(2, 8, 1, &["a.ml"]),
+    ///     traced_builder(3, 16, 1, &["b.ml"]),
+    ///     traced_builder(4, 32, 1, &["c.ml"]),
+    ///     traced_builder(5, 64, 1, &["dead.ml"]),
+    ///     // Ignored, no allocation site.
+    ///     builder(6, 128, 1),
+    /// ];
+    /// let data = data(vec![Diff::new(secs(1), new, vec![(uid(5), secs(1))])]);
+    ///
+    /// let top = |n| {
+    ///     data.top_alloc_sites(&Filters::new(), n)
+    ///         .into_iter()
+    ///         .map(|(loc, bytes, count)| (loc.file.to_string(), bytes, count))
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// let site = |file: &str, bytes, count| (file.to_string(), bytes, count);
+    /// // Same size: more allocations first, then by location, `d.ml` was registered first.
+    /// assert_eq!(
+    ///     top(10),
+    ///     vec![site("c.ml", 32, 1), site("a.ml", 16, 2), site("d.ml", 16, 1), site("b.ml", 16, 1)],
+    /// );
+    /// assert_eq!(top(2), vec![site("c.ml", 32, 1), site("a.ml", 16, 2)]);
+    /// ```
+    pub fn top_alloc_sites(&self, filters: &Filters, n: usize) -> Vec<(alloc::Loc, u64, usize)> {
+        let time = self.current_time;
+        let mut sites: HMap<alloc::Loc, (u64, usize)> = HMap::new();
+        for alloc in self.iter_allocs() {
+            if !alloc.is_live_at(time)
+                || (!filters.filters().is_empty() && filters.first_match(&time, alloc).is_none())
+            {
+                continue;
+            }
+            alloc.alloc_site_do(|site| {
+                if let Some(site) = site {
+                    let (bytes, count) = sites.entry((*site.loc).clone()).or_insert((0, 0));
+                    *bytes += alloc.real_size;
+                    *count += 1;
+                }
+            })
+        }
+
+        let mut sites: Vec<_> = sites
+            .into_iter()
+            .map(|(loc, (bytes, count))| (loc, bytes, count))
+            .collect();
+        sites.sort_by(|(loc_1, bytes_1, count_1), (loc_2, bytes_2, count_2)| {
+            bytes_2
+                .cmp(bytes_1)
+                .then_with(|| count_2.cmp(count_1))
+                .then_with(|| loc_1.cmp(loc_2))
+        });
+        sites.truncate(n);
+        sites
+    }
+
     /// True if there are any new events since some timestamp.
     pub fn has_new_stuff_since(&self, time: Option<(uid::Alloc, time::SinceStart)>) -> bool {
         if let Some((uid, tod)) = time {
@@ -426,15 +1275,77 @@ impl Data {
         self.init = Some(init);
         self.uid_map.clear();
         self.tod_map.clear();
+        self.dropped.clear();
+        self.live.clear();
         self.current_time = time::SinceStart::zero();
+        self.diff_count = 0;
+        self.errors.clear();
     }
 
     /// Builds a new allocation.
+    ///
+    /// Allocations skipped by the CTF parser, see [`ctf::MaxAllocSize::Skip`], do not shift the
+    /// allocations and collections that come after them.
+    ///
+    /// ```rust
+    /// use charts::data::Data;
+    /// use std::cell::RefCell;
+    ///
+    /// let bytes = std::fs::read("../ctf/rsc/lists_and_sets.ctf").unwrap();
+    /// // Size, time of creation and time of death of all the allocations in the data.
+    /// let load = |max_alloc_size| {
+    ///     let data = RefCell::new(Data::new());
+    ///     let mut factory = Box::new(charts::alloc_data::mem::Factory::new(false));
+    ///     ctf::parse(
+    ///         &bytes,
+    ///         &mut factory,
+    ///         ctf::ParseOptions {
+    ///             max_alloc_size,
+    ///             ..ctf::ParseOptions::default()
+    ///         },
+    ///         |_, _| (),
+    ///         || true,
+    ///         |_, init| data.borrow_mut().reset("dump_dir", init),
+    ///         |_, builder| data.borrow_mut().build_new(builder).unwrap(),
+    ///         |_, time, uid| data.borrow_mut().add_dead(time, uid).unwrap(),
+    ///         |_, _| (),
+    ///     )
+    ///     .unwrap();
+    ///     drop(factory);
+    ///     let data = data.into_inner();
+    ///     data.iter_allocs()
+    ///         .map(|alloc| (alloc.size, alloc.toc, alloc.tod))
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// let all = load(None);
+    /// let bound = 4;
+    /// let small = load(Some(ctf::MaxAllocSize::Skip(bound)));
+    /// assert!(small.len() < all.len());
+    /// assert!(small.iter().any(|(_, _, tod)| tod.is_some()));
+    /// // Sizes are in bytes, the bound is in (64-bit) machine words.
+    /// let expected: Vec<_> = all
+    ///     .into_iter()
+    ///     .filter(|(size, _, _)| *size as u64 <= bound * 8)
+    ///     .collect();
+    /// assert_eq!(small, expected);
+    /// ```
     pub fn build_new(&mut self, alloc: alloc::Builder) -> Res<()> {
+        self.try_build_new(alloc).map(|_| ())
+    }
+
+    /// Builds a new allocation, returns `false` if it was dropped because of a UID collision.
+    fn try_build_new(&mut self, alloc: alloc::Builder) -> Res<bool> {
         if self.current_time != alloc.toc {
             self.current_time = alloc.toc.clone()
         }
         let uid = self.uid_map.next_index();
+        match alloc.uid_hint {
+            Some(hint) if hint < uid => {
+                return self.uid_collision(hint, alloc.toc, &alloc.trace());
+            }
+            _ => (),
+        }
         let alloc = alloc.build(
             &self
                 .init
@@ -444,18 +1355,26 @@ impl Data {
             uid,
         )?;
 
-        self.add_new(alloc)
+        self.try_add_new(alloc)
     }
 
     /// Registers a new allocation.
     pub fn add_new(&mut self, alloc: Alloc) -> Res<()> {
-        self.stats
-            .as_mut()
-            .ok_or_else(|| "trying to add allocation before initialization")?
-            .total_size += alloc.real_size as u64;
+        self.try_add_new(alloc).map(|_| ())
+    }
+
+    /// Registers a new allocation, returns `false` if it was dropped because of a UID collision.
+    ///
+    /// Statistics only account for the allocation if it is accepted.
+    fn try_add_new(&mut self, alloc: Alloc) -> Res<bool> {
+        if self.stats.is_none() {
+            bail!("trying to add allocation before initialization")
+        }
         self.current_time = alloc.toc;
         let uid = self.uid_map.next_index();
-        if uid != alloc.uid {
+        if alloc.uid < uid {
+            return self.uid_collision(alloc.uid, alloc.toc, &alloc.trace());
+        } else if uid != alloc.uid {
             bail!(
                 "unexpected allocation index {}, expected {}",
                 alloc.uid,
@@ -463,19 +1382,29 @@ impl Data {
             )
         }
 
-        if let Some(tod) = alloc.tod.clone() {
-            self.add_dead(tod, uid.clone())?
+        if let Some(stats) = self.stats.as_mut() {
+            stats.total_size += alloc.real_size
         }
-
+        self.live.add_new(alloc.toc, alloc.real_size);
+        let tod = alloc.tod;
         let uid_check = self.uid_map.push(alloc);
         debug_assert!(uid == uid_check);
 
-        Ok(())
+        if let Some(tod) = tod {
+            self.add_dead(tod, uid)?
+        }
+
+        Ok(true)
     }
 
     /// Registers an allocation's death.
     pub fn add_dead(&mut self, timestamp: time::SinceStart, uid: uid::Alloc) -> Res<()> {
-        self.uid_map[uid].set_tod(timestamp)?;
+        let alloc = self
+            .uid_map
+            .get_mut(uid)
+            .ok_or_else(|| format!("trying to kill unknown allocation #{}", uid))?;
+        alloc.set_tod(timestamp)?;
+        self.live.add_dead(timestamp, alloc.real_size);
         self.current_time = timestamp;
         let is_new = self.tod_map_get_mut(timestamp).insert(uid.clone());
         if !is_new {
@@ -488,11 +1417,149 @@ impl Data {
     }
 
     /// Registers a diff.
+    ///
+    /// The diff is checked before anything changes: diffs that cannot be applied are rejected as a
+    /// whole with a recoverable error, see [`err::Severity`]. This is the case of diffs that are out
+    /// of order, whose allocations are not ordered by time of creation, that kill allocations that
+    /// do not exist or are already dead, and of UID collisions in strict mode, see [`is_strict`].
+    ///
+    /// ```rust
+    /// use charts::{fixture::*, prelude::alloc::Diff};
+    /// let mut data = data(vec![Diff::new(secs(1), vec![builder(0, 8, 1)], vec![])]);
+    /// let size = data.stats().unwrap().total_size;
+    ///
+    /// // A new allocation, and a collision with the first one.
+    /// let new = vec![builder(1, 16, 2), builder(0, 32, 2)];
+    /// let err = data.add_diff(Diff::new(secs(2), new, vec![])).unwrap_err();
+    /// assert!(err.is_recoverable());
+    /// assert!(err.to_string().contains("allocation UID collision"));
+    ///
+    /// // A new allocation, and a double death.
+    /// let dead = vec![(uid(0), secs(3)), (uid(0), secs(3))];
+    /// let err = data.add_diff(Diff::new(secs(3), vec![builder(1, 16, 3)], dead)).unwrap_err();
+    /// assert!(err.is_recoverable());
+    ///
+    /// // Nothing changed.
+    /// assert_eq!(data.iter_allocs().count(), 1);
+    /// assert_eq!(data.diff_count(), 1);
+    /// assert_eq!(data.stats().unwrap().total_size, size);
+    /// assert_eq!(data[uid(0)].tod, None);
+    /// ```
+    ///
+    /// [`is_strict`]: #method.is_strict (is_strict method)
     pub fn add_diff(&mut self, diff: alloc::Diff) -> Res<()> {
+        self.raw_add_diff(diff)?;
+        self.check_invariants().chain_err(|| "after adding diff")?;
+        Ok(())
+    }
+
+    /// Registers some diffs, in order.
+    ///
+    /// Same as calling [`add_diff`] on each diff, but only checks the data invariants once, after
+    /// all the diffs have been registered.
+    ///
+    /// Fails on diffs that are out of order, *i.e.* whose time precedes the current time. Such
+    /// errors are recoverable, see [`err::Severity`], as the diff is rejected before it changes
+    /// anything.
+    ///
+    /// ```rust
+    /// use charts::{fixture::{self, secs}, prelude::alloc::Diff};
+    /// let diff = |time| Diff::new(secs(time), vec![], vec![]);
+    /// let mut data = fixture::data(vec![]);
+    /// data.add_diffs(vec![diff(1), diff(2), diff(2)]).unwrap();
+    /// let err = data.add_diffs(vec![diff(3), diff(1)]).unwrap_err();
+    /// assert!(err.to_string().contains("out-of-order diff"));
+    /// assert!(err.is_recoverable());
+    /// ```
+    ///
+    /// [`add_diff`]: #method.add_diff (add_diff method)
+    pub fn add_diffs(&mut self, diffs: Vec<alloc::Diff>) -> Res<()> {
+        let count = diffs.len();
+        for diff in diffs {
+            self.raw_add_diff(diff)?
+        }
+        self.check_invariants()
+            .chain_err(|| format!("after adding {} diffs", count))?;
+        Ok(())
+    }
+
+    /// Registers some tagged diffs, in order, skipping the ones rejected with a recoverable error.
+    ///
+    /// Returns the tags of the rejected diffs, with the corresponding error. Only the errors raised
+    /// before a diff changes anything are recoverable, see [`err::Severity`], other errors are
+    /// returned right away. Checks the data invariants once, like [`add_diffs`].
+    ///
+    /// ```rust
+    /// use charts::{fixture::{self, secs}, prelude::alloc::Diff};
+    /// let diff = |time| Diff::new(secs(time), vec![], vec![]);
+    /// let mut data = fixture::data(vec![]);
+    /// let rejected = data
+    ///     .add_diffs_skipping(vec![("a", diff(2)), ("b", diff(1)), ("c", diff(3))])
+    ///     .unwrap();
+    /// assert_eq!(rejected.len(), 1);
+    /// assert_eq!(rejected[0].0, "b");
+    /// assert!(rejected[0].1.to_string().contains("out-of-order diff"));
+    /// assert_eq!(data.diff_count(), 2);
+    /// ```
+    ///
+    /// Diffs killing allocations that do not exist or are already dead are rejected too.
+    ///
+    /// ```rust
+    /// use charts::{fixture::*, prelude::alloc::Diff};
+    /// let mut data = data(vec![Diff::new(secs(1), vec![builder(0, 8, 1)], vec![])]);
+    /// let rejected = data
+    ///     .add_diffs_skipping(vec![
+    ///         ("unknown", Diff::new(secs(2), vec![], vec![(uid(7), secs(2))])),
+    ///         ("ok", Diff::new(secs(3), vec![], vec![(uid(0), secs(3))])),
+    ///         ("twice", Diff::new(secs(4), vec![], vec![(uid(0), secs(4))])),
+    ///     ])
+    ///     .unwrap();
+    /// let tags: Vec<_> = rejected.iter().map(|(tag, _)| *tag).collect();
+    /// assert_eq!(tags, vec!["unknown", "twice"]);
+    /// assert!(rejected.iter().all(|(_, err)| err.is_recoverable()));
+    /// assert!(rejected[0].1.to_string().contains("unknown allocation #7"));
+    /// assert_eq!(data.diff_count(), 2);
+    /// assert_eq!(data[uid(0)].tod, Some(secs(3)));
+    /// ```
+    ///
+    /// [`add_diffs`]: #method.add_diffs (add_diffs method)
+    pub fn add_diffs_skipping<T>(
+        &mut self,
+        diffs: Vec<(T, alloc::Diff)>,
+    ) -> Res<Vec<(T, err::Error)>> {
+        let count = diffs.len();
+        let mut rejected = vec![];
+        for (tag, diff) in diffs {
+            match self.raw_add_diff(diff) {
+                Ok(()) => (),
+                Err(e) if e.is_recoverable() => rejected.push((tag, e)),
+                Err(e) => return Err(e),
+            }
+        }
+        self.check_invariants()
+            .chain_err(|| format!("after adding {} diffs", count))?;
+        Ok(rejected)
+    }
+
+    /// Counts some skipped diff files in the statistics, see [`AllocStats::skipped_files`].
+    pub fn add_skipped_files(&mut self, count: usize) {
+        if let Some(stats) = self.stats.as_mut() {
+            stats.skipped_files += count
+        }
+    }
+
+    /// Registers a diff, does not check the data invariants.
+    fn raw_add_diff(&mut self, diff: alloc::Diff) -> Res<()> {
+        if diff.time < self.current_time {
+            bail!(err::ErrorKind::Diff(format!(
+                "out-of-order diff: diff time {} precedes current time {}",
+                diff.time, self.current_time
+            )))
+        }
+        self.check_diff(&diff)?;
         self.current_time = diff.time;
 
         if let Some(stats) = self.stats.as_mut() {
-            stats.alloc_count += diff.new.len();
             stats.duration = diff.time;
         } else {
             if self.init.is_some() {
@@ -502,16 +1569,127 @@ impl Data {
             }
         }
 
+        let mut accepted = 0;
         for alloc in diff.new {
-            self.build_new(alloc)?
+            let uid_hint = alloc.uid_hint;
+            if self.try_build_new(alloc)? {
+                accepted += 1
+            } else if let Some(uid) = uid_hint {
+                *self.dropped.entry(uid).or_insert(0) += 1;
+            }
+        }
+        if let Some(stats) = self.stats.as_mut() {
+            stats.alloc_count += accepted
         }
         for (uid, tod) in diff.dead {
+            if Self::take_dropped(&mut self.dropped, uid) {
+                log::warn!("dropping the death of dropped allocation #{}", uid);
+                continue;
+            }
             self.add_dead(tod, uid)?
         }
-        self.check_invariants().chain_err(|| "after adding diff")?;
+        self.diff_count += 1;
         Ok(())
     }
 
+    /// Checks that a diff can be registered, without changing anything.
+    ///
+    /// Fails with a recoverable [`err::ErrorKind::Diff`] error if it cannot, see [`add_diff`].
+    ///
+    /// [`add_diff`]: #method.add_diff (add_diff method)
+    fn check_diff(&self, diff: &alloc::Diff) -> Res<()> {
+        let first_new = self.uid_map.next_index();
+        // UID of the next new allocation of the diff.
+        let mut next = first_new;
+        // New allocations of the diff that are not dropped.
+        let mut accepted: Vec<&alloc::Builder> = Vec::with_capacity(diff.new.len());
+        // Allocations dropped because of a UID collision, including the ones of the diff.
+        let mut dropped = self.dropped.clone();
+        // UIDs of the allocations of the diff that are dead.
+        let mut dead = BTSet::new();
+        // Time of creation of the most recent allocation.
+        let mut last_toc = self.uid_map.last().map(|(_, alloc)| alloc.toc);
+
+        for alloc in &diff.new {
+            match alloc.uid_hint {
+                Some(hint) if hint < next => {
+                    if self.strict {
+                        let existing = if hint < first_new {
+                            let existing = &self.uid_map[hint];
+                            (existing.toc, existing.trace())
+                        } else {
+                            let existing = accepted[hint.get() - first_new.get()];
+                            (existing.toc, existing.trace())
+                        };
+                        bail!(err::ErrorKind::Diff(Self::uid_collision_msg(
+                            hint,
+                            (existing.0, &existing.1[..]),
+                            (alloc.toc, &alloc.trace()[..]),
+                        )))
+                    }
+                    *dropped.entry(hint).or_insert(0) += 1;
+                    continue;
+                }
+                Some(hint) if hint > next => bail!(err::ErrorKind::Diff(format!(
+                    "unexpected allocation UID #{}, expected #{}",
+                    hint, next
+                ))),
+                _ => (),
+            }
+            if let Some(last_toc) = last_toc {
+                if alloc.toc < last_toc {
+                    bail!(err::ErrorKind::Diff(format!(
+                        "allocation #{} is created at {}, before the previous allocation ({})",
+                        next, alloc.toc, last_toc
+                    )))
+                }
+            }
+            last_toc = Some(alloc.toc);
+            if alloc.tod.is_some() {
+                dead.insert(next);
+            }
+            accepted.push(alloc);
+            next.inc()
+        }
+
+        for (uid, tod) in &diff.dead {
+            if Self::take_dropped(&mut dropped, *uid) {
+                continue;
+            } else if *uid >= next {
+                bail!(err::ErrorKind::Diff(format!(
+                    "death of unknown allocation #{} at {}",
+                    uid, tod
+                )))
+            }
+            let already_dead = self
+                .uid_map
+                .get(*uid)
+                .map(|alloc| alloc.tod.is_some())
+                .unwrap_or(false);
+            if already_dead || !dead.insert(*uid) {
+                bail!(err::ErrorKind::Diff(format!(
+                    "death of allocation #{} at {}, which is already dead",
+                    uid, tod
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes the death of an allocation dropped because of a UID collision, if any.
+    fn take_dropped(dropped: &mut BTMap<uid::Alloc, usize>, uid: uid::Alloc) -> bool {
+        match dropped.get_mut(&uid) {
+            None => false,
+            Some(count) => {
+                *count -= 1;
+                if *count == 0 {
+                    dropped.remove(&uid);
+                }
+                true
+            }
+        }
+    }
+
     /// Checks that all data invariants hold.
     ///
     /// - only active in `debug`, does nothing in `release`.
@@ -538,6 +1716,95 @@ pub fn add_diff(diff: alloc::Diff) -> Res<()> {
     Ok(())
 }
 
+/// Sets the strict flag of the global data, see [`Data::is_strict`].
+///
+/// [`Data::is_strict`]: crate::data::Data::is_strict (is_strict method of Data)
+pub fn set_strict(strict: bool) -> Res<()> {
+    get_mut()
+        .chain_err(|| "while setting the strict flag")?
+        .set_strict(strict);
+    Ok(())
+}
+
+/// Registers a non-fatal error of the run in the global data, see [`Data::add_err`].
+///
+/// [`Data::add_err`]: crate::data::Data::add_err (add_err method of Data)
+pub fn add_err(err: impl Into<String>) -> Res<()> {
+    get_mut()
+        .chain_err(|| "while registering an error")?
+        .add_err(err);
+    Ok(())
+}
+
+/// Sets the capacity of the error log of the global data, see [`Data::set_error_capacity`].
+///
+/// [`Data::set_error_capacity`]: crate::data::Data::set_error_capacity (set_error_capacity method
+/// of Data)
+pub fn set_error_capacity(capacity: usize) -> Res<()> {
+    get_mut()
+        .chain_err(|| "while setting the capacity of the error log")?
+        .set_error_capacity(capacity);
+    Ok(())
+}
+
+/// Pauses the ingestion of new diffs, see [`Data::is_paused`].
+///
+/// [`Data::is_paused`]: crate::data::Data::is_paused (is_paused method of Data)
+pub fn pause_ingestion() -> Res<()> {
+    get_mut()
+        .chain_err(|| "while pausing ingestion")?
+        .set_paused(true);
+    Ok(())
+}
+
+/// Resumes the ingestion of new diffs, see [`Data::is_paused`].
+///
+/// The diffs found while paused are registered the next time the watcher polls the dump directory.
+///
+/// [`Data::is_paused`]: crate::data::Data::is_paused (is_paused method of Data)
+pub fn resume_ingestion() -> Res<()> {
+    get_mut()
+        .chain_err(|| "while resuming ingestion")?
+        .set_paused(false);
+    Ok(())
+}
+
+/// True if the ingestion of new diffs is paused, see [`Data::is_paused`].
+///
+/// [`Data::is_paused`]: crate::data::Data::is_paused (is_paused method of Data)
+pub fn is_ingestion_paused() -> Res<bool> {
+    get().map(|data| data.is_paused())
+}
+
+/// Registers some diffs, in order.
+///
+/// Takes the write lock on the data only once, see [`Data::add_diffs`].
+///
+/// [`Data::add_diffs`]: crate::data::Data::add_diffs (add_diffs method of Data)
+pub fn add_diffs(diffs: Vec<alloc::Diff>) -> Res<()> {
+    let mut data = get_mut().chain_err(|| "while registering some diffs")?;
+    data.add_diffs(diffs)?;
+    Ok(())
+}
+
+/// Registers some tagged diffs in the global data, see [`Data::add_diffs_skipping`].
+///
+/// [`Data::add_diffs_skipping`]: crate::data::Data::add_diffs_skipping (add_diffs_skipping method
+/// of Data)
+pub fn add_diffs_skipping<T>(diffs: Vec<(T, alloc::Diff)>) -> Res<Vec<(T, err::Error)>> {
+    let mut data = get_mut().chain_err(|| "while registering some diffs")?;
+    data.add_diffs_skipping(diffs)
+}
+
+/// Counts some skipped diff files in the statistics of the global data, see
+/// [`AllocStats::skipped_files`].
+pub fn add_skipped_files(count: usize) -> Res<()> {
+    get_mut()
+        .chain_err(|| "while counting skipped diff files")?
+        .add_skipped_files(count);
+    Ok(())
+}
+
 /// Data invariants.
 pub mod invariants {
     use super::*;
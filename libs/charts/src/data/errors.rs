@@ -0,0 +1,138 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Bounded log of the recent errors of a run.
+
+/// Bounded log of the most recent errors, drops the oldest errors when full.
+///
+/// Keeps track of how many errors were dropped, so that a long-running (and possibly flaky) live
+/// capture does not accumulate errors forever.
+///
+/// ```rust
+/// use charts::data::ErrorLog;
+/// let mut log = ErrorLog::new(2);
+/// log.push("error 1");
+/// log.push("error 2");
+/// assert_eq!(log.iter().collect::<Vec<_>>(), vec!["error 1", "error 2"]);
+/// assert_eq!(log.dropped(), 0);
+///
+/// // Over capacity, the oldest error is dropped.
+/// log.push("error 3");
+/// assert_eq!(log.iter().collect::<Vec<_>>(), vec!["error 2", "error 3"]);
+/// assert_eq!(log.dropped(), 1);
+/// log.push("error 4");
+/// log.push("error 5");
+/// assert_eq!(log.iter().collect::<Vec<_>>(), vec!["error 4", "error 5"]);
+/// assert_eq!(log.dropped(), 3);
+/// assert_eq!(log.total(), 5);
+///
+/// // Shrinking also drops the oldest errors.
+/// log.set_capacity(1);
+/// assert_eq!(log.iter().collect::<Vec<_>>(), vec!["error 5"]);
+/// assert_eq!(log.dropped(), 4);
+///
+/// // A zero capacity drops everything.
+/// log.set_capacity(0);
+/// log.push("error 6");
+/// assert!(log.is_empty());
+/// assert_eq!(log.dropped(), 6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ErrorLog {
+    /// Recent errors, oldest first.
+    errors: std::collections::VecDeque<String>,
+    /// Maximum number of errors stored.
+    capacity: usize,
+    /// Number of errors dropped so far.
+    dropped: usize,
+}
+
+impl Default for ErrorLog {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl ErrorLog {
+    /// Default capacity.
+    pub const DEFAULT_CAPACITY: usize = 1000;
+
+    /// Constructor.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            errors: std::collections::VecDeque::with_capacity(capacity.min(Self::DEFAULT_CAPACITY)),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    /// Maximum number of errors stored.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of errors stored, dropping the oldest errors if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.shrink()
+    }
+
+    /// Number of errors stored.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+    /// True if no errors are stored.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Number of errors dropped so far.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+    /// Number of errors pushed so far, including the dropped ones.
+    pub fn total(&self) -> usize {
+        self.dropped + self.errors.len()
+    }
+
+    /// Iterator over the errors stored, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.errors.iter().map(String::as_str)
+    }
+
+    /// Pushes an error, dropping the oldest one if full.
+    pub fn push(&mut self, err: impl Into<String>) {
+        self.errors.push_back(err.into());
+        self.shrink()
+    }
+
+    /// Forgets all the errors, and resets the dropped counter. Does not change the capacity.
+    pub fn clear(&mut self) {
+        self.errors.clear();
+        self.dropped = 0;
+    }
+
+    /// Drops the oldest errors until the log fits its capacity.
+    fn shrink(&mut self) {
+        while self.errors.len() > self.capacity {
+            self.errors.pop_front();
+            self.dropped += 1;
+        }
+    }
+}
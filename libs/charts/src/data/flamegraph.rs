@@ -0,0 +1,204 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Flamegraph aggregation of live allocations.
+//!
+//! A [`Flamegraph`] folds the traces of some allocations into a tree of [`alloc::Loc`]s, summing
+//! the (real) sizes of the allocations. It serializes to the *folded stacks* format used by
+//! Brendan Gregg's `flamegraph.pl`, *i.e.* one line per stack of the form
+//!
+//! ```text
+//! <filter>;<frame>;<frame>;...;<frame> <size>
+//! ```
+//!
+//! where frames go from the root of the stack to the allocation site.
+
+prelude! {}
+
+use super::Data;
+
+/// A node in a flamegraph.
+#[derive(Debug, Clone, Default)]
+pub struct Node {
+    /// Size allocated by this frame directly, *i.e.* this frame is the allocation site.
+    self_size: u64,
+    /// Sub-frames.
+    children: BTMap<alloc::Loc, Node>,
+}
+
+impl Node {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Size allocated by this frame directly.
+    pub fn self_size(&self) -> u64 {
+        self.self_size
+    }
+    /// Total size allocated by this frame and its sub-frames.
+    pub fn total_size(&self) -> u64 {
+        self.children
+            .values()
+            .fold(self.self_size, |acc, child| acc + child.total_size())
+    }
+    /// Sub-frames accessor.
+    pub fn children(&self) -> &BTMap<alloc::Loc, Node> {
+        &self.children
+    }
+
+    /// Adds a size for a trace, from the root of the trace to the allocation site.
+    fn add(&mut self, trace: impl Iterator<Item = Arc<alloc::Loc>>, size: u64) {
+        let mut current = self;
+        for loc in trace {
+            current = current
+                .children
+                .entry((*loc).clone())
+                .or_insert_with(Node::new);
+        }
+        current.self_size += size
+    }
+
+    /// Writes the folded stacks for this node and its sub-frames.
+    fn write_folded(&self, stack: &mut String, target: &mut String) {
+        use std::fmt::Write;
+        if self.self_size > 0 {
+            // Writing to a string cannot fail.
+            let _ = writeln!(target, "{} {}", stack, self.self_size);
+        }
+        for (loc, child) in &self.children {
+            let len = stack.len();
+            stack.push(';');
+            stack.push_str(&frame_name(loc));
+            child.write_folded(stack, target);
+            stack.truncate(len)
+        }
+    }
+}
+
+/// Name of a frame in the folded stacks format.
+///
+/// Semicolons are frame separators, so they are replaced by colons.
+fn frame_name(loc: &alloc::Loc) -> String {
+    format!(
+        "{}:{}:{}-{}",
+        loc.file, loc.line, loc.span.lbound, loc.span.ubound
+    )
+    .replace(';', ":")
+}
+
+/// A flamegraph, with one root per filter.
+#[derive(Debug, Clone, Default)]
+pub struct Flamegraph {
+    /// Maps filters to their name and their root node.
+    roots: BTMap<uid::Line, (String, Node)>,
+}
+
+impl Flamegraph {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Roots accessor.
+    pub fn roots(&self) -> &BTMap<uid::Line, (String, Node)> {
+        &self.roots
+    }
+
+    /// Adds an allocation, given the filter it belongs to.
+    ///
+    /// Traces are stored from the root of the stack to the allocation site, regardless of the
+    /// `callstack_is_rev` flag of the dump: the allocation factory reverses reversed callstacks
+    /// when registering them (see `alloc::mem::Factory::register_trace`).
+    pub fn add(&mut self, filter: uid::Line, filter_name: &str, alloc: &Alloc) {
+        let frames = alloc::mem::trace::Frames::new(alloc.trace());
+        self.roots
+            .entry(filter)
+            .or_insert_with(|| (filter_name.replace(';', ":"), Node::new()))
+            .1
+            .add(frames, alloc.real_size)
+    }
+
+    /// Serializes the flamegraph in the folded stacks format.
+    pub fn to_folded(&self) -> String {
+        let mut target = String::new();
+        let mut stack = String::new();
+        for (name, root) in self.roots.values() {
+            stack.clear();
+            stack.push_str(name);
+            root.write_folded(&mut stack, &mut target)
+        }
+        target
+    }
+}
+
+impl fmt::Display for Flamegraph {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.to_folded().fmt(fmt)
+    }
+}
+
+impl Data {
+    /// Folds the allocations live at some time into a flamegraph.
+    ///
+    /// If `at_time` is `None`, uses the current time of the data.
+    ///
+    /// ```rust
+    /// use charts::{filter::Filters, fixture::*, prelude::alloc::Diff};
+    /// let main_a = &["main.ml", "a.ml"];
+    /// let data = data(vec![
+    ///     Diff::new(
+    ///         secs(1),
+    ///         vec![
+    ///             traced_builder(0, 8, 1, main_a),
+    ///             traced_builder(1, 16, 1, &["main.ml", "b.ml"]),
+    ///             traced_builder(2, 32, 1, main_a),
+    ///         ],
+    ///         vec![],
+    ///     ),
+    ///     Diff::new(secs(2), vec![], vec![(uid(2), secs(2))]),
+    /// ]);
+    /// let folded = |time| data.flamegraph(&Filters::new(), time).to_folded();
+    /// assert_eq!(
+    ///     folded(None),
+    ///     "catch all;main.ml:1:0-1;a.ml:1:0-1 8\n\
+    ///     catch all;main.ml:1:0-1;b.ml:1:0-1 16\n",
+    /// );
+    /// // Before the death of the last allocation.
+    /// assert_eq!(
+    ///     folded(Some(secs(1))),
+    ///     "catch all;main.ml:1:0-1;a.ml:1:0-1 40\n\
+    ///     catch all;main.ml:1:0-1;b.ml:1:0-1 16\n",
+    /// );
+    /// ```
+    pub fn flamegraph(&self, filters: &Filters, at_time: Option<time::SinceStart>) -> Flamegraph {
+        let time = at_time.unwrap_or(self.current_time);
+        let mut graph = Flamegraph::new();
+        for alloc in self.iter_allocs() {
+            if !alloc.is_live_at(time) {
+                continue;
+            }
+            match filters.first_match(&time, alloc) {
+                Some(filter) => graph.add(filter.uid().into(), filter.name(), alloc),
+                None => graph.add(uid::Line::CatchAll, filters.catch_all().name(), alloc),
+            }
+        }
+        graph
+    }
+}
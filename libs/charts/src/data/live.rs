@@ -0,0 +1,123 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! History of the live set of the allocations.
+
+prelude! {}
+
+/// Size of the live set over time, as a step function.
+///
+/// Stores, for each time at which some allocations were created or died, the number and total
+/// (real) size of the allocations live right after these events. Events usually come in
+/// chronological order, in which case registering one is `O(log n)` where `n` is the number of
+/// steps. Older events are supported, but cost `O(n)` in the worst case.
+///
+/// ```rust
+/// use charts::{data::live::LiveHistory, prelude::time::SinceStart};
+/// let secs = SinceStart::from_secs;
+/// let mut history = LiveHistory::new();
+/// history.add_new(secs(1), 8);
+/// history.add_new(secs(2), 16);
+/// history.add_dead(secs(3), 8);
+/// // Out of order.
+/// history.add_new(secs(1), 4);
+///
+/// assert_eq!(history.at(secs(0)), (0, 0));
+/// assert_eq!(history.at(secs(1)), (2, 12));
+/// assert_eq!(history.at(secs(2)), (3, 28));
+/// assert_eq!(history.at(secs(3)), (2, 20));
+/// assert_eq!(history.at(secs(7)), (2, 20));
+/// assert_eq!(history.before(secs(1)), (0, 0));
+/// assert_eq!(history.before(secs(3)), (3, 28));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LiveHistory {
+    /// Time, live count and live size after all the events at that time, sorted by time.
+    steps: Vec<(time::SinceStart, usize, u64)>,
+}
+
+impl LiveHistory {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets all the steps.
+    pub fn clear(&mut self) {
+        self.steps.clear()
+    }
+
+    /// Registers the creation of an allocation of `size` bytes.
+    pub fn add_new(&mut self, toc: time::SinceStart, size: u64) {
+        self.update(toc, |count, bytes| (count + 1, bytes + size))
+    }
+
+    /// Registers the death of an allocation of `size` bytes.
+    pub fn add_dead(&mut self, tod: time::SinceStart, size: u64) {
+        self.update(tod, |count, bytes| {
+            (count.saturating_sub(1), bytes.saturating_sub(size))
+        })
+    }
+
+    /// Applies `delta` to the step at `time`, and to all the steps after it.
+    fn update(&mut self, time: time::SinceStart, delta: impl Fn(usize, u64) -> (usize, u64)) {
+        let idx = self.steps.partition_point(|(t, _, _)| *t < time);
+        if self
+            .steps
+            .get(idx)
+            .map(|(t, _, _)| *t != time)
+            .unwrap_or(true)
+        {
+            let (count, bytes) = idx
+                .checked_sub(1)
+                .map(|prev| (self.steps[prev].1, self.steps[prev].2))
+                .unwrap_or((0, 0));
+            self.steps.insert(idx, (time, count, bytes))
+        }
+        for (_, count, bytes) in &mut self.steps[idx..] {
+            let (new_count, new_bytes) = delta(*count, *bytes);
+            *count = new_count;
+            *bytes = new_bytes;
+        }
+    }
+
+    /// Number and total size of the allocations live right before `time`.
+    ///
+    /// Ignores the events at `time`, *i.e.* the allocations created at `time` are not counted
+    /// while the ones that die at `time` are. `O(log n)` where `n` is the number of steps.
+    pub fn before(&self, time: time::SinceStart) -> (usize, u64) {
+        let idx = self.steps.partition_point(|(t, _, _)| *t < time);
+        idx.checked_sub(1)
+            .map(|step| (self.steps[step].1, self.steps[step].2))
+            .unwrap_or((0, 0))
+    }
+
+    /// Number and total size of the allocations live at `time`.
+    ///
+    /// An allocation is live at its time of creation, but not at its time of death, see
+    /// [`Alloc::is_live_at`]. `O(log n)` where `n` is the number of steps.
+    ///
+    /// [`Alloc::is_live_at`]: alloc::Alloc::is_live_at (is_live_at method of Alloc)
+    pub fn at(&self, time: time::SinceStart) -> (usize, u64) {
+        let idx = self.steps.partition_point(|(t, _, _)| *t <= time);
+        idx.checked_sub(1)
+            .map(|step| (self.steps[step].1, self.steps[step].2))
+            .unwrap_or((0, 0))
+    }
+}
@@ -0,0 +1,482 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Snapshots of the allocation data, see [`Data::save_snapshot`].
+//!
+//! Parsing a large memtrace CTF file takes a while, a snapshot stores the result of the parsing so
+//! that the next runs of memthol on the same file can skip it. Snapshots are [`bincode`] files
+//! starting with some magic bytes and the [`VERSION`] of the format.
+//!
+//! A snapshot is only valid for the exact source file it was created from. Since the
+//! [run identifier] of some data is generated randomly when loading, it cannot identify the
+//! source. Instead, snapshots store a fingerprint of their source: its length, its date of last
+//! modification, and a hash of its first bytes (which contain the header of memtrace CTF files).
+//! Loading a snapshot fails, and the source file must be parsed again, when
+//!
+//! - the snapshot was written by a version of memthol with a different [`VERSION`];
+//! - the source file does not exist anymore, or its fingerprint changed;
+//! - the source file was modified after the snapshot was written;
+//! - the word size override, see [`super::set_word_size_override`], is not the one the source was
+//!   parsed with;
+//! - the strict flag, see [`Data::is_strict`], is not the one the source was parsed with, since
+//!   lenient parsing may drop allocations.
+//!
+//! Loading a snapshot generates a new run identifier, so that clients restart their charts as they
+//! would after parsing the source again.
+//!
+//! [`bincode`]: base::bincode (The bincode crate)
+//! [run identifier]: super::Data::run_id (run_id method of Data)
+
+prelude! {}
+
+use std::{
+    collections::HashMap as Map,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use super::Data;
+
+/// Magic bytes at the start of all snapshot files.
+const MAGIC: &[u8] = b"memthol snapshot";
+
+/// Version of the snapshot format, must be bumped whenever the format changes.
+pub const VERSION: u8 = 4;
+
+/// Number of bytes at the start of the source file that are hashed in its fingerprint.
+const HASHED_PREFIX_LEN: u64 = 64 * 1024;
+
+/// Identifies a specific version of a source file, see the [module-level documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Fingerprint {
+    /// Length of the file in bytes.
+    len: u64,
+    /// Date of last modification, seconds and nanoseconds since the UNIX epoch.
+    modified: (u64, u32),
+    /// FNV-1a hash of the first [`HASHED_PREFIX_LEN`] bytes of the file.
+    prefix_hash: u64,
+}
+impl Fingerprint {
+    /// Fingerprint of a file.
+    fn of(path: &Path) -> Res<Self> {
+        let mut file = std::fs::File::open(path)?;
+        let meta = file.metadata()?;
+        let modified = since_epoch(meta.modified()?);
+
+        let mut prefix = Vec::with_capacity(HASHED_PREFIX_LEN as usize);
+        (&mut file)
+            .take(HASHED_PREFIX_LEN)
+            .read_to_end(&mut prefix)?;
+        // FNV-1a, unlike the standard hasher it is guaranteed not to change across versions.
+        let prefix_hash = prefix
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+                (hash ^ (*byte as u64)).wrapping_mul(0x0100_0000_01b3)
+            });
+
+        Ok(Self {
+            len: meta.len(),
+            modified,
+            prefix_hash,
+        })
+    }
+}
+
+/// Seconds and nanoseconds since the UNIX epoch.
+fn since_epoch(time: SystemTime) -> (u64, u32) {
+    let duration = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    (duration.as_secs(), duration.subsec_nanos())
+}
+
+/// Location in a snapshot trace: file (index in the strings), line, column span and count.
+type SnapshotLoc = (usize, usize, (usize, usize), usize);
+
+/// Allocation in a snapshot, its UID is its index in the allocations of the snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotAlloc {
+    /// Allocation kind.
+    kind: alloc::AllocKind,
+    /// Size of the allocation, in bytes.
+    size: u32,
+    /// Sample count.
+    nsamples: u32,
+    /// Index of the trace of the allocation in the traces of the snapshot.
+    trace: usize,
+    /// Index of the labels of the allocation in the labels of the snapshot.
+    labels: usize,
+    /// Time of creation.
+    toc: time::SinceStart,
+    /// Time of death.
+    tod: Option<time::SinceStart>,
+    /// PID of the process that made the allocation, if known.
+    pid: Option<u64>,
+}
+
+/// Content of a snapshot file, after the magic bytes and the version.
+///
+/// Traces and labels are interned in memory, they are stored once and referred to by index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    /// Path to the source file.
+    source: PathBuf,
+    /// Fingerprint of the source file.
+    fingerprint: Fingerprint,
+    /// Word size override the source was parsed with.
+    word_size_override: Option<u8>,
+    /// Strict flag the source was parsed with.
+    strict: bool,
+    /// Init of the data.
+    init: alloc::Init,
+    /// Time of the latest event of the data.
+    current_time: time::SinceStart,
+    /// File names and labels.
+    strs: Vec<String>,
+    /// Traces, as lists of locations.
+    traces: Vec<Vec<SnapshotLoc>>,
+    /// Lists of labels, as indices in `strs`.
+    labels: Vec<Vec<usize>>,
+    /// Allocations, in UID order.
+    allocs: Vec<SnapshotAlloc>,
+}
+
+/// Interns values as indices, used to build snapshots.
+struct Interner<K> {
+    /// Maps values to their index.
+    map: Map<K, usize>,
+}
+impl<K: std::hash::Hash + Eq> Interner<K> {
+    /// Constructor.
+    fn new() -> Self {
+        Self { map: Map::new() }
+    }
+    /// Index of a value, `add` pushes the value in its storage when it is new.
+    fn index(&mut self, key: K, add: impl FnOnce()) -> usize {
+        let next = self.map.len();
+        *self.map.entry(key).or_insert_with(|| {
+            add();
+            next
+        })
+    }
+}
+
+impl Data {
+    /// Writes a snapshot of the data to a file, see the [snapshot module](super::snapshot).
+    ///
+    /// The source of the data is the path the data was [reset](Self::reset) with, which must be a
+    /// file. Fails if the data is not initialized.
+    ///
+    /// ```rust
+    /// use charts::{data::Data, prelude::time};
+    /// use std::cell::RefCell;
+    /// let dir = std::env::temp_dir().join(format!("memthol_snapshot_{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// let source = dir.join("source.ctf");
+    /// let snapshot = dir.join("source.snapshot");
+    /// std::fs::copy("../ctf/rsc/lists_and_sets.ctf", &source).unwrap();
+    ///
+    /// let bytes = std::fs::read(&source).unwrap();
+    /// let data = RefCell::new(Data::new());
+    /// let mut factory = Box::new(charts::alloc_data::mem::Factory::new(false));
+    /// ctf::parse(
+    ///     &bytes,
+    ///     &mut factory,
+    ///     ctf::ParseOptions::default(),
+    ///     |_, _| (),
+    ///     || true,
+    ///     |_, init| data.borrow_mut().reset(&source, init),
+    ///     |_, builder| data.borrow_mut().build_new(builder).unwrap(),
+    ///     |_, time, uid| data.borrow_mut().add_dead(time, uid).unwrap(),
+    ///     |_, time| data.borrow_mut().mark_timestamp(time),
+    /// )
+    /// .unwrap();
+    /// drop(factory);
+    /// let mut data = data.into_inner();
+    /// data.fill_stats().unwrap();
+    /// data.save_snapshot(&snapshot).unwrap();
+    ///
+    /// let loaded = Data::load_snapshot(&snapshot, true).unwrap();
+    /// // Loading is a new run.
+    /// assert_ne!(loaded.run_id().unwrap(), data.run_id().unwrap());
+    /// assert_eq!(loaded.current_time(), data.current_time());
+    /// assert_eq!(loaded.stats(), data.stats());
+    /// let all = |data: &Data| {
+    ///     data.iter_allocs()
+    ///         .map(|alloc| (alloc.uid, alloc.real_size, alloc.toc, alloc.tod, alloc.trace()))
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(all(&loaded), all(&data));
+    /// let time = time::SinceStart::from_secs(1);
+    /// assert_eq!(loaded.live_before(time), data.live_before(time));
+    ///
+    /// // So does loading it with another strict flag.
+    /// let err = Data::load_snapshot(&snapshot, false).err().unwrap();
+    /// assert!(err.to_string().contains("strict flag"));
+    ///
+    /// // Modifying the source invalidates the snapshot.
+    /// std::fs::write(&source, b"not a ctf file").unwrap();
+    /// let err = Data::load_snapshot(&snapshot, true).err().unwrap();
+    /// assert!(err.to_string().contains("out of date"));
+    ///
+    /// std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Res<()> {
+        let path = path.as_ref();
+        let snapshot = self
+            .to_snapshot()
+            .chain_err(|| format!("while building snapshot `{}`", path.display()))?;
+        let file = std::fs::File::create(path)
+            .chain_err(|| format!("while creating snapshot file `{}`", path.display()))?;
+        let mut writer = std::io::BufWriter::new(file);
+        writer
+            .write_all(MAGIC)
+            .and_then(|()| writer.write_all(&[VERSION]))
+            .chain_err(|| format!("while writing snapshot file `{}`", path.display()))?;
+        base::bincode::serialize_into(&mut writer, &snapshot)
+            .chain_err(|| format!("while writing snapshot file `{}`", path.display()))?;
+        writer
+            .flush()
+            .chain_err(|| format!("while writing snapshot file `{}`", path.display()))?;
+        Ok(())
+    }
+
+    /// Loads a snapshot written by [`Self::save_snapshot`].
+    ///
+    /// Fails if the snapshot is not valid anymore, see the [snapshot module](super::snapshot) for
+    /// the invalidation rules. The result has a new run identifier, is in strict mode iff `strict`
+    /// and is not paused, see [`Self::is_strict`] and [`Self::is_paused`].
+    pub fn load_snapshot(path: impl AsRef<Path>, strict: bool) -> Res<Self> {
+        let path = path.as_ref();
+        let snapshot = Self::read_snapshot(path)
+            .chain_err(|| format!("while reading snapshot file `{}`", path.display()))?;
+
+        let invalid = |reason: &str| -> Res<Self> {
+            bail!(
+                "snapshot `{}` of `{}` is {}",
+                path.display(),
+                snapshot.source.display(),
+                reason
+            )
+        };
+        let fingerprint = match Fingerprint::of(&snapshot.source) {
+            Ok(fingerprint) => fingerprint,
+            Err(_) => return invalid("invalid, cannot access its source"),
+        };
+        let written = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .chain_err(|| format!("while reading snapshot file `{}`", path.display()))?;
+        if fingerprint != snapshot.fingerprint
+            || snapshot.fingerprint.modified > since_epoch(written)
+        {
+            return invalid("out of date, its source changed");
+        }
+        if snapshot.word_size_override != super::word_size_override() {
+            return invalid("invalid, its source was parsed with a different word size override");
+        }
+        if snapshot.strict != strict {
+            return invalid("invalid, its source was parsed with a different strict flag");
+        }
+
+        Self::from_snapshot(snapshot)
+            .chain_err(|| format!("while loading snapshot file `{}`", path.display()))
+    }
+
+    /// Reads the content of a snapshot file, checks the magic bytes and the version.
+    fn read_snapshot(path: &Path) -> Res<Snapshot> {
+        let file = std::fs::File::open(path)?;
+        let mut reader = std::io::BufReader::new(file);
+        let mut header = vec![0; MAGIC.len() + 1];
+        reader
+            .read_exact(&mut header)
+            .chain_err(|| "not a memthol snapshot")?;
+        if &header[..MAGIC.len()] != MAGIC {
+            bail!("not a memthol snapshot")
+        }
+        let version = header[MAGIC.len()];
+        if version != VERSION {
+            bail!(
+                "unsupported snapshot format version {}, expected version {}",
+                version,
+                VERSION
+            )
+        }
+        Ok(base::bincode::deserialize_from(reader)?)
+    }
+
+    /// Snapshot of the data.
+    fn to_snapshot(&self) -> Res<Snapshot> {
+        let init = self
+            .init
+            .clone()
+            .ok_or("cannot snapshot uninitialized data")?;
+        let source = self
+            .stats
+            .as_ref()
+            .map(|stats| stats.dump_dir.clone())
+            .ok_or("cannot snapshot uninitialized data")?;
+        if !source.is_file() {
+            bail!(
+                "cannot snapshot data from `{}`, only memtrace CTF files are supported",
+                source.display()
+            )
+        }
+        // Snapshots can be loaded from another working directory.
+        let source = source.canonicalize().unwrap_or(source);
+        let fingerprint = Fingerprint::of(&source)
+            .chain_err(|| format!("while fingerprinting `{}`", source.display()))?;
+
+        let (mut strs, mut traces, mut labels) = (vec![], vec![], vec![]);
+        let (mut str_map, mut trace_map, mut labels_map) =
+            (Interner::new(), Interner::new(), Interner::new());
+        let mut str_index = |s: alloc::Str| str_map.index(s, || strs.push(s.to_string()));
+
+        let mut allocs = Vec::with_capacity(self.uid_map.len());
+        for alloc in &self.uid_map[..] {
+            let trace = trace_map.index(alloc.trace_uid(), || {
+                let trace = alloc
+                    .trace()
+                    .iter()
+                    .map(|cloc| {
+                        let loc = &cloc.loc;
+                        let span = (loc.span.lbound, loc.span.ubound);
+                        (str_index(loc.file), loc.line, span, cloc.cnt)
+                    })
+                    .collect();
+                traces.push(trace)
+            });
+            let labels = labels_map.index(alloc.labels_uid(), || {
+                let list = alloc.iter_labels().map(&mut str_index).collect();
+                labels.push(list)
+            });
+            allocs.push(SnapshotAlloc {
+                kind: alloc.kind,
+                size: alloc.size,
+                nsamples: alloc.nsamples,
+                trace,
+                labels,
+                toc: alloc.toc,
+                tod: alloc.tod,
+                pid: alloc.pid,
+            })
+        }
+
+        Ok(Snapshot {
+            source,
+            fingerprint,
+            word_size_override: super::word_size_override(),
+            strict: self.strict,
+            init,
+            current_time: self.current_time,
+            strs,
+            traces,
+            labels,
+            allocs,
+        })
+    }
+
+    /// Rebuilds some data from a snapshot.
+    ///
+    /// Registers the allocations and then their deaths, so that everything derived from them is
+    /// built the same way as when parsing. The run identifier of the snapshot is replaced by a new
+    /// one.
+    fn from_snapshot(snapshot: Snapshot) -> Res<Self> {
+        let Snapshot {
+            source,
+            strict,
+            mut init,
+            current_time,
+            strs,
+            traces,
+            labels,
+            allocs,
+            ..
+        } = snapshot;
+
+        // Interning, the factory must be dropped before anything reads strings, traces or labels.
+        let mut factory = alloc_data::mem::Factory::new(false);
+        let str_at = |index: usize| -> Res<&String> {
+            Ok(strs
+                .get(index)
+                .ok_or_else(|| format!("unknown string #{}", index))?)
+        };
+        let mut trace_uids = Vec::with_capacity(traces.len());
+        for trace in traces {
+            let mut locs = Vec::with_capacity(trace.len());
+            for (file, line, span, cnt) in trace {
+                let loc = factory.register_loc(str_at(file)?, line, span);
+                locs.push(alloc::CLoc::new(loc, cnt))
+            }
+            trace_uids.push(factory.register_trace(locs))
+        }
+        let mut labels_uids = Vec::with_capacity(labels.len());
+        for list in labels {
+            let mut labels = Vec::with_capacity(list.len());
+            for index in list {
+                labels.push(factory.register_str(str_at(index)?))
+            }
+            labels_uids.push(factory.register_labels(labels))
+        }
+        drop(factory);
+
+        // New run, see the module-level documentation.
+        init.run_id = base::rand::random();
+        let mut data = Self::new();
+        data.set_strict(strict);
+        data.reset(source, init);
+        let mut deaths = BTMap::<_, Vec<_>>::new();
+        for (uid, alloc) in allocs.into_iter().enumerate() {
+            let uid = uid::Alloc::from(uid);
+            let trace = trace_uids
+                .get(alloc.trace)
+                .cloned()
+                .ok_or_else(|| format!("unknown trace #{}", alloc.trace))?;
+            let labels = labels_uids
+                .get(alloc.labels)
+                .cloned()
+                .ok_or_else(|| format!("unknown labels #{}", alloc.labels))?;
+            let mut builder = alloc::Builder::new(
+                Some(uid),
+                alloc.kind,
+                alloc.size,
+                trace,
+                labels,
+                alloc.toc,
+                None,
+            )
+            .nsamples(alloc.nsamples);
+            builder.pid = alloc.pid;
+            data.build_new(builder)?;
+            if let Some(tod) = alloc.tod {
+                deaths.entry(tod).or_default().push(uid)
+            }
+        }
+        for (tod, uids) in deaths {
+            for uid in uids {
+                data.add_dead(tod, uid)?
+            }
+        }
+        data.mark_timestamp(current_time);
+        data.fill_stats()?;
+        data.check_invariants()?;
+
+        Ok(data)
+    }
+}
@@ -24,12 +24,132 @@ prelude! {}
 use std::{
     ffi::OsString,
     path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
     thread::sleep,
     time::{Duration, SystemTime},
 };
 
+/// What the [`Watcher`] does with malformed diff files.
+///
+/// A diff file is malformed if it does not parse, or if it is rejected by the data with a
+/// recoverable error, see [`err::Severity`]. Skipped files are counted in the statistics, see
+/// [`AllocStats::skipped_files`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MalformedPolicy {
+    /// Reports and skips malformed diff files.
+    Skip,
+    /// Reports malformed diff files, and moves them to the [`Self::DIR`] sub-directory of the
+    /// dump directory.
+    MoveAside,
+    /// Stops with a fatal error.
+    Abort,
+}
+impl MalformedPolicy {
+    /// Sub-directory of the dump directory malformed diff files are moved to by [`Self::MoveAside`].
+    pub const DIR: &'static str = "malformed";
+
+    /// Names of the policies, as accepted by [`std::str::FromStr`].
+    pub const NAMES: [&'static str; 3] = ["skip", "move", "abort"];
+}
+impl Default for MalformedPolicy {
+    fn default() -> Self {
+        Self::Skip
+    }
+}
+impl std::str::FromStr for MalformedPolicy {
+    type Err = err::Error;
+    fn from_str(s: &str) -> Res<Self> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "move" => Ok(Self::MoveAside),
+            "abort" => Ok(Self::Abort),
+            _ => bail!(err::ErrorKind::Config(format!(
+                "unknown malformed diff policy `{}`, expected one of {}",
+                s,
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+/// Configuration of the [`Watcher`], only relevant for dump directories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatcherConfig {
+    /// Time to wait before polling the dump directory again, when nothing new was found.
+    pub poll_interval: Duration,
+    /// Time to wait for more diff files once new ones show up.
+    ///
+    /// All the diff files found within this window are registered together, ordered by timestamp.
+    /// If zero, new diff files are registered as soon as they are found.
+    pub debounce: Duration,
+    /// What to do with malformed diff files.
+    pub malformed: MalformedPolicy,
+}
+impl WatcherConfig {
+    /// Default polling interval, in milliseconds.
+    pub const DEFAULT_POLL_INTERVAL_MS: u64 = 100;
+    /// Default debounce window, in milliseconds.
+    pub const DEFAULT_DEBOUNCE_MS: u64 = 0;
+
+    /// Constructor, malformed diff files are skipped.
+    pub fn new(poll_interval: Duration, debounce: Duration) -> Self {
+        Self {
+            poll_interval,
+            debounce,
+            malformed: MalformedPolicy::default(),
+        }
+    }
+
+    /// Sets the malformed diff files policy.
+    pub fn with_malformed(mut self, malformed: MalformedPolicy) -> Self {
+        self.malformed = malformed;
+        self
+    }
+}
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_millis(Self::DEFAULT_POLL_INTERVAL_MS),
+            Duration::from_millis(Self::DEFAULT_DEBOUNCE_MS),
+        )
+    }
+}
+
+/// Requests handled by the thread of the last watcher spawned, see [`Watcher::spawn`].
+#[derive(Debug, Clone)]
+enum Request {
+    /// Re-parse the target from scratch, with the cancellation flag of the new load.
+    Rescan(Arc<AtomicBool>),
+}
+
+/// Number of watcher threads currently running, see [`Watcher::is_running`].
+static RUNNING: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Counts a watcher thread as running until dropped, even if the thread panics.
+struct RunningGuard;
+impl RunningGuard {
+    /// Constructor, registers a running watcher thread.
+    fn new() -> Self {
+        RUNNING.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Self
+    }
+}
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+lazy_static! {
+    /// Sends requests to the thread of the last watcher spawned.
+    static ref REQUESTS: sync::Mutex<Option<std::sync::mpsc::Sender<Request>>> =
+        sync::Mutex::new(None);
+}
+
 /// Daemon monitoring files.
 pub struct Watcher {
+    /// Configuration.
+    config: WatcherConfig,
     /// Directory to watch.
     dir: String,
     /// Temporary file used by memthol to write dumps.
@@ -47,8 +167,10 @@ pub struct Watcher {
 
     /// Diff paths, used when gathering new diffs.
     new_diff_paths: Vec<PathBuf>,
-    /// New diffs.
-    new_diffs: Vec<alloc::Diff>,
+    /// Paths of the diffs found while ingestion was paused, see [`super::Data::is_paused`].
+    paused_diff_paths: Vec<PathBuf>,
+    /// New diffs, and the path of the file they come from.
+    new_diffs: Vec<(PathBuf, alloc::Diff)>,
 
     /// Buffer for file-reading.
     buf: String,
@@ -56,18 +178,13 @@ pub struct Watcher {
 
 impl Watcher {
     /// Spawns a watcher.
-    pub fn spawn(target: impl AsRef<Path>, forever: bool) {
-        let path = target.as_ref();
-
-        if path.is_file() {
-            let path = path.display().to_string();
-            let _ = std::thread::spawn(move || match Self::ctf_run(path) {
-                Ok(()) => (),
-                Err(e) => err::register_fatal(e),
-            });
-        } else if path.is_dir() {
-            let mut watcher = Self::new(target);
+    ///
+    /// The watcher thread loads `target`, and then serves the requests sent by [`Self::rescan_all`]
+    /// until another watcher is spawned.
+    pub fn spawn(target: impl AsRef<Path>, forever: bool, config: WatcherConfig) {
+        let path = target.as_ref().to_path_buf();
 
+        if path.is_dir() {
             log::warn!("running on legacy memthol dump format");
             log::warn!("this will probably not work with this version of memthol");
             log::warn!("unless the diffs must verify the following invariant:");
@@ -77,22 +194,111 @@ impl Watcher {
                 "meaning successive UIDs `uid_i` and `uid_j` \
                 must be such that `uid_j == uid_i + 1`"
             );
-
-            let _ = std::thread::spawn(move || match watcher.run(forever) {
-                Ok(()) => (),
-                Err(e) => err::register_non_fatal(e.to_pretty()),
-            });
-        } else {
+        } else if !path.is_file() {
             err::register_fatal(format!(
                 "expected dump directory or memtrace CTF file\n\
                 got `{}` which is neither or a file nor a directory",
                 path.display()
-            ))
+            ));
+            return;
+        }
+
+        let cancelled = match super::progress::new_load() {
+            Ok(cancelled) => cancelled,
+            Err(e) => {
+                err::register_fatal(e);
+                return;
+            }
+        };
+
+        let (sender, requests) = std::sync::mpsc::channel();
+        match REQUESTS.lock() {
+            // Dropping the previous sender stops the previous watcher thread once it is done.
+            Ok(mut current) => *current = Some(sender),
+            Err(e) => err::register_non_fatal(format!("while registering watcher thread: {}", e)),
         }
+
+        let _ = std::thread::spawn(move || {
+            let mut cancelled = cancelled;
+            // Memtrace CTF files are loaded once, the thread counts as running as long as it
+            // serves requests. Dump directories only count while they are watched.
+            let mut running = Some(RunningGuard::new());
+            loop {
+                if path.is_file() {
+                    match Self::ctf_run(&path, &cancelled) {
+                        Ok(()) => (),
+                        Err(e) => err::register_fatal(e),
+                    }
+                } else {
+                    match Self::new(&path, config).run(forever, &cancelled) {
+                        Ok(()) => (),
+                        // Recoverable errors only stop the watcher.
+                        Err(e) if e.is_recoverable() => Self::report(e),
+                        Err(e) => err::register_fatal(e),
+                    }
+                    running = None;
+                }
+
+                match requests.recv() {
+                    Ok(Request::Rescan(flag)) => {
+                        cancelled = flag;
+                        // Several rescan requests in a row only need one rescan, the last one.
+                        while let Ok(Request::Rescan(flag)) = requests.try_recv() {
+                            cancelled = flag
+                        }
+                        running.get_or_insert_with(RunningGuard::new);
+
+                        log::info!("rescanning `{}` from scratch", path.display());
+                        let res = super::get_mut()
+                            .chain_err(|| "while clearing the data")
+                            .map(|mut data| data.clear())
+                            .and_then(|()| super::progress::set_unknown());
+                        if let Err(e) = res {
+                            err::register_fatal(e);
+                            break;
+                        }
+                    }
+                    // Another watcher was spawned.
+                    Err(_) => break,
+                }
+            }
+        });
+    }
+
+    /// True if a watcher thread is running.
+    ///
+    /// False before the first watcher is spawned, and after the watcher thread stopped or panicked.
+    /// Also false when the watcher of a dump directory stopped watching it, *e.g.* because its load
+    /// was cancelled, until the next rescan, see [`Self::rescan_all`].
+    pub fn is_running() -> bool {
+        RUNNING.load(std::sync::atomic::Ordering::SeqCst) > 0
+    }
+
+    /// Re-parses the target of the last watcher spawned from scratch.
+    ///
+    /// Cancels the current load of the watcher thread, if any, and asks it to clear the global
+    /// data and load its target again, with the same configuration. Everything goes through the
+    /// watcher thread so that no two loads ever run concurrently. The charts notice the new run by
+    /// its new run identifier and restart.
+    ///
+    /// Returns the cancellation flag of the new load, see [`super::progress::new_load`].
+    pub fn rescan_all() -> Res<Arc<AtomicBool>> {
+        let requests = REQUESTS.lock().map_err(|e| e.to_string())?;
+        let requests = requests
+            .as_ref()
+            .ok_or("cannot rescan, no target was loaded yet")?;
+        super::progress::cancel()?;
+        let cancelled = super::progress::new_load()?;
+        requests
+            .send(Request::Rescan(cancelled.clone()))
+            .map_err(|_| "cannot rescan, the watcher thread is not running")?;
+        Ok(cancelled)
     }
 
     /// Runs on a memtrace CTF file.
-    pub fn ctf_run(target: impl AsRef<Path>) -> Res<()> {
+    ///
+    /// Stops parsing when `cancelled` is raised, see [`super::progress::new_load`].
+    pub fn ctf_run(target: impl AsRef<Path>, cancelled: &AtomicBool) -> Res<()> {
         base::new_time_stats! {
             struct Prof {
                 total => "total",
@@ -104,9 +310,18 @@ impl Watcher {
         prof.total.start();
 
         let target = target.as_ref();
+        super::set_parse_stats(None)?;
 
         log::info!("loading ctf file `{}`", target.display());
 
+        let snapshot = super::snapshot_path()?;
+        if let Some(snapshot) = snapshot.as_ref().filter(|snapshot| snapshot.exists()) {
+            if Self::load_snapshot(target, snapshot)? {
+                super::progress::set_done()?;
+                return Ok(());
+            }
+        }
+
         prof.load.start();
         let bytes = {
             use std::io::Read;
@@ -129,12 +344,17 @@ impl Watcher {
 
         let mut factory = data::FullFactory::new(false);
         prof.parse.start();
-        ctf::parse(
+        let stats = ctf::parse(
             &bytes,
             &mut factory,
-            |bytes_progress| {
-                err::unwrap_register_fatal(super::progress::set_loaded(bytes_progress))
+            ctf::ParseOptions {
+                word_size_override: super::word_size_override(),
+                ..ctf::ParseOptions::default()
+            },
+            |loaded, total| {
+                err::unwrap_register_fatal(super::progress::set_progress(loaded, total))
             },
+            || !cancelled.load(std::sync::atomic::Ordering::SeqCst),
             |factory, init| {
                 if factory.data.has_init() {
                     panic!("live profiling restart is not supported yet")
@@ -148,8 +368,20 @@ impl Watcher {
         )
         .chain_err(|| format!("while parsing ctf file `{}`", target.display()))?;
         prof.parse.stop();
+        super::set_parse_stats(Some(stats))?;
 
         factory.fill_stats()?;
+        // Releases the locks, writing a snapshot needs to read the traces and the data.
+        drop(factory);
+
+        if let Some(snapshot) = snapshot {
+            if !cancelled.load(std::sync::atomic::Ordering::SeqCst) {
+                match super::get().and_then(|data| data.save_snapshot(&snapshot)) {
+                    Ok(()) => log::info!("wrote snapshot `{}`", snapshot.display()),
+                    Err(e) => log::warn!("{}", e.to_pretty()),
+                }
+            }
+        }
 
         super::progress::set_done()?;
 
@@ -164,12 +396,59 @@ impl Watcher {
         Ok(())
     }
 
+    /// Loads the data of a memtrace CTF file from a snapshot, see [`super::Data::load_snapshot`].
+    ///
+    /// Returns `false` if the snapshot is not valid, or not a snapshot of `target`. Keeps the flags
+    /// and the error log of the current data.
+    fn load_snapshot(target: &Path, snapshot: &Path) -> Res<bool> {
+        let strict = super::get()
+            .chain_err(|| "while loading a snapshot")?
+            .is_strict();
+        let loaded = match super::Data::load_snapshot(snapshot, strict) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                log::info!("{}", e.to_pretty());
+                log::info!("ignoring snapshot `{}`", snapshot.display());
+                return Ok(false);
+            }
+        };
+        let canonical = |path: &Path| path.canonicalize().ok();
+        let source = loaded.stats.as_ref().map(|stats| stats.dump_dir.as_path());
+        if source.and_then(canonical).is_none() || source.and_then(canonical) != canonical(target) {
+            log::info!(
+                "ignoring snapshot `{}`, it is not a snapshot of `{}`",
+                snapshot.display(),
+                target.display()
+            );
+            return Ok(false);
+        }
+
+        let mut data = super::get_mut().chain_err(|| "while loading a snapshot")?;
+        let (paused, errors) = (data.is_paused(), std::mem::take(&mut data.errors));
+        *data = loaded;
+        data.set_paused(paused);
+        data.errors = errors;
+        log::info!("loaded snapshot `{}`", snapshot.display());
+        Ok(true)
+    }
+
     /// Runs the watcher.
-    pub fn run(&mut self, forever: bool) -> Res<()> {
+    ///
+    /// Stops when `cancelled` is raised, see [`super::progress::new_load`]. The directory is not
+    /// watched anymore afterwards.
+    ///
+    /// When running `forever`, recoverable errors (see [`err::Severity`]) raised while registering
+    /// new diffs are reported with [`Self::report`] and the watcher keeps going. Fatal errors stop
+    /// the watcher, unless the run was restarted in the meantime.
+    pub fn run(&mut self, forever: bool, cancelled: &AtomicBool) -> Res<()> {
+        use std::sync::atomic::Ordering::SeqCst;
         crate::data::progress::set_unknown()?;
 
         // First init read.
         'first_init: loop {
+            if cancelled.load(SeqCst) {
+                return Ok(());
+            }
             if let Some(init) = self.try_read_init()? {
                 let mut data =
                     super::get_mut().chain_err(|| "while registering the initial state")?;
@@ -199,6 +478,11 @@ impl Watcher {
 
         // Diff-reading loop.
         loop {
+            if cancelled.load(SeqCst) {
+                log::info!("stopped watching `{}`", self.dir);
+                break Ok(());
+            }
+
             if let Some(init) = self
                 .try_read_init()
                 .chain_err(|| "while checking whether the init file of the run has changed")?
@@ -227,15 +511,17 @@ impl Watcher {
                 }
                 Ok(false) => {
                     // Nothing new, sleep for a bit.
-                    sleep(Duration::from_millis(100))
+                    sleep(self.config.poll_interval)
                 }
                 Err(e) => {
-                    if forever {
+                    if !forever {
+                        bail!(e)
+                    } else if e.is_recoverable() {
+                        Self::report(e)
+                    } else {
                         // There was a problem, remember it in `diff_error` and loop to check
                         // whether a restart happened.
                         diff_error = Some(e)
-                    } else {
-                        bail!(e)
                     }
                 }
             }
@@ -250,22 +536,25 @@ impl Watcher {
 /// # Generic helpers.
 impl Watcher {
     /// Constructor.
-    pub fn new(dir: impl AsRef<Path>) -> Self {
+    pub fn new(dir: impl AsRef<Path>, config: WatcherConfig) -> Self {
         let dir = dir.as_ref().display().to_string();
         let tmp_file = "tmp.memthol".into();
         let init_file = "init.memthol".into();
         let init_last_modified = None;
         let known_files = BTSet::new();
         let new_diff_paths = vec![];
+        let paused_diff_paths = vec![];
         let new_diffs = vec![];
         let buf = String::new();
         let mut slf = Self {
+            config,
             dir,
             tmp_file,
             init_file,
             init_last_modified,
             known_files,
             new_diff_paths,
+            paused_diff_paths,
             new_diffs,
             buf,
         };
@@ -275,10 +564,11 @@ impl Watcher {
 
     /// Resets the watcher's state.
     ///
-    /// - clears `self.known_files` and `self.new_diffs`;
+    /// - clears `self.known_files`, `self.paused_diff_paths` and `self.new_diffs`;
     /// - adds `self.tmp_file` and `self.init_file` to `self.known_files`.
     pub fn reset(&mut self) {
         self.known_files.clear();
+        self.paused_diff_paths.clear();
         self.new_diffs.clear();
         let is_new = self.known_files.insert((&self.tmp_file).into());
         debug_assert!(is_new);
@@ -296,6 +586,16 @@ impl Watcher {
         Ok(())
     }
 
+    /// Reports a recoverable error, see [`err::Severity`].
+    ///
+    /// The error is registered as non-fatal, and added to the error log of the data (see
+    /// [`super::Data::add_err`]).
+    pub fn report(e: err::Error) {
+        let e = e.to_pretty();
+        err::unwrap_register_non_fatal(super::add_err(e.clone()));
+        err::register_non_fatal(e)
+    }
+
     /// Reads the content of a file and applies something to that content.
     ///
     /// - clears `self.buf` once it's done.
@@ -389,64 +689,272 @@ impl Watcher {
 impl Watcher {
     /// Gathers and registers new diffs.
     ///
-    /// - sleeps for `100` milliseconds if there are no new diffs;
+    /// - gathers the diffs with [`Self::gather_batch`];
+    /// - while ingestion is paused, only remembers the paths of the new diffs, which are
+    ///   registered with the next batch once ingestion resumes (see [`super::Data::is_paused`]);
     /// - asserts `self.new_diffs.is_empty()`.
-    /// - returns `true` if something new was discovered.
+    /// - returns `true` if something new was registered.
     /// - `update_progress` indicates that the `crate::data::progress` needs to be updated
+    /// - malformed diff files are handled by [`Self::handle_malformed`], other errors are returned.
+    ///
+    /// ```rust
+    /// use charts::data::{self, MalformedPolicy, Watcher, WatcherConfig};
+    /// use std::fs;
+    /// let dir = std::env::temp_dir().join(format!("memthol_malformed_{}", std::process::id()));
+    /// fs::create_dir_all(&dir).unwrap();
+    /// fs::write(dir.join("init.memthol"), "start: 0.0\nword_size: 64\n").unwrap();
+    ///
+    /// let config = WatcherConfig::default().with_malformed(MalformedPolicy::MoveAside);
+    /// let mut watcher = Watcher::new(&dir, config);
+    /// let init = watcher.try_read_init().unwrap().unwrap();
+    /// watcher.reset_run(init).unwrap();
+    ///
+    /// fs::write(
+    ///     dir.join("diff_1.memthol"),
+    ///     "1.0 new { 0: Minor 2 [ `main.ml`:1:2-3#1 ] [] 1.0 _ } dead {}",
+    /// )
+    /// .unwrap();
+    /// fs::write(dir.join("diff_2.memthol"), "2.0 new { 1: Minor 2 [ ] [] 2.0").unwrap();
+    ///
+    /// // The good diff is registered despite the corrupt one.
+    /// assert!(watcher.register_new_diffs(false).unwrap());
+    /// assert_eq!(data::alloc_count().unwrap(), 1);
+    /// let stats = data::Data::get_stats().unwrap().unwrap();
+    /// assert_eq!(stats.skipped_files, 1);
+    /// assert_eq!(data::get().unwrap().errors().len(), 1);
+    ///
+    /// // The corrupt diff was moved aside.
+    /// assert!(!dir.join("diff_2.memthol").exists());
+    /// assert!(dir.join(MalformedPolicy::DIR).join("diff_2.memthol").exists());
+    /// // Moving it did not make it a new file.
+    /// assert!(!watcher.register_new_diffs(false).unwrap());
+    ///
+    /// fs::remove_dir_all(&dir).unwrap();
+    /// ```
     pub fn register_new_diffs(&mut self, update_progress: bool) -> Res<bool> {
         debug_assert!(self.new_diffs.is_empty());
 
+        let mut new_diff_paths = self.gather_batch()?;
+
+        if super::is_ingestion_paused()? {
+            if !new_diff_paths.is_empty() {
+                log::debug!(
+                    "ingestion paused, buffering {} new diff(s)",
+                    new_diff_paths.len()
+                );
+                self.paused_diff_paths.extend(new_diff_paths);
+            }
+            data::progress::set_done()?;
+            return Ok(false);
+        } else if !self.paused_diff_paths.is_empty() {
+            log::info!(
+                "ingestion resumed, registering {} buffered diff(s)",
+                self.paused_diff_paths.len()
+            );
+            new_diff_paths.extend(self.paused_diff_paths.drain(0..));
+        }
+
+        let new_stuff = !new_diff_paths.is_empty();
+
+        if new_stuff {
+            if update_progress {
+                crate::data::progress::set_total(new_diff_paths.len())?;
+            }
+
+            // Malformed diff files, and the corresponding errors.
+            let mut malformed = vec![];
+
+            {
+                let data = super::get().chain_err(|| "while accessing init info from data")?;
+                let init = data
+                    .init
+                    .as_ref()
+                    .ok_or_else(|| "trying to parse diffs when no init file has been parsed yet")?;
+
+                while let Some(diff_path) = new_diff_paths.pop() {
+                    let diff = self.load(init, diff_path.clone());
+                    if update_progress {
+                        crate::data::progress::inc_loaded()?;
+                    }
+                    match diff {
+                        Ok(diff) => self.new_diffs.push((diff_path, diff)),
+                        Err(e) if e.is_recoverable() => malformed.push((diff_path, e)),
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+
+            // Directory iteration order is arbitrary, sort the diffs. This could be more
+            // efficient by having `gather_new_diffs` insert in a sorted list.
+            Self::sort_diffs(&mut self.new_diffs);
+
+            let rejected = super::add_diffs_skipping(self.new_diffs.drain(0..).collect())?;
+            malformed.extend(rejected);
+
+            // Handling needs the data lock, which is released at this point.
+            self.handle_malformed(malformed)?;
+        }
+
+        data::progress::set_done()?;
+
+        Ok(new_stuff)
+    }
+
+    /// Handles some malformed diff files and the corresponding errors, see [`MalformedPolicy`].
+    ///
+    /// Fails with a fatal error on malformed diff files if the policy is
+    /// [`MalformedPolicy::Abort`].
+    pub fn handle_malformed(&mut self, malformed: Vec<(PathBuf, err::Error)>) -> Res<()> {
+        if malformed.is_empty() {
+            return Ok(());
+        }
+
+        if self.config.malformed == MalformedPolicy::Abort {
+            let count = malformed.len();
+            let (path, e) = malformed.into_iter().next().expect("non-empty list");
+            return Err(e.chain_err(|| {
+                err::ErrorKind::Config(format!(
+                    "aborting on malformed diff file `{}` ({} malformed file(s) in this batch)",
+                    path.display(),
+                    count,
+                ))
+            }));
+        }
+
+        super::add_skipped_files(malformed.len())?;
+        for (path, e) in malformed {
+            let e = if self.config.malformed == MalformedPolicy::MoveAside {
+                match self.move_aside(&path) {
+                    Ok(target) => e.chain_err(|| {
+                        format!("moved malformed diff file to `{}`", target.display())
+                    }),
+                    Err(move_err) => {
+                        Self::report(move_err);
+                        e.chain_err(|| "skipping malformed diff file")
+                    }
+                }
+            } else {
+                e.chain_err(|| "skipping malformed diff file")
+            };
+            Self::report(e)
+        }
+        Ok(())
+    }
+
+    /// Moves a diff file to the [`MalformedPolicy::DIR`] sub-directory of the dump directory.
+    ///
+    /// Returns the new path of the file.
+    fn move_aside(&mut self, path: &Path) -> Res<PathBuf> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| format!("illegal diff file path `{}`", path.display()))?;
+        let dir = Path::new(&self.dir).join(MalformedPolicy::DIR);
+        std::fs::create_dir_all(&dir)
+            .chain_err(|| format!("while creating directory `{}`", dir.display()))?;
+        let target = dir.join(file_name);
+        std::fs::rename(path, &target).chain_err(|| {
+            format!(
+                "while moving malformed diff file `{}` to `{}`",
+                path.display(),
+                target.display()
+            )
+        })?;
+        Ok(target)
+    }
+
+    /// Gathers the paths of the new diff files, without loading them.
+    ///
+    /// If there are new diff files, waits for `self.config.debounce` so that the diff files written
+    /// in the meantime are part of the same batch.
+    ///
+    /// ```rust
+    /// use charts::data::{Watcher, WatcherConfig};
+    /// use std::{fs, time::Duration};
+    /// let dir = std::env::temp_dir().join(format!("memthol_gather_batch_{}", std::process::id()));
+    /// fs::create_dir_all(&dir).unwrap();
+    /// fs::write(dir.join("init.memthol"), "start: 0.0\nword_size: 64\n").unwrap();
+    ///
+    /// let config = WatcherConfig::new(Duration::from_millis(10), Duration::from_millis(500));
+    /// let mut watcher = Watcher::new(&dir, config);
+    /// assert!(watcher.try_read_init().unwrap().is_some());
+    /// // Nothing yet, returns right away.
+    /// assert!(watcher.gather_batch().unwrap().is_empty());
+    ///
+    /// fs::write(dir.join("diff_1.memthol"), "").unwrap();
+    /// // Written during the debounce window of the batch triggered by `diff_1`.
+    /// let writer = {
+    ///     let dir = dir.clone();
+    ///     std::thread::spawn(move || {
+    ///         std::thread::sleep(Duration::from_millis(100));
+    ///         fs::write(dir.join("diff_2.memthol"), "").unwrap()
+    ///     })
+    /// };
+    /// let mut batch: Vec<_> = watcher
+    ///     .gather_batch()
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+    ///     .collect();
+    /// writer.join().unwrap();
+    /// batch.sort();
+    /// assert_eq!(batch, vec!["diff_1.memthol", "diff_2.memthol"]);
+    /// // Both are known now.
+    /// assert!(watcher.gather_batch().unwrap().is_empty());
+    ///
+    /// fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn gather_batch(&mut self) -> Res<Vec<PathBuf>> {
+        debug_assert!(self.new_diff_paths.is_empty());
+
         // I don't know why, but sometimes `gather_new_diffs` will miss diff files when the profiler
         // is running. Diffs following the missing diff file(s) will not make sense and will crash
         // diff registration.
         //
         // So, this first call retrieves the highest time of last modification of the diffs
         // gathered.
-        let upper_bound = self.gather_new_diffs(None)?;
+        let mut upper_bound = self.gather_new_diffs(None)?;
 
-        let new_stuff = upper_bound.is_some();
+        // Batch the diffs written within the debounce window.
+        if upper_bound.is_some() && self.config.debounce > Duration::from_secs(0) {
+            sleep(self.config.debounce);
+            let more = self.gather_new_diffs(None)?;
+            upper_bound = upper_bound.max(more);
+        }
 
         // Now, `upper_bound.is_none()` iff no diff was found. In this case we do nothing.
-        if new_stuff {
+        if upper_bound.is_some() {
             // If `upper_bound.is_some()`, we gather new diffs again but this time we give the upper
             // bound we got previously. This tells diff gathering to ignore everything more recent
             // than `upper_bound`. So, we will catch any intermediary diff we might have missed.
             self.gather_new_diffs(upper_bound)?;
-
-            if !self.new_diff_paths.is_empty() {
-                if update_progress {
-                    crate::data::progress::set_total(self.new_diff_paths.len())?;
-                }
-
-                {
-                    let data = super::get().chain_err(|| "while accessing init info from data")?;
-                    let init = data.init.as_ref().ok_or_else(|| {
-                        "trying to parse diffs when no init file has been parsed yet"
-                    })?;
-
-                    while let Some(diff_path) = self.new_diff_paths.pop() {
-                        let diff = self.load(init, diff_path)?;
-                        if update_progress {
-                            crate::data::progress::inc_loaded()?;
-                        }
-                        self.new_diffs.push(diff);
-                    }
-                }
-
-                // Sort the diffs. This could be more efficient by having `gather_new_diffs` insert
-                // in a sorted list.
-                self.new_diffs
-                    .sort_by(|diff_1, diff_2| diff_1.time.cmp(&diff_2.time));
-
-                for diff in self.new_diffs.drain(0..) {
-                    super::add_diff(diff)?;
-                }
-            }
         }
 
-        data::progress::set_done()?;
+        Ok(std::mem::replace(&mut self.new_diff_paths, vec![]))
+    }
 
-        Ok(new_stuff)
+    /// Sorts some diffs chronologically.
+    ///
+    /// Diffs are sorted by their timestamp, then by the path of the file they come from, so that
+    /// the result does not depend on the order in which the files were discovered.
+    ///
+    /// ```rust
+    /// use charts::{data::Watcher, prelude::{alloc::Diff, time}};
+    /// let diff = |name: &str, secs| {
+    ///     let diff = Diff::new(time::SinceStart::from_secs(secs), vec![], vec![]);
+    ///     (std::path::PathBuf::from(name), diff)
+    /// };
+    /// let mut diffs = vec![diff("c", 3), diff("a2", 1), diff("b", 2), diff("a1", 1), diff("d", 0)];
+    /// Watcher::sort_diffs(&mut diffs);
+    /// let names: Vec<_> = diffs.iter().map(|(path, _)| path.display().to_string()).collect();
+    /// assert_eq!(names, vec!["d", "a1", "a2", "b", "c"]);
+    /// ```
+    pub fn sort_diffs(diffs: &mut [(PathBuf, alloc::Diff)]) {
+        diffs.sort_by(|(path_1, diff_1), (path_2, diff_2)| {
+            diff_1
+                .time
+                .cmp(&diff_2.time)
+                .then_with(|| path_1.cmp(path_2))
+        })
     }
 
     /// Gathers the new diff files.
@@ -541,10 +1049,16 @@ impl Watcher {
         Ok(highest_last_modified)
     }
 
+    /// Loads a diff file.
+    ///
+    /// Fails with a recoverable [`err::ErrorKind::Parse`] error if the file is malformed, and with
+    /// a fatal error if it cannot be read.
     fn load(&mut self, init: &alloc::Init, path: PathBuf) -> Res<alloc::Diff> {
         self.read_content(&path, |content| {
             use alloc_data::parser::Parseable;
-            let diff = alloc::Diff::parse_with(content, init)?;
+            let diff = alloc::Diff::parse_with(content, init).chain_err(|| {
+                err::ErrorKind::Parse(format!("malformed diff file `{}`", path.display()))
+            })?;
             Ok(diff)
         })
         .chain_err(|| format!("while reading content of file `{}`", path.to_string_lossy()))
@@ -23,6 +23,8 @@
 
 prelude! {}
 
+pub mod alive;
+pub mod kind;
 pub mod label;
 pub mod loc;
 pub mod ord;
@@ -34,17 +36,27 @@ pub mod sub;
 #[cfg(any(test, feature = "server"))]
 pub mod gen;
 
+pub use alive::AliveAtFilter;
 #[cfg(any(test, feature = "server"))]
 pub use gen::FilterGen;
+pub use kind::KindFilter;
 pub use label::LabelFilter;
 pub use loc::LocFilter;
 use ord::OrdFilter;
 pub use spec::FilterSpec;
 pub use sub::SubFilter;
 
-/// A filter over allocation sizes.
+/// A filter over allocation sizes, in bytes (see [`alloc_data::Alloc::size`]).
 pub type SizeFilter = OrdFilter<u32>;
 
+/// A filter over the number of samples of an allocation.
+///
+/// The [`SizeFilter`] works on the size of the allocation in bytes. When the sample rate
+/// is not `1.0`, memtrace only samples some of the words allocated, and the number of samples of
+/// an allocation can be quite different from its size. This filter works on the raw sample count
+/// instead, which is what the real size of an allocation is estimated from.
+pub type SamplesFilter = OrdFilter<u32>;
+
 /// A filter over allocation lifetimes.
 pub type LifetimeFilter = OrdFilter<time::Lifetime>;
 impl LifetimeFilter {
@@ -67,6 +79,109 @@ where
     fn apply(&self, alloc_data: &Data) -> bool;
 }
 
+/// Outcome of applying a filter to an allocation, see [`Filter::explain`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchOutcome {
+    /// The allocation matches.
+    Match,
+    /// The allocation does not match, with a short description of each failed check.
+    NoMatch(Vec<String>),
+}
+impl MatchOutcome {
+    /// Failure constructor.
+    pub fn no_match(reason: impl Into<String>) -> Self {
+        Self::NoMatch(vec![reason.into()])
+    }
+
+    /// True if the allocation matches.
+    pub fn is_match(&self) -> bool {
+        match self {
+            Self::Match => true,
+            Self::NoMatch(_) => false,
+        }
+    }
+}
+/// Conjunction of some outcomes.
+impl std::iter::FromIterator<MatchOutcome> for MatchOutcome {
+    fn from_iter<I: IntoIterator<Item = MatchOutcome>>(outcomes: I) -> Self {
+        let mut reasons = vec![];
+        for outcome in outcomes {
+            if let Self::NoMatch(mut sub_reasons) = outcome {
+                reasons.append(&mut sub_reasons)
+            }
+        }
+        if reasons.is_empty() {
+            Self::Match
+        } else {
+            Self::NoMatch(reasons)
+        }
+    }
+}
+impl fmt::Display for MatchOutcome {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Match => "match".fmt(fmt),
+            Self::NoMatch(reasons) => write!(fmt, "no match: {}", reasons.join(", ")),
+        }
+    }
+}
+
+/// Explains how a custom filter handles an allocation, see [`Filters::explain`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterExplanation {
+    /// UID of the filter.
+    pub uid: uid::Filter,
+    /// Name of the filter.
+    pub name: String,
+    /// Outcome of the filter on the allocation.
+    pub outcome: MatchOutcome,
+}
+impl FilterExplanation {
+    /// True if the filter matches the allocation.
+    pub fn is_match(&self) -> bool {
+        self.outcome.is_match()
+    }
+}
+
+/// Explains how the filters handle an allocation, see [`Filters::explain`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Explanation {
+    /// Allocation explained.
+    pub alloc: uid::Alloc,
+    /// Custom filters, in order of priority.
+    pub filters: Vec<FilterExplanation>,
+}
+impl Explanation {
+    /// UIDs of the filters matching the allocation, in order of priority.
+    pub fn matches(&self) -> impl Iterator<Item = uid::Filter> + '_ {
+        self.filters
+            .iter()
+            .filter(|filter| filter.is_match())
+            .map(|filter| filter.uid)
+    }
+
+    /// Filter the allocation is attributed to, `None` if it is the catch-all filter.
+    ///
+    /// This is the first filter matching the allocation.
+    pub fn attributed_to(&self) -> Option<uid::Filter> {
+        self.matches().next()
+    }
+}
+
+/// Kind of reset to apply to the filters, see [`Filters::reset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reset {
+    /// Keeps the filter memory.
+    ///
+    /// Legal only when the filter predicates did not change, *e.g.* when only names or colors
+    /// changed.
+    Soft,
+    /// Forgets the filter memory, allocations will be attributed again from scratch.
+    ///
+    /// Mandatory when the filter predicates, or the order of the filters, changed.
+    Hard,
+}
+
 /// Filter comparison kind.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum CmpKind {
@@ -106,8 +221,14 @@ impl fmt::Display for CmpKind {
 pub enum FilterKind {
     /// Size filter.
     Size,
+    /// Number of samples filter.
+    Samples,
     /// Lifetime filter.
     Lifetime,
+    /// Alive-at-some-time filter.
+    AliveAt,
+    /// Allocation kind filter.
+    Kind,
     /// Label filter.
     Label,
     /// Location filter.
@@ -117,7 +238,10 @@ impl fmt::Display for FilterKind {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Size => write!(fmt, "size"),
+            Self::Samples => write!(fmt, "samples"),
             Self::Lifetime => write!(fmt, "lifetime"),
+            Self::AliveAt => write!(fmt, "alive at"),
+            Self::Kind => write!(fmt, "kind"),
             Self::Label => write!(fmt, "labels"),
             Self::Loc => write!(fmt, "callstack"),
         }
@@ -132,7 +256,10 @@ impl FilterKind {
             // below, which yields all variants, must be updated.
             match Self::Size {
                 Self::Size => (),
+                Self::Samples => (),
                 Self::Lifetime => (),
+                Self::AliveAt => (),
+                Self::Kind => (),
                 Self::Label => (),
                 Self::Loc => (),
             }
@@ -141,7 +268,10 @@ impl FilterKind {
         // Lists all `FilterKind` variants.
         vec![
             FilterKind::Size,
+            FilterKind::Samples,
             FilterKind::Lifetime,
+            FilterKind::AliveAt,
+            FilterKind::Kind,
             // FilterKind::Label,
             FilterKind::Loc,
         ]
@@ -174,6 +304,10 @@ pub struct Filters {
     filters: Vec<Filter>,
     /// Remembers which filter is responsible for an allocation.
     memory: BTMap<uid::Alloc, uid::Filter>,
+    /// Allocation counts not sent to the client yet, see [`Self::update_delta`].
+    delta: stats::FilterDelta,
+    /// Last events counted in the delta, see [`data::Data::last_events`].
+    delta_last: Option<(uid::Alloc, time::SinceStart)>,
 }
 
 impl Filters {
@@ -184,6 +318,8 @@ impl Filters {
             catch_all: FilterSpec::new_catch_all(),
             everything: FilterSpec::new_everything(),
             memory: BTMap::new(),
+            delta: stats::FilterDelta::new(),
+            delta_last: None,
         }
     }
     /// Constructor.
@@ -193,6 +329,8 @@ impl Filters {
             catch_all: FilterSpec::new_catch_all(),
             everything: FilterSpec::new_everything(),
             memory: BTMap::new(),
+            delta: stats::FilterDelta::new(),
+            delta_last: None,
         }
     }
 
@@ -208,6 +346,13 @@ impl Filters {
     pub fn filters(&self) -> &Vec<Filter> {
         &self.filters
     }
+    /// Iterator over all the specifications: everything, custom filters, and catch-all.
+    pub fn specs(&self) -> impl Iterator<Item = &FilterSpec> {
+        Some(&self.everything)
+            .into_iter()
+            .chain(self.filters.iter().map(Filter::spec))
+            .chain(Some(&self.catch_all))
+    }
 
     /// Runs filter generation.
     ///
@@ -258,42 +403,118 @@ impl Filters {
         self.filters.iter_mut()
     }
 
-    /// Remembers that an allocation is handled by some filter.
-    fn remember(
-        memory: &mut BTMap<uid::Alloc, uid::Filter>,
-        alloc: uid::Alloc,
-        filter: uid::Filter,
-    ) {
-        let prev = memory.insert(alloc, filter);
-        let collision = prev.map(|uid| uid != filter).unwrap_or(false);
-        if collision {
-            panic!("filter memory collision")
-        }
-    }
-
     /// Searches for a filter that matches on the input allocation.
+    ///
+    /// Always evaluates the filters and remembers the result, for the death of the allocation,
+    /// see [`Self::find_dead_match`]. Allocations no filter matches are forgotten, which matters
+    /// after a [`Reset::Soft`] as the memory might attribute them to some filter.
     pub fn find_match(
         &mut self,
         timestamp: &time::SinceStart,
         alloc: &Alloc,
     ) -> Option<uid::Filter> {
-        for filter in &self.filters {
-            if filter.apply(timestamp, alloc) {
-                Self::remember(&mut self.memory, alloc.uid().clone(), filter.uid());
-                return Some(filter.uid());
-            }
+        let uid = self.first_match(timestamp, alloc).map(Filter::uid);
+        if let Some(uid) = uid {
+            self.memory.insert(alloc.uid().clone(), uid);
+        } else {
+            self.memory.remove(alloc.uid());
+        }
+        uid
+    }
+
+    /// First filter that matches on the input allocation, if any.
+    ///
+    /// Unlike [`Self::find_match`], does not remember the match.
+    pub fn first_match(&self, timestamp: &time::SinceStart, alloc: &Alloc) -> Option<&Filter> {
+        self.filters
+            .iter()
+            .find(|filter| filter.apply(timestamp, alloc))
+    }
+
+    /// All the filters that match the input allocation, regardless of priority.
+    ///
+    /// Diagnostic function, does not remember anything, see [`Self::explain`].
+    pub fn all_matches(&self, timestamp: &time::SinceStart, alloc: &Alloc) -> Vec<uid::Filter> {
+        self.filters
+            .iter()
+            .filter(|filter| filter.apply(timestamp, alloc))
+            .map(Filter::uid)
+            .collect()
+    }
+
+    /// Explains how the filters handle the input allocation.
+    ///
+    /// Diagnostic function, does not remember anything. The explanation lists all the custom
+    /// filters in order of priority, *i.e.* in the order they are tried, along with the reasons why
+    /// the allocation does not match each of them. In particular, if no filter matches then the
+    /// allocation goes to the catch-all filter.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     alloc_data::prelude::*,
+    ///     filter::{ord::Cmp, *},
+    ///     fixture::{self, size_filter},
+    /// };
+    /// let toc = time::SinceStart::zero();
+    /// let alloc = fixture::alloc(7, AllocKind::Minor, 100, 0, None);
+    ///
+    /// let (big, small) = (size_filter(Cmp::Ge, 50), size_filter(Cmp::Le, 10));
+    /// let huge = size_filter(Cmp::Ge, 75);
+    /// let uids = (big.uid(), small.uid(), huge.uid());
+    /// let filters = Filters::new_with(vec![big, small, huge]);
+    ///
+    /// assert_eq!(filters.all_matches(&toc, &alloc), vec![uids.0, uids.2]);
+    /// let explanation = filters.explain(&toc, &alloc);
+    /// assert_eq!(explanation.alloc, uid::Alloc::from(7usize));
+    /// assert_eq!(explanation.attributed_to(), Some(uids.0));
+    /// assert_eq!(explanation.filters[1].uid, uids.1);
+    /// assert_eq!(
+    ///     explanation.filters[1].outcome,
+    ///     MatchOutcome::NoMatch(vec!["size 100 is not ≤ 10".into()]),
+    /// );
+    /// ```
+    pub fn explain(&self, timestamp: &time::SinceStart, alloc: &Alloc) -> Explanation {
+        let filters = self
+            .filters
+            .iter()
+            .map(|filter| FilterExplanation {
+                uid: filter.uid(),
+                name: filter.name().into(),
+                outcome: filter.explain(timestamp, alloc),
+            })
+            .collect();
+        Explanation {
+            alloc: alloc.uid().clone(),
+            filters,
         }
-        None
     }
 
     /// Searches for a filter that matches on the input allocation, for its death.
+    ///
+    /// Returns the filter the allocation was attributed to by [`Self::find_match`], if any.
     pub fn find_dead_match(&mut self, alloc: &uid::Alloc) -> Option<uid::Filter> {
         self.memory.get(alloc).map(|uid| *uid)
     }
 
     /// Resets all the filters.
-    pub fn reset(&mut self) {
-        self.memory.clear()
+    ///
+    /// A [`Reset::Soft`] keeps the memory, while a [`Reset::Hard`] clears it. After a hard reset,
+    /// the next [delta](Self::take_delta) is a [reset](stats::FilterDelta::is_reset).
+    pub fn reset(&mut self, reset: Reset) {
+        match reset {
+            Reset::Soft => (),
+            Reset::Hard => {
+                self.memory.clear();
+                self.delta = stats::FilterDelta::new_reset();
+                self.delta_last = None;
+            }
+        }
+    }
+
+    /// Retrieves the allocation counts accumulated since the last call, see
+    /// [`stats::FilterDelta`].
+    pub fn take_delta(&mut self) -> stats::FilterDelta {
+        std::mem::take(&mut self.delta)
     }
 
     /// Fold over all the filter UIDs.
@@ -321,19 +542,124 @@ impl Filters {
 /// # Message handling
 impl Filters {
     /// Applies a filter message.
-    pub fn update(&mut self, msg: msg::to_server::FiltersMsg) -> Res<(msg::to_client::Msgs, bool)> {
+    ///
+    /// The charts `settings` are used to decide the color of new filters. Also returns the kind of
+    /// reset the points must be reloaded with, if they must be reloaded.
+    pub fn update(
+        &mut self,
+        msg: msg::to_server::FiltersMsg,
+        settings: &settings::Charts,
+    ) -> Res<(msg::to_client::Msgs, Option<Reset>)> {
         use msg::to_server::FiltersMsg::*;
-        let (res, should_reload) = match msg {
-            RequestNew => (self.add_new(), false),
-            RequestNewSub(uid) => (self.add_new_sub(uid), false),
-            Revert => (self.revert(), false),
+        match msg {
+            RequestNew => self.add_new(settings).map(|msgs| (msgs, None)),
+            RequestNewSub(uid) => self.add_new_sub(uid).map(|msgs| (msgs, None)),
+            ToggleActive(uid) => {
+                self.toggle_active(uid)?;
+                // Only the series change, allocations stay attributed to the same filters.
+                Ok((vec![], Some(Reset::Soft)))
+            }
+            Reorder(order) => {
+                self.reorder(order)?;
+                // Priorities changed, allocations might go to different filters.
+                Ok((vec![], Some(Reset::Hard)))
+            }
+            Revert => self.revert().map(|msgs| (msgs, None)),
+            RequestLeaks => {
+                let mut msgs = self.add_leaks(settings, Self::leak_time()?)?;
+                msgs.extend(self.leaks_notice()?);
+                Ok((msgs, Some(Reset::Hard)))
+            }
             UpdateAll {
                 everything,
                 filters,
                 catch_all,
-            } => (self.update_all(everything, filters, catch_all), true),
-        };
-        res.map(|msgs| (msgs, should_reload))
+            } => {
+                let errors = Self::check_all(&filters);
+                if errors.is_empty() {
+                    self.update_all(everything, filters, catch_all, settings.name_colors())
+                        .map(|(msgs, reset)| (msgs, Some(reset)))
+                } else {
+                    Ok((errors, None))
+                }
+            }
+        }
+    }
+
+    /// Checks the subfilters of some filters.
+    ///
+    /// Yields one error message for each illegal subfilter.
+    ///
+    /// ```rust
+    /// use charts::filter::{label::LabelSpec, sub::RawSubFilter, *};
+    /// use charts::msg::to_client::{FiltersMsg, Msg};
+    ///
+    /// let mut filter = Filter::new(FilterSpec::new(charts::color::Color::new(0, 0, 0))).unwrap();
+    /// let label = |s: &str| LabelFilter::contain(vec![LabelSpec::new(s).unwrap()]);
+    /// let label = |s: &str| SubFilter::from(label(s));
+    /// let good = label(r##"#"a.*"#"##);
+    /// // Compiles fine client-side, but it is way too big for the server.
+    /// let bad = label(r##"#"\w{50}"#"##);
+    /// let bad_uid = bad.uid();
+    /// filter.insert(good).unwrap();
+    /// filter.insert(bad).unwrap();
+    ///
+    /// let errors = Filters::check_all(&[filter.clone()]);
+    /// assert_eq!(errors.len(), 1);
+    /// match &errors[0] {
+    ///     Msg::Filters(FiltersMsg::SubError { filter: f_uid, sub, .. }) => {
+    ///         assert_eq!(*f_uid, filter.uid());
+    ///         assert_eq!(*sub, bad_uid);
+    ///     }
+    ///     msg => panic!("unexpected message {}", msg),
+    /// }
+    /// ```
+    pub fn check_all(filters: &[Filter]) -> msg::to_client::Msgs {
+        let mut errors = vec![];
+        for filter in filters {
+            for sub in filter.iter() {
+                if let Err(e) = sub.check() {
+                    errors.push(msg::to_client::FiltersMsg::sub_error(
+                        filter.uid(),
+                        sub.uid(),
+                        e.to_string(),
+                    ))
+                }
+            }
+        }
+        errors
+    }
+
+    /// Registers some new filters after the existing ones.
+    ///
+    /// Fails if a filter has an illegal subfilter, or if its UID is already registered. On success,
+    /// the filters are sent to the client (see [`Self::revert`]).
+    ///
+    /// ```rust
+    /// use charts::{color::Color, filter::*};
+    /// let filter = Filter::new(FilterSpec::new(Color::new(0, 0, 0))).unwrap();
+    /// let mut filters = Filters::new();
+    /// filters.add_all(vec![filter.clone()]).unwrap();
+    /// assert_eq!(filters.len(), 1);
+    /// assert!(filters.add_all(vec![filter]).is_err());
+    /// assert_eq!(filters.len(), 1);
+    /// ```
+    pub fn add_all(&mut self, filters: Vec<Filter>) -> Res<msg::to_client::Msgs> {
+        for (index, filter) in filters.iter().enumerate() {
+            let mut known = self.filters.iter().chain(&filters[0..index]);
+            if known.any(|known| known.uid() == filter.uid()) {
+                bail!(
+                    "cannot add filter with already registered UID #{}",
+                    filter.uid()
+                )
+            }
+            for sub in filter.iter() {
+                sub.check()
+                    .chain_err(|| format!("illegal subfilter in filter `{}`", filter.name()))?
+            }
+        }
+        self.filters.extend(filters);
+        self.revert()
     }
 
     /// Sends all the filters to the client.
@@ -347,26 +673,265 @@ impl Filters {
     }
 
     /// Updates all the filters.
+    ///
+    /// If `name_colors`, renamed filters whose color was derived from their previous name get a
+    /// new color derived from their new name. Colors set by the user are left untouched.
+    ///
+    /// Also returns the kind of reset the update requires: [`Reset::Soft`] if the new filters have
+    /// the same predicates as the old ones, in the same order, and [`Reset::Hard`] otherwise.
+    ///
+    /// ```rust
+    /// use charts::{color::Color, filter::*};
+    /// let mut filter = Filter::new(FilterSpec::new(Color::new(0, 0, 0))).unwrap();
+    /// filter.insert(SubFilter::from(SizeFilter::cmp(ord::Cmp::Ge, 50))).unwrap();
+    /// let mut filters = Filters::new_with(vec![filter.clone()]);
+    /// let (everything, catch_all) = (filters.everything().clone(), filters.catch_all().clone());
+    ///
+    /// // Only the color changes.
+    /// filter.spec_mut().set_color(Color::new(255, 0, 0));
+    /// let (_, reset) = filters
+    ///     .update_all(everything.clone(), vec![filter.clone()], catch_all.clone(), false)
+    ///     .unwrap();
+    /// assert_eq!(reset, Reset::Soft);
+    ///
+    /// // The predicate changes.
+    /// filter.insert(SubFilter::from(SizeFilter::cmp(ord::Cmp::Le, 100))).unwrap();
+    /// let (_, reset) = filters
+    ///     .update_all(everything, vec![filter], catch_all, false)
+    ///     .unwrap();
+    /// assert_eq!(reset, Reset::Hard);
+    /// ```
     pub fn update_all(
         &mut self,
         everything: FilterSpec,
-        filters: Vec<Filter>,
+        mut filters: Vec<Filter>,
         catch_all: FilterSpec,
-    ) -> Res<msg::to_client::Msgs> {
+        name_colors: bool,
+    ) -> Res<(msg::to_client::Msgs, Reset)> {
+        let same_preds = self.filters.len() == filters.len()
+            && self
+                .filters
+                .iter()
+                .zip(filters.iter())
+                .all(|(old, new)| old.same_preds(new));
+        let reset = if same_preds { Reset::Soft } else { Reset::Hard };
+        let mut msgs = vec![];
+        if name_colors {
+            let mut recolored = false;
+            for filter in filters.iter_mut() {
+                let old = self
+                    .filters
+                    .iter()
+                    .find(|old| old.uid() == filter.uid())
+                    .map(Filter::spec);
+                if let Some(old) = old {
+                    let spec = filter.spec_mut();
+                    if old.name() != spec.name()
+                        && old.color() == spec.color()
+                        && *old.color() == Color::from_name(old.name())
+                    {
+                        let color = Color::from_name(spec.name());
+                        spec.set_color(color);
+                        recolored = true
+                    }
+                }
+            }
+            if recolored {
+                msgs.push(msg::to_client::FiltersMsg::revert(
+                    everything.clone(),
+                    filters.clone(),
+                    catch_all.clone(),
+                ))
+            }
+        }
         self.catch_all = catch_all;
         self.everything = everything;
         self.filters = filters;
-        Ok(vec![])
+        Ok((msgs, reset))
+    }
+
+    /// Sets the order of the custom filters, *i.e.* their priority.
+    ///
+    /// Fails if `order` is not a permutation of the UIDs of the filters, in which case the filters
+    /// are left untouched.
+    ///
+    /// ```rust
+    /// use charts::{color::Color, filter::*};
+    /// let filter = || Filter::new(FilterSpec::new(Color::new(0, 0, 0))).unwrap();
+    /// let (f_1, f_2, f_3) = (filter(), filter(), filter());
+    /// let (uid_1, uid_2, uid_3) = (f_1.uid(), f_2.uid(), f_3.uid());
+    /// let mut filters = Filters::new_with(vec![f_1, f_2, f_3]);
+    /// let order = |filters: &Filters| filters.iter().map(Filter::uid).collect::<Vec<_>>();
+    ///
+    /// filters.reorder(vec![uid_3, uid_1, uid_2]).unwrap();
+    /// assert_eq!(order(&filters), vec![uid_3, uid_1, uid_2]);
+    ///
+    /// // Missing filter.
+    /// let err = filters.reorder(vec![uid_1, uid_2]).unwrap_err();
+    /// assert!(err.to_string().contains("is missing"));
+    /// // Duplicate filter.
+    /// let err = filters.reorder(vec![uid_1, uid_2, uid_1]).unwrap_err();
+    /// assert!(err.to_string().contains("appears more than once"));
+    /// assert_eq!(order(&filters), vec![uid_3, uid_1, uid_2]);
+    /// ```
+    pub fn reorder(&mut self, order: Vec<uid::Filter>) -> Res<()> {
+        let mut seen = BTSet::new();
+        for uid in &order {
+            if !seen.insert(*uid) {
+                bail!(
+                    "illegal filter order, filter #{} appears more than once",
+                    uid
+                )
+            }
+            if self.filters.iter().all(|filter| filter.uid() != *uid) {
+                bail!("illegal filter order, unknown filter UID #{}", uid)
+            }
+        }
+        if let Some(missing) = self.filters.iter().find(|f| !seen.contains(&f.uid())) {
+            bail!("illegal filter order, filter #{} is missing", missing.uid())
+        }
+        self.filters
+            .sort_by_key(|filter| order.iter().position(|uid| *uid == filter.uid()));
+        Ok(())
     }
 
-    /// Adds a new filter.
-    pub fn add_new(&mut self) -> Res<msg::to_client::Msgs> {
-        let spec = FilterSpec::new(Color::random());
+    /// Activates an inactive filter, or deactivates an active one.
+    ///
+    /// See [`FilterSpec::is_active`].
+    ///
+    /// ```rust
+    /// use charts::filter::*;
+    /// let mut filters = Filters::new();
+    /// let uid = filters.catch_all().uid();
+    /// assert!(filters.catch_all().is_active());
+    /// filters.toggle_active(uid).unwrap();
+    /// assert!(!filters.catch_all().is_active());
+    /// filters.toggle_active(uid).unwrap();
+    /// assert!(filters.catch_all().is_active());
+    /// ```
+    pub fn toggle_active(&mut self, uid: uid::Line) -> Res<()> {
+        let spec = match uid {
+            uid::Line::Everything => &mut self.everything,
+            uid::Line::CatchAll => &mut self.catch_all,
+            uid::Line::Filter(uid) => self.get_mut(uid)?.1.spec_mut(),
+        };
+        spec.set_active(!spec.is_active());
+        Ok(())
+    }
+
+    /// Adds a new filter, its color is decided by the charts `settings`.
+    pub fn add_new(&mut self, settings: &settings::Charts) -> Res<msg::to_client::Msgs> {
+        let mut spec = FilterSpec::new(Color::BLACK);
+        let color = settings.new_filter_color(spec.name(), self.filters.len());
+        spec.set_color(color);
         let filter = Filter::new(spec).chain_err(|| "while creating new filter")?;
         let msg = msg::to_client::FiltersMsg::add(filter);
         Ok(vec![msg])
     }
 
+    /// Adds and registers a filter keeping the allocations alive at `end`.
+    ///
+    /// When `end` is the end of the run, these are the *leaked* allocations: the ones that were
+    /// never freed. The filter comes first so that it catches all the leaked allocations,
+    /// regardless of the other filters. If there already is a filter named `leaks`, its subfilters
+    /// are replaced and it is moved first instead. Yields the message sending the new list of
+    /// filters to the client.
+    ///
+    /// ```rust
+    /// use charts::{chart::settings, filter::*, prelude::time};
+    /// let mut filters = Filters::new();
+    /// let settings = settings::Charts::new();
+    /// filters.add_new(&settings).unwrap();
+    /// let end = time::SinceStart::from_secs(7);
+    /// let msgs = filters.add_leaks(&settings, end).unwrap();
+    /// assert_eq!(msgs.len(), 1);
+    /// let leaks = &filters.filters()[0];
+    /// assert_eq!(leaks.spec().name(), "leaks");
+    /// assert_eq!(leaks.iter().count(), 1);
+    /// let (uid, len) = (leaks.uid(), filters.len());
+    ///
+    /// // Asking again updates the same filter.
+    /// let later = time::SinceStart::from_secs(11);
+    /// filters.add_leaks(&settings, later).unwrap();
+    /// assert_eq!(filters.len(), len);
+    /// let leaks = &filters.filters()[0];
+    /// assert_eq!(leaks.uid(), uid);
+    /// assert_eq!(leaks.iter().count(), 1);
+    /// let sub = leaks.iter().next().unwrap().to_string();
+    /// assert!(sub.ends_with(&format!("(alive {})", AliveAtFilter::new(later))), "{}", sub);
+    /// ```
+    pub fn add_leaks(
+        &mut self,
+        settings: &settings::Charts,
+        end: time::SinceStart,
+    ) -> Res<msg::to_client::Msgs> {
+        let mut filter = if let Some(idx) = self.filters.iter().position(|f| f.name() == "leaks") {
+            let mut filter = self.filters.remove(idx);
+            let subs: Vec<_> = filter.iter().map(SubFilter::uid).collect();
+            for sub in subs {
+                filter
+                    .remove(sub)
+                    .chain_err(|| "while updating leaks filter")?
+            }
+            filter
+        } else {
+            let mut spec = FilterSpec::new(Color::BLACK);
+            spec.set_name("leaks");
+            let color = settings.new_filter_color(spec.name(), self.filters.len());
+            spec.set_color(color);
+            Filter::new(spec).chain_err(|| "while creating leaks filter")?
+        };
+        filter
+            .insert(SubFilter::from(AliveAtFilter::new(end)))
+            .chain_err(|| "while creating leaks filter")?;
+        self.filters.insert(0, filter);
+        self.revert()
+    }
+
+    /// Time at which live allocations are considered leaked, see [`Data::leak_time`].
+    ///
+    /// [`Data::leak_time`]: crate::data::Data::leak_time (leak_time method of Data)
+    #[cfg(any(test, feature = "server"))]
+    fn leak_time() -> Res<time::SinceStart> {
+        Ok(data::get()?.leak_time())
+    }
+    /// Time at which live allocations are considered leaked.
+    ///
+    /// Only the server knows the data, so this always fails.
+    #[cfg(not(any(test, feature = "server")))]
+    fn leak_time() -> Res<time::SinceStart> {
+        bail!("cannot compute the end of the run client-side")
+    }
+
+    /// Counts the leaked allocations, warns the client if the run is not over.
+    ///
+    /// While the run is going on, leaked allocations might still be freed later.
+    #[cfg(any(test, feature = "server"))]
+    fn leaks_notice(&self) -> Res<Option<msg::to_client::Msg>> {
+        let data = data::get()?;
+        let count = data.leaked(self).len();
+        if data.end_time().is_some() {
+            log::info!("{} allocation(s) were never freed", count);
+            Ok(None)
+        } else {
+            Ok(Some(msg::to_client::Msg::alert(
+                format!(
+                    "{} allocation(s) are not freed yet, \
+                    but the run is not over and they might still be freed",
+                    count
+                ),
+                false,
+            )))
+        }
+    }
+    /// Counts the leaked allocations, warns the client if the run is not over.
+    ///
+    /// Only the server knows the data, so this never yields anything.
+    #[cfg(not(any(test, feature = "server")))]
+    fn leaks_notice(&self) -> Res<Option<msg::to_client::Msg>> {
+        Ok(None)
+    }
+
     /// Adds a new sub-filter.
     pub fn add_new_sub(&mut self, uid: uid::Filter) -> Res<msg::to_client::Msgs> {
         let msg = msg::to_client::FiltersMsg::add_sub(uid, SubFilter::default());
@@ -374,32 +939,112 @@ impl Filters {
     }
 
     /// Extract filter statistics.
+    ///
+    /// Goes over all the allocations, the client maintains its statistics from the
+    /// [deltas](Self::take_delta) of the points messages instead.
     #[cfg(any(test, feature = "server"))]
     pub fn filter_stats(&self) -> Res<stats::AllFilterStats> {
+        let data = data::get()?;
         let mut stats = stats::AllFilterStats::new();
-        let mut registered = 0;
+        let (mut registered, mut registered_dead) = (0, 0);
 
-        for (_, filter) in &self.memory {
+        for (alloc, filter) in &self.memory {
+            let dead = data
+                .get_alloc(*alloc)
+                .map(|alloc| alloc.tod().is_some())
+                .unwrap_or(false);
             registered += 1;
-            stats.stats_do((*filter).into(), |stats| stats.inc())
+            if dead {
+                registered_dead += 1
+            }
+            stats.stats_do((*filter).into(), |stats| {
+                stats.inc();
+                if dead {
+                    stats.dead_count += 1
+                }
+            })
         }
 
-        let total = data::alloc_count()?;
-        if registered > total {
+        let total = data.alloc_count();
+        let total_dead = data.iter_dead_since(time::SinceStart::zero()).count();
+        if registered > total || registered_dead > total_dead {
             bail!(
-                "inconsistent state, extracted filter stats for {} allocation, \
-                but allocation count is {}",
+                "inconsistent state, extracted filter stats for {} allocation(s) ({} dead), \
+                but allocation count is {} ({} dead)",
                 registered,
+                registered_dead,
                 total,
+                total_dead,
             )
         }
 
         stats.stats_do(uid::Line::CatchAll, |stats| {
-            stats.alloc_count = total - registered
+            stats.alloc_count = total - registered;
+            stats.dead_count = total_dead - registered_dead;
         });
 
         Ok(stats)
     }
+
+    /// Counts the new and dead allocations since the last update, for each filter.
+    ///
+    /// The counts accumulate until retrieved with [`Self::take_delta`]. New allocations go to the
+    /// first filter that matches them, deaths go to the filter that owned the allocation, as for
+    /// the charts (see [`Self::find_match`] and [`Self::find_dead_match`]).
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     filter::{ord::Cmp, *},
+    ///     fixture::*,
+    ///     prelude::{alloc::Diff, *},
+    /// };
+    /// let new = vec![builder(0, 100, 1), builder(1, 1, 1)];
+    /// let mut data = data(vec![Diff::new(secs(1), new, vec![])]);
+    ///
+    /// let big = size_filter(Cmp::Ge, 50);
+    /// let big_line = uid::Line::Filter(big.uid());
+    /// let mut filters = Filters::new_with(vec![big]);
+    ///
+    /// filters.update_delta(&data).unwrap();
+    /// let delta = filters.take_delta();
+    /// assert!(!delta.is_reset());
+    /// assert_eq!(delta.get(big_line).unwrap().new, 1);
+    /// assert_eq!(delta.get(uid::Line::CatchAll).unwrap().new, 1);
+    ///
+    /// let dead = vec![(uid(0), secs(2))];
+    /// data.add_diff(Diff::new(secs(2), vec![builder(2, 100, 2)], dead)).unwrap();
+    /// filters.update_delta(&data).unwrap();
+    /// let delta = filters.take_delta();
+    /// assert_eq!(delta.get(big_line), Some(&stats::LineDelta { new: 1, dead: 1 }));
+    /// assert_eq!(delta.get(uid::Line::CatchAll), None);
+    ///
+    /// // Counting starts over after a hard reset.
+    /// filters.reset(Reset::Hard);
+    /// filters.update_delta(&data).unwrap();
+    /// let delta = filters.take_delta();
+    /// assert!(delta.is_reset());
+    /// assert_eq!(delta.get(big_line), Some(&stats::LineDelta { new: 2, dead: 1 }));
+    /// assert!(filters.take_delta().is_empty());
+    /// ```
+    #[cfg(any(test, feature = "server"))]
+    pub fn update_delta(&mut self, data: &data::Data) -> Res<()> {
+        let last = self.delta_last;
+        data.iter_new_events(last, |new_or_dead| {
+            let (f_uid, new) = match new_or_dead {
+                Either::Left(alloc) => (self.find_match(data.current_time(), alloc), true),
+                Either::Right((_, alloc)) => (self.find_dead_match(alloc.uid()), false),
+            };
+            let line = f_uid.map(uid::Line::Filter).unwrap_or(uid::Line::CatchAll);
+            if new {
+                self.delta.add_new(line)
+            } else {
+                self.delta.add_dead(line)
+            }
+            Ok(true)
+        })?;
+        self.delta_last = data.last_events();
+        Ok(())
+    }
 }
 
 /// A filter that combines `SubFilter`s.
@@ -458,6 +1103,31 @@ impl Filter {
         true
     }
 
+    /// Applies the filters to an allocation, explains why the allocation does not match.
+    ///
+    /// Slower than [`Self::apply`], only meant for diagnostics.
+    pub fn explain(&self, timestamp: &time::SinceStart, alloc: &Alloc) -> MatchOutcome {
+        self.subs
+            .values()
+            .map(|filter| filter.explain(timestamp, alloc))
+            .collect()
+    }
+
+    /// True if both filters have the same UID and the same subfilters.
+    ///
+    /// Ignores the filter specifications, *i.e.* names and colors.
+    pub fn same_preds(&self, other: &Self) -> bool {
+        self.uid() == other.uid()
+            && self.subs.len() == other.subs.len()
+            && self
+                .subs
+                .iter()
+                .zip(other.subs.iter())
+                .all(|((lft_uid, lft), (rgt_uid, rgt))| {
+                    lft_uid == rgt_uid && lft.raw() == rgt.raw()
+                })
+    }
+
     /// Removes a subfilter.
     pub fn remove(&mut self, sub_uid: uid::SubFilter) -> Res<()> {
         let prev = self.subs.remove(&sub_uid);
@@ -0,0 +1,116 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+//! Filter over allocations alive at some point in time.
+
+prelude! {}
+
+/// Keeps the allocations that are alive at some point in time.
+///
+/// An allocation is alive at `at` if `toc <= at < tod`, where an allocation that never dies has an
+/// infinite time of death.
+///
+/// ```rust
+/// use charts::{
+///     alloc_data::prelude::*,
+///     filter::AliveAtFilter,
+///     fixture::{alloc, secs},
+/// };
+/// // Created at 2 seconds, the first one dies at 5 seconds.
+/// let dead = alloc(0, AllocKind::Minor, 1, 2, Some(5));
+/// let live = alloc(1, AllocKind::Minor, 1, 2, None);
+///
+/// let at = |time| AliveAtFilter::new(secs(time));
+/// // Not born yet.
+/// assert!(!at(1).apply(&dead));
+/// assert!(!at(1).apply(&live));
+/// // Exactly at the time of creation.
+/// assert!(at(2).apply(&dead));
+/// assert!(at(2).apply(&live));
+/// // Exactly at the time of death.
+/// assert!(!at(5).apply(&dead));
+/// assert!(at(5).apply(&live));
+/// // Way after the time of death.
+/// assert!(!at(1_000).apply(&dead));
+/// assert!(at(1_000).apply(&live));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AliveAtFilter {
+    /// Time at which allocations must be alive.
+    at: time::SinceStart,
+}
+
+impl AliveAtFilter {
+    /// Constructor.
+    pub fn new(at: time::SinceStart) -> Self {
+        Self { at }
+    }
+
+    /// Time at which allocations must be alive.
+    pub fn at(&self) -> time::SinceStart {
+        self.at
+    }
+
+    /// Applies the filter to an allocation.
+    pub fn apply(&self, alloc: &Alloc) -> bool {
+        alloc.is_live_at(self.at)
+    }
+
+    /// Updates the filter.
+    ///
+    /// Returns `true` iff the filter actually changed.
+    pub fn update(&mut self, update: AliveAtUpdate) -> Res<bool> {
+        let has_changed = match update {
+            AliveAtUpdate::At(at) => {
+                if at != self.at {
+                    self.at = at;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        Ok(has_changed)
+    }
+}
+
+/// An update for an alive-at filter.
+pub enum AliveAtUpdate {
+    /// Change the time at which allocations must be alive.
+    At(time::SinceStart),
+}
+
+base::implement! {
+    impl AliveAtFilter {
+        Display {
+            |&self, fmt| write!(fmt, "@ {}", self.at)
+        }
+
+        Default {
+            Self::new(time::SinceStart::zero()),
+        }
+    }
+
+    impl AliveAtUpdate {
+        Display {
+            |&self, fmt| match self {
+                Self::At(at) => write!(fmt, "alive at {}", at),
+            }
+        }
+    }
+}
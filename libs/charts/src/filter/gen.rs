@@ -23,9 +23,12 @@
 //! parameters (if any) for an actual filter-generation strategy. The following functions allow to
 //! interact with this global configuration: [`get`], [`set`], and [`set_from_cla`].
 //!
+//! Filters can also be loaded from a configuration file, see the [`config`] module.
+//!
 //! # Filter Generation Strategies
 //!
-//! Strategies are defined in sub-modules such as [`inactive`] and [`alloc_site`]. They typically
+//! Strategies are defined in sub-modules such as [`inactive`], [`alloc_site`], [`by_file`],
+//! [`by_kind`] and [`by_size`]. They typically
 //! define a unit-struct (*e.g.* [`AllocSite`]) implementing the [`FilterGenExt` trait][ext] which
 //! defines a parameter type [`Params`] among other things.
 //!
@@ -48,11 +51,19 @@ prelude! {}
 pub mod parser;
 
 pub mod alloc_site;
+pub mod by_file;
+pub mod by_kind;
+pub mod by_size;
 pub mod chart_gen;
+pub mod config;
 pub mod inactive;
 
 use self::{
     alloc_site::{AllocSite, AllocSiteParams},
+    by_file::{ByFile, ByFileParams},
+    by_kind::ByKind,
+    by_size::BySizeBucket,
+    config::{Config, FromConfig},
     inactive::Inactive,
     parser::Parser,
 };
@@ -75,10 +86,16 @@ pub fn set(gen: impl Into<FilterGen>) {
 
 /// Sets the active filter generator from a command-line argument.
 ///
-/// See [`FilterGen::from_cla`] for details.
+/// If `args` starts with `@`, the rest of `args` is the path to a configuration file to load the
+/// filters from, see the [`config`] module. Otherwise, see [`FilterGen::from_cla`] for details.
 pub fn set_from_cla(args: &str) -> Res<()> {
-    let gen = FilterGen::from_cla(args)
-        .chain_err(|| format!("while parsing filter-gen argument `{}`", args))?;
+    let args = args.trim();
+    let gen = if let Some(path) = args.strip_prefix(FromConfig::PREFIX) {
+        Config::load(path.trim()).map(FilterGen::Config)
+    } else {
+        FilterGen::from_cla(args)
+    }
+    .chain_err(|| format!("while parsing filter-gen argument `{}`", args))?;
     set(gen);
     Ok(())
 }
@@ -100,6 +117,14 @@ lazy_static! {
 pub enum FilterGen {
     /// Generate one allocation filter per allocation site.
     AllocSite(AllocSiteParams),
+    /// Generate one allocation filter per top allocation-site file.
+    ByFile(ByFileParams),
+    /// Generate one allocation filter per allocation kind.
+    ByKind,
+    /// Generate one allocation filter per order-of-magnitude band of sizes.
+    BySizeBucket,
+    /// Load the filters and charts from a configuration file.
+    Config(Config),
     /// No filter generation.
     Inactive,
 }
@@ -108,6 +133,11 @@ impl From<AllocSiteParams> for FilterGen {
         Self::AllocSite(params)
     }
 }
+impl From<ByFileParams> for FilterGen {
+    fn from(params: ByFileParams) -> Self {
+        Self::ByFile(params)
+    }
+}
 
 impl Default for FilterGen {
     fn default() -> Self {
@@ -125,6 +155,9 @@ macro_rules! all_gens {
         [
             $($pref)* Inactive $($suff)*,
             $($pref)* AllocSite $($suff)*,
+            $($pref)* ByFile $($suff)*,
+            $($pref)* ByKind $($suff)*,
+            $($pref)* BySizeBucket $($suff)*,
         ]
     };
     // Generates an array `[ ... ]` token tree. Its elements are the names of the generators, each
@@ -139,6 +172,10 @@ impl FilterGen {
     pub fn run(self, data: &data::Data) -> Res<(Filters, Vec<chart::Chart>)> {
         match self {
             Self::AllocSite(params) => AllocSite::work(data, params),
+            Self::ByFile(params) => ByFile::work(data, params),
+            Self::ByKind => ByKind::work(data, ()),
+            Self::BySizeBucket => BySizeBucket::work(data, ()),
+            Self::Config(config) => FromConfig::work(data, config),
             Self::Inactive => Inactive::work(data, ()),
         }
     }
@@ -173,7 +210,8 @@ among several generators and parameterize them.
 
 This flag takes a string argument, which has shapes
 - `<gen> {{ <params> }}`: use generator `<gen>` with parameters `<params>`, or
-- `<gen>`: use generator `<gen>` in its default mode.
+- `<gen>`: use generator `<gen>` in its default mode, or
+- `@<path>`: load the filters from configuration file `<path>`.
 
 The different generators are
 
@@ -184,6 +222,7 @@ The different generators are
         for add_help in all_gens!(::add_help).iter() {
             add_help(&mut s)
         }
+        FromConfig::add_help(&mut s);
 
         s
     }
@@ -0,0 +1,149 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Top-source-file-based automatic filter generation.
+//!
+//! Parameterized with an optional `count: usize`. This generator generates one filter for each of
+//! the `count` allocation-site-files responsible for the most allocations. Allocations from the
+//! other files go to the catch-all filter.
+//!
+//! When no `count` parameter is present, the generator keeps the top [`DEFAULT_COUNT`] files.
+
+prelude! {}
+
+use filter::gen::*;
+
+/// Default number of files to generate filters for.
+pub const DEFAULT_COUNT: usize = 10;
+
+/// Parameters for the top-file generator.
+#[derive(Debug, Clone)]
+pub struct ByFileParams {
+    /// Number of files to generate filters for.
+    count: usize,
+}
+impl Default for ByFileParams {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_COUNT,
+        }
+    }
+}
+
+impl ByFileParams {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Unit-struct handling CLAP and creating/running the top-file generator.
+pub struct ByFile;
+
+/// Name of the `count` key.
+const COUNT_KEY: &str = "count";
+
+impl FilterGenExt for ByFile {
+    type Params = ByFileParams;
+
+    const KEY: &'static str = "by_file";
+    const FMT: Option<&'static str> = Some("count: <int>");
+
+    fn work(data: &data::Data, params: Self::Params) -> Res<(Filters, Vec<chart::Chart>)> {
+        let mut counts: BTMap<String, usize> = BTMap::new();
+        for alloc in data.iter_allocs() {
+            alloc.alloc_site_do(|cloc_opt| {
+                if let Some(cloc) = cloc_opt {
+                    cloc.loc
+                        .file
+                        .str_do(|file| *counts.entry(file.to_string()).or_insert(0) += 1)
+                }
+            })
+        }
+
+        // Rev-sorting by number of allocations, ties are broken by file name.
+        let mut files: Vec<_> = counts.into_iter().collect();
+        files.sort_by(|(lft_file, lft), (rgt_file, rgt)| {
+            rgt.cmp(lft).then_with(|| lft_file.cmp(rgt_file))
+        });
+        files.truncate(params.count);
+
+        let mut colors = Color::randoms(files.len()).into_iter();
+        let mut filters = Vec::with_capacity(files.len());
+        for (file, _count) in files {
+            let color = colors.next().expect(
+                "internal error, the number of colors is not consistent with the number of files",
+            );
+            let mut spec = filter::FilterSpec::new(color);
+            spec.set_name(file.clone());
+            let mut filter = filter::Filter::new(spec)?;
+            filter.insert(alloc_site::AllocSiteWork::generate_subfilter(&file))?;
+            filters.push(filter)
+        }
+
+        let filters = Filters::new_with(filters);
+        let charts = chart_gen::default(&filters)?;
+        Ok((filters, charts))
+    }
+
+    fn parse_args(parser: Option<Parser>) -> Option<FilterGen> {
+        let mut parser = if let Some(parser) = parser {
+            parser
+        } else {
+            return Some(Self::Params::default().into());
+        };
+
+        let mut params = ByFileParams::default();
+
+        if parser.id_tag(COUNT_KEY) {
+            parser.ws();
+            if !parser.char(':') {
+                return None;
+            }
+            parser.ws();
+            params.count = parser.usize()?;
+        } else {
+            return None;
+        }
+
+        parser.ws();
+        if !parser.is_at_eoi() {
+            return None;
+        }
+
+        Some(params.into())
+    }
+
+    fn add_help(s: &mut String) {
+        s.push_str(&format!(
+            "\
+- top file generator: `{0} {{ {1} }}`
+    Generates one filter for each of the `{2}` allocation site files responsible for the most
+    allocations.
+    Defaults: `{2}: {3}`.
+
+\
+            ",
+            Self::KEY,
+            Self::FMT.unwrap(),
+            COUNT_KEY,
+            DEFAULT_COUNT,
+        ));
+    }
+}
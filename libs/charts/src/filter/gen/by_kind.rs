@@ -0,0 +1,117 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Allocation-kind-based automatic filter generation.
+//!
+//! Generates one filter per allocation kind appearing in the data.
+
+prelude! {}
+
+use alloc::AllocKind;
+use filter::gen::*;
+
+/// Unit-struct handling the allocation-kind generator.
+///
+/// ```rust
+/// use charts::{
+///     filter::{gen::FilterGen, sub::RawSubFilter, KindFilter},
+///     prelude::*,
+/// };
+/// charts::data::start("../ctf/rsc/synthetic.ctf", Default::default()).unwrap();
+/// # while charts::data::progress::get().unwrap().is_some()
+/// #     || charts::data::alloc_count().unwrap() == 0
+/// # {
+/// #     std::thread::sleep(std::time::Duration::from_millis(50))
+/// # }
+/// let data = charts::data::get().unwrap();
+/// let kinds: BTSet<_> = data.iter_allocs().map(|alloc| *alloc.kind()).collect();
+///
+/// let (filters, charts) = FilterGen::from_cla("by_kind").unwrap().run(&data).unwrap();
+/// assert_eq!(charts.len(), 1);
+/// assert_eq!(filters.len(), kinds.len());
+/// for (filter, kind) in filters.iter().zip(&kinds) {
+///     let subs: Vec<_> = filter.iter().map(|sub| sub.raw().clone()).collect();
+///     assert_eq!(subs, vec![RawSubFilter::Kind(KindFilter::new(*kind))]);
+/// }
+/// // Each allocation goes to the filter of its kind.
+/// for alloc in data.iter_allocs() {
+///     let filter = filters.first_match(&alloc.toc(), alloc).unwrap();
+///     let subs: Vec<_> = filter.iter().map(|sub| sub.raw().clone()).collect();
+///     assert_eq!(subs, vec![RawSubFilter::Kind(KindFilter::new(*alloc.kind()))]);
+/// }
+/// ```
+pub struct ByKind;
+
+impl ByKind {
+    /// Name of the filter for some allocation kind.
+    fn name(kind: AllocKind) -> String {
+        match kind {
+            AllocKind::Unknown => "unknown kind".into(),
+            kind => kind.as_str().into(),
+        }
+    }
+}
+
+impl FilterGenExt for ByKind {
+    type Params = ();
+
+    const KEY: &'static str = "by_kind";
+    const FMT: Option<&'static str> = None;
+
+    fn work(data: &data::Data, (): Self::Params) -> Res<(Filters, Vec<chart::Chart>)> {
+        let kinds: BTSet<AllocKind> = data.iter_allocs().map(|alloc| *alloc.kind()).collect();
+
+        let mut colors = Color::randoms(kinds.len()).into_iter();
+        let mut filters = Vec::with_capacity(kinds.len());
+        for kind in kinds {
+            let color = colors.next().expect(
+                "internal error, the number of colors is not consistent with the number of kinds",
+            );
+            let mut spec = filter::FilterSpec::new(color);
+            spec.set_name(Self::name(kind));
+            let mut filter = filter::Filter::new(spec)?;
+            filter.insert(filter::KindFilter::new(kind))?;
+            filters.push(filter)
+        }
+
+        let filters = Filters::new_with(filters);
+        let charts = chart_gen::default(&filters)?;
+        Ok((filters, charts))
+    }
+
+    fn parse_args(parser: Option<Parser>) -> Option<FilterGen> {
+        if parser.is_none() {
+            Some(FilterGen::ByKind)
+        } else {
+            None
+        }
+    }
+
+    fn add_help(s: &mut String) {
+        s.push_str(&format!(
+            "\
+- allocation kind generator: `{0}`
+    Generates one filter per allocation kind (minor, major...) appearing in the data.
+
+\
+            ",
+            Self::KEY,
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Size-based automatic filter generation.
+//!
+//! Generates one filter per order-of-magnitude band of allocation sizes appearing in the data:
+//! `[0, 9]`, `[10, 99]`, `[100, 999]`, *etc.* Sizes are in bytes, see [`Alloc::size`]. Since they
+//! are multiples of the word size of the run, the first band only holds the smallest allocations,
+//! *e.g.* one-word allocations on 64-bit runs.
+//!
+//! [`Alloc::size`]: alloc_data::Alloc::size (size method of Alloc)
+
+prelude! {}
+
+use filter::gen::*;
+
+/// Unit-struct handling the size-bucket generator.
+pub struct BySizeBucket;
+
+impl BySizeBucket {
+    /// Order of magnitude of a size, *i.e.* the index of its band.
+    ///
+    /// ```rust
+    /// use charts::filter::gen::by_size::BySizeBucket;
+    /// assert_eq!(BySizeBucket::magnitude(0), 0);
+    /// assert_eq!(BySizeBucket::magnitude(9), 0);
+    /// assert_eq!(BySizeBucket::magnitude(10), 1);
+    /// assert_eq!(BySizeBucket::magnitude(999), 2);
+    /// assert_eq!(BySizeBucket::magnitude(u32::MAX), 9);
+    /// ```
+    pub fn magnitude(mut size: u32) -> u32 {
+        let mut magnitude = 0;
+        while size >= 10 {
+            size /= 10;
+            magnitude += 1
+        }
+        magnitude
+    }
+
+    /// Bounds of the band of sizes of some order of magnitude, inclusive.
+    ///
+    /// ```rust
+    /// use charts::filter::gen::by_size::BySizeBucket;
+    /// assert_eq!(BySizeBucket::bounds(0), (0, 9));
+    /// assert_eq!(BySizeBucket::bounds(2), (100, 999));
+    /// assert_eq!(BySizeBucket::bounds(9), (1_000_000_000, u32::MAX));
+    /// ```
+    pub fn bounds(magnitude: u32) -> (u32, u32) {
+        let lb = if magnitude == 0 {
+            0
+        } else {
+            10u32.pow(magnitude)
+        };
+        let ub = 10u32
+            .checked_pow(magnitude + 1)
+            .map(|pow| pow - 1)
+            .unwrap_or(u32::MAX);
+        (lb, ub)
+    }
+}
+
+impl FilterGenExt for BySizeBucket {
+    type Params = ();
+
+    const KEY: &'static str = "by_size_bucket";
+    const FMT: Option<&'static str> = None;
+
+    fn work(data: &data::Data, (): Self::Params) -> Res<(Filters, Vec<chart::Chart>)> {
+        let magnitudes: BTSet<u32> = data
+            .iter_allocs()
+            .map(|alloc| Self::magnitude(alloc.size()))
+            .collect();
+
+        let mut colors = Color::randoms(magnitudes.len()).into_iter();
+        let mut filters = Vec::with_capacity(magnitudes.len());
+        for magnitude in magnitudes {
+            let color = colors.next().expect(
+                "internal error, the number of colors is not consistent with the number of bands",
+            );
+            let (lb, ub) = Self::bounds(magnitude);
+            let mut spec = filter::FilterSpec::new(color);
+            spec.set_name(format!("size in [{}, {}] bytes", lb, ub));
+            let mut filter = filter::Filter::new(spec)?;
+            filter.insert(filter::SizeFilter::between(lb, ub)?)?;
+            filters.push(filter)
+        }
+
+        let filters = Filters::new_with(filters);
+        let charts = chart_gen::default(&filters)?;
+        Ok((filters, charts))
+    }
+
+    fn parse_args(parser: Option<Parser>) -> Option<FilterGen> {
+        if parser.is_none() {
+            Some(FilterGen::BySizeBucket)
+        } else {
+            None
+        }
+    }
+
+    fn add_help(s: &mut String) {
+        s.push_str(&format!(
+            "\
+- size bucket generator: `{0}`
+    Generates one filter per order-of-magnitude band of allocation sizes appearing in the data,
+    `[0, 9]`, `[10, 99]`, `[100, 999]`... bytes.
+
+\
+            ",
+            Self::KEY,
+        ));
+    }
+}
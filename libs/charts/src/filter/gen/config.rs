@@ -0,0 +1,214 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Filter generation from a configuration file.
+//!
+//! Activated by passing `@<path>` to `--filter_gen`, see [`set_from_cla`]. The file is a JSON
+//! serialization of either
+//!
+//! - a list of [`Filter`]s, or
+//! - an object with a `filters` field storing a list of [`Filter`]s, and an optional `charts`
+//!   field storing a list of [`ChartSpec`]s.
+//!
+//! Filters and charts get fresh UIDs when loaded, so that they cannot clash with the ones created
+//! during the session. Charts refer to filters by their UID in the file.
+//!
+//! [`ChartSpec`]: chart::ChartSpec (ChartSpec struct)
+
+prelude! {}
+
+use filter::gen::*;
+
+/// Configuration file, as stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawConfig {
+    /// Filters, in order of priority.
+    pub filters: Vec<Filter>,
+    /// Chart specifications.
+    #[serde(default)]
+    pub charts: Vec<chart::ChartSpec>,
+}
+
+/// Filters and charts loaded from a configuration file.
+///
+/// ```rust
+/// use charts::{color::Color, filter::{gen::config::Config, *}};
+/// let path = std::env::temp_dir().join("memthol_filter_gen_config_doc_test.json");
+/// let path = path.to_str().unwrap();
+///
+/// let mut spec = FilterSpec::new(Color::new(0, 0, 0));
+/// spec.set_name("big");
+/// let mut filter = Filter::new(spec).unwrap();
+/// filter.insert(SubFilter::from(SizeFilter::cmp(ord::Cmp::Ge, 50))).unwrap();
+/// std::fs::write(path, serde_json::to_string(&vec![filter.clone()]).unwrap()).unwrap();
+///
+/// let config = Config::load(path).unwrap();
+/// assert_eq!(config.filters().len(), 1);
+/// let loaded = &config.filters()[0];
+/// assert_eq!(loaded.name(), "big");
+/// // Fresh UIDs, same predicates.
+/// assert_ne!(loaded.uid(), filter.uid());
+/// assert!(loaded.iter().map(SubFilter::raw).eq(filter.iter().map(SubFilter::raw)));
+///
+/// std::fs::write(path, "[ { \"not\": \"a filter\" } ]").unwrap();
+/// let err = Config::load(path).unwrap_err();
+/// assert!(err.to_string().contains("while loading configuration file"));
+/// assert!(err.to_pretty().contains("ill-formed configuration file"));
+/// std::fs::remove_file(path).unwrap();
+/// let err = Config::load(path).unwrap_err();
+/// assert!(err.to_string().contains("cannot read configuration file"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Filters, with fresh UIDs.
+    filters: Vec<Filter>,
+    /// Chart specifications, with fresh UIDs and referring to the fresh filter UIDs.
+    charts: Vec<chart::ChartSpec>,
+}
+
+impl Config {
+    /// Filters, in order of priority.
+    pub fn filters(&self) -> &[Filter] {
+        &self.filters
+    }
+    /// Chart specifications.
+    pub fn charts(&self) -> &[chart::ChartSpec] {
+        &self.charts
+    }
+
+    /// Loads and validates a configuration file.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Res<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read configuration file `{}`: {}", path.display(), e))?;
+        Self::from_json(&content)
+            .chain_err(|| format!("while loading configuration file `{}`", path.display()))
+    }
+
+    /// Parses and validates a JSON configuration.
+    pub fn from_json(json: &str) -> Res<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| format!("ill-formed configuration file, {}", e))?;
+        let raw = if value.is_array() {
+            serde_json::from_value(value).map(|filters| RawConfig {
+                filters,
+                charts: vec![],
+            })
+        } else {
+            serde_json::from_value(value)
+        }
+        .map_err(|e| format!("ill-formed configuration file, {}", e))?;
+        Self::from_raw(raw)
+    }
+
+    /// Validates a raw configuration, and gives fresh UIDs to its filters and charts.
+    pub fn from_raw(raw: RawConfig) -> Res<Self> {
+        let mut lines = BTMap::new();
+        lines.insert(uid::Line::Everything, uid::Line::Everything);
+        lines.insert(uid::Line::CatchAll, uid::Line::CatchAll);
+
+        let mut filters = Vec::with_capacity(raw.filters.len());
+        for old in raw.filters {
+            let old_spec = old.spec();
+            let mut spec = filter::FilterSpec::new(*old_spec.color());
+            spec.set_name(old_spec.name());
+            spec.set_active(old_spec.is_active());
+            spec.set_description(old_spec.description().map(String::from));
+            let mut filter = Filter::new(spec)?;
+            for sub in old.iter() {
+                sub.check()
+                    .chain_err(|| format!("illegal subfilter in filter `{}`", old.name()))?;
+                filter.insert(filter::SubFilter::new(
+                    uid::SubFilter::fresh(),
+                    sub.raw().clone(),
+                ))?
+            }
+            let prev = lines.insert(old_spec.uid(), filter.spec().uid());
+            if prev.is_some() {
+                bail!("filter UID #{} appears more than once", old.uid())
+            }
+            filters.push(filter)
+        }
+
+        let mut charts = Vec::with_capacity(raw.charts.len());
+        for old in raw.charts {
+            let mut active = BTMap::new();
+            for (line, is_active) in old.active() {
+                let line = lines.get(line).ok_or_else(|| {
+                    format!("chart #{} refers to unknown filter {}", old.uid(), line)
+                })?;
+                active.insert(*line, *is_active);
+            }
+            for line in lines.values() {
+                active.entry(*line).or_insert(false);
+            }
+            let mut spec = chart::ChartSpec::new(*old.x_axis(), *old.y_axis(), active);
+            spec.set_origin(old.origin());
+            spec.set_title(old.title().map(String::from))
+                .chain_err(|| format!("illegal title for chart #{}", old.uid()))?;
+            charts.push(spec)
+        }
+
+        Ok(Self { filters, charts })
+    }
+}
+
+/// Unit-struct handling the configuration-file generator.
+///
+/// Unlike the other generators, this one is not activated by a key but by a `@` prefix, see
+/// [`set_from_cla`].
+pub struct FromConfig;
+
+impl FromConfig {
+    /// Prefix of `--filter_gen` arguments that are configuration file paths.
+    pub const PREFIX: char = '@';
+
+    /// Adds help about itself to a `String`.
+    pub fn add_help(s: &mut String) {
+        s.push_str(&format!(
+            "\
+- configuration file: `{0}<path>`
+    Loads the filters, and optionally the charts, from a JSON file at `<path>`. The file stores
+    either a list of filters, or an object with a `filters` list and an optional `charts` list.
+
+\
+            ",
+            Self::PREFIX,
+        ));
+    }
+
+    /// Runs the generator on some data.
+    pub fn work(_data: &data::Data, config: Config) -> Res<(Filters, Vec<chart::Chart>)> {
+        let Config {
+            filters,
+            charts: specs,
+        } = config;
+        let filters = Filters::new_with(filters);
+        let charts = if specs.is_empty() {
+            chart_gen::default(&filters)?
+        } else {
+            let mut charts = Vec::with_capacity(specs.len());
+            for spec in specs {
+                charts.push(chart::Chart::from_spec(None, &filters, spec)?)
+            }
+            charts
+        };
+        Ok((filters, charts))
+    }
+}
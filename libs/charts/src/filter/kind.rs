@@ -0,0 +1,99 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Filter over allocation kinds.
+
+prelude! {}
+
+/// Keeps the allocations of some kind.
+///
+/// ```rust
+/// use charts::{alloc_data::prelude::*, filter::KindFilter, fixture::alloc};
+/// let minor = alloc(0, AllocKind::Minor, 1, 0, None);
+/// let major = alloc(1, AllocKind::Major, 1, 0, None);
+///
+/// let filter = KindFilter::new(AllocKind::Major);
+/// assert!(!filter.apply(&minor));
+/// assert!(filter.apply(&major));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KindFilter {
+    /// Kind of the allocations to keep.
+    kind: alloc::AllocKind,
+}
+
+impl KindFilter {
+    /// Constructor.
+    pub fn new(kind: alloc::AllocKind) -> Self {
+        Self { kind }
+    }
+
+    /// Kind of the allocations to keep.
+    pub fn kind(&self) -> alloc::AllocKind {
+        self.kind
+    }
+
+    /// Applies the filter to an allocation.
+    pub fn apply(&self, alloc: &Alloc) -> bool {
+        *alloc.kind() == self.kind
+    }
+
+    /// Updates the filter.
+    ///
+    /// Returns `true` iff the filter actually changed.
+    pub fn update(&mut self, update: KindUpdate) -> Res<bool> {
+        let has_changed = match update {
+            KindUpdate::Kind(kind) => {
+                if kind != self.kind {
+                    self.kind = kind;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        Ok(has_changed)
+    }
+}
+
+/// An update for an allocation kind filter.
+pub enum KindUpdate {
+    /// Change the kind of the allocations to keep.
+    Kind(alloc::AllocKind),
+}
+
+base::implement! {
+    impl KindFilter {
+        Display {
+            |&self, fmt| write!(fmt, "= {}", self.kind)
+        }
+
+        Default {
+            Self::new(alloc::AllocKind::Minor),
+        }
+    }
+
+    impl KindUpdate {
+        Display {
+            |&self, fmt| match self {
+                Self::Kind(kind) => write!(fmt, "kind {}", kind),
+            }
+        }
+    }
+}
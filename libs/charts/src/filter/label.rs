@@ -32,6 +32,66 @@ pub type LabelPred = string_like::Pred;
 /// An update for a label filter.
 pub type LabelUpdate = string_like::Update;
 
+/// Maximum size of a compiled label regex, in bytes.
+///
+/// Label regexes run on every single allocation, the server uses this limit instead of `regex`'s
+/// default one (ten times bigger) to reject regexes that would slow point generation down too much.
+pub const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Namespace of a label.
+///
+/// The namespace of a label is whatever appears before its first `:`. Labels without a `:` are in
+/// the empty namespace, which is where user-defined labels usually live, as opposed to labels added
+/// automatically such as `source:run-a`.
+///
+/// ```rust
+/// use charts::filter::label::namespace_of;
+/// assert_eq!(namespace_of("source:run-a"), "source");
+/// assert_eq!(namespace_of("a:b:c"), "a");
+/// assert_eq!(namespace_of("my label"), "");
+/// ```
+pub fn namespace_of(label: &str) -> &str {
+    label.find(':').map(|idx| &label[0..idx]).unwrap_or("")
+}
+
+/// Parses a namespace restriction, as typed by users.
+///
+/// `**` lifts the restriction, anything else restricts to the corresponding namespace. In
+/// particular, the empty string restricts to labels that have no namespace.
+///
+/// ```rust
+/// use charts::filter::label::*;
+/// assert_eq!(parse_namespace("**"), None);
+/// assert_eq!(parse_namespace(" source "), Some("source".into()));
+/// assert_eq!(parse_namespace(""), Some("".into()));
+///
+/// let labels: Vec<_> = ["my label", "source:run-a"]
+///     .iter()
+///     .map(|s| charts::alloc_data::prelude::Str::new(s))
+///     .collect();
+/// let only = |s: &str| LabelFilter::contain(vec![LabelSpec::new(s).unwrap()]);
+/// // Without restriction, all labels are taken into account.
+/// assert!(!only("my label").matches(&labels));
+/// // Restricting to the empty namespace ignores `source:run-a`.
+/// let filter = only("my label").with_namespace(parse_namespace(""));
+/// assert!(filter.matches(&labels));
+/// let filter = only("source:run-a").with_namespace(parse_namespace("source"));
+/// assert!(filter.matches(&labels));
+/// ```
+pub fn parse_namespace(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s == "**" {
+        None
+    } else {
+        Some(s.into())
+    }
+}
+
+/// String representation of a namespace restriction, inverse of [`parse_namespace`].
+pub fn namespace_to_string(namespace: Option<&str>) -> String {
+    namespace.unwrap_or("**").into()
+}
+
 /// Label specification.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LabelSpec {
@@ -103,6 +163,11 @@ impl string_like::SpecExt for LabelSpec {
             Self::Regex(_) => false,
         }
     }
+
+    /// True if the label's namespace is `namespace`, see [`namespace_of`].
+    fn in_namespace(data: &Self::Data, namespace: &str) -> bool {
+        data.str_do(|s| namespace_of(s) == namespace)
+    }
 }
 
 impl fmt::Display for LabelSpec {
@@ -152,6 +217,22 @@ impl LabelSpec {
             Self::Regex(_) => false,
         }
     }
+
+    /// Checks that a label specification is legal.
+    ///
+    /// Regexes are compiled again with a size limit of [`REGEX_SIZE_LIMIT`].
+    ///
+    /// [`REGEX_SIZE_LIMIT`]: crate::filter::label::REGEX_SIZE_LIMIT (REGEX_SIZE_LIMIT constant)
+    pub fn check(&self) -> Res<()> {
+        match self {
+            Self::Anything | Self::Value(_) => Ok(()),
+            Self::Regex(regex) => regex::RegexBuilder::new(regex.as_str())
+                .size_limit(REGEX_SIZE_LIMIT)
+                .build()
+                .map(|_| ())
+                .map_err(|e| format!("illegal regex `{}`: {}", regex, e).into()),
+        }
+    }
 }
 
 impl From<String> for LabelSpec {
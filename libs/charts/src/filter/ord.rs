@@ -120,11 +120,63 @@ where
     Num: PartialEq + PartialOrd + fmt::Debug,
 {
     /// Creates an interval filter.
+    ///
+    /// Fails if `lb > ub`, the bounds are never swapped. Both bounds are inclusive, so `lb == ub`
+    /// is legal and matches a single value.
+    ///
+    /// ```rust
+    /// use charts::{filter::{FilterExt, LifetimeFilter, SizeFilter}, prelude::*};
+    /// let single = SizeFilter::between(8, 8).unwrap();
+    /// assert!(single.apply(&8));
+    /// assert!(!single.apply(&7));
+    /// assert!(!single.apply(&9));
+    ///
+    /// let err = SizeFilter::between(9, 8).unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "illegal interval [9, 8]: lower bound is greater than upper bound",
+    /// );
+    ///
+    /// let (short, long) = (
+    ///     time::Lifetime::from(std::time::Duration::from_millis(10)),
+    ///     time::Lifetime::from(std::time::Duration::from_secs(1)),
+    /// );
+    /// assert!(LifetimeFilter::between(short, short).is_ok());
+    /// assert!(LifetimeFilter::between(short, long).is_ok());
+    /// assert!(LifetimeFilter::between(long, short).is_err());
+    /// ```
     pub fn between(lb: Num, ub: Num) -> Res<Self> {
+        Self::check_bounds(&lb, &ub)?;
+        Ok(Self::In { lb, ub })
+    }
+
+    /// Fails if `lb > ub`.
+    fn check_bounds(lb: &Num, ub: &Num) -> Res<()> {
         if lb <= ub {
-            Ok(Self::In { lb, ub })
+            Ok(())
         } else {
-            bail!("illegal interval [{:?}, {:?}]", lb, ub)
+            bail!(
+                "illegal interval [{:?}, {:?}]: lower bound is greater than upper bound",
+                lb,
+                ub
+            )
+        }
+    }
+
+    /// Checks that the filter is legal, *i.e.* it is not an interval with inverted bounds.
+    ///
+    /// Filters built with [`Self::between`] are always legal, this is meant for filters coming from
+    /// the client or from a file.
+    ///
+    /// ```rust
+    /// use charts::filter::SizeFilter;
+    /// assert!(SizeFilter::In { lb: 3, ub: 3 }.check().is_ok());
+    /// assert!(SizeFilter::In { lb: 4, ub: 3 }.check().is_err());
+    /// ```
+    pub fn check(&self) -> Res<()> {
+        match self {
+            Self::Cmp { .. } => Ok(()),
+            Self::In { lb, ub } => Self::check_bounds(lb, ub),
         }
     }
 
@@ -212,6 +264,9 @@ where
 /// An update for a size filter.
 pub type SizeUpdate = Update<u32>;
 
+/// An update for a number of samples filter.
+pub type SamplesUpdate = Update<u32>;
+
 /// An update for a lifetime filter.
 pub type LifetimeUpdate = Update<time::Lifetime>;
 
@@ -237,8 +292,19 @@ impl<Val: fmt::Display> fmt::Display for Update<Val> {
     }
 }
 
-impl<Num: fmt::Display + PartialEq + Eq> OrdFilter<Num> {
+impl<Num: fmt::Display + fmt::Debug + PartialOrd + Eq> OrdFilter<Num> {
     /// Updates the filter.
+    ///
+    /// Fails if the update would invert the bounds of an interval, in which case the filter is left
+    /// unchanged.
+    ///
+    /// ```rust
+    /// use charts::filter::{ord::SizeUpdate, SizeFilter};
+    /// let mut filter = SizeFilter::between(2, 5).unwrap();
+    /// assert!(filter.update(SizeUpdate::InLb(5)).unwrap());
+    /// assert!(filter.update(SizeUpdate::InUb(4)).is_err());
+    /// assert_eq!(filter, SizeFilter::between(5, 5).unwrap());
+    /// ```
     pub fn update(&mut self, update: Update<Num>) -> Res<bool> {
         let has_changed = match self {
             Self::Cmp { cmp, val } => match update {
@@ -265,6 +331,7 @@ impl<Num: fmt::Display + PartialEq + Eq> OrdFilter<Num> {
             Self::In { lb, ub } => match update {
                 Update::InLb(val) => {
                     if val != *lb {
+                        Self::check_bounds(&val, ub)?;
                         *lb = val;
                         true
                     } else {
@@ -273,6 +340,7 @@ impl<Num: fmt::Display + PartialEq + Eq> OrdFilter<Num> {
                 }
                 Update::InUb(val) => {
                     if val != *ub {
+                        Self::check_bounds(lb, &val)?;
                         *ub = val;
                         true
                     } else {
@@ -27,11 +27,40 @@ use super::*;
 ///
 /// - an optional UID;
 /// - a name;
-/// - a color.
+/// - a color;
+/// - an activation flag, inactive filters have no series in the charts;
+/// - an optional description, which is only metadata for the user and does not affect matching.
 ///
 /// The UID is optional because the filter specification can belong the "catch all" line of charts.
 /// It is made from the points that all filters miss.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Specifications written before the activation flag existed used a `disabled` key with the
+/// opposite meaning. Self-describing formats still accept it, and the flag defaults to active.
+/// Specifications written before descriptions existed have no description.
+///
+/// ```rust
+/// use charts::filter::FilterSpec;
+/// let spec = FilterSpec::new_catch_all();
+/// let json = serde_json::to_string(&spec).unwrap();
+/// assert_eq!(serde_json::from_str::<FilterSpec>(&json).unwrap(), spec);
+///
+/// let legacy = json.replace(r#""active":true"#, r#""disabled":true"#);
+/// assert!(!serde_json::from_str::<FilterSpec>(&legacy).unwrap().is_active());
+/// let legacy = json.replace(r#""active":true"#, r#""disabled":false"#);
+/// assert!(serde_json::from_str::<FilterSpec>(&legacy).unwrap().is_active());
+/// let missing = json.replace(r#","active":true"#, "");
+/// assert!(serde_json::from_str::<FilterSpec>(&missing).unwrap().is_active());
+///
+/// let mut spec = spec;
+/// spec.set_description(Some("allocations from the request parser".into()));
+/// let json = serde_json::to_string(&spec).unwrap();
+/// assert_eq!(serde_json::from_str::<FilterSpec>(&json).unwrap(), spec);
+/// let bytes = base::bincode::serialize(&spec).unwrap();
+/// assert_eq!(base::bincode::deserialize::<FilterSpec>(&bytes).unwrap(), spec);
+/// let legacy = json.replace(r#","description":"allocations from the request parser""#, "");
+/// assert_eq!(serde_json::from_str::<FilterSpec>(&legacy).unwrap().description(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct FilterSpec {
     /// Uid of the filter.
     ///
@@ -41,7 +70,81 @@ pub struct FilterSpec {
     name: String,
     /// Color of the filter.
     color: Color,
+    /// False if the filter is inactive, *i.e.* hidden from the charts.
+    active: bool,
+    /// Optional description of the filter.
+    description: Option<String>,
 }
+
+impl<'de> ::serde::Deserialize<'de> for FilterSpec {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        use ::serde::de::{self, MapAccess, SeqAccess, Visitor};
+
+        const FIELDS: &[&str] = &["uid", "name", "color", "active", "description"];
+
+        struct SpecVisitor;
+        impl<'de> Visitor<'de> for SpecVisitor {
+            type Value = FilterSpec;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a filter specification")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<FilterSpec, A::Error> {
+                let uid = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let name = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let color = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                let active = seq.next_element()?.unwrap_or(true);
+                let description = seq.next_element()?.unwrap_or(None);
+                Ok(FilterSpec {
+                    uid,
+                    name,
+                    color,
+                    active,
+                    description,
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<FilterSpec, A::Error> {
+                let (mut uid, mut name, mut color, mut active, mut description) =
+                    (None, None, None, None, None);
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "uid" => uid = Some(map.next_value()?),
+                        "name" => name = Some(map.next_value()?),
+                        "color" => color = Some(map.next_value()?),
+                        "active" => active = Some(map.next_value()?),
+                        "description" => description = map.next_value()?,
+                        // Legacy key, with the opposite meaning.
+                        "disabled" => active = Some(!map.next_value::<bool>()?),
+                        _ => {
+                            let _: de::IgnoredAny = map.next_value()?;
+                        }
+                    }
+                }
+                Ok(FilterSpec {
+                    uid: uid.ok_or_else(|| de::Error::missing_field("uid"))?,
+                    name: name.ok_or_else(|| de::Error::missing_field("name"))?,
+                    color: color.ok_or_else(|| de::Error::missing_field("color"))?,
+                    active: active.unwrap_or(true),
+                    description,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("FilterSpec", FIELDS, SpecVisitor)
+    }
+}
+
 impl FilterSpec {
     /// Constructor for user-defined filters.
     pub fn new(color: Color) -> Self {
@@ -51,6 +154,8 @@ impl FilterSpec {
             uid: uid::Line::Filter(uid),
             name,
             color,
+            active: true,
+            description: None,
         }
     }
 
@@ -60,6 +165,8 @@ impl FilterSpec {
             uid: uid::Line::CatchAll,
             name: "catch all".into(),
             color: Color::new(0x01, 0x93, 0xff),
+            active: true,
+            description: None,
         }
     }
 
@@ -69,6 +176,8 @@ impl FilterSpec {
             uid: uid::Line::Everything,
             name: "everything".into(),
             color: Color::new(0xff, 0x66, 0x00),
+            active: true,
+            description: None,
         }
     }
 
@@ -107,4 +216,27 @@ impl FilterSpec {
     pub fn set_color(&mut self, color: Color) {
         self.color = color
     }
+
+    /// True if the filter is active.
+    ///
+    /// Inactive filters have no series in the charts, but they still catch allocations: hiding a
+    /// filter does not change what the other filters, including the catch-all one, match.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+    /// Activates or deactivates the filter, see [`Self::is_active`].
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active
+    }
+
+    /// Description accessor.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    /// Description setter.
+    ///
+    /// Descriptions are only metadata, they do not change what the filter matches.
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description
+    }
 }
@@ -28,11 +28,16 @@ prelude! {}
 pub struct FilterStats {
     /// Number of allocation caught by the filter.
     pub alloc_count: usize,
+    /// Number of allocations caught by the filter that died.
+    pub dead_count: usize,
 }
 impl FilterStats {
     /// Constructor.
     pub fn new() -> Self {
-        Self { alloc_count: 0 }
+        Self {
+            alloc_count: 0,
+            dead_count: 0,
+        }
     }
 
     /// Increments the number of allocations.
@@ -83,4 +88,99 @@ impl AllFilterStats {
     pub fn get(&self, filter: uid::Line) -> Option<&FilterStats> {
         self.stats.get(&filter)
     }
+
+    /// Applies a delta, see [`FilterDelta`].
+    ///
+    /// If the delta is a [reset](FilterDelta::is_reset), the counts restart from zero.
+    ///
+    /// ```rust
+    /// use charts::{filter::stats::*, prelude::*};
+    /// let mut stats = AllFilterStats::new();
+    /// let mut delta = FilterDelta::new();
+    /// delta.add_new(uid::Line::CatchAll);
+    /// delta.add_new(uid::Line::CatchAll);
+    /// delta.add_dead(uid::Line::CatchAll);
+    /// stats.apply(&delta);
+    /// stats.apply(&delta);
+    /// let catch_all = stats.get(uid::Line::CatchAll).unwrap();
+    /// assert_eq!((catch_all.alloc_count, catch_all.dead_count), (4, 2));
+    ///
+    /// let mut reset = FilterDelta::new_reset();
+    /// reset.add_new(uid::Line::CatchAll);
+    /// stats.apply(&reset);
+    /// assert_eq!(stats.get(uid::Line::CatchAll).unwrap().alloc_count, 1);
+    /// ```
+    pub fn apply(&mut self, delta: &FilterDelta) {
+        if delta.reset {
+            self.stats.clear()
+        }
+        for (line, line_delta) in &delta.deltas {
+            let stats = self.stats_mut(*line);
+            stats.alloc_count += line_delta.new;
+            stats.dead_count += line_delta.dead;
+        }
+    }
+}
+
+/// Allocation counts of a filter in a [`FilterDelta`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineDelta {
+    /// Number of new allocations caught by the filter.
+    pub new: usize,
+    /// Number of allocations caught by the filter that died.
+    pub dead: usize,
+}
+
+/// Per-filter allocation counts, sent with each points message.
+///
+/// Lets the client maintain its [`AllFilterStats`] by accumulating counters, see
+/// [`AllFilterStats::apply`], instead of requiring the server to recount all the allocations each
+/// time it sends points.
+///
+/// A delta counts the allocations since the previous delta, unless it is a
+/// [reset](Self::is_reset). Editing the filters (their predicates or their order) attributes all
+/// the allocations again from scratch: the next delta is then a reset, and counts all the
+/// allocations so far. The client drops its counts before applying a reset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterDelta {
+    /// True if the counts start from zero.
+    reset: bool,
+    /// Map from filters to their counts.
+    deltas: BTMap<uid::Line, LineDelta>,
+}
+impl FilterDelta {
+    /// Constructor, for a delta that is not a reset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Constructor, for a reset.
+    pub fn new_reset() -> Self {
+        Self {
+            reset: true,
+            deltas: BTMap::new(),
+        }
+    }
+
+    /// True if the counts start from zero.
+    pub fn is_reset(&self) -> bool {
+        self.reset
+    }
+    /// True if the delta does not change anything.
+    pub fn is_empty(&self) -> bool {
+        !self.reset && self.deltas.is_empty()
+    }
+
+    /// Counts for a specific filter, if any.
+    pub fn get(&self, filter: uid::Line) -> Option<&LineDelta> {
+        self.deltas.get(&filter)
+    }
+
+    /// Registers a new allocation for a filter.
+    pub fn add_new(&mut self, filter: uid::Line) {
+        self.deltas.entry(filter).or_default().new += 1
+    }
+    /// Registers the death of an allocation for a filter.
+    pub fn add_dead(&mut self, filter: uid::Line) {
+        self.deltas.entry(filter).or_default().dead += 1
+    }
 }
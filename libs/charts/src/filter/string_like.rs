@@ -79,6 +79,16 @@ pub trait SpecExt: Default + Clone + fmt::Display + Sized {
 
     /// True if the specification matches a repetition of anything.
     fn matches_anything(&self) -> bool;
+
+    /// True if some data belongs to a namespace.
+    ///
+    /// Used by filters restricted to a namespace, see [`StringLikeFilter::namespace`]. Data that
+    /// does not have a notion of namespace is always in the namespace, which is the default
+    /// behavior.
+    fn in_namespace(data: &Self::Data, namespace: &str) -> bool {
+        let _ = (data, namespace);
+        true
+    }
 }
 
 /// A filter for a lists of string-like elements.
@@ -88,6 +98,9 @@ pub struct StringLikeFilter<Spec> {
     pred: Pred,
     /// The specifications.
     specs: Vec<Spec>,
+    /// Optional namespace restriction, data outside of this namespace is ignored.
+    #[serde(default)]
+    namespace: Option<String>,
 }
 
 impl<Spec> fmt::Display for StringLikeFilter<Spec>
@@ -95,7 +108,11 @@ where
     Spec: SpecExt,
 {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{} {} [", Spec::DATA_DESC, self.pred)?;
+        write!(fmt, "{}", Spec::DATA_DESC)?;
+        if let Some(namespace) = &self.namespace {
+            write!(fmt, " in namespace `{}`", namespace)?
+        }
+        write!(fmt, " {} [", self.pred)?;
         for spec in &self.specs {
             write!(fmt, " ... {}", spec)?
         }
@@ -106,13 +123,18 @@ where
 impl<Spec> StringLikeFilter<Spec> {
     /// Constructor.
     pub fn new(pred: Pred, specs: Vec<Spec>) -> Self {
-        Self { pred, specs }
+        Self {
+            pred,
+            specs,
+            namespace: None,
+        }
     }
     /// "Contain" constructor.
     pub fn contain(specs: Vec<Spec>) -> Self {
         Self {
             pred: Pred::Contain,
             specs,
+            namespace: None,
         }
     }
     /// "Exclude" constructor.
@@ -120,6 +142,7 @@ impl<Spec> StringLikeFilter<Spec> {
         Self {
             pred: Pred::Exclude,
             specs,
+            namespace: None,
         }
     }
 
@@ -131,6 +154,16 @@ impl<Spec> StringLikeFilter<Spec> {
     pub fn specs(&self) -> &Vec<Spec> {
         &self.specs
     }
+    /// Namespace restriction of a filter, if any.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Restricts the filter to a namespace, or lifts the restriction if `None`.
+    pub fn with_namespace(mut self, namespace: Option<String>) -> Self {
+        self.namespace = namespace;
+        self
+    }
 }
 
 impl<Spec> FilterExt<Arc<Vec<Spec::Data>>> for StringLikeFilter<Spec>
@@ -200,8 +233,18 @@ where
     }
 
     /// True if the filter input data is a match for the filter.
+    ///
+    /// If the filter is restricted to a namespace, data outside of this namespace is ignored.
     pub fn matches(&self, data: &[Spec::Data]) -> bool {
-        let res = Self::check_contain(&self.specs, data);
+        let res = if let Some(namespace) = &self.namespace {
+            let data: Vec<&Spec::Data> = data
+                .iter()
+                .filter(|data| Spec::in_namespace(data, namespace))
+                .collect();
+            Self::check_contain(&self.specs, &data)
+        } else {
+            Self::check_contain(&self.specs, data)
+        };
         match self.pred {
             Pred::Contain => res,
             Pred::Exclude => !res,
@@ -211,7 +254,10 @@ where
     /// Helper handling the suffix case.
     ///
     /// Returns nothing if the specs do not fall in the suffix case.
-    fn check_suffix(specs: &Vec<Spec>, data: &[Spec::Data]) -> Option<bool> {
+    fn check_suffix<Data>(specs: &Vec<Spec>, data: &[Data]) -> Option<bool>
+    where
+        Data: std::borrow::Borrow<Spec::Data>,
+    {
         let mut slice = &specs[0..];
 
         if !slice.is_empty() {
@@ -246,7 +292,7 @@ where
 
         for spec in rev_slice {
             if let Some(data) = rev_data.next() {
-                if spec.matches(data) {
+                if spec.matches(data.borrow()) {
                     continue;
                 } else {
                     return Some(false);
@@ -262,7 +308,10 @@ where
     }
 
     /// Helper that returns true if the input data verifies the input specs.
-    fn check_contain(specs: &Vec<Spec>, data: &[Spec::Data]) -> bool {
+    fn check_contain<Data>(specs: &Vec<Spec>, data: &[Data]) -> bool
+    where
+        Data: std::borrow::Borrow<Spec::Data>,
+    {
         if let Some(res) = Self::check_suffix(specs, data) {
             return res;
         }
@@ -298,7 +347,7 @@ where
             };
 
             'find_match: while let Some(data) = data.next() {
-                if spec.matches(data) {
+                if spec.matches(data.borrow()) {
                     // Found a match.
                     continue 'next_spec;
                 } else if can_skip {
@@ -327,6 +376,8 @@ pub enum Update {
     Add(usize),
     /// Replace a specification at some position.
     Replace(usize, String),
+    /// Change the namespace restriction of the filter.
+    Namespace(Option<String>),
 }
 impl fmt::Display for Update {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
@@ -334,6 +385,8 @@ impl fmt::Display for Update {
             Self::Pred(pred) => write!(fmt, "pred <- {}", pred),
             Self::Add(index) => write!(fmt, "specs <- add at {}", index),
             Self::Replace(index, spec) => write!(fmt, "specs[{}] <- {}", index, spec),
+            Self::Namespace(Some(namespace)) => write!(fmt, "namespace <- {}", namespace),
+            Self::Namespace(None) => write!(fmt, "namespace <- none"),
         }
     }
 }
@@ -370,6 +423,14 @@ where
                 self.specs[index] = spec;
                 true
             }
+            Update::Namespace(namespace) => {
+                if namespace != self.namespace {
+                    self.namespace = namespace;
+                    true
+                } else {
+                    false
+                }
+            }
         };
         Ok(has_changed)
     }
@@ -29,8 +29,14 @@ use filter::*;
 pub enum RawSubFilter {
     /// Filter over allocation sizes.
     Size(SizeFilter),
+    /// Filter over the number of samples.
+    Samples(SamplesFilter),
     /// Filter over lifetime.
     Lifetime(LifetimeFilter),
+    /// Filter over allocations alive at some time.
+    AliveAt(AliveAtFilter),
+    /// Filter over allocation kinds.
+    Kind(KindFilter),
     /// Filter over labels.
     Label(LabelFilter),
     /// Filter over locations.
@@ -42,16 +48,36 @@ impl RawSubFilter {
     pub fn kind(&self) -> FilterKind {
         match self {
             Self::Size(_) => FilterKind::Size,
+            Self::Samples(_) => FilterKind::Samples,
             Self::Lifetime(_) => FilterKind::Lifetime,
+            Self::AliveAt(_) => FilterKind::AliveAt,
+            Self::Kind(_) => FilterKind::Kind,
             Self::Label(_) => FilterKind::Label,
             Self::Loc(_) => FilterKind::Loc,
         }
     }
 
     /// Applies the filter to an allocation.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     alloc_data::prelude::*,
+    ///     filter::{ord::Cmp, sub::RawSubFilter, SamplesFilter, SizeFilter},
+    ///     fixture,
+    /// };
+    /// let toc = time::SinceStart::zero();
+    /// // An allocation of 100 bytes, of which only 7 were sampled.
+    /// let alloc = fixture::alloc(0, AllocKind::Minor, 100, 0, None).nsamples(7);
+    ///
+    /// let size = RawSubFilter::Size(SizeFilter::cmp(Cmp::Ge, 10));
+    /// let samples = RawSubFilter::Samples(SamplesFilter::cmp(Cmp::Ge, 10));
+    /// assert!(size.apply(&toc, &alloc));
+    /// assert!(!samples.apply(&toc, &alloc));
+    /// ```
     pub fn apply(&self, timestamp: &time::SinceStart, alloc: &Alloc) -> bool {
         match self {
             RawSubFilter::Size(filter) => filter.apply(&alloc.size),
+            RawSubFilter::Samples(filter) => filter.apply(&alloc.nsamples),
             RawSubFilter::Lifetime(filter) => {
                 let timestamp = alloc
                     .tod()
@@ -59,11 +85,80 @@ impl RawSubFilter {
                     .unwrap_or(*timestamp);
                 filter.apply_at(&timestamp, &alloc.toc())
             }
+            RawSubFilter::AliveAt(filter) => filter.apply(alloc),
+            RawSubFilter::Kind(filter) => filter.apply(alloc),
             RawSubFilter::Label(filter) => filter.apply(&alloc.labels()),
             RawSubFilter::Loc(filter) => filter.apply(&alloc.trace()),
         }
     }
 
+    /// Applies the filter to an allocation, explains why the allocation does not match.
+    ///
+    /// Slower than [`Self::apply`], only meant for diagnostics.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     alloc_data::prelude::*,
+    ///     filter::{ord::Cmp, sub::RawSubFilter, AliveAtFilter, MatchOutcome, SizeFilter},
+    ///     fixture::{alloc, secs},
+    /// };
+    /// let (alloc, now) = (alloc(0, AllocKind::Minor, 100, 0, None), secs(2));
+    ///
+    /// let size = RawSubFilter::Size(SizeFilter::cmp(Cmp::Ge, 10));
+    /// assert_eq!(size.explain(&now, &alloc), MatchOutcome::Match);
+    /// let size = RawSubFilter::Size(SizeFilter::cmp(Cmp::Le, 10));
+    /// assert_eq!(
+    ///     size.explain(&now, &alloc).to_string(),
+    ///     "no match: size 100 is not ≤ 10",
+    /// );
+    /// let alive = RawSubFilter::AliveAt(AliveAtFilter::new(now));
+    /// assert!(alive.explain(&now, &alloc).is_match());
+    /// ```
+    pub fn explain(&self, timestamp: &time::SinceStart, alloc: &Alloc) -> MatchOutcome {
+        if self.apply(timestamp, alloc) {
+            return MatchOutcome::Match;
+        }
+        let reason = match self {
+            RawSubFilter::Size(filter) => format!("size {} is not {}", alloc.size, filter),
+            RawSubFilter::Samples(filter) => {
+                format!("sample count {} is not {}", alloc.nsamples, filter)
+            }
+            RawSubFilter::Lifetime(filter) => {
+                let timestamp = alloc
+                    .tod()
+                    .map(|tod| std::cmp::min(tod, *timestamp))
+                    .unwrap_or(*timestamp);
+                let lifetime = (timestamp - alloc.toc()).to_lifetime();
+                format!("lifetime {} is not {}", lifetime, filter)
+            }
+            RawSubFilter::AliveAt(filter) => format!("not alive {}", filter),
+            RawSubFilter::Kind(filter) => {
+                format!("kind {} is not {}", alloc.kind(), filter.kind())
+            }
+            RawSubFilter::Label(filter) => format!("labels do not satisfy `{}`", filter),
+            RawSubFilter::Loc(filter) => format!("callstack does not satisfy `{}`", filter),
+        };
+        MatchOutcome::no_match(reason)
+    }
+
+    /// Checks that a sub-filter is legal.
+    ///
+    /// Label filters are illegal when one of their regexes is too big, and ordered filters are
+    /// illegal when they are intervals with inverted bounds (see [`ord::OrdFilter::check`]).
+    pub fn check(&self) -> Res<()> {
+        match self {
+            Self::Label(filter) => {
+                for spec in filter.specs() {
+                    spec.check()?
+                }
+                Ok(())
+            }
+            Self::Size(filter) | Self::Samples(filter) => filter.check(),
+            Self::Lifetime(filter) => filter.check(),
+            Self::AliveAt(_) | Self::Kind(_) | Self::Loc(_) => Ok(()),
+        }
+    }
+
     /// Changes the filter kind of a sub-filter.
     ///
     /// Returns `true` iff the filter actually changed.
@@ -88,10 +183,22 @@ impl RawSubFilter {
                 Update::Size(update) => filter.update(update),
                 _ => fail!(),
             },
+            Self::Samples(filter) => match update {
+                Update::Samples(update) => filter.update(update),
+                _ => fail!(),
+            },
             Self::Lifetime(filter) => match update {
                 Update::Lifetime(update) => filter.update(update),
                 _ => fail!(),
             },
+            Self::AliveAt(filter) => match update {
+                Update::AliveAt(update) => filter.update(update),
+                _ => fail!(),
+            },
+            Self::Kind(filter) => match update {
+                Update::Kind(update) => filter.update(update),
+                _ => fail!(),
+            },
             Self::Label(filter) => match update {
                 Update::Label(update) => filter.update(update),
                 _ => fail!(),
@@ -176,8 +283,14 @@ impl SubFilter {
 pub enum Update {
     /// Size filter update.
     Size(ord::SizeUpdate),
+    /// Number of samples filter update.
+    Samples(ord::SamplesUpdate),
     /// Lifetime filter update.
     Lifetime(ord::LifetimeUpdate),
+    /// Alive-at filter update.
+    AliveAt(alive::AliveAtUpdate),
+    /// Allocation kind filter update.
+    Kind(kind::KindUpdate),
     /// Label filter update.
     Label(label::LabelUpdate),
     /// Location filter update.
@@ -204,6 +317,12 @@ base::implement! {
             from LifetimeFilter => |filter| Self::from(
                 uid::SubFilter::fresh(), RawSubFilter::from(filter)
             ),
+            from AliveAtFilter => |filter| Self::from(
+                uid::SubFilter::fresh(), RawSubFilter::from(filter)
+            ),
+            from KindFilter => |filter| Self::from(
+                uid::SubFilter::fresh(), RawSubFilter::from(filter)
+            ),
             from LabelFilter => |filter| Self::from(
                 uid::SubFilter::fresh(), RawSubFilter::from(filter)
             ),
@@ -227,7 +346,10 @@ base::implement! {
         Display {
             |&self, fmt| match self {
                 Self::Size(filter) => write!(fmt, "size {}", filter),
+                Self::Samples(filter) => write!(fmt, "samples {}", filter),
                 Self::Lifetime(filter) => write!(fmt, "lifetime {}", filter),
+                Self::AliveAt(filter) => write!(fmt, "alive {}", filter),
+                Self::Kind(filter) => write!(fmt, "kind {}", filter),
                 Self::Label(filter) => write!(fmt, "labels {}", filter),
                 Self::Loc(filter) => write!(fmt, "callstack {}", filter),
             }
@@ -240,12 +362,17 @@ base::implement! {
         From {
             from FilterKind => |kind| match kind {
                 FilterKind::Size => SizeFilter::default().into(),
+                FilterKind::Samples => Self::Samples(SamplesFilter::default()),
                 FilterKind::Lifetime => LifetimeFilter::default().into(),
+                FilterKind::AliveAt => AliveAtFilter::default().into(),
+                FilterKind::Kind => KindFilter::default().into(),
                 FilterKind::Label => LabelFilter::default().into(),
                 FilterKind::Loc => LocFilter::default().into(),
             },
             from SizeFilter => |filter| Self::Size(filter),
             from LifetimeFilter => |filter| Self::Lifetime(filter),
+            from AliveAtFilter => |filter| Self::AliveAt(filter),
+            from KindFilter => |filter| Self::Kind(filter),
             from LabelFilter => |filter| Self::Label(filter),
             from LocFilter => |filter| Self::Loc(filter),
         }
@@ -255,7 +382,10 @@ base::implement! {
         Display {
             |&self, fmt| match self {
                 Self::Size(update) => update.fmt(fmt),
+                Self::Samples(update) => update.fmt(fmt),
                 Self::Lifetime(update) => update.fmt(fmt),
+                Self::AliveAt(update) => update.fmt(fmt),
+                Self::Kind(update) => update.fmt(fmt),
                 Self::Label(update) => update.fmt(fmt),
                 Self::Loc(update) => update.fmt(fmt),
             }
@@ -0,0 +1,115 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Fixtures for the examples of the documentation.
+//!
+//! Not part of the API, only meant to keep the examples focused on what they illustrate.
+
+use alloc_data::prelude::*;
+
+use crate::{
+    color::Color,
+    filter::{ord::Cmp, Filter, FilterSpec, SizeFilter, SubFilter},
+};
+
+/// Point in time, in seconds since the start of the run.
+pub fn secs(secs: u64) -> time::SinceStart {
+    time::SinceStart::from_secs(secs)
+}
+
+/// Allocation UID.
+pub fn uid(uid: usize) -> uid::Alloc {
+    uid::Alloc::from(uid)
+}
+
+/// An empty trace and empty labels.
+pub fn trace_and_labels() -> (Trace, Labels) {
+    let mut factory = alloc_data::mem::Factory::new(false);
+    (factory.register_trace(vec![]), factory.empty_labels())
+}
+
+/// Builder for a minor allocation with an empty trace and no labels, created at `toc` seconds.
+pub fn builder(uid: usize, size: u32, toc: u64) -> Builder {
+    let (trace, labels) = trace_and_labels();
+    let uid = Some(self::uid(uid));
+    Builder::new(uid, AllocKind::Minor, size, trace, labels, secs(toc), None)
+}
+
+/// Builder for a minor allocation with no labels, created at `toc` seconds, whose trace goes
+/// through line `1` of some files, from the root of the stack to the allocation site.
+pub fn traced_builder(uid: usize, size: u32, toc: u64, files: &[&str]) -> Builder {
+    let mut factory = alloc_data::mem::Factory::new(false);
+    let locs = files
+        .iter()
+        .map(|file| CLoc::new(factory.register_loc(file, 1, (0, 1)), 1))
+        .collect();
+    let (trace, labels) = (factory.register_trace(locs), factory.empty_labels());
+    let uid = Some(self::uid(uid));
+    Builder::new(uid, AllocKind::Minor, size, trace, labels, secs(toc), None)
+}
+
+/// Allocation with an empty trace and no labels, created at `toc` seconds and dead at `tod`
+/// seconds, if any.
+///
+/// All the allocations are sampled, *i.e.* the sampling rate is `1`.
+pub fn alloc(uid: usize, kind: AllocKind, size: u32, toc: u64, tod: Option<u64>) -> Alloc {
+    let (trace, labels) = trace_and_labels();
+    let rate = SampleRate::new(1.0, 64);
+    Alloc::new(
+        &rate,
+        uid,
+        kind,
+        size,
+        trace,
+        labels,
+        secs(toc),
+        tod.map(secs),
+    )
+}
+
+/// Filter on the size of the allocations, *e.g.* `size_filter(Cmp::Ge, 8)`.
+pub fn size_filter(cmp: Cmp, size: u32) -> Filter {
+    let mut filter = Filter::new(FilterSpec::new(Color::new(0, 0, 0)))
+        .expect("filter creation cannot fail without sub-filters");
+    filter
+        .insert(SubFilter::from(SizeFilter::cmp(cmp, size)))
+        .expect("inserting a sub-filter in a fresh filter cannot fail");
+    filter
+}
+
+/// Data of a run with the default init, see [`data_with`].
+#[cfg(any(test, feature = "server"))]
+pub fn data(diffs: Vec<Diff>) -> crate::data::Data {
+    data_with(Init::default(), diffs)
+}
+
+/// Replaces the global data with the data of a run with the default init, see [`data`].
+#[cfg(any(test, feature = "server"))]
+pub fn set_global_data(diffs: Vec<Diff>) {
+    *crate::data::get_mut().expect("cannot access the global data") = data(diffs)
+}
+
+/// Data of a run with some init, after registering some diffs.
+#[cfg(any(test, feature = "server"))]
+pub fn data_with(init: Init, diffs: Vec<Diff>) -> crate::data::Data {
+    let mut data = crate::data::Data::new();
+    data.reset("dump_dir", init);
+    data.add_diffs(diffs).expect("illegal diffs");
+    data
+}
@@ -19,16 +19,17 @@
 
 //! Server-side chart handling.
 //!
-//! Note that most types in this crate implement (de)serialization to/from json. These types
-//! implement the [`Json`] trait which provides straightforward conversion functions. This is used
-//! heavily for server/client exchanges.
+//! Note that most types in this crate implement serde's (de)serialization traits. Server/client
+//! exchanges go through [`msg::to_server::Msg`] and [`msg::to_client::Msg`], which are encoded as
+//! binary messages with [`bincode`] (see their `to_bytes` and `from_bytes` functions) as it is
+//! much more compact than JSON for large lists of points.
 //!
 //! # Basic Workflow
 //!
 //! All allocation-related data is stored in a global state in the [`data`] module. It features a
 //! [`Watcher`] type which, after [`start`]ing it, will monitor a directory for init and diff files.
 //!
-//! [`Json`]: yew::format::Json (The Json trait)
+//! [`bincode`]: base::bincode (The bincode crate)
 //! [`Watcher`]: data::Watcher (The Watcher struct in module data)
 //! [`start`]: data::start (The start function in module data)
 
@@ -46,6 +47,9 @@ pub mod color;
 #[cfg(any(test, feature = "server"))]
 pub mod data;
 pub mod filter;
+#[cfg(any(test, doctest, feature = "fixture"))]
+#[doc(hidden)]
+pub mod fixture;
 pub mod msg;
 pub mod point;
 
@@ -73,14 +77,24 @@ pub struct Charts {
     /// List of filters.
     filters: Filters,
     /// Start time of the run.
+    start_time: Option<time::Date>,
+    /// Sample rate of the run, see [`chart::ChartSpec::sample_rate`].
+    sample_rate: Option<SampleRate>,
+    /// Identifier of the run.
     ///
     /// This is used to check whether we need to detect that the init file of the run has changed
     /// and that we need to reset the charts.
-    start_time: Option<time::Date>,
+    run_id: Option<u64>,
     /// List of messages for the client, populated/drained when receiving messages.
     to_client_msgs: msg::to_client::Msgs,
     /// Settings.
     settings: settings::Charts,
+    /// Time cursor shared by all the charts, see [`Self::set_cursor`].
+    cursor: Option<time::SinceStart>,
+    /// Cancellation flag of the last load requested by these charts, see [`data::rescan`].
+    ///
+    /// A client can only cancel the loads it requested.
+    load: Option<Arc<std::sync::atomic::AtomicBool>>,
 }
 
 #[cfg(any(test, feature = "server"))]
@@ -91,8 +105,12 @@ impl Charts {
             charts: vec![],
             filters: Filters::new(),
             start_time: None,
+            sample_rate: None,
+            run_id: None,
             to_client_msgs: msg::to_client::Msgs::with_capacity(7),
             settings: settings::Charts::new(),
+            cursor: None,
+            load: None,
         }
     }
 
@@ -108,6 +126,57 @@ impl Charts {
     pub fn start_time(&self) -> Option<&time::Date> {
         self.start_time.as_ref()
     }
+    /// Time cursor shared by all the charts, if any.
+    pub fn cursor(&self) -> Option<time::SinceStart> {
+        self.cursor
+    }
+
+    /// Sets the time cursor shared by all the charts, returns the message for the client.
+    ///
+    /// Cursors past the end of the data are moved to the end of the data, so that the cursor is
+    /// always a point in time the data knows about. The data only grows, so such a cursor stays
+    /// at the same place while the run goes on. The message carries the total size of the
+    /// allocations live at the (moved) cursor that the filters of the charts match, or of all of
+    /// them if there are no filters, see [`data::Data::live_bytes_at`].
+    ///
+    /// ```rust
+    /// use charts::{msg::to_client, prelude::*, Charts};
+    /// charts::data::start("../ctf/rsc/synthetic.ctf", Default::default()).unwrap();
+    /// # while charts::data::progress::get().unwrap().is_some()
+    /// #     || charts::data::alloc_count().unwrap() == 0
+    /// # {
+    /// #     std::thread::sleep(std::time::Duration::from_millis(50))
+    /// # }
+    /// let end = *charts::data::get().unwrap().current_time();
+    /// let mut charts = Charts::new();
+    ///
+    /// let msg = charts.set_cursor(Some(end + time::SinceStart::from_secs(60))).unwrap();
+    /// assert_eq!(charts.cursor(), Some(end));
+    /// match msg {
+    ///     to_client::Msg::Charts(to_client::ChartsMsg::Cursor { time, .. }) => {
+    ///         assert_eq!(time, Some(end))
+    ///     }
+    ///     msg => panic!("unexpected message {}", msg),
+    /// }
+    ///
+    /// charts.set_cursor(None).unwrap();
+    /// assert_eq!(charts.cursor(), None);
+    /// ```
+    pub fn set_cursor(&mut self, cursor: Option<time::SinceStart>) -> Res<msg::to_client::Msg> {
+        let (cursor, live_bytes) = match cursor {
+            Some(cursor) => {
+                let data = data::get().chain_err(|| "while setting the time cursor")?;
+                let cursor = std::cmp::min(cursor, *data.current_time());
+                (Some(cursor), data.live_bytes_at(cursor, &self.filters))
+            }
+            None => (None, 0),
+        };
+        self.cursor = cursor;
+        for chart in &mut self.charts {
+            chart.settings_mut().set_cursor(cursor)
+        }
+        Ok(msg::to_client::ChartsMsg::cursor(cursor, live_bytes))
+    }
 
     /// Runs filter generation.
     ///
@@ -119,16 +188,31 @@ impl Charts {
             charts,
             filters,
             start_time: None,
+            sample_rate: None,
+            run_id: None,
             to_client_msgs: msg::to_client::Msgs::with_capacity(7),
             settings: settings::Charts::new(),
+            cursor: None,
+            load: None,
         })
     }
 
     /// Pushes a new chart.
-    pub fn push(&mut self, chart: Chart) {
+    ///
+    /// Sets the sample rate of the chart's spec to the one of the current run, if any.
+    pub fn push(&mut self, mut chart: Chart) {
+        chart.spec_mut().set_sample_rate(self.sample_rate.clone());
         self.charts.push(chart)
     }
 
+    /// Chart accessor.
+    pub fn get(&self, uid: uid::Chart) -> Res<&Chart> {
+        self.charts
+            .iter()
+            .find(|chart| chart.uid() == uid)
+            .ok_or_else(|| format!("cannot access chart with unknown UID #{}", uid).into())
+    }
+
     /// Chart mutable accessor.
     pub fn get_mut(&mut self, uid: uid::Chart) -> Res<&mut Chart> {
         for chart in self.charts.iter_mut() {
@@ -144,16 +228,27 @@ impl Charts {
 impl Charts {
     /// Restarts the charts and the filters if needed.
     fn restart_if_needed(&mut self) -> Res<bool> {
-        let data = data::get();
-        let start_time = data
-            .and_then(|data| data.start_time())
+        let (run_id, start_time, sample_rate) = data::get()
+            .and_then(|data| {
+                let sample_rate = data.init().map(|init| init.sample_rate.clone());
+                Ok((data.run_id()?, data.start_time()?, sample_rate))
+            })
             .chain_err(|| "while checking if the charts should be restarted")?;
-        if self.start_time != Some(start_time) {
+        if self.run_id != Some(run_id) {
+            self.run_id = Some(run_id);
             self.start_time = Some(start_time);
+            self.sample_rate = sample_rate;
             for chart in &mut self.charts {
+                chart.settings_mut().set_start_date(Some(start_time));
+                chart.spec_mut().set_sample_rate(self.sample_rate.clone());
+                self.to_client_msgs
+                    .push(msg::to_client::ChartMsg::sample_rate(
+                        chart.uid(),
+                        self.sample_rate.clone(),
+                    ));
                 chart.reset(&self.filters)
             }
-            self.filters.reset();
+            self.filters.reset(filter::Reset::Hard);
             Ok(true)
         } else {
             Ok(false)
@@ -164,65 +259,148 @@ impl Charts {
     ///
     /// The boolean indicates whether the points should overwrite existing points. It is typically
     /// true when the init file of the run has changed (the run was restarted).
+    ///
+    /// A chart failing to produce its points does not prevent the other charts from producing
+    /// theirs. The error is logged, and a [`msg::to_client::ChartMsg::Error`] for the failing
+    /// chart is registered; it is retrieved with [`Self::drain_to_client_msgs`].
+    ///
+    /// Also counts the new and dead allocations of each filter, see [`Self::take_filter_delta`].
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     chart::{axis::*, settings::Resolution, Chart},
+    ///     color::Color,
+    ///     filter::*,
+    ///     msg::to_client::{ChartMsg, ChartsMsg, Msg},
+    ///     prelude::{alloc::Diff, *},
+    ///     Charts,
+    /// };
+    /// charts::data::start("../ctf/rsc/synthetic.ctf", Default::default()).unwrap();
+    /// # while charts::data::progress::get().unwrap().is_some() {
+    /// #     std::thread::sleep(std::time::Duration::from_millis(10))
+    /// # }
+    /// # while charts::data::get().unwrap().alloc_count() == 0 {
+    /// #     std::thread::sleep(std::time::Duration::from_millis(10))
+    /// # }
+    /// let resolution = Resolution { width: 100, height: 100 };
+    /// let window = TimeWindopt::new(None, None);
+    /// let mut charts = Charts::new();
+    /// // Handles the start of the run, which resets all the charts.
+    /// charts.new_points(true).unwrap();
+    ///
+    /// let filter = Filter::new(FilterSpec::new(Color::new(0, 0, 0))).unwrap();
+    /// let ok = charts
+    ///     .create_chart(XAxis::Time, YAxis::TotalSize, vec![filter])
+    ///     .unwrap();
+    /// charts.get_mut(ok).unwrap().settings_mut().set_resolution(resolution);
+    ///
+    /// // Drawn without the filter registered above, this chart fails on the deaths of the
+    /// // allocations the filter catches.
+    /// let mut stale = Chart::new(
+    ///     &Filters::new(),
+    ///     XAxis::Time,
+    ///     YAxis::TotalSize,
+    ///     Filters::new().uid_map(true),
+    /// )
+    /// .unwrap();
+    /// stale.settings_mut().set_resolution(resolution);
+    /// stale.new_points(true, &mut Filters::new(), &window).unwrap();
+    /// let stale_uid = stale.uid();
+    /// charts.push(stale);
+    /// let _ = charts.drain_to_client_msgs().count();
+    ///
+    /// // Some allocation dies at the end of the run.
+    /// let dead = {
+    ///     let data = charts::data::get().unwrap();
+    ///     let alloc = data.iter_allocs().find(|alloc| alloc.tod().is_none()).unwrap();
+    ///     (*alloc.uid(), data.end_time().unwrap())
+    /// };
+    /// charts::data::add_diff(Diff::new(dead.1, vec![], vec![dead])).unwrap();
+    ///
+    /// let (points, _) = charts.new_points(false).unwrap();
+    /// // The healthy chart has points, the stale one only produces an error.
+    /// assert!(points.get(&ok).is_some());
+    /// assert!(points.get(&stale_uid).is_none());
+    /// let errors: Vec<_> = charts
+    ///     .drain_to_client_msgs()
+    ///     .filter_map(|msg| match msg {
+    ///         Msg::Charts(ChartsMsg::Chart {
+    ///             uid,
+    ///             msg: ChartMsg::Error(_),
+    ///         }) => Some(uid),
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(errors, vec![stale_uid]);
+    /// ```
     pub fn new_points(&mut self, init: bool) -> Res<(point::ChartPoints, bool)> {
+        // No run yet, happens while the data is being (re)loaded.
+        if !data::get()?.has_init() {
+            return Ok((point::ChartPoints::new(), false));
+        }
         let restarted = self.restart_if_needed()?;
+        let time_windopt = self.settings.time_windopt_until(data::get()?.end_time());
         let mut points = point::ChartPoints::new();
         for chart in &mut self.charts {
-            if let Some(chart_points) = chart.new_points(
-                restarted || init,
-                &mut self.filters,
-                self.settings.time_windopt(),
-            )? {
-                let prev = points.insert(chart.uid(), chart_points);
-                debug_assert!(prev.is_none())
+            match chart.new_points(restarted || init, &mut self.filters, &time_windopt) {
+                Ok(Some(chart_points)) => {
+                    let prev = points.insert(chart.uid(), chart_points);
+                    debug_assert!(prev.is_none())
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    let e = e.chain_err(|| {
+                        format!("while extracting points for chart #{}", chart.uid())
+                    });
+                    log::error!("{}", e.to_pretty());
+                    self.to_client_msgs
+                        .push(msg::to_client::ChartMsg::error(chart.uid(), e.to_pretty()));
+                }
             }
         }
+        self.filters
+            .update_delta(&*data::get()?)
+            .chain_err(|| "while counting the allocations of the filters")?;
+        self.strip_inactive_lines(&mut points);
         Ok((points, restarted || init))
     }
 
+    /// Allocation counts of the filters since the last call, for the next points message.
+    ///
+    /// The counts are updated by [`Self::new_points`] and [`Self::reload_points`].
+    pub fn take_filter_delta(&mut self) -> filter::stats::FilterDelta {
+        self.filters.take_delta()
+    }
+
     /// Handles a charts message from the client.
     pub fn handle_chart_msg(&mut self, msg: msg::to_server::ChartsMsg) -> Res<bool> {
         debug_assert!(self.to_client_msgs.is_empty());
 
         let reloaded = match msg {
             msg::to_server::ChartsMsg::New(x_axis, y_axis) => {
-                let all_active = self.filters.fold(BTMap::new(), |mut map, uid| {
-                    let prev = map.insert(uid, true);
-                    debug_assert_eq!(prev, None);
-                    map
-                });
-                let nu_chart = chart::Chart::new(&mut self.filters, x_axis, y_axis, all_active)
-                    .chain_err(|| "while creating new chart")?;
-
-                // Chart creation message.
-                self.to_client_msgs
-                    .push(msg::to_client::ChartsMsg::new_chart(
-                        nu_chart.spec().clone(),
-                        nu_chart.settings().clone(),
-                    ));
-                // // Initial points message.
-                // let points = nu_chart.new_points(&mut self.filters, true).chain_err(|| {
-                //     format!(
-                //         "while generating the initial points for new chart #{}",
-                //         nu_chart.uid()
-                //     )
-                // })?;
-                // to_client_msgs.push(msg::to_client::ChartMsg::new_points(nu_chart.uid(), points));
-
-                self.charts.push(nu_chart);
+                self.create_chart(x_axis, y_axis, vec![])?;
+                true
+            }
+
+            msg::to_server::ChartsMsg::Create {
+                x_axis,
+                y_axis,
+                filters,
+            } => {
+                self.create_chart(x_axis, y_axis, filters)?;
                 true
             }
 
             msg::to_server::ChartsMsg::Reload => {
-                let msg = self.reload_points(None, false)?;
+                let msg = self.reload_points(None, false, filter::Reset::Hard)?;
                 self.to_client_msgs.push(msg);
                 true
             }
 
             msg::to_server::ChartsMsg::ChartUpdate { uid, msg } => {
-                let reload = self.get_mut(uid)?.update(msg);
+                let reload = self.get_mut(uid)?.update(msg)?;
                 if reload {
-                    let msg = self.reload_points(Some(uid), false)?;
+                    let msg = self.reload_points(Some(uid), false, filter::Reset::Hard)?;
                     self.to_client_msgs.push(msg);
                     reload
                 } else {
@@ -230,10 +408,21 @@ impl Charts {
                 }
             }
 
+            msg::to_server::ChartsMsg::SetCursor(cursor) => {
+                let msg = self.set_cursor(cursor)?;
+                self.to_client_msgs.push(msg);
+                false
+            }
+
             msg::to_server::ChartsMsg::Settings(settings) => {
                 let send_new_points = self.settings.overwrite(settings);
+                // The client updates the time format of its charts itself.
+                let time_format = self.settings.time_format();
+                for chart in &mut self.charts {
+                    chart.settings_mut().set_time_format(time_format)
+                }
                 if send_new_points {
-                    let msg = self.reload_points(None, false)?;
+                    let msg = self.reload_points(None, false, filter::Reset::Hard)?;
                     self.to_client_msgs.push(msg);
                 }
                 false
@@ -243,12 +432,87 @@ impl Charts {
         Ok(reloaded)
     }
 
+    /// Registers some new filters and creates a new chart, in one call.
+    ///
+    /// The chart has all the filters active, including the new ones, which are registered after
+    /// the existing filters. The messages for the client are the same as the ones interactive
+    /// creation would yield: the new filters if any, the new chart, and the reloaded points if
+    /// the filters changed. When not going through [`Self::handle_msg`], they are retrieved with
+    /// [`Self::drain_to_client_msgs`].
+    pub fn create_chart(
+        &mut self,
+        x_axis: chart::axis::XAxis,
+        y_axis: chart::axis::YAxis,
+        filters: Vec<filter::Filter>,
+    ) -> Res<uid::Chart> {
+        let new_filters = !filters.is_empty();
+        if new_filters {
+            let msgs = self
+                .filters
+                .add_all(filters)
+                .chain_err(|| "while registering the filters of a new chart")?;
+            self.to_client_msgs.extend(msgs);
+        }
+
+        let all_active = self.filters.uid_map(true);
+        let mut nu_chart = chart::Chart::new(&self.filters, x_axis, y_axis, all_active)
+            .chain_err(|| "while creating new chart")?;
+        nu_chart
+            .settings_mut()
+            .set_time_format(self.settings.time_format());
+        nu_chart.settings_mut().set_start_date(self.start_time);
+        nu_chart
+            .spec_mut()
+            .set_sample_rate(self.sample_rate.clone());
+        nu_chart.settings_mut().set_cursor(self.cursor);
+        let uid = nu_chart.uid();
+
+        // Chart creation message.
+        self.to_client_msgs
+            .push(msg::to_client::ChartsMsg::new_chart(
+                nu_chart.spec().clone(),
+                nu_chart.settings().clone(),
+            ));
+        self.charts.push(nu_chart);
+
+        if new_filters {
+            let msg = self.reload_points(None, true, filter::Reset::Hard)?;
+            self.to_client_msgs.push(msg);
+        }
+
+        Ok(uid)
+    }
+
+    /// Drains the pending messages for the client.
+    ///
+    /// Only needed after calling functions such as [`Self::create_chart`] directly, as
+    /// [`Self::handle_msg`] already yields the messages it produces.
+    pub fn drain_to_client_msgs(&mut self) -> impl Iterator<Item = msg::to_client::Msg> + '_ {
+        self.to_client_msgs.drain(0..)
+    }
+
+    /// Removes the lines of inactive filters from some points.
+    ///
+    /// See [`filter::FilterSpec::is_active`].
+    fn strip_inactive_lines(&self, points: &mut point::ChartPoints) {
+        for spec in self.filters.specs() {
+            if !spec.is_active() {
+                points.remove_line(spec.uid())
+            }
+        }
+    }
+
     /// Recomputes all the points, and returns them as a message for the client.
+    ///
+    /// The filters are reset using `reset`: [`filter::Reset::Soft`] is only legal if the filter
+    /// predicates did not change since the points were last computed.
     pub fn reload_points(
         &mut self,
         uid: Option<uid::Chart>,
         refresh_filters: bool,
+        reset: filter::Reset,
     ) -> Res<msg::to_client::Msg> {
+        let time_windopt = self.settings.time_windopt_until(data::get()?.end_time());
         let mut new_points = point::ChartPoints::new();
         for chart in &mut self.charts {
             if let Some(uid) = uid {
@@ -257,9 +521,9 @@ impl Charts {
                 }
             }
             chart.reset(&self.filters);
-            self.filters.reset();
+            self.filters.reset(reset);
             let points_opt = chart
-                .new_points(true, &mut self.filters, self.settings.time_windopt())
+                .new_points(true, &mut self.filters, &time_windopt)
                 .chain_err(|| format!("while generating points for chart #{}", chart.uid()))?;
             if let Some(points) = points_opt {
                 let prev = new_points.insert(chart.uid(), points);
@@ -268,12 +532,83 @@ impl Charts {
                 }
             }
         }
+        self.filters
+            .update_delta(&*data::get()?)
+            .chain_err(|| "while counting the allocations of the filters")?;
+        self.strip_inactive_lines(&mut new_points);
         Ok(msg::to_client::ChartsMsg::new_points(
             new_points,
             refresh_filters,
+            self.filters.take_delta(),
         ))
     }
 
+    /// Handles a data request from the client.
+    pub fn handle_data_msg(&mut self, msg: msg::to_server::DataMsg) -> Res<msg::to_client::Msg> {
+        use msg::to_server::DataMsg::*;
+        let res = match msg {
+            Flamegraph { at_time } => {
+                let graph = data::get()?.flamegraph(&self.filters, at_time);
+                msg::to_client::DataMsg::Flamegraph(graph.to_folded())
+            }
+            TopAllocSites { n } => {
+                let sites = data::get()?.top_alloc_sites(&self.filters, n);
+                msg::to_client::DataMsg::TopAllocSites(sites)
+            }
+            Explain { alloc } => {
+                let data = data::get()?;
+                let alloc = data
+                    .get_alloc(alloc)
+                    .ok_or_else(|| format!("cannot explain unknown allocation #{}", alloc))?;
+                msg::to_client::DataMsg::Explain(self.filters.explain(data.current_time(), alloc))
+            }
+            Search { query, limit } => {
+                let allocs = data::get()?.search(&query, limit);
+                msg::to_client::DataMsg::SearchResults { query, allocs }
+            }
+            ExportCsv { chart } => {
+                let time_windopt = self.settings.time_windopt_until(data::get()?.end_time());
+                let csv = self
+                    .get(chart)?
+                    .export_csv(&self.filters, &time_windopt)
+                    .chain_err(|| format!("while exporting chart #{}", chart))?;
+                msg::to_client::DataMsg::Csv { chart, csv }
+            }
+            Reparse => {
+                self.load = Some(data::rescan()?);
+                return Ok(msg::to_client::Msg::Info);
+            }
+            CancelLoading => {
+                match self.load.as_ref() {
+                    Some(load) => load.store(true, std::sync::atomic::Ordering::SeqCst),
+                    None => log::info!("ignoring cancel request, no load was requested"),
+                }
+                return Ok(msg::to_client::Msg::Info);
+            }
+            PauseIngestion => {
+                data::pause_ingestion()?;
+                msg::to_client::DataMsg::IngestionPaused(true)
+            }
+            ResumeIngestion => {
+                data::resume_ingestion()?;
+                msg::to_client::DataMsg::IngestionPaused(false)
+            }
+            RunInfo => {
+                let data = data::get()?;
+                let errors = data.errors();
+                self.to_client_msgs.push(
+                    msg::to_client::DataMsg::Errors {
+                        errors: errors.iter().map(String::from).collect(),
+                        dropped: errors.dropped(),
+                    }
+                    .into(),
+                );
+                msg::to_client::DataMsg::RunInfo(data.init().map(crate::RunInfo::new))
+            }
+        };
+        Ok(res.into())
+    }
+
     /// Handles a message from the client.
     pub fn handle_msg<'me>(
         &'me mut self,
@@ -282,14 +617,20 @@ impl Charts {
         use msg::to_server::Msg::*;
 
         let reload = match msg {
+            Start | Resume(_) => bail!("unexpected session message `{}`", msg),
             Charts(msg) => self.handle_chart_msg(msg)?,
             Filters(msg) => {
-                let (mut msgs, should_reload) = self.filters.update(msg)?;
-                if should_reload {
-                    msgs.push(self.reload_points(None, true)?)
+                let (mut msgs, reset) = self.filters.update(msg, &self.settings)?;
+                if let Some(reset) = reset {
+                    msgs.push(self.reload_points(None, true, reset)?)
                 }
                 self.to_client_msgs.extend(msgs);
-                should_reload
+                reset.is_some()
+            }
+            Data(msg) => {
+                let msg = self.handle_data_msg(msg)?;
+                self.to_client_msgs.push(msg);
+                false
             }
         };
 
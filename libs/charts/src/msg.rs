@@ -22,6 +22,11 @@
 prelude! {}
 use filter::*;
 
+/// Session token, minted by the server when a client first connects.
+///
+/// Clients use it to resume their session after losing their connection to the server.
+pub type SessionToken = u64;
+
 /// Chart settings message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ChartSettingsMsg {
@@ -33,6 +38,14 @@ pub enum ChartSettingsMsg {
     SetDisplayMode(chart::settings::DisplayMode),
     /// Changes the resolution of a chart.
     SetResolution(chart::settings::Resolution),
+    /// Changes the number of buckets of a histogram.
+    SetBucketCount(usize),
+    /// Switches between log-scale and linear buckets for a histogram.
+    SetLogBuckets(bool),
+    /// Switches between log-scale and linear y-axis.
+    SetYLog(bool),
+    /// Splits (or not) the lines of a time chart by allocation kind.
+    SetSplitByKind(bool),
 }
 
 impl ChartSettingsMsg {
@@ -70,6 +83,42 @@ impl ChartSettingsMsg {
     {
         (uid, Self::SetResolution(resolution.into())).into()
     }
+
+    /// Changes the number of buckets of a histogram.
+    pub fn set_bucket_count<Res>(uid: uid::Chart, count: usize) -> Res
+    where
+        (uid::Chart, Self): Into<Res>,
+    {
+        (uid, Self::SetBucketCount(count)).into()
+    }
+
+    /// Switches between log-scale and linear buckets for a histogram.
+    pub fn set_log_buckets<Res>(uid: uid::Chart, log: bool) -> Res
+    where
+        (uid::Chart, Self): Into<Res>,
+    {
+        (uid, Self::SetLogBuckets(log)).into()
+    }
+
+    /// Switches between log-scale and linear y-axis.
+    ///
+    /// Only impacts rendering, see [`set_y_log`](chart::settings::Chart::set_y_log) for details.
+    pub fn set_y_log<Res>(uid: uid::Chart, log: bool) -> Res
+    where
+        (uid::Chart, Self): Into<Res>,
+    {
+        (uid, Self::SetYLog(log)).into()
+    }
+
+    /// Splits (or not) the lines of a time chart by allocation kind.
+    ///
+    /// See [`set_split_by_kind`](chart::settings::Chart::set_split_by_kind) for details.
+    pub fn set_split_by_kind<Res>(uid: uid::Chart, split: bool) -> Res
+    where
+        (uid::Chart, Self): Into<Res>,
+    {
+        (uid, Self::SetSplitByKind(split)).into()
+    }
 }
 
 impl fmt::Display for ChartSettingsMsg {
@@ -79,6 +128,10 @@ impl fmt::Display for ChartSettingsMsg {
             Self::SetDisplayMode(mode) => write!(fmt, "set display mode: {}", mode.desc()),
             Self::ChangeTitle(title) => write!(fmt, "change title: {}", title),
             Self::SetResolution(resolution) => write!(fmt, "set resolution: {}", resolution),
+            Self::SetBucketCount(count) => write!(fmt, "set bucket count: {}", count),
+            Self::SetLogBuckets(log) => write!(fmt, "set log buckets: {}", log),
+            Self::SetYLog(log) => write!(fmt, "set log y-axis: {}", log),
+            Self::SetSplitByKind(split) => write!(fmt, "set split by kind: {}", split),
         }
     }
 }
@@ -91,19 +144,33 @@ pub mod to_server {
     pub type Msgs = Vec<Msg>;
 
     /// Messages from the client to the server.
+    ///
+    /// The first message of a client on a new connection must be either [`Msg::Start`] or
+    /// [`Msg::Resume`].
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub enum Msg {
+        /// Starts a new session.
+        Start,
+        /// Resumes a session after a connection loss.
+        Resume(SessionToken),
+
         /// Operations over charts.
         Charts(ChartsMsg),
 
         /// Operation over filters.
         Filters(FiltersMsg),
+
+        /// Requests over the allocation data.
+        Data(DataMsg),
     }
     impl fmt::Display for Msg {
         fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
             match self {
+                Self::Start => "start".fmt(fmt),
+                Self::Resume(token) => write!(fmt, "resume({:x})", token),
                 Self::Charts(msg) => write!(fmt, "charts({})", msg),
                 Self::Filters(msg) => write!(fmt, "filters({})", msg),
+                Self::Data(msg) => write!(fmt, "data({})", msg),
             }
         }
     }
@@ -127,6 +194,7 @@ pub mod to_server {
                 from (uid::Chart, ChartSettingsMsg) => |pair| Self::Charts(ChartsMsg::from(pair)),
                 from FiltersMsg => |msg| Self::Filters(msg),
                 from ChartsMsg => |msg| Self::Charts(msg),
+                from DataMsg => |msg| Self::Data(msg),
             }
 
             Into {
@@ -146,6 +214,18 @@ pub mod to_server {
     pub enum ChartsMsg {
         /// Creates a new chart.
         New(chart::axis::XAxis, chart::axis::YAxis),
+        /// Registers some new filters and creates a new chart, in one go.
+        ///
+        /// Yields the same messages for the client as [`ChartsMsg::New`] followed by a filter update
+        /// adding `filters`, see `Charts::create_chart` server-side.
+        Create {
+            /// X-axis of the new chart.
+            x_axis: chart::axis::XAxis,
+            /// Y-axis of the new chart.
+            y_axis: chart::axis::YAxis,
+            /// Filters to register before creating the chart.
+            filters: Vec<Filter>,
+        },
         /// Reloads all charts.
         Reload,
         /// An update for a specific chart.
@@ -157,14 +237,23 @@ pub mod to_server {
         },
         /// New value for the global charts settings.
         Settings(settings::Charts),
+        /// Sets the time cursor shared by all the charts, `None` removes it.
+        ///
+        /// See `Charts::set_cursor` server-side for cursors outside of the data range.
+        SetCursor(Option<time::SinceStart>),
     }
     impl fmt::Display for ChartsMsg {
         fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
             match self {
                 Self::New(_, _) => write!(fmt, "new chart"),
+                Self::Create { filters, .. } => {
+                    write!(fmt, "create chart with {} new filter(s)", filters.len())
+                }
                 Self::Reload => write!(fmt, "reload"),
                 Self::ChartUpdate { uid, msg } => write!(fmt, "update({}, {})", uid, msg),
                 Self::Settings(_) => write!(fmt, "new settings"),
+                Self::SetCursor(Some(time)) => write!(fmt, "set cursor at {}", time),
+                Self::SetCursor(None) => write!(fmt, "remove cursor"),
             }
         }
     }
@@ -173,6 +262,19 @@ pub mod to_server {
         pub fn new(x: chart::axis::XAxis, y: chart::axis::YAxis) -> Msg {
             Self::New(x, y).into()
         }
+        /// Constructs a chart creation message that also registers some filters.
+        pub fn create(
+            x_axis: chart::axis::XAxis,
+            y_axis: chart::axis::YAxis,
+            filters: Vec<Filter>,
+        ) -> Msg {
+            Self::Create {
+                x_axis,
+                y_axis,
+                filters,
+            }
+            .into()
+        }
         /// Reloads all charts.
         pub fn reload() -> Msg {
             Self::Reload.into()
@@ -181,6 +283,10 @@ pub mod to_server {
         pub fn settings(settings: settings::Charts) -> Msg {
             Self::Settings(settings).into()
         }
+        /// Sets the time cursor shared by all the charts.
+        pub fn set_cursor(cursor: Option<time::SinceStart>) -> Msg {
+            Self::SetCursor(cursor).into()
+        }
     }
 
     base::implement! {
@@ -200,11 +306,23 @@ pub mod to_server {
     pub enum ChartMsg {
         /// Settings update.
         SettingsUpdate(ChartSettingsMsg),
+        /// Sets the custom title of the chart, `None` falls back on its description.
+        SetTitle(Option<String>),
+    }
+    impl ChartMsg {
+        /// Sets the custom title of a chart, see [`ChartSpec::set_title`].
+        ///
+        /// [`ChartSpec::set_title`]: crate::chart::ChartSpec::set_title
+        pub fn set_title(uid: uid::Chart, title: Option<String>) -> Msg {
+            (uid, Self::SetTitle(title)).into()
+        }
     }
     impl fmt::Display for ChartMsg {
         fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
             match self {
                 Self::SettingsUpdate(_) => write!(fmt, "settings update"),
+                Self::SetTitle(Some(title)) => write!(fmt, "set title `{}`", title),
+                Self::SetTitle(None) => write!(fmt, "reset title"),
             }
         }
     }
@@ -243,6 +361,29 @@ pub mod to_server {
         /// Requests the current server-side list of filters.
         Revert,
 
+        /// Adds a filter showing the leaked allocations, *i.e.* the ones never freed.
+        ///
+        /// Unlike [`FiltersMsg::RequestNew`], the server registers the filter right away and
+        /// answers with its new list of filters (*via* [`FiltersMsg::Revert`]), discarding any
+        /// unsaved modifications client-side.
+        ///
+        /// [`FiltersMsg::Revert`]: to_client::FiltersMsg::Revert
+        /// (The Revert message)
+        RequestLeaks,
+
+        /// Activates an inactive filter, or deactivates an active one.
+        ///
+        /// Inactive filters have no series in the charts, but still catch allocations. Unlike
+        /// [`FiltersMsg::UpdateAll`], this applies right away.
+        ToggleActive(uid::Line),
+
+        /// Sets the order of the custom filters, *i.e.* their priority.
+        ///
+        /// The list must be a permutation of the UIDs of the filters the server knows. Like
+        /// [`FiltersMsg::ToggleActive`], this applies right away: the client is expected to have
+        /// reordered its filters already, the server only reloads the points.
+        Reorder(Vec<uid::Filter>),
+
         /// Updates all the filters.
         UpdateAll {
             /// New specificationfor the "everything" filter.
@@ -259,6 +400,9 @@ pub mod to_server {
                 Self::RequestNew => write!(fmt, "request new"),
                 Self::RequestNewSub(_) => write!(fmt, "request new sub"),
                 Self::Revert => write!(fmt, "revert"),
+                Self::RequestLeaks => write!(fmt, "request leaks"),
+                Self::ToggleActive(uid) => write!(fmt, "toggle active {}", uid),
+                Self::Reorder(order) => write!(fmt, "reorder {} filter(s)", order.len()),
                 Self::UpdateAll { .. } => write!(fmt, "update all"),
             }
         }
@@ -277,6 +421,18 @@ pub mod to_server {
         pub fn revert() -> Msg {
             Self::Revert.into()
         }
+        /// Activates an inactive filter, or deactivates an active one.
+        pub fn toggle_active(uid: uid::Line) -> Msg {
+            Self::ToggleActive(uid).into()
+        }
+        /// Sets the order of the custom filters.
+        pub fn reorder(order: Vec<uid::Filter>) -> Msg {
+            Self::Reorder(order).into()
+        }
+        /// Adds a filter showing the leaked allocations.
+        pub fn request_leaks() -> Msg {
+            Self::RequestLeaks.into()
+        }
 
         /// Updates all the filters.
         pub fn update_all(
@@ -292,6 +448,131 @@ pub mod to_server {
             .into()
         }
     }
+
+    /// Requests over the allocation data.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub enum DataMsg {
+        /// Requests the flamegraph of the allocations live at some time, in the folded stacks
+        /// format.
+        ///
+        /// If `at_time` is `None`, the flamegraph is for the latest time of the data.
+        Flamegraph {
+            /// Time at which the allocations must be live.
+            at_time: Option<time::SinceStart>,
+        },
+        /// Requests the `n` largest allocation sites, in bytes, of the live allocations.
+        TopAllocSites {
+            /// Maximum number of allocation sites.
+            n: usize,
+        },
+        /// Requests an explanation of which filters match an allocation, and why.
+        Explain {
+            /// Allocation to explain.
+            alloc: uid::Alloc,
+        },
+        /// Searches for allocations by UID or by trace.
+        Search {
+            /// Allocation UID or file substring.
+            query: String,
+            /// Maximum number of results.
+            limit: usize,
+        },
+        /// Requests the full-resolution points of a chart, as CSV.
+        ExportCsv {
+            /// Chart to export.
+            chart: uid::Chart,
+        },
+        /// Re-parses all the data from scratch.
+        ///
+        /// The charts restart once the data is reloaded, as they would for a new profiling run.
+        Reparse,
+        /// Stops the current load as soon as possible, keeping everything loaded so far.
+        ///
+        /// Only cancels the load requested by the last [`Self::Reparse`] of the client, if any.
+        /// Dump directories are not watched anymore afterwards, until the next reparse, and the
+        /// health probe of the server reports it.
+        CancelLoading,
+        /// Stops registering the new diffs of the dump directory.
+        ///
+        /// The profiled process keeps writing diff files, only the analysis is paused. Memtrace
+        /// CTF files are loaded in one go and are not affected.
+        PauseIngestion,
+        /// Registers the diffs found while paused, and resumes ingestion.
+        ResumeIngestion,
+        /// Requests information about the profiling run, see [`RunInfo`].
+        ///
+        /// The recent errors of the run are sent along with it.
+        RunInfo,
+    }
+
+    impl DataMsg {
+        /// Flamegraph request constructor.
+        pub fn flamegraph(at_time: Option<time::SinceStart>) -> Msg {
+            Self::Flamegraph { at_time }.into()
+        }
+        /// Top allocation sites request constructor.
+        pub fn top_alloc_sites(n: usize) -> Msg {
+            Self::TopAllocSites { n }.into()
+        }
+        /// Allocation explanation request constructor.
+        pub fn explain(alloc: uid::Alloc) -> Msg {
+            Self::Explain { alloc }.into()
+        }
+        /// Allocation search request constructor.
+        pub fn search(query: impl Into<String>, limit: usize) -> Msg {
+            Self::Search {
+                query: query.into(),
+                limit,
+            }
+            .into()
+        }
+        /// CSV export request constructor.
+        pub fn export_csv(chart: uid::Chart) -> Msg {
+            Self::ExportCsv { chart }.into()
+        }
+        /// Reparse request constructor.
+        pub fn reparse() -> Msg {
+            Self::Reparse.into()
+        }
+        /// Load cancellation request constructor.
+        pub fn cancel_loading() -> Msg {
+            Self::CancelLoading.into()
+        }
+        /// Ingestion pause request constructor.
+        pub fn pause_ingestion() -> Msg {
+            Self::PauseIngestion.into()
+        }
+        /// Ingestion resume request constructor.
+        pub fn resume_ingestion() -> Msg {
+            Self::ResumeIngestion.into()
+        }
+        /// Run information request constructor.
+        pub fn run_info() -> Msg {
+            Self::RunInfo.into()
+        }
+    }
+
+    impl fmt::Display for DataMsg {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Self::Flamegraph { at_time: None } => "flamegraph".fmt(fmt),
+                Self::Flamegraph {
+                    at_time: Some(time),
+                } => write!(fmt, "flamegraph at {}", time),
+                Self::TopAllocSites { n } => write!(fmt, "top {} allocation sites", n),
+                Self::Explain { alloc } => write!(fmt, "explain allocation #{}", alloc),
+                Self::Search { query, limit } => {
+                    write!(fmt, "search for at most {} allocations: `{}`", limit, query)
+                }
+                Self::ExportCsv { chart } => write!(fmt, "export chart #{} as CSV", chart),
+                Self::Reparse => "reparse".fmt(fmt),
+                Self::CancelLoading => "cancel loading".fmt(fmt),
+                Self::PauseIngestion => "pause ingestion".fmt(fmt),
+                Self::ResumeIngestion => "resume ingestion".fmt(fmt),
+                Self::RunInfo => "run info".fmt(fmt),
+            }
+        }
+    }
 }
 
 /// Messages from the server to the client.
@@ -301,6 +582,11 @@ pub mod to_client {
     /// A list of messages from the server to the client.
     pub type Msgs = Vec<Msg>;
 
+    /// Header byte of an uncompressed message.
+    const RAW_HEADER: u8 = 0;
+    /// Header byte of a deflate-compressed message.
+    const DEFLATE_HEADER: u8 = 1;
+
     /// Messages from the server to the client.
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub enum Msg {
@@ -328,6 +614,10 @@ pub mod to_client {
         Filters(FiltersMsg),
         /// Some filter statistics.
         FilterStats(filter::stats::AllFilterStats),
+        /// Answer to a data request.
+        Data(DataMsg),
+        /// Token of the client's session.
+        Session(SessionToken),
     }
     impl Msg {
         /// Constructor for `Info`.
@@ -358,14 +648,75 @@ pub mod to_client {
             Self::FilterStats(stats)
         }
 
-        /// Encodes the message as bytes.
+        /// Encodes the message as bytes, without compression.
+        ///
+        /// The first byte is a header indicating whether the rest of the bytes are compressed, see
+        /// [`Self::from_bytes`].
+        ///
+        /// ```rust
+        /// # use charts::msg::to_client::*;
+        /// let msg = Msg::alert("some alert message", true);
+        /// let bytes = msg.to_bytes().unwrap();
+        /// assert_eq!(bytes[0], 0);
+        /// let decoded = Msg::from_bytes(&bytes).unwrap();
+        /// assert_eq!(decoded.to_bytes().unwrap(), bytes);
+        ///
+        /// assert!(Msg::from_bytes(&[]).is_err());
+        /// let mut unknown = bytes.clone();
+        /// unknown[0] = 7;
+        /// assert!(Msg::from_bytes(&unknown).is_err());
+        /// ```
         pub fn to_bytes(&self) -> Res<Vec<u8>> {
-            Ok(base::bincode::serialize(self)?)
+            let mut bytes = vec![RAW_HEADER];
+            base::bincode::serialize_into(&mut bytes, self)?;
+            Ok(bytes)
+        }
+
+        /// Encodes the message as bytes, compressed if its encoding is at least `threshold` bytes
+        /// long.
+        ///
+        /// ```rust
+        /// # use charts::msg::to_client::*;
+        /// let msg = Msg::alert("some alert message ".repeat(100), false);
+        /// let bytes = msg.to_compressed_bytes(128).unwrap();
+        /// assert!(bytes.len() < msg.to_bytes().unwrap().len());
+        /// let decoded = Msg::from_bytes(&bytes).unwrap();
+        /// assert_eq!(decoded.to_bytes().unwrap(), msg.to_bytes().unwrap());
+        /// ```
+        #[cfg(feature = "compression")]
+        pub fn to_compressed_bytes(&self, threshold: usize) -> Res<Vec<u8>> {
+            use std::io::Write;
+            let size = base::bincode::serialized_size(self)?;
+            if size < threshold as u64 {
+                return self.to_bytes();
+            }
+            let mut encoder = flate2::write::DeflateEncoder::new(
+                vec![DEFLATE_HEADER],
+                flate2::Compression::fast(),
+            );
+            base::bincode::serialize_into(&mut encoder, self)?;
+            encoder.flush()?;
+            Ok(encoder.finish()?)
         }
 
         /// Decodes the message from bytes.
+        ///
+        /// The first byte indicates whether the message is compressed, which is only supported
+        /// with the `compression` feature.
         pub fn from_bytes(bytes: &[u8]) -> Res<Self> {
-            Ok(base::bincode::deserialize(bytes)?)
+            match bytes.split_first() {
+                Some((&RAW_HEADER, bytes)) => Ok(base::bincode::deserialize(bytes)?),
+                #[cfg(feature = "compression")]
+                Some((&DEFLATE_HEADER, bytes)) => Ok(base::bincode::deserialize_from(
+                    flate2::read::DeflateDecoder::new(bytes),
+                )?),
+                #[cfg(not(feature = "compression"))]
+                Some((&DEFLATE_HEADER, _)) => {
+                    bail!("cannot decode compressed message, compression support is deactivated")
+                }
+                Some((header, _)) => bail!("unknown message header `{}`", header),
+                None => bail!("cannot decode empty message"),
+            }
         }
 
         /// True if the message is a minor message.
@@ -379,7 +730,9 @@ pub mod to_client {
                 | Self::LoadProgress(_)
                 | Self::AllocStats(_)
                 | Self::DoneLoading
-                | Self::FilterStats(_) => true,
+                | Self::FilterStats(_)
+                | Self::Data(_)
+                | Self::Session(_) => true,
             }
         }
     }
@@ -395,6 +748,8 @@ pub mod to_client {
                 Self::FilterStats(_) => "filter stats".fmt(fmt),
                 Self::DoneLoading => "done loading".fmt(fmt),
                 Self::Filters(_) => "filter".fmt(fmt),
+                Self::Data(msg) => write!(fmt, "data({})", msg),
+                Self::Session(token) => write!(fmt, "session({:x})", token),
             }
         }
     }
@@ -404,6 +759,64 @@ pub mod to_client {
             From {
                 from ChartsMsg => |msg| Self::Charts(msg),
                 from FiltersMsg => |msg| Self::Filters(msg),
+                from DataMsg => |msg| Self::Data(msg),
+            }
+        }
+    }
+
+    /// Answers to data requests.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub enum DataMsg {
+        /// A flamegraph in the folded stacks format.
+        Flamegraph(String),
+        /// Largest allocation sites: location, size in bytes and number of allocations.
+        TopAllocSites(Vec<(alloc::Loc, u64, usize)>),
+        /// Explanation of which filters match an allocation.
+        Explain(filter::Explanation),
+        /// Allocations matching a search query.
+        SearchResults {
+            /// Query the results are for.
+            query: String,
+            /// UIDs of the matching allocations.
+            allocs: Vec<uid::Alloc>,
+        },
+        /// Full-resolution points of a chart, as CSV.
+        Csv {
+            /// Chart the points are for.
+            chart: uid::Chart,
+            /// CSV content.
+            csv: String,
+        },
+        /// Whether the ingestion of new diffs is paused, sent after a pause/resume request.
+        IngestionPaused(bool),
+        /// Information about the profiling run, `None` if no run is loaded yet.
+        RunInfo(Option<RunInfo>),
+        /// Recent non-fatal errors of the run, sent along with the run information.
+        Errors {
+            /// Recent errors, oldest first.
+            errors: Vec<String>,
+            /// Number of older errors dropped because the server's error log was full.
+            dropped: usize,
+        },
+    }
+
+    impl fmt::Display for DataMsg {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Self::Flamegraph(_) => "flamegraph".fmt(fmt),
+                Self::TopAllocSites(sites) => write!(fmt, "{} top allocation sites", sites.len()),
+                Self::Explain(explanation) => {
+                    write!(fmt, "explanation for allocation #{}", explanation.alloc)
+                }
+                Self::SearchResults { query, allocs } => {
+                    write!(fmt, "{} search results for `{}`", allocs.len(), query)
+                }
+                Self::Csv { chart, .. } => write!(fmt, "CSV export of chart #{}", chart),
+                Self::IngestionPaused(paused) => write!(fmt, "ingestion paused: {}", paused),
+                Self::RunInfo(_) => "run info".fmt(fmt),
+                Self::Errors { errors, dropped } => {
+                    write!(fmt, "{} recent errors ({} dropped)", errors.len(), dropped)
+                }
             }
         }
     }
@@ -426,9 +839,24 @@ pub mod to_client {
             points: point::ChartPoints,
             /// If true, refresh all filters.
             refresh_filters: bool,
+            /// Allocation counts of the filters, see [`filter::stats::FilterDelta`].
+            delta: filter::stats::FilterDelta,
         },
         /// Some points to append to existing points.
-        AddPoints(point::ChartPoints),
+        AddPoints {
+            /// New points.
+            points: point::ChartPoints,
+            /// Allocation counts of the filters, see [`filter::stats::FilterDelta`].
+            delta: filter::stats::FilterDelta,
+        },
+        /// New time cursor, shared by all the charts.
+        Cursor {
+            /// Time cursor, `None` if there is no cursor.
+            time: Option<time::SinceStart>,
+            /// Total size of the allocations live at the cursor that the filters match, `0` if there
+            /// is no cursor.
+            live_bytes: u64,
+        },
     }
     impl ChartsMsg {
         /// Constructor for `NewChart`.
@@ -436,23 +864,45 @@ pub mod to_client {
             Msg::charts(Self::NewChart(spec, settings))
         }
         /// Constructor for `NewPoints`.
-        pub fn new_points(points: point::ChartPoints, refresh_filters: bool) -> Msg {
+        pub fn new_points(
+            points: point::ChartPoints,
+            refresh_filters: bool,
+            delta: filter::stats::FilterDelta,
+        ) -> Msg {
             Msg::charts(Self::NewPoints {
                 points,
                 refresh_filters,
+                delta,
             })
         }
         /// Constructor for `AddPoints`.
-        pub fn add_points(points: point::ChartPoints) -> Msg {
-            Msg::charts(Self::AddPoints(points))
+        pub fn add_points(points: point::ChartPoints, delta: filter::stats::FilterDelta) -> Msg {
+            Msg::charts(Self::AddPoints { points, delta })
+        }
+
+        /// Constructor for `Cursor`.
+        pub fn cursor(time: Option<time::SinceStart>, live_bytes: u64) -> Msg {
+            Msg::charts(Self::Cursor { time, live_bytes })
         }
 
         /// Constructs a `NewPoints` if `overwrite`, and a `AddPoints` otherwise.
-        pub fn points(points: point::ChartPoints, overwrite: bool) -> Msg {
+        pub fn points(
+            points: point::ChartPoints,
+            overwrite: bool,
+            delta: filter::stats::FilterDelta,
+        ) -> Msg {
             if overwrite {
-                Self::new_points(points, false)
+                Self::new_points(points, false, delta)
             } else {
-                Self::add_points(points)
+                Self::add_points(points, delta)
+            }
+        }
+
+        /// Allocation counts of the filters, if the message carries points.
+        pub fn filter_delta(&self) -> Option<&filter::stats::FilterDelta> {
+            match self {
+                Self::NewPoints { delta, .. } | Self::AddPoints { delta, .. } => Some(delta),
+                Self::NewChart(_, _) | Self::Chart { .. } | Self::Cursor { .. } => None,
             }
         }
     }
@@ -473,7 +923,7 @@ pub mod to_client {
                     }
                     Ok(())
                 }
-                Self::AddPoints(points) => {
+                Self::AddPoints { points, .. } => {
                     "add points:".fmt(fmt)?;
                     for (idx, (uid, points)) in points.iter().enumerate() {
                         write!(fmt, " ")?;
@@ -484,6 +934,10 @@ pub mod to_client {
                     }
                     Ok(())
                 }
+                Self::Cursor {
+                    time: Some(time), ..
+                } => write!(fmt, "cursor at {}", time),
+                Self::Cursor { time: None, .. } => "no cursor".fmt(fmt),
             }
         }
     }
@@ -497,6 +951,12 @@ pub mod to_client {
         NewPoints(point::Points),
         /// Some points to append.
         Points(point::Points),
+        /// The chart failed to produce its points.
+        ///
+        /// The other charts are unaffected, this chart keeps its current points.
+        Error(String),
+        /// Sets the sample rate of the run, see [`chart::ChartSpec::sample_rate`].
+        SampleRate(Option<SampleRate>),
     }
 
     impl ChartMsg {
@@ -514,6 +974,20 @@ pub mod to_client {
                 msg: Self::Points(points),
             })
         }
+        /// Error produced while extracting the points of a chart.
+        pub fn error(uid: uid::Chart, err: impl Into<String>) -> Msg {
+            Msg::charts(ChartsMsg::Chart {
+                uid,
+                msg: Self::Error(err.into()),
+            })
+        }
+        /// Sets the sample rate of the run.
+        pub fn sample_rate(uid: uid::Chart, rate: Option<SampleRate>) -> Msg {
+            Msg::charts(ChartsMsg::Chart {
+                uid,
+                msg: Self::SampleRate(rate),
+            })
+        }
     }
 
     impl fmt::Display for ChartMsg {
@@ -521,6 +995,9 @@ pub mod to_client {
             match self {
                 Self::NewPoints(points) => write!(fmt, "{} new points", points.len()),
                 Self::Points(points) => write!(fmt, "add {} points", points.len()),
+                Self::Error(_) => "error".fmt(fmt),
+                Self::SampleRate(Some(rate)) => write!(fmt, "sample rate {}", rate.sample_rate),
+                Self::SampleRate(None) => "no sample rate".fmt(fmt),
             }
         }
     }
@@ -554,6 +1031,15 @@ pub mod to_client {
             /// Specification for the `catch_all` filter.
             catch_all: FilterSpec,
         },
+        /// A subfilter sent by the client is illegal, the filters were not updated.
+        SubError {
+            /// UID of the filter the subfilter belongs to.
+            filter: uid::Filter,
+            /// UID of the illegal subfilter.
+            sub: uid::SubFilter,
+            /// Error message.
+            msg: String,
+        },
         // /// Updates all the specs.
         // UpdateSpecs(BTMap<uid::Line, FilterSpec>),
     }
@@ -577,6 +1063,16 @@ pub mod to_client {
             .into()
         }
 
+        /// Reports an illegal subfilter.
+        pub fn sub_error(filter: uid::Filter, sub: uid::SubFilter, msg: impl Into<String>) -> Msg {
+            Self::SubError {
+                filter,
+                sub,
+                msg: msg.into(),
+            }
+            .into()
+        }
+
         // /// Updates all the specs.
         // pub fn update_specs(specs: BTMap<uid::Line, FilterSpec>) -> Msg {
         //     Self::UpdateSpecs(specs).into()
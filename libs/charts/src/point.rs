@@ -21,6 +21,43 @@
 
 prelude! {}
 
+/// Number of allocation kinds, used to pick the shade of a [`SubLine`].
+const KIND_COUNT: usize = 5;
+
+/// Key of a sub-line: the part of a line that only considers one kind of allocation.
+///
+/// Time charts split their lines by allocation kind when their
+/// [settings](settings::Chart::set_split_by_kind) ask for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SubLine {
+    /// Line the sub-line belongs to.
+    pub line: uid::Line,
+    /// Kind of the allocations of the sub-line.
+    pub kind: alloc::AllocKind,
+}
+impl SubLine {
+    /// Constructor.
+    pub fn new(line: uid::Line, kind: alloc::AllocKind) -> Self {
+        Self { line, kind }
+    }
+
+    /// Name of the sub-line, given the name of its line.
+    ///
+    /// ```rust
+    /// use charts::{point::SubLine, prelude::{alloc::AllocKind, uid}};
+    /// let sub_line = SubLine::new(uid::Line::CatchAll, AllocKind::Major);
+    /// assert_eq!(sub_line.name("catch all"), "catch all (Major)");
+    /// ```
+    pub fn name(&self, line_name: &str) -> String {
+        format!("{} ({})", line_name, self.kind)
+    }
+
+    /// Color of the sub-line, a shade of the color of its line.
+    pub fn color(&self, line_color: &Color) -> Color {
+        line_color.shade(self.kind as usize, KIND_COUNT)
+    }
+}
+
 /// A point value.
 ///
 /// Stores a value for each filter, and the value for the catch-all filter.
@@ -28,6 +65,8 @@ prelude! {}
 pub struct PointVal<Val> {
     /// Values for filter lines.
     pub map: BTMap<uid::Line, Val>,
+    /// Values for the sub-lines, empty unless lines are split by allocation kind.
+    pub kinds: BTMap<SubLine, Val>,
 }
 impl<Val> PointVal<Val> {
     /// Constructor.
@@ -41,12 +80,36 @@ impl<Val> PointVal<Val> {
         for filter in filters.filters() {
             map.insert(uid::Line::Filter(filter.uid()), default.clone());
         }
-        Self { map }
+        Self {
+            map,
+            kinds: BTMap::new(),
+        }
     }
 
     /// Empty constructor.
     pub fn empty() -> Self {
-        Self { map: BTMap::new() }
+        Self {
+            map: BTMap::new(),
+            kinds: BTMap::new(),
+        }
+    }
+
+    /// Removes the value for some line and its sub-lines, if any.
+    pub fn remove(&mut self, uid: uid::Line) -> Option<Val> {
+        self.kinds.retain(|sub_line, _| sub_line.line != uid);
+        self.map.remove(&uid)
+    }
+
+    /// Number of values, including the values of the sub-lines.
+    pub fn len(&self) -> usize {
+        self.map.len() + self.kinds.len()
+    }
+
+    /// Values of the sub-lines of a line.
+    pub fn sub_lines(&self, uid: uid::Line) -> impl Iterator<Item = (&SubLine, &Val)> {
+        self.kinds
+            .iter()
+            .filter(move |(sub_line, _)| sub_line.line == uid)
     }
 
     /// True if the inner map is empty.
@@ -84,7 +147,11 @@ impl<Val> PointVal<Val> {
         for (uid, val) in self.map {
             map.insert(uid, f(uid, val)?);
         }
-        let res = PointVal { map };
+        let mut kinds = BTMap::new();
+        for (sub_line, val) in self.kinds {
+            kinds.insert(sub_line, f(sub_line.line, val)?);
+        }
+        let res = PointVal { map, kinds };
         Ok(res)
     }
 
@@ -100,7 +167,14 @@ impl<Val> PointVal<Val> {
                 debug_assert_eq!(prev, None);
             }
         }
-        let res = PointVal { map };
+        let mut kinds = BTMap::new();
+        for (sub_line, val) in self.kinds {
+            if let Some(res) = f(sub_line.line, val)? {
+                let prev = kinds.insert(sub_line, res);
+                debug_assert_eq!(prev, None);
+            }
+        }
+        let res = PointVal { map, kinds };
         Ok(res)
     }
 }
@@ -156,7 +230,7 @@ where
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let Point {
             key,
-            vals: PointVal { map },
+            vals: PointVal { map, .. },
         } = self;
         write!(fmt, "{{ x: {}", key)?;
         for (uid, val) in map.iter() {
@@ -189,6 +263,50 @@ base::implement! {
     }
 }
 
+/// Rate quantity, in bytes per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Rate {
+    /// Actual rate value.
+    pub rate: u64,
+}
+impl Rate {
+    /// Constructor.
+    pub fn new(rate: impl Into<u64>) -> Self {
+        Self { rate: rate.into() }
+    }
+
+    /// Rate of some bytes over some duration.
+    ///
+    /// The rate over an empty duration is zero.
+    ///
+    /// ```rust
+    /// use charts::{point::Rate, prelude::*};
+    /// let rate = |bytes, secs, nanos| Rate::of(bytes, time::SinceStart::from_nano_timestamp(secs, nanos));
+    /// assert_eq!(rate(10, 2, 0), Rate::new(5u64));
+    /// assert_eq!(rate(10, 0, 500_000_000), Rate::new(20u64));
+    /// assert_eq!(rate(10, 0, 0), Rate::new(0u64));
+    /// ```
+    pub fn of(bytes: u64, duration: time::SinceStart) -> Self {
+        let nanos = duration.as_nanos();
+        let rate = if nanos == 0 {
+            0
+        } else {
+            u64::try_from(bytes as u128 * 1_000_000_000 / nanos).unwrap_or(u64::MAX)
+        };
+        Self::new(rate)
+    }
+}
+base::implement! {
+    impl Rate {
+        Display {
+            |&self, fmt| self.rate.fmt(fmt),
+        }
+        From {
+            from u64 => |rate| Self::new(rate),
+        }
+    }
+}
+
 /// Extension trait for coordinates.
 ///
 /// Note that the type of the values appearing in a point are not necessarily the same type expected
@@ -204,11 +322,24 @@ where
         + coord::ValueFormatter<Self::Coord>
         + coord::Ranged<ValueType = Self::Coord>
         + From<std::ops::Range<Self::Coord>>,
+    Self::LogRange:
+        'static + coord::ValueFormatter<Self::Coord> + coord::Ranged<ValueType = Self::Coord>,
 {
     /// Type of the values as they will be passed to the graph.
     type Coord;
     /// Type of coordinate ranges.
     type Range;
+    /// Type of log-scale coordinate ranges.
+    ///
+    /// Coordinates that do not support log-scale just use [`Range`](CoordExt::Range).
+    type LogRange;
+
+    /// Turns a range of coordinates into a log-scale range, if log-scale is supported.
+    ///
+    /// Defaults to `None`, *i.e.* log-scale is not supported.
+    fn log_range(_range: std::ops::Range<Self::Coord>) -> Option<Self::LogRange> {
+        None
+    }
 
     /// Generates a default `Self` value.
     fn default_val() -> Self;
@@ -227,6 +358,7 @@ where
 impl CoordExt for time::Date {
     type Coord = time::chrono::Duration;
     type Range = coord::RangedDuration;
+    type LogRange = coord::RangedDuration;
     fn default_val() -> Self {
         time::Date::from_timestamp(0, 0)
     }
@@ -247,6 +379,7 @@ impl CoordExt for time::Date {
 impl CoordExt for time::SinceStart {
     type Coord = time::chrono::Duration;
     type Range = coord::RangedDuration;
+    type LogRange = coord::RangedDuration;
     fn default_val() -> Self {
         time::SinceStart::zero()
     }
@@ -264,9 +397,23 @@ impl CoordExt for time::SinceStart {
     }
 }
 
+/// Log-scale range over `u64` coordinates.
+///
+/// Zero has no logarithm, so the lower bound is raised to `1`. Zero values are thus drawn at the
+/// bottom of the y-axis, as if they were `1`.
+fn u64_log_range(range: std::ops::Range<u64>) -> coord::LogCoord<u64> {
+    let lbound = range.start.max(1);
+    let ubound = range.end.max(lbound + 1);
+    coord::LogRange(lbound..ubound).into()
+}
+
 impl CoordExt for u64 {
     type Coord = u64;
     type Range = coord::RangedCoordu64;
+    type LogRange = coord::LogCoord<u64>;
+    fn log_range(range: std::ops::Range<u64>) -> Option<coord::LogCoord<u64>> {
+        Some(u64_log_range(range))
+    }
     fn default_val() -> Self {
         0
     }
@@ -287,6 +434,34 @@ impl CoordExt for u64 {
 impl CoordExt for Size {
     type Coord = u64;
     type Range = coord::RangedCoordu64;
+    type LogRange = coord::LogCoord<u64>;
+    fn log_range(range: std::ops::Range<u64>) -> Option<coord::LogCoord<u64>> {
+        Some(u64_log_range(range))
+    }
+    fn default_val() -> Self {
+        0.into()
+    }
+    fn zero() -> u64 {
+        0
+    }
+    fn is_zero(val: &u64) -> bool {
+        *val == 0
+    }
+    fn default_min() -> u64 {
+        0
+    }
+    fn default_max() -> u64 {
+        5
+    }
+}
+
+impl CoordExt for Rate {
+    type Coord = u64;
+    type Range = coord::RangedCoordu64;
+    type LogRange = coord::LogCoord<u64>;
+    fn log_range(range: std::ops::Range<u64>) -> Option<coord::LogCoord<u64>> {
+        Some(u64_log_range(range))
+    }
     fn default_val() -> Self {
         0.into()
     }
@@ -307,6 +482,7 @@ impl CoordExt for Size {
 impl CoordExt for f32 {
     type Coord = f32;
     type Range = coord::RangedCoordf32;
+    type LogRange = coord::RangedCoordf32;
     fn default_val() -> Self {
         0.0
     }
@@ -347,6 +523,11 @@ impl RatioExt for Size {
         Ok(((slf * 100) as f32) / (max as f32))
     }
 }
+impl RatioExt for Rate {
+    fn ratio_wrt(&self, max: &Self) -> Res<f32> {
+        self.rate.ratio_wrt(&max.rate)
+    }
+}
 impl RatioExt for time::chrono::Duration {
     fn ratio_wrt(&self, max: &Self) -> Res<f32> {
         let (slf, max) = match (self.num_nanoseconds(), max.num_nanoseconds()) {
@@ -363,6 +544,14 @@ pub trait RangesExt<X, Y> {
     fn ranges(&self, is_active: impl Fn(uid::Line) -> bool) -> Ranges<Option<X>, Option<Y>>;
 }
 
+/// Peaks extension trait.
+pub trait PeaksExt<X, Y> {
+    /// Computes the peak of each filter: its maximum value, and the first key reaching it.
+    ///
+    /// Ignores the filters that are not active.
+    fn peaks(&self, is_active: impl Fn(uid::Line) -> bool) -> BTMap<uid::Line, (X, Y)>;
+}
+
 /// Extension trait for point values.
 pub trait PointValExt<Val>
 where
@@ -376,13 +565,25 @@ where
     fn val_coord_processor(range: &Range<Val>, x: &Val) -> Val::Coord;
     /// Formatter for the axis labels.
     fn val_label_formatter(val: &Val::Coord) -> String;
+    /// Formatter for the axis labels, given some chart settings.
+    ///
+    /// Defaults to `val_label_formatter`, *i.e.* ignores the settings.
+    fn val_label_formatter_with(_settings: &settings::Chart, val: &Val::Coord) -> String {
+        Self::val_label_formatter(val)
+    }
+    /// Coordinate of a time cursor, if it is in `range`.
+    ///
+    /// Defaults to `None`, *i.e.* values that are not points in time have no cursor.
+    fn cursor_coord(_range: &Range<Val>, _cursor: time::SinceStart) -> Option<Val::Coord> {
+        None
+    }
 }
 
 impl<X, Y> ChartRender<X, Y> for PolyPoints<X, Y>
 where
     X: CoordExt,
     Y: CoordExt,
-    Self: RangesExt<X, Y> + PointValExt<X> + PointValExt<Y>,
+    Self: RangesExt<X, Y> + PeaksExt<X, Y> + PointValExt<X> + PointValExt<Y>,
 {
     fn points(&self) -> std::slice::Iter<Point<X, Y>> {
         self.iter()
@@ -392,12 +593,12 @@ where
 /// Mesh configuration extension trait.
 pub trait StyleExt {
     /// Applies a mesh configuration.
-    fn mesh_conf<X, Y, DB>(
-        &self,
-        configure_mesh: &mut plotters::chart::MeshStyle<X::Range, Y::Range, DB>,
-    ) where
-        X: CoordExt,
-        Y: CoordExt,
+    ///
+    /// Generic over the x/y coordinate ranges, since the y-axis can be linear or log-scale.
+    fn mesh_conf<XR, YR, DB>(&self, configure_mesh: &mut plotters::chart::MeshStyle<XR, YR, DB>)
+    where
+        XR: coord::Ranged,
+        YR: coord::Ranged,
         DB: plotters::prelude::DrawingBackend;
 
     /// Creates a shape style.
@@ -409,7 +610,7 @@ pub trait ChartRender<X, Y>
 where
     X: CoordExt,
     Y: CoordExt,
-    Self: RangesExt<X, Y> + PointValExt<X> + PointValExt<Y>,
+    Self: RangesExt<X, Y> + PeaksExt<X, Y> + PointValExt<X> + PointValExt<Y>,
 {
     /// Processes the ranges for both axis.
     fn ranges_processor(ranges: Ranges<Option<X>, Option<Y>>) -> Res<Ranges<X, Y>> {
@@ -440,6 +641,10 @@ where
     fn x_label_formatter(val: &X::Coord) -> String {
         <Self as PointValExt<X>>::val_label_formatter(val)
     }
+    /// X-axis label formatter, given some chart settings.
+    fn x_label_formatter_with(settings: &settings::Chart, val: &X::Coord) -> String {
+        <Self as PointValExt<X>>::val_label_formatter_with(settings, val)
+    }
     /// Y-axis label formatter.
     fn y_label_formatter(val: &Y::Coord) -> String {
         <Self as PointValExt<Y>>::val_label_formatter(val)
@@ -448,6 +653,38 @@ where
     /// Yields the actual points.
     fn points(&self) -> std::slice::Iter<Point<X, Y>>;
 
+    /// Draws the time cursor of the settings as a vertical guide, if any.
+    ///
+    /// The guide is only drawn if the cursor is in the x-range of the points, see
+    /// [`PointValExt::cursor_coord`].
+    fn draw_cursor<DB, YR>(
+        settings: &settings::Chart,
+        chart_cxt: &mut plotters::prelude::ChartContext<DB, coord::Cartesian2d<X::Range, YR>>,
+        x_range: &Range<X>,
+    ) -> Res<()>
+    where
+        DB: plotters::prelude::DrawingBackend,
+        YR: coord::Ranged,
+    {
+        use plotters::prelude::*;
+        let x = match settings
+            .cursor()
+            .and_then(|cursor| <Self as PointValExt<X>>::cursor_coord(x_range, cursor))
+        {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let y_range = chart_cxt.y_range();
+        let guide = PathElement::new(
+            vec![(x.clone(), y_range.start), (x, y_range.end)],
+            ShapeStyle::from(&BLACK.mix(0.6)).stroke_width(2),
+        );
+        chart_cxt
+            .draw_series(std::iter::once(guide))
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
     /// Renders some points on a graph.
     fn render<'spec, DB>(
         &self,
@@ -496,10 +733,15 @@ where
     }
 
     /// Normal display mode rendering.
+    ///
+    /// Also draws the [sub-lines](SubLine) of each series, if any, and marks the peak of each
+    /// series. If the chart's settings ask for a log-scale y-axis and
+    /// the y-axis coordinates [support it](CoordExt::log_range), the y-axis is log-scale; zero
+    /// values are then drawn at the bottom of the y-axis.
     fn chart_render<'spec, DB>(
         &self,
-        _settings: &settings::Chart,
-        mut chart_builder: plotters::prelude::ChartBuilder<DB>,
+        settings: &settings::Chart,
+        chart_builder: plotters::prelude::ChartBuilder<DB>,
         style_conf: &impl StyleExt,
         is_active: impl Fn(uid::Line) -> bool,
         active_filters: impl Iterator<Item = &'spec filter::FilterSpec>,
@@ -507,17 +749,64 @@ where
     where
         DB: plotters::prelude::DrawingBackend,
     {
-        let opt_ranges = self.ranges(is_active);
+        let opt_ranges = self.ranges(&is_active);
         let raw_ranges = Self::ranges_processor(opt_ranges)?;
         let ranges = Self::coord_ranges_processor(&raw_ranges)?;
-
-        use plotters::prelude::*;
+        let peaks = self.peaks(&is_active);
 
         let x_range: X::Range = (ranges.x.lbound..ranges.x.ubound).into();
+
+        if settings.y_log() {
+            if let Some(y_range) = Y::log_range(ranges.y.lbound.clone()..ranges.y.ubound.clone()) {
+                return self.chart_render_on(
+                    settings,
+                    chart_builder,
+                    style_conf,
+                    active_filters,
+                    &raw_ranges,
+                    &peaks,
+                    x_range,
+                    y_range,
+                );
+            }
+        }
+
         let y_range: Y::Range = (ranges.y.lbound..ranges.y.ubound).into();
+        self.chart_render_on(
+            settings,
+            chart_builder,
+            style_conf,
+            active_filters,
+            &raw_ranges,
+            &peaks,
+            x_range,
+            y_range,
+        )
+    }
+
+    /// Normal display mode rendering, given the actual x/y coordinate ranges.
+    ///
+    /// The y-axis range is generic so that it can be either linear or log-scale.
+    #[allow(clippy::too_many_arguments)]
+    fn chart_render_on<'spec, DB, YR>(
+        &self,
+        settings: &settings::Chart,
+        mut chart_builder: plotters::prelude::ChartBuilder<DB>,
+        style_conf: &impl StyleExt,
+        active_filters: impl Iterator<Item = &'spec filter::FilterSpec>,
+        raw_ranges: &Ranges<X, Y>,
+        peaks: &BTMap<uid::Line, (X, Y)>,
+        x_range: X::Range,
+        y_range: YR,
+    ) -> Res<()>
+    where
+        DB: plotters::prelude::DrawingBackend,
+        YR: coord::Ranged<ValueType = Y::Coord> + coord::ValueFormatter<Y::Coord>,
+    {
+        use plotters::prelude::*;
 
         // Alright, time to build the actual chart context used for drawing.
-        let mut chart_cxt: ChartContext<DB, coord::Cartesian2d<X::Range, Y::Range>> = chart_builder
+        let mut chart_cxt: ChartContext<DB, coord::Cartesian2d<X::Range, YR>> = chart_builder
             .build_cartesian_2d(x_range, y_range)
             .map_err(|e| e.to_string())?;
 
@@ -526,10 +815,10 @@ where
             let mut mesh = chart_cxt.configure_mesh();
 
             // Apply caller's configuration.
-            style_conf.mesh_conf::<X, Y, DB>(&mut mesh);
+            style_conf.mesh_conf(&mut mesh);
 
             // Set x/y formatters and draw this thing.
-            mesh.x_label_formatter(&Self::x_label_formatter)
+            mesh.x_label_formatter(&|x| Self::x_label_formatter_with(settings, x))
                 .y_label_formatter(&Self::y_label_formatter)
                 .draw()
                 .map_err(|e| e.to_string())?;
@@ -551,11 +840,60 @@ where
             let style = style_conf.shape_conf(filter_spec.color());
 
             chart_cxt
-                .draw_series(LineSeries::new(points, style))
+                .draw_series(LineSeries::new(points, style.clone()))
                 .map_err(|e| e.to_string())?;
+
+            // Sub-lines, if any, in a lighter shade of the line's color and named at their first
+            // point.
+            let sub_lines: BTSet<SubLine> = self
+                .points()
+                .flat_map(|point| point.vals.sub_lines(f_uid).map(|(sub_line, _)| *sub_line))
+                .collect();
+            for sub_line in sub_lines {
+                let points = self.points().filter_map(|point| {
+                    point.vals.kinds.get(&sub_line).map(|val| {
+                        (
+                            Self::x_coord_processor(&raw_ranges.x, &point.key),
+                            Self::y_coord_processor(&raw_ranges.y, val),
+                        )
+                    })
+                });
+                let first = points.clone().next();
+                let sub_style = style_conf.shape_conf(&sub_line.color(filter_spec.color()));
+
+                chart_cxt
+                    .draw_series(LineSeries::new(points, sub_style.clone()))
+                    .map_err(|e| e.to_string())?;
+
+                if let Some(first) = first {
+                    let label_style =
+                        TextStyle::from(("sans-serif", 15).into_font()).color(&sub_style.color);
+                    let name = EmptyElement::at(first)
+                        + Text::new(sub_line.name(filter_spec.name()), (7, -20), label_style);
+                    chart_cxt
+                        .draw_series(std::iter::once(name))
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+
+            // Mark the peak, if any.
+            if let Some((x, y)) = peaks.get(&f_uid) {
+                let y = Self::y_coord_processor(&raw_ranges.y, y);
+                if !Y::is_zero(&y) {
+                    let label = format!("peak: {}", Self::y_label_formatter(&y));
+                    let label_style =
+                        TextStyle::from(("sans-serif", 15).into_font()).color(&style.color);
+                    let marker = EmptyElement::at((Self::x_coord_processor(&raw_ranges.x, x), y))
+                        + Circle::new((0, 0), 5, style.filled())
+                        + Text::new(label, (7, -20), label_style);
+                    chart_cxt
+                        .draw_series(std::iter::once(marker))
+                        .map_err(|e| e.to_string())?;
+                }
+            }
         }
 
-        Ok(())
+        Self::draw_cursor(settings, &mut chart_cxt, &raw_ranges.x)
     }
     /// Stacked area rendering.
     fn chart_render_stacked_area<'spec, DB>(
@@ -641,7 +979,7 @@ where
     /// Stacked area rendering.
     fn chart_render_stacked_area_custom<'spec, DB, RealY: CoordExt>(
         &self,
-        _settings: &settings::Chart,
+        settings: &settings::Chart,
         mut chart_builder: plotters::prelude::ChartBuilder<DB>,
         style_conf: &impl StyleExt,
         is_active: impl Fn(uid::Line) -> bool,
@@ -724,10 +1062,10 @@ where
             let mut mesh = chart_cxt.configure_mesh();
 
             // Apply caller's configuration.
-            style_conf.mesh_conf::<X, RealY, DB>(&mut mesh);
+            style_conf.mesh_conf(&mut mesh);
 
             // Set x/y formatters and draw this thing.
-            mesh.x_label_formatter(&Self::x_label_formatter)
+            mesh.x_label_formatter(&|x| Self::x_label_formatter_with(settings, x))
                 .y_label_formatter(&label_formatter)
                 .draw()
                 .map_err(|e| e.to_string())?;
@@ -772,7 +1110,7 @@ where
                 .map_err(|e| e.to_string())?;
         }
 
-        Ok(())
+        Self::draw_cursor(settings, &mut chart_cxt, &raw_ranges.x)
     }
 }
 
@@ -831,6 +1169,28 @@ where
     }
 }
 
+impl<X, Y> PeaksExt<X, Y> for PolyPoints<X, Y>
+where
+    X: Clone,
+    Y: PartialOrd + Clone,
+{
+    fn peaks(&self, is_active: impl Fn(uid::Line) -> bool) -> BTMap<uid::Line, (X, Y)> {
+        let mut peaks: BTMap<uid::Line, (&X, &Y)> = BTMap::new();
+        for point in self {
+            for (uid, val) in point.vals.map.iter().filter(|(uid, _)| is_active(**uid)) {
+                let peak = peaks.entry(*uid).or_insert((&point.key, val));
+                if *peak.1 < *val {
+                    *peak = (&point.key, val)
+                }
+            }
+        }
+        peaks
+            .into_iter()
+            .map(|(uid, (x, y))| (uid, (x.clone(), y.clone())))
+            .collect()
+    }
+}
+
 impl<Y> PointValExt<time::Date> for PolyPoints<time::Date, Y> {
     fn val_range_processor(range: Range<Option<time::Date>>) -> Res<Range<time::Date>> {
         match (range.lbound, range.ubound) {
@@ -852,19 +1212,9 @@ impl<Y> PointValExt<time::Date> for PolyPoints<time::Date, Y> {
         x.date().clone() - range.lbound.date().clone()
     }
     fn val_label_formatter(date: &<time::Date as CoordExt>::Coord) -> String {
-        let mut res = time::SinceStart::from(date.to_std().unwrap())
-            .display_millis()
-            .to_string();
-        'rm_trailing_zeros: while let Some(last) = res.pop() {
-            if last == '0' {
-                continue 'rm_trailing_zeros;
-            } else {
-                res.push(last);
-                break 'rm_trailing_zeros;
-            }
-        }
-        res.push('s');
-        res
+        time::SinceStart::from(date.to_std().unwrap())
+            .display_human()
+            .to_string()
     }
 }
 
@@ -888,20 +1238,26 @@ impl<Y> PointValExt<time::SinceStart> for PolyPoints<time::SinceStart, Y> {
     ) -> <time::SinceStart as CoordExt>::Coord {
         x.to_chrono_duration()
     }
-    fn val_label_formatter(date: &<time::SinceStart as CoordExt>::Coord) -> String {
-        let mut res = time::SinceStart::from(date.to_std().unwrap())
-            .display_millis()
-            .to_string();
-        'rm_trailing_zeros: while let Some(last) = res.pop() {
-            if last == '0' {
-                continue 'rm_trailing_zeros;
-            } else {
-                res.push(last);
-                break 'rm_trailing_zeros;
-            }
+    fn cursor_coord(
+        range: &Range<time::SinceStart>,
+        cursor: time::SinceStart,
+    ) -> Option<<time::SinceStart as CoordExt>::Coord> {
+        if range.lbound <= cursor && cursor <= range.ubound {
+            Some(cursor.to_chrono_duration())
+        } else {
+            None
         }
-        res.push('s');
-        res
+    }
+    fn val_label_formatter_with(
+        settings: &settings::Chart,
+        date: &<time::SinceStart as CoordExt>::Coord,
+    ) -> String {
+        settings.format_time(time::SinceStart::from(date.to_std().unwrap()))
+    }
+    fn val_label_formatter(date: &<time::SinceStart as CoordExt>::Coord) -> String {
+        time::SinceStart::from(date.to_std().unwrap())
+            .display_human()
+            .to_string()
     }
 }
 
@@ -930,6 +1286,26 @@ impl<X> PointValExt<Size> for PolyPoints<X, Size> {
     }
 }
 
+impl<X> PointValExt<Rate> for PolyPoints<X, Rate> {
+    fn val_range_processor(range: Range<Option<Rate>>) -> Res<Range<Rate>> {
+        Ok(range.unwrap_or_else(|| Rate::default_min().into(), || Rate::default_max().into()))
+    }
+    fn val_coord_range_processor(range: &Range<Rate>) -> Res<Range<<Rate as CoordExt>::Coord>> {
+        Ok(Range::new(
+            range.lbound.rate,
+            range.ubound.rate.max(Rate::default_max()),
+        ))
+    }
+    fn val_coord_processor(_range: &Range<Rate>, x: &Rate) -> <Rate as CoordExt>::Coord {
+        x.rate
+    }
+    fn val_label_formatter(val: &<Rate as CoordExt>::Coord) -> String {
+        let mut s = num_fmt::bin_str_do(*val as f64, base::identity);
+        s.push_str("B/s");
+        s
+    }
+}
+
 impl<X> PointValExt<u64> for PolyPoints<X, u64> {
     fn val_range_processor(range: Range<Option<u64>>) -> Res<Range<u64>> {
         Ok(range.unwrap_or_else(u64::default_min, u64::default_max))
@@ -956,24 +1332,45 @@ impl<X> PointValExt<u64> for PolyPoints<X, u64> {
 /// Points representing size over time.
 pub type TimeSizePoints = PolyPoints<time::SinceStart, Size>;
 
+/// Points representing a rate over time, one per time window.
+///
+/// The key of a point is the end of its time window.
+pub type TimeRatePoints = PolyPoints<time::SinceStart, Rate>;
+
 /// Some points for a time chart.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TimePoints {
     /// Size over time.
     Size(TimeSizePoints),
+    /// Rate over time.
+    Rate(TimeRatePoints),
 }
 
 base::implement! {
     impl From for TimePoints {
-        from TimeSizePoints => |points| Self::Size(points)
+        from TimeSizePoints => |points| Self::Size(points),
+        from TimeRatePoints => |points| Self::Rate(points),
     }
 }
 
 impl TimePoints {
+    /// Removes the points of a line.
+    pub fn remove_line(&mut self, uid: uid::Line) {
+        match self {
+            Self::Size(points) => points.iter_mut().for_each(|point| {
+                point.vals.remove(uid);
+            }),
+            Self::Rate(points) => points.iter_mut().for_each(|point| {
+                point.vals.remove(uid);
+            }),
+        }
+    }
+
     /// True if there are no points.
     pub fn is_empty(&self) -> bool {
         match self {
             Self::Size(points) => points.is_empty(),
+            Self::Rate(points) => points.is_empty(),
         }
     }
 
@@ -983,20 +1380,72 @@ impl TimePoints {
     pub fn len(&self) -> usize {
         match self {
             Self::Size(points) => points.len(),
+            Self::Rate(points) => points.len(),
         }
     }
     /// Total number of points.
     pub fn point_count(&self) -> usize {
+        match self {
+            Self::Size(points) => points.iter().fold(0, |acc, point| acc + point.vals.len()),
+            Self::Rate(points) => points.iter().fold(0, |acc, point| acc + point.vals.len()),
+        }
+    }
+
+    /// Peak of each filter, with the first time it was reached.
+    ///
+    /// Peaks are sizes in bytes for size points, and rates in bytes per second for rate points.
+    ///
+    /// ```rust
+    /// use charts::{
+    ///     chart::{axis::*, settings::Resolution, Chart},
+    ///     filter::Filters,
+    ///     fixture::*,
+    ///     point::Points,
+    ///     prelude::{alloc::Diff, *},
+    /// };
+    /// set_global_data(vec![
+    ///     Diff::new(secs(1), vec![builder(0, 8, 1)], vec![]),
+    ///     Diff::new(secs(2), vec![builder(1, 16, 2), builder(2, 32, 2)], vec![]),
+    ///     // Deaths after the peak.
+    ///     Diff::new(secs(3), vec![], vec![(uid(1), secs(3)), (uid(2), secs(3))]),
+    ///     Diff::new(secs(4), vec![builder(3, 16, 4)], vec![(uid(0), secs(4))]),
+    /// ]);
+    ///
+    /// let mut filters = Filters::new();
+    /// let mut chart = Chart::new(
+    ///     &filters,
+    ///     XAxis::Time,
+    ///     YAxis::TotalSize,
+    ///     filters.uid_map(true),
+    /// )
+    /// .unwrap();
+    /// chart.settings_mut().set_resolution(Resolution { width: 100, height: 100 });
+    /// let window = TimeWindopt::new(None, None);
+    /// let points = match chart.new_points(true, &mut filters, &window).unwrap() {
+    ///     Some(Points::Time(points)) => points,
+    ///     points => panic!("unexpected points {:?}", points),
+    /// };
+    /// assert_eq!(points.peaks()[&uid::Line::Everything], (secs(2), 56));
+    /// ```
+    pub fn peaks(&self) -> BTMap<uid::Line, (time::SinceStart, u64)> {
         match self {
             Self::Size(points) => points
-                .iter()
-                .fold(0, |acc, point| acc + point.vals.map.len()),
+                .peaks(|_| true)
+                .into_iter()
+                .map(|(line, (time, size))| (line, (time, size.size)))
+                .collect(),
+            Self::Rate(points) => points
+                .peaks(|_| true)
+                .into_iter()
+                .map(|(line, (time, rate))| (line, (time, rate.rate)))
+                .collect(),
         }
     }
 
     /// Extends some points with other points, returns `true` iff new points were added.
     ///
-    /// Fails if the two kinds of points are not compatible.
+    /// Rate points replace the current ones, since all the windows are recomputed when the time
+    /// window changes. Fails if the two kinds of points are not compatible.
     pub fn extend(&mut self, other: &mut Self) -> Res<bool> {
         let new_stuff = match (self, other) {
             (Self::Size(self_points), Self::Size(points)) => {
@@ -1004,6 +1453,13 @@ impl TimePoints {
                 self_points.extend(points.drain(0..));
                 new_stuff
             }
+            (Self::Rate(self_points), Self::Rate(points)) => {
+                std::mem::swap(self_points, points);
+                true
+            }
+            (Self::Size(_), Self::Rate(_)) | (Self::Rate(_), Self::Size(_)) => {
+                bail!("cannot extend size points with rate points, or the other way around")
+            }
         };
         Ok(new_stuff)
     }
@@ -1028,22 +1484,308 @@ impl TimePoints {
                 is_active,
                 active_filters,
             ),
+            Self::Rate(points) => points.render(
+                settings,
+                chart_builder,
+                style_conf,
+                is_active,
+                active_filters,
+            ),
+        }
+    }
+}
+
+/// Points for a histogram, one per bucket.
+///
+/// The key of a point is the (inclusive) size range of its bucket.
+pub type BucketPoints = PolyPoints<Range<u64>, u64>;
+
+/// Some points for a histogram chart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HistogramPoints {
+    /// Number of allocations in each size bucket.
+    Count(BucketPoints),
+    /// Total size of the allocations in each size bucket.
+    Size(BucketPoints),
+    /// Number of allocations in each lifetime bucket, in microseconds.
+    LifetimeCount(BucketPoints),
+    /// Total size of the allocations in each lifetime bucket, in microseconds.
+    LifetimeSize(BucketPoints),
+}
+
+impl HistogramPoints {
+    /// Removes the points of a line.
+    pub fn remove_line(&mut self, uid: uid::Line) {
+        match self {
+            Self::Count(points)
+            | Self::Size(points)
+            | Self::LifetimeCount(points)
+            | Self::LifetimeSize(points) => points.iter_mut().for_each(|point| {
+                point.vals.remove(uid);
+            }),
+        }
+    }
+
+    /// The actual points.
+    pub fn points(&self) -> &BucketPoints {
+        match self {
+            Self::Count(points)
+            | Self::Size(points)
+            | Self::LifetimeCount(points)
+            | Self::LifetimeSize(points) => points,
+        }
+    }
+
+    /// True if there are no points.
+    pub fn is_empty(&self) -> bool {
+        self.points().is_empty()
+    }
+
+    /// Number of buckets.
+    pub fn len(&self) -> usize {
+        self.points().len()
+    }
+    /// Total number of points.
+    pub fn point_count(&self) -> usize {
+        self.points()
+            .iter()
+            .fold(0, |acc, point| acc + point.vals.len())
+    }
+
+    /// Replaces its points by `other`'s, as a histogram is a snapshot of the live allocations.
+    ///
+    /// Always returns `true`.
+    pub fn extend(&mut self, other: &mut Self) -> Res<bool> {
+        std::mem::swap(self, other);
+        Ok(true)
+    }
+
+    /// Formatter for the y-axis labels.
+    fn y_label_formatter(&self) -> fn(&u64) -> String {
+        match self {
+            Self::Count(_) | Self::LifetimeCount(_) => {
+                <PolyPoints<Range<u64>, u64> as PointValExt<u64>>::val_label_formatter
+            }
+            Self::Size(_) | Self::LifetimeSize(_) => {
+                <PolyPoints<Range<u64>, Size> as PointValExt<Size>>::val_label_formatter
+            }
+        }
+    }
+
+    /// Formats a bucket bound.
+    ///
+    /// ```rust
+    /// use charts::point::{BucketPoints, HistogramPoints};
+    /// let sizes = HistogramPoints::Count(BucketPoints::new());
+    /// assert_eq!(sizes.bound_label(2048), "2.00KiB");
+    /// let lifetimes = HistogramPoints::LifetimeCount(BucketPoints::new());
+    /// assert_eq!(lifetimes.bound_label(7), "7µs");
+    /// assert_eq!(lifetimes.bound_label(2_500), "2.50ms");
+    /// assert_eq!(lifetimes.bound_label(3_000_000), "3.00s");
+    /// ```
+    pub fn bound_label(&self, bound: u64) -> String {
+        match self {
+            Self::Count(_) | Self::Size(_) => {
+                let mut s = num_fmt::bin_str_do(bound as f64, base::identity);
+                s.push('B');
+                s
+            }
+            Self::LifetimeCount(_) | Self::LifetimeSize(_) => {
+                if bound < 1_000 {
+                    format!("{}µs", bound)
+                } else if bound < 1_000_000 {
+                    format!("{:.2}ms", bound as f64 / 1_000.0)
+                } else {
+                    format!("{:.2}s", bound as f64 / 1_000_000.0)
+                }
+            }
+        }
+    }
+
+    /// Renders the points as a bar chart.
+    ///
+    /// Buckets all have the same width regardless of their size range, the bars of the different
+    /// filters are drawn side by side.
+    pub fn render<'spec, DB>(
+        &self,
+        _settings: &settings::Chart,
+        mut chart_builder: plotters::prelude::ChartBuilder<DB>,
+        style_conf: &impl StyleExt,
+        is_active: impl Fn(uid::Line) -> bool,
+        active_filters: impl Iterator<Item = &'spec filter::FilterSpec> + Clone,
+    ) -> Res<()>
+    where
+        DB: plotters::prelude::DrawingBackend,
+    {
+        use plotters::prelude::*;
+
+        /// Part of the width of a bucket left empty on each side of its bars.
+        const BUCKET_MARGIN: f32 = 0.1;
+
+        let points = self.points();
+        let y_max = points
+            .iter()
+            .flat_map(|point| {
+                point
+                    .vals
+                    .map
+                    .iter()
+                    .filter(|(uid, _)| is_active(**uid))
+                    .map(|(_, val)| *val)
+            })
+            .max()
+            .unwrap_or(0)
+            .max(u64::default_max());
+
+        let x_range: coord::RangedCoordf32 = (0.0..(points.len().max(1) as f32)).into();
+        let y_range: coord::RangedCoordu64 = (0..y_max).into();
+
+        let mut chart_cxt: ChartContext<
+            DB,
+            coord::Cartesian2d<coord::RangedCoordf32, coord::RangedCoordu64>,
+        > = chart_builder
+            .build_cartesian_2d(x_range, y_range)
+            .map_err(|e| e.to_string())?;
+
+        // Labels the bucket boundaries with their size or lifetime.
+        let x_label_formatter = |x: &f32| {
+            let idx = x.round();
+            if (x - idx).abs() > f32::EPSILON {
+                return String::new();
+            }
+            let idx = idx as usize;
+            let bound = if let Some(point) = points.get(idx) {
+                point.key.lbound
+            } else if let Some(point) = points.last() {
+                point.key.ubound
+            } else {
+                return String::new();
+            };
+            self.bound_label(bound)
+        };
+        let y_label_formatter = self.y_label_formatter();
+
+        // Mesh configuration.
+        {
+            let mut mesh = chart_cxt.configure_mesh();
+
+            // Apply caller's configuration.
+            style_conf.mesh_conf(&mut mesh);
+
+            mesh.x_labels(points.len() + 1)
+                .x_label_formatter(&x_label_formatter)
+                .y_label_formatter(&y_label_formatter)
+                .draw()
+                .map_err(|e| e.to_string())?;
         }
+
+        let filter_count = active_filters.clone().count();
+        let bar_width = (1.0 - 2.0 * BUCKET_MARGIN) / (filter_count.max(1) as f32);
+
+        for (filter_idx, filter_spec) in active_filters.enumerate() {
+            let f_uid = filter_spec.uid();
+            let style = style_conf.shape_conf(filter_spec.color()).filled();
+            let bars = points.iter().enumerate().filter_map(|(idx, point)| {
+                point.vals.map.get(&f_uid).map(|val| {
+                    let lbound = idx as f32 + BUCKET_MARGIN + filter_idx as f32 * bar_width;
+                    Rectangle::new([(lbound, 0), (lbound + bar_width, *val)], style.clone())
+                })
+            });
+            chart_cxt.draw_series(bars).map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes a CSV field if needed, see [RFC 4180](https://tools.ietf.org/html/rfc4180).
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
     }
 }
 
+/// CSV representation of some points, see [`Points::to_csv`].
+///
+/// - `x_header` and `x_cells` are the header and the cells of the x-value column(s), they are not
+///   escaped.
+fn poly_points_to_csv<'spec, X, Y>(
+    points: &PolyPoints<X, Y>,
+    x_header: &str,
+    x_cells: impl Fn(&X) -> String,
+    specs: impl Iterator<Item = &'spec filter::FilterSpec>,
+) -> String
+where
+    Y: fmt::Display + Clone,
+{
+    // Lines and sub-lines, with their names.
+    let mut columns: Vec<(uid::Line, Option<SubLine>, String)> = vec![];
+    for spec in specs {
+        let line = spec.uid();
+        columns.push((line, None, spec.name().into()));
+        let sub_lines: BTSet<SubLine> = points
+            .iter()
+            .flat_map(|point| point.vals.sub_lines(line).map(|(sub_line, _)| *sub_line))
+            .collect();
+        for sub_line in sub_lines {
+            columns.push((line, Some(sub_line), sub_line.name(spec.name())))
+        }
+    }
+
+    let mut csv = x_header.to_string();
+    for (_, _, name) in &columns {
+        csv.push(',');
+        csv.push_str(&csv_field(name))
+    }
+    csv.push('\n');
+
+    let mut last: Vec<Option<Y>> = vec![None; columns.len()];
+    for point in points {
+        csv.push_str(&x_cells(&point.key));
+        for ((line, sub_line, _), last) in columns.iter().zip(last.iter_mut()) {
+            let val = match sub_line {
+                None => point.vals.map.get(line),
+                Some(sub_line) => point.vals.kinds.get(sub_line),
+            };
+            if let Some(val) = val {
+                *last = Some(val.clone())
+            }
+            csv.push(',');
+            if let Some(val) = last {
+                csv.push_str(&val.to_string())
+            }
+        }
+        csv.push('\n')
+    }
+
+    csv
+}
+
 /// Some points for a particular chart type.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Points {
     /// Points for a time chart.
     Time(TimePoints),
+    /// Points for a histogram.
+    Histogram(HistogramPoints),
 }
 
 impl Points {
+    /// Removes the points of a line.
+    pub fn remove_line(&mut self, uid: uid::Line) {
+        match self {
+            Self::Time(points) => points.remove_line(uid),
+            Self::Histogram(points) => points.remove_line(uid),
+        }
+    }
+
     /// True if there are no points.
     pub fn is_empty(&self) -> bool {
         match self {
             Self::Time(points) => points.is_empty(),
+            Self::Histogram(points) => points.is_empty(),
         }
     }
 
@@ -1053,12 +1795,14 @@ impl Points {
     pub fn len(&self) -> usize {
         match self {
             Self::Time(points) => points.len(),
+            Self::Histogram(points) => points.len(),
         }
     }
     /// Total number of points.
     pub fn point_count(&self) -> usize {
         match self {
             Self::Time(points) => points.point_count(),
+            Self::Histogram(points) => points.point_count(),
         }
     }
 
@@ -1068,6 +1812,53 @@ impl Points {
     pub fn extend(&mut self, other: &mut Self) -> Res<bool> {
         match (self, other) {
             (Self::Time(self_points), Self::Time(points)) => self_points.extend(points),
+            (Self::Histogram(self_points), Self::Histogram(points)) => self_points.extend(points),
+            (Self::Time(_), Self::Histogram(_)) | (Self::Histogram(_), Self::Time(_)) => {
+                bail!("cannot extend time points with histogram points, or the other way around")
+            }
+        }
+    }
+
+    /// CSV representation of the points, for the lines of some filters.
+    ///
+    /// Has a header row, and one row per point. The first column(s) are the x-value of the points:
+    /// time in seconds for time charts, lower and upper bounds of the bucket for histograms. Then
+    /// comes one column per line of `specs`, each followed by one column per [sub-line](SubLine)
+    /// of this line, if any. Since points only store the values that changed since the previous
+    /// point, missing values are the ones of the previous point (empty before the first value).
+    ///
+    /// Columns follow the order of `specs`, not the order of the UIDs of the lines: with
+    /// [`filter::Filters::specs`], the catch-all line is always the last column.
+    ///
+    /// ```rust
+    /// use charts::{filter::Filters, point::*, prelude::*};
+    /// let filters = Filters::new();
+    /// let secs = time::SinceStart::from_secs;
+    /// let mut later = PointVal::empty();
+    /// later.map.insert(uid::Line::CatchAll, Size::new(8u64));
+    /// let points = Points::from(vec![
+    ///     Point::new(secs(0), PointVal::new(Size::new(0u64), &filters)),
+    ///     Point::new(secs(1), later),
+    /// ]);
+    /// assert_eq!(
+    ///     points.to_csv(filters.specs()),
+    ///     "time (s),everything,catch all\n0.000000,0,0\n1.000000,0,8\n",
+    /// );
+    /// ```
+    pub fn to_csv<'spec>(&self, specs: impl Iterator<Item = &'spec filter::FilterSpec>) -> String {
+        match self {
+            Self::Time(TimePoints::Size(points)) => {
+                poly_points_to_csv(points, "time (s)", |time| time.to_string(), specs)
+            }
+            Self::Time(TimePoints::Rate(points)) => {
+                poly_points_to_csv(points, "time (s)", |time| time.to_string(), specs)
+            }
+            Self::Histogram(points) => poly_points_to_csv(
+                points.points(),
+                "lbound,ubound",
+                |bucket| format!("{},{}", bucket.lbound, bucket.ubound),
+                specs,
+            ),
         }
     }
 
@@ -1091,6 +1882,13 @@ impl Points {
                 is_active,
                 active_filters,
             ),
+            Self::Histogram(points) => points.render(
+                settings,
+                chart_builder,
+                style_conf,
+                is_active,
+                active_filters,
+            ),
         }
     }
 }
@@ -1103,8 +1901,67 @@ where
         Self::Time(points.into())
     }
 }
+impl From<HistogramPoints> for Points {
+    fn from(points: HistogramPoints) -> Self {
+        Self::Histogram(points)
+    }
+}
 
 /// Some points for all the charts.
+///
+/// # Ordering
+///
+/// Everything is stored in ordered maps so that (de)serialization is deterministic: charts are
+/// sorted by UID, and so are the lines of each point (custom filters, then catch-all, then
+/// everything, see [`uid::Line`]). Exports do not follow UID order but the display order of the
+/// filters: the everything line first, then the custom filters in order of priority, and the
+/// catch-all line **always last** (see [`Points::to_csv`]). In particular, two identical reloads
+/// yield the exact same columns.
+///
+/// ```rust
+/// use charts::{
+///     chart::{axis::*, settings::Resolution, Chart},
+///     color::Color,
+///     filter::*,
+///     prelude::*,
+/// };
+/// charts::data::start("../ctf/rsc/synthetic.ctf", Default::default()).unwrap();
+/// # while charts::data::progress::get().unwrap().is_some()
+/// #     || charts::data::alloc_count().unwrap() == 0
+/// # {
+/// #     std::thread::sleep(std::time::Duration::from_millis(50))
+/// # }
+/// let filter = |name: &str| {
+///     let mut spec = FilterSpec::new(Color::new(0, 0, 0));
+///     spec.set_name(name);
+///     Filter::new(spec).unwrap()
+/// };
+/// let (second, first) = (filter("second"), filter("first"));
+/// // `first` has the highest UID, but it comes first in the list of filters.
+/// assert!(first.uid() > second.uid());
+/// let mut filters = Filters::new_with(vec![first, second]);
+/// let window = TimeWindopt::new(None, None);
+/// let mut chart = Chart::new(
+///     &filters,
+///     XAxis::Time,
+///     YAxis::TotalSize,
+///     filters.uid_map(true),
+/// ).unwrap();
+/// chart.settings_mut().set_resolution(Resolution { width: 100, height: 100 });
+///
+/// let mut reload = || {
+///     chart.reset(&filters);
+///     filters.reset(Reset::Hard);
+///     let points = chart.new_points(true, &mut filters, &window).unwrap().unwrap();
+///     points.to_csv(filters.specs())
+/// };
+/// let (csv, reloaded_csv) = (reload(), reload());
+/// assert_eq!(
+///     csv.lines().next(),
+///     Some("time (s),everything,first,second,catch all"),
+/// );
+/// assert_eq!(csv, reloaded_csv);
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChartPoints {
     /// The actual points.
@@ -1122,6 +1979,13 @@ impl ChartPoints {
     pub fn is_empty(&self) -> bool {
         self.points.iter().all(|(_uid, points)| points.is_empty())
     }
+
+    /// Removes the points of a line in all the charts.
+    pub fn remove_line(&mut self, uid: uid::Line) {
+        self.points
+            .values_mut()
+            .for_each(|points| points.remove_line(uid))
+    }
 }
 
 base::implement! {
@@ -32,6 +32,7 @@ pub mod alloc {
 pub mod coord {
     pub use plotters::coord::{
         cartesian::Cartesian2d,
+        combinators::{LogCoord, LogRange},
         ranged1d::{AsRangedCoord, Ranged, ValueFormatter},
         types::{RangedCoordf32, RangedCoordu32, RangedCoordu64, RangedDuration},
     };
@@ -59,7 +60,7 @@ base::cfg_item! {
 
 pub use crate::{
     chart::{self, settings},
-    color::Color,
+    color::{self, Color},
     filter::{self, Filter, Filters},
     msg,
     point::{self, Point, PointVal, Points},
@@ -151,6 +152,35 @@ impl LoadInfo {
     }
 }
 
+/// Information about a profiling run, derived from its [`alloc::Init`].
+///
+/// The information about the executable is only available for memtrace CTF dumps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunInfo {
+    /// Date at which the run started.
+    pub start_time: time::Date,
+    /// End time of the run, if known.
+    pub end_time: Option<time::SinceStart>,
+    /// Size of machine words in bits.
+    pub word_size: usize,
+    /// Sampling rate.
+    pub sample_rate: f64,
+    /// Information about the profiled executable, if known.
+    pub exe_info: Option<alloc::ExeInfo>,
+}
+impl RunInfo {
+    /// Constructor.
+    pub fn new(init: &alloc::Init) -> Self {
+        Self {
+            start_time: init.start_time,
+            end_time: init.end_time,
+            word_size: init.word_size,
+            sample_rate: init.sample_rate.sample_rate,
+            exe_info: init.exe_info.clone(),
+        }
+    }
+}
+
 /// Allocation statistics.
 ///
 /// Sent to the client so that it can display basic informations (run date, allocation count...).
@@ -166,6 +196,10 @@ pub struct AllocStats {
     pub start_date: time::Date,
     /// Duration of the run.
     pub duration: time::SinceStart,
+    /// Number of malformed diff files skipped, see [`data::MalformedPolicy`].
+    ///
+    /// [`data::MalformedPolicy`]: crate::data::MalformedPolicy (MalformedPolicy enum)
+    pub skipped_files: usize,
 }
 #[cfg(any(test, feature = "server"))]
 impl AllocStats {
@@ -179,6 +213,7 @@ impl AllocStats {
             total_size: 0,
             start_date,
             duration: time::SinceStart::zero(),
+            skipped_files: 0,
         }
     }
 
@@ -0,0 +1,223 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Top allocation sites report.
+
+prelude! {}
+
+/// Number of allocation sites requested to the server.
+pub const TOP_COUNT: usize = 20;
+
+/// Column the allocation sites are sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    /// Allocation site.
+    Loc,
+    /// Size in bytes.
+    Bytes,
+    /// Number of allocations.
+    Count,
+}
+
+impl SortBy {
+    /// Header of the column.
+    pub fn desc(self) -> &'static str {
+        match self {
+            Self::Loc => "allocation site",
+            Self::Bytes => "size",
+            Self::Count => "allocations",
+        }
+    }
+}
+
+/// Allocation sites messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// Sorts the table by some column, reverses the order if the table is already sorted by it.
+    Sort(SortBy),
+    /// Closes the report.
+    Close,
+}
+
+base::implement! {
+    impl Msg {
+        Display {
+            |&self, fmt| match self {
+                Self::Sort(col) => write!(fmt, "sort by {}", col.desc()),
+                Self::Close => write!(fmt, "close"),
+            }
+        }
+    }
+}
+
+/// Top allocation sites report, sent by the server.
+pub struct AllocSites {
+    /// Model link.
+    link: Link,
+    /// Allocation sites, if any: location, size in bytes and number of allocations.
+    sites: Option<Vec<(alloc::Loc, u64, usize)>>,
+    /// Column the sites are sorted by.
+    sort_by: SortBy,
+    /// True if the sites are sorted in reverse order.
+    rev: bool,
+}
+
+impl AllocSites {
+    /// Constructor.
+    pub fn new(link: Link) -> Self {
+        Self {
+            link,
+            sites: None,
+            sort_by: SortBy::Bytes,
+            rev: false,
+        }
+    }
+
+    /// Message requesting the top allocation sites to the server.
+    pub fn request() -> msg::Msg {
+        msg::to_server::DataMsg::top_alloc_sites(TOP_COUNT).into()
+    }
+
+    /// Sets the allocation sites, as sent by the server.
+    pub fn set(&mut self, sites: Vec<(alloc::Loc, u64, usize)>) -> ShouldRender {
+        self.sites = Some(sites);
+        self.sort();
+        true
+    }
+
+    /// Sorts the sites.
+    ///
+    /// Sizes and counts are sorted in decreasing order by default, locations in increasing order.
+    /// Ties are broken by size, then count, then location.
+    fn sort(&mut self) {
+        let (sort_by, rev) = (self.sort_by, self.rev);
+        if let Some(sites) = self.sites.as_mut() {
+            sites.sort_by(|(loc_1, bytes_1, count_1), (loc_2, bytes_2, count_2)| {
+                let by_bytes = bytes_2.cmp(bytes_1);
+                let by_count = count_2.cmp(count_1);
+                let by_loc = loc_1.cmp(loc_2);
+                let order = match sort_by {
+                    SortBy::Loc => by_loc.then(by_bytes).then(by_count),
+                    SortBy::Bytes => by_bytes.then(by_count).then(by_loc),
+                    SortBy::Count => by_count.then(by_bytes).then(by_loc),
+                };
+                if rev {
+                    order.reverse()
+                } else {
+                    order
+                }
+            })
+        }
+    }
+
+    /// Handles a message.
+    pub fn update(&mut self, msg: Msg) -> Res<ShouldRender> {
+        match msg {
+            Msg::Sort(sort_by) => {
+                if self.sort_by == sort_by {
+                    self.rev = !self.rev
+                } else {
+                    self.sort_by = sort_by;
+                    self.rev = false
+                }
+                self.sort()
+            }
+            Msg::Close => self.sites = None,
+        }
+        Ok(true)
+    }
+
+    /// Renders the report, if any.
+    pub fn render(&self) -> Html {
+        define_style! {
+            CONTAINER = {
+                padding(0 px, 0 px, 30 px, 0 px),
+            };
+            TABLE = {
+                width(100%),
+            };
+            HEADER = {
+                bold,
+                pointer,
+                text_align(center),
+                border(bottom, 2 px, black),
+            };
+            CELL = {
+                text_align(center),
+                padding(0 px, 10 px),
+            };
+        }
+
+        let sites = if let Some(sites) = self.sites.as_ref() {
+            sites
+        } else {
+            return html! {};
+        };
+
+        let header = |col: SortBy| {
+            let arrow = if self.sort_by != col {
+                ""
+            } else if self.rev {
+                " ▴"
+            } else {
+                " ▾"
+            };
+            html! {
+                <th
+                    style = HEADER
+                    onclick = self.link.callback(move |_| msg::Msg::from(Msg::Sort(col)))
+                >
+                    {col.desc()}{arrow}
+                </th>
+            }
+        };
+
+        html! {
+            <div
+                style = CONTAINER
+            >
+                {layout::section_title("top allocation sites")}
+                {layout::button::text::render_default_button(
+                    "alloc_sites_close",
+                    "close",
+                    Some(self.link.callback(|_| msg::Msg::from(Msg::Close))),
+                    false,
+                )}
+                <table
+                    style = TABLE
+                >
+                    <tr>
+                        {header(SortBy::Loc)}
+                        {header(SortBy::Bytes)}
+                        {header(SortBy::Count)}
+                    </tr>
+                    {for sites.iter().map(|(loc, bytes, count)| html! {
+                        <tr>
+                            <td style = CELL>{loc}</td>
+                            <td style = CELL>
+                                {num_fmt::bin_str_do(*bytes as f64, |mut s| { s.push('B'); s })}
+                            </td>
+                            <td style = CELL>{num_fmt::str_do(*count as f64, identity)}</td>
+                        </tr>
+                    })}
+                </table>
+            </div>
+        }
+    }
+}
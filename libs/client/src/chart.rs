@@ -56,6 +56,13 @@ impl Charts {
         &self.dom_node_id
     }
 
+    /// Removes all the charts.
+    ///
+    /// Used when resuming a session, as the server sends all the charts again.
+    pub fn clear(&mut self) {
+        self.charts.clear()
+    }
+
     /// Number of charts.
     pub fn len(&self) -> usize {
         self.charts.len()
@@ -109,6 +116,13 @@ impl Charts {
             Destroy(uid) => self.destroy(uid),
 
             RefreshFilters => self.refresh_filters(filters),
+            SetTimeFormat(time_format) => {
+                for chart in &mut self.charts {
+                    chart.set_time_format(time_format)
+                }
+                // Rendering is done at JS-level, no need to render the HTML.
+                Ok(false)
+            }
 
             NewChartSetX(x_axis) => self.new_chart.set_x_axis(x_axis),
             NewChartSetY(y_axis) => self.new_chart.set_y_axis(y_axis),
@@ -129,6 +143,20 @@ impl Charts {
         }
     }
 
+    /// Sets the start date of the run in all charts.
+    pub fn set_start_date(&mut self, start_date: time::Date) {
+        for chart in &mut self.charts {
+            chart.set_start_date(start_date)
+        }
+    }
+
+    /// Sets the time cursor in all charts.
+    pub fn set_cursor(&mut self, cursor: Option<time::SinceStart>, live_bytes: u64) {
+        for chart in &mut self.charts {
+            chart.set_cursor(cursor, live_bytes)
+        }
+    }
+
     /// Refreshes all filters in all charts.
     fn refresh_filters(&mut self, filters: filter::Reference) -> Res<ShouldRender> {
         for chart in &mut self.charts {
@@ -228,6 +256,7 @@ impl Charts {
             ChartsMsg::NewPoints {
                 mut points,
                 refresh_filters,
+                ..
             } => {
                 for chart in &mut self.charts {
                     if let Some(points) = points.remove(&chart.uid()) {
@@ -239,7 +268,7 @@ impl Charts {
                 }
                 true
             }
-            ChartsMsg::AddPoints(mut points) => {
+            ChartsMsg::AddPoints { mut points, .. } => {
                 for chart in &mut self.charts {
                     if let Some(points) = points.remove(&chart.uid()) {
                         chart.add_points(points, filters, stats)?
@@ -248,18 +277,26 @@ impl Charts {
                 false
             }
 
+            ChartsMsg::Cursor { time, live_bytes } => {
+                self.set_cursor(time, live_bytes);
+                true
+            }
+
             ChartsMsg::Chart { uid, msg } => {
                 let (_index, chart) = self.get_mut(uid)?;
                 match msg {
                     ChartMsg::NewPoints(points) => chart.overwrite_points(points)?,
                     ChartMsg::Points(points) => chart.add_points(points, filters, stats)?,
+                    ChartMsg::Error(e) => {
+                        bail!("failed to refresh chart `{}`: {}", chart.title(), e)
+                    }
+                    ChartMsg::SampleRate(rate) => chart.set_sample_rate(rate),
                 }
                 true
-            }
-            // msg => bail!(
-            //     "unsupported message from server: {}",
-            //     msg.as_json().unwrap_or_else(|_| format!("{:?}", msg))
-            // ),
+            } // msg => bail!(
+              //     "unsupported message from server: {}",
+              //     msg.as_json().unwrap_or_else(|_| format!("{:?}", msg))
+              // ),
         };
         Ok(should_render)
     }
@@ -301,6 +338,8 @@ pub struct Chart {
     redraw: bool,
     /// True if the chart settings are visible.
     settings_visible: bool,
+    /// Total size of the allocations live at the time cursor, if any.
+    cursor_live_bytes: u64,
 }
 impl Chart {
     /// Constructor.
@@ -323,6 +362,7 @@ impl Chart {
             prev_active: BTMap::new(),
             settings_visible: false,
             redraw: true,
+            cursor_live_bytes: 0,
         })
     }
 
@@ -332,7 +372,27 @@ impl Chart {
         match msg {
             SettingsToggleVisible => self.toggle_settings_visible(),
             FilterToggleVisible(l_uid) => self.filter_toggle_visible(l_uid)?,
-            SettingsUpdate(msg) => self.settings.update(msg),
+            SettingsUpdate(msg) => {
+                // Bucket and split-by-kind settings impact point generation, the server needs to
+                // know about them.
+                if let charts::msg::ChartSettingsMsg::SetBucketCount(_)
+                | charts::msg::ChartSettingsMsg::SetLogBuckets(_)
+                | charts::msg::ChartSettingsMsg::SetSplitByKind(_) = msg
+                {
+                    let to_server: msg::to_server::Msg = (self.spec.uid(), msg.clone()).into();
+                    self.link.send_message(Msg::ToServer(to_server))
+                }
+                self.settings.update(msg)
+            }
+            SetTitle(title) => {
+                self.spec.set_title(title.clone())?;
+                self.settings.set_title(self.spec.display_title());
+                self.link
+                    .send_message(Msg::ToServer(msg::to_server::ChartMsg::set_title(
+                        self.spec.uid(),
+                        title,
+                    )))
+            }
         }
         Ok(true)
     }
@@ -363,10 +423,10 @@ impl Chart {
     pub fn settings(&self) -> &settings::Chart {
         &self.settings
     }
-    /// Chart title.
+    /// Chart title: its custom title if any, its description otherwise.
     #[inline]
-    pub fn title(&self) -> &str {
-        self.settings().title()
+    pub fn title(&self) -> String {
+        self.spec.display_title()
     }
 
     /// Chart specification.
@@ -411,6 +471,79 @@ impl Chart {
 
 /// # Features that (can) trigger a re-draw.
 impl Chart {
+    /// Sets the format of the time values.
+    pub fn set_time_format(&mut self, time_format: settings::TimeFormat) {
+        if self.settings.time_format() != time_format {
+            self.settings.set_time_format(time_format);
+            self.redraw = true;
+        }
+    }
+
+    /// Sets the start date of the run.
+    pub fn set_start_date(&mut self, start_date: time::Date) {
+        if self.settings.start_date() != Some(&start_date) {
+            self.settings.set_start_date(Some(start_date));
+            self.redraw = true;
+        }
+    }
+
+    /// Sets the sample rate of the run, see [`ChartSpec::sample_rate`].
+    pub fn set_sample_rate(&mut self, rate: Option<SampleRate>) {
+        if self.spec.sample_rate() != rate.as_ref() {
+            self.spec.set_sample_rate(rate);
+            self.redraw = true;
+        }
+    }
+    /// Describes the sample rate if the values of the chart are estimates, `None` otherwise.
+    pub fn estimate_desc(&self) -> Option<String> {
+        self.spec
+            .sample_rate()
+            .filter(|rate| rate.is_sampled())
+            .map(|rate| {
+                format!(
+                    "values are estimates, the run samples allocations at rate {}",
+                    rate.sample_rate
+                )
+            })
+    }
+
+    /// Sets the time cursor.
+    pub fn set_cursor(&mut self, cursor: Option<time::SinceStart>, live_bytes: u64) {
+        self.cursor_live_bytes = live_bytes;
+        if self.settings.cursor() != cursor {
+            self.settings.set_cursor(cursor);
+            self.redraw = true;
+        }
+    }
+    /// Description of the time cursor, `None` if there is no cursor or the chart is not a time
+    /// chart.
+    ///
+    /// If the values of the chart are estimates, the live size is prefixed with `≈` and followed by
+    /// the number of samples it comes from.
+    pub fn cursor_desc(&self) -> Option<String> {
+        if self.spec.x_axis().is_histogram() {
+            return None;
+        }
+        self.settings.cursor().map(|cursor| {
+            let mut desc = format!(
+                "cursor at {}, {}{} live",
+                self.settings.format_time(cursor),
+                self.spec.estimate_prefix(),
+                num_fmt::bin_str_do(self.cursor_live_bytes as f64, |mut s| {
+                    s.push('B');
+                    s
+                }),
+            );
+            if let Some(samples) = self.spec.raw_samples_of(self.cursor_live_bytes) {
+                desc.push_str(&format!(
+                    " ({} samples)",
+                    num_fmt::str_do(samples as f64, identity)
+                ))
+            }
+            desc
+        })
+    }
+
     /// Toggles the visibility of a filter for the chart.
     pub fn filter_toggle_visible(&mut self, uid: uid::Line) -> Res<()> {
         if let Some(is_visible) = self.spec.active_mut().get_mut(&uid) {
@@ -600,10 +733,10 @@ impl Chart {
 #[derive(Debug, Clone, Copy)]
 struct Styler;
 impl charts::point::StyleExt for Styler {
-    fn mesh_conf<X, Y, DB>(&self, mesh: &mut plotters::chart::MeshStyle<X::Range, Y::Range, DB>)
+    fn mesh_conf<XR, YR, DB>(&self, mesh: &mut plotters::chart::MeshStyle<XR, YR, DB>)
     where
-        X: charts::point::CoordExt,
-        Y: charts::point::CoordExt,
+        XR: charts::prelude::coord::Ranged,
+        YR: charts::prelude::coord::Ranged,
         DB: plotters::prelude::DrawingBackend,
     {
         mesh.disable_x_mesh()
@@ -0,0 +1,194 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Allocation explanation report.
+
+prelude! {}
+
+/// Explanation messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// Opens the report.
+    Open,
+    /// Requests the explanation of an allocation to the server.
+    Request(uid::Alloc),
+    /// Closes the report.
+    Close,
+}
+
+base::implement! {
+    impl Msg {
+        Display {
+            |&self, fmt| match self {
+                Self::Open => write!(fmt, "open"),
+                Self::Request(uid) => write!(fmt, "request #{}", uid),
+                Self::Close => write!(fmt, "close"),
+            }
+        }
+    }
+}
+
+/// Explains which filters match an allocation, and why.
+pub struct Explain {
+    /// Model link.
+    link: Link,
+    /// True if the report is open.
+    open: bool,
+    /// UID of the allocation to explain.
+    uid: usize,
+    /// Explanation sent by the server, if any.
+    explanation: Option<charts::filter::Explanation>,
+}
+
+impl Explain {
+    /// Constructor.
+    pub fn new(link: Link) -> Self {
+        Self {
+            link,
+            open: false,
+            uid: 0,
+            explanation: None,
+        }
+    }
+
+    /// Sets the explanation, as sent by the server.
+    pub fn set(&mut self, explanation: charts::filter::Explanation) -> ShouldRender {
+        self.open = true;
+        self.explanation = Some(explanation);
+        true
+    }
+
+    /// Handles a message.
+    pub fn update(&mut self, msg: Msg) -> Res<ShouldRender> {
+        match msg {
+            Msg::Open => self.open = true,
+            Msg::Request(uid) => {
+                self.uid = uid.get();
+                self.explanation = None;
+                self.link
+                    .send_message(msg::to_server::DataMsg::explain(uid))
+            }
+            Msg::Close => {
+                self.open = false;
+                self.explanation = None
+            }
+        }
+        Ok(true)
+    }
+
+    /// Renders the report, if open.
+    pub fn render(&self, model: &Model) -> Html {
+        define_style! {
+            CONTAINER = {
+                padding(0 px, 0 px, 30 px, 0 px),
+            };
+            INPUT = {
+                width(20%),
+                height(30 px),
+                padding(10 px, 0 px),
+            };
+            TABLE = {
+                width(100%),
+            };
+            HEADER = {
+                bold,
+                text_align(center),
+                border(bottom, 2 px, black),
+            };
+            CELL = {
+                text_align(center),
+                padding(0 px, 10 px),
+            };
+        }
+
+        if !self.open {
+            return html! {};
+        }
+
+        let input = layout::input::usize_input(model, self.uid, |res| match res {
+            Ok(uid) => Msg::Request(uid.into()).into(),
+            Err(e) => msg::Msg::err(e),
+        });
+
+        let explanation = if let Some(explanation) = self.explanation.as_ref() {
+            let attributed_to = explanation.attributed_to();
+            let verdict = |f_explanation: &charts::filter::FilterExplanation| {
+                if attributed_to == Some(f_explanation.uid) {
+                    "match, allocation attributed to this filter".to_string()
+                } else if f_explanation.is_match() {
+                    "match, but a filter with higher priority matches too".to_string()
+                } else {
+                    f_explanation.outcome.to_string()
+                }
+            };
+            html! {
+                <>
+                    <table
+                        style = TABLE
+                    >
+                        <tr>
+                            <th style = HEADER>{"filter"}</th>
+                            <th style = HEADER>{"verdict"}</th>
+                        </tr>
+                        {for explanation.filters.iter().map(|f_explanation| html! {
+                            <tr>
+                                <td style = CELL>{&f_explanation.name}</td>
+                                <td style = CELL>{verdict(f_explanation)}</td>
+                            </tr>
+                        })}
+                    </table>
+                    {
+                        if attributed_to.is_none() {
+                            html! {
+                                <div style = CELL>
+                                    {"no filter matches, allocation attributed to the catch-all filter"}
+                                </div>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <div
+                style = CONTAINER
+            >
+                {layout::section_title("explain allocation")}
+                {layout::button::text::render_default_button(
+                    "explain_close",
+                    "close",
+                    Some(self.link.callback(|_| msg::Msg::from(Msg::Close))),
+                    false,
+                )}
+                <div
+                    style = INPUT
+                >
+                    {"allocation UID "}
+                    {input}
+                </div>
+                {explanation}
+            </div>
+        }
+    }
+}
@@ -21,7 +21,10 @@
 
 prelude! {}
 
-pub use charts::filter::{stats::AllFilterStats, Filter, FilterSpec, SubFilter};
+pub use charts::filter::{
+    stats::{AllFilterStats, FilterDelta},
+    Filter, FilterSpec, SubFilter,
+};
 
 /// Stores filter states.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -118,23 +121,24 @@ impl FilterStates {
         let has_user_filters = !self.filters.is_empty();
         (
             // Everything.
-            if is_active(&self.everything) == active {
+            if self.everything.is_active() && is_active(&self.everything) == active {
                 Some(&self.everything)
             } else {
                 None
             },
             // Custom filters.
             if has_user_filters {
-                Some(
-                    self.filters
-                        .iter()
-                        .filter(move |filter| is_active(filter.spec()) == active),
-                )
+                Some(self.filters.iter().filter(move |filter| {
+                    filter.spec().is_active() && is_active(filter.spec()) == active
+                }))
             } else {
                 None
             },
             // Catch-all.
-            if has_user_filters && is_active(&self.catch_all) == active {
+            if has_user_filters
+                && self.catch_all.is_active()
+                && is_active(&self.catch_all) == active
+            {
                 Some(&self.catch_all)
             } else {
                 None
@@ -165,6 +169,8 @@ impl FilterStates {
     }
 
     /// Yields an iterator ovec the filter specifications.
+    ///
+    /// Skips inactive filters, see [`FilterSpec::is_active`].
     pub fn specs_iter(&self) -> impl Iterator<Item = &FilterSpec> + Clone {
         Some(&self.everything)
             .into_iter()
@@ -177,6 +183,7 @@ impl FilterStates {
                 }
                 .into_iter(),
             )
+            .filter(|spec| spec.is_active())
     }
 
     /// Applies a function to all specification filters.
@@ -225,6 +232,8 @@ pub struct FilterInfo {
     pub states: Memory<FilterStates>,
     /// Filter statistics for the reference filter states.
     pub reference_stats: AllFilterStats,
+    /// Errors reported by the server for some subfilters.
+    pub sub_errors: BTMap<uid::SubFilter, String>,
 }
 
 impl FilterInfo {
@@ -235,9 +244,15 @@ impl FilterInfo {
             link,
             states,
             reference_stats: AllFilterStats::new(),
+            sub_errors: BTMap::new(),
         }
     }
 
+    /// Error reported by the server for a subfilter, if any.
+    pub fn sub_error(&self, uid: uid::SubFilter) -> Option<&str> {
+        self.sub_errors.get(&uid).map(String::as_str)
+    }
+
     /// Stats accessor (stats are for the reference filters).
     pub fn ref_stats(&self) -> &AllFilterStats {
         &self.reference_stats
@@ -246,6 +261,17 @@ impl FilterInfo {
     pub fn update_ref_stats(&mut self, stats: AllFilterStats) {
         self.reference_stats = stats
     }
+    /// Updates the reference stats with the allocation counts of a points message.
+    ///
+    /// Returns `true` if the stats changed.
+    pub fn apply_ref_stats_delta(&mut self, delta: &FilterDelta) -> bool {
+        if delta.is_empty() {
+            false
+        } else {
+            self.reference_stats.apply(delta);
+            true
+        }
+    }
 
     /// Current filter accessor.
     pub fn current(&self) -> Current {
@@ -399,6 +425,29 @@ impl FilterInfo {
         Ok(())
     }
 
+    /// Changes the description of a filter.
+    ///
+    /// An empty description removes the description.
+    fn change_description(&mut self, uid: uid::Line, new_description: ChangeData) -> Res<()> {
+        let new_description = match new_description {
+            yew::html::ChangeData::Value(txt) => txt,
+            err @ yew::html::ChangeData::Select(_) | err @ yew::html::ChangeData::Files(_) => {
+                bail!("unexpected text field update {:?}", err)
+            }
+        };
+        let new_description = if new_description.trim().is_empty() {
+            None
+        } else {
+            Some(new_description)
+        };
+        let (_, spec) = self
+            .get_mut(uid)
+            .chain_err(|| "while updating a filter's description")?;
+        spec.set_description(new_description);
+
+        Ok(())
+    }
+
     /// Changes the color of a filter.
     fn change_color(&mut self, uid: uid::Line, new_color: ChangeData) -> Res<()> {
         let new_color = match new_color {
@@ -417,6 +466,33 @@ impl FilterInfo {
         Ok(())
     }
 
+    /// Activates an inactive filter, or deactivates an active one.
+    ///
+    /// Applies to both the current and the reference states, so that it does not count as an
+    /// edition. The server is notified right away if it knows the filter.
+    fn toggle_active(&mut self, uid: uid::Line) -> Res<()> {
+        let known = self.states.reference().get(uid).is_ok();
+        if !known {
+            // New filter, the server will learn about it when the user saves.
+            let (_, spec) = self
+                .get_mut(uid)
+                .chain_err(|| "while (de)activating a filter")?;
+            spec.set_active(!spec.is_active());
+            return Ok(());
+        }
+
+        let mut res = Ok(());
+        self.states.do_both(|states| match states.get_mut(uid) {
+            Ok((_, spec)) => spec.set_active(!spec.is_active()),
+            Err(e) => res = Err(e),
+        });
+        res.chain_err(|| "while (de)activating a filter")?;
+        self.link
+            .send_message(msg::to_server::FiltersMsg::toggle_active(uid));
+        self.link.send_message(msg::ChartsMsg::refresh_filters());
+        Ok(())
+    }
+
     /// Applies an update to a filter.
     fn filter_update(filter: &mut Filter, msg: FilterMsg) -> Res<ShouldRender> {
         match msg {
@@ -452,6 +528,7 @@ impl FilterInfo {
 
                 // Overwrite reference to be the current state.
                 self.states.overwrite_reference();
+                self.sub_errors.clear();
 
                 // Model must now refresh its filters.
                 self.link.send_message(msg::ChartsMsg::refresh_filters());
@@ -468,6 +545,13 @@ impl FilterInfo {
                 self.change_name(uid, new_name)?;
                 Ok(true)
             }
+            Msg::FilterSpec {
+                uid,
+                msg: SpecMsg::ChangeDescription(new_description),
+            } => {
+                self.change_description(uid, new_description)?;
+                Ok(true)
+            }
             Msg::FilterSpec {
                 uid,
                 msg: SpecMsg::ChangeColor(new_color),
@@ -475,7 +559,18 @@ impl FilterInfo {
                 self.change_color(uid, new_color)?;
                 Ok(true)
             }
+            Msg::FilterSpec {
+                uid,
+                msg: SpecMsg::ToggleActive,
+            } => {
+                self.toggle_active(uid)?;
+                Ok(true)
+            }
             Msg::Filter { uid, msg } => {
+                match &msg {
+                    FilterMsg::Sub(sub) => self.sub_errors.remove(&sub.uid()),
+                    FilterMsg::RmSub(sub_uid) => self.sub_errors.remove(sub_uid),
+                };
                 let (_index, filter) = self.get_filter_mut(uid)?;
                 Self::filter_update(filter, msg)
             }
@@ -544,6 +639,14 @@ impl FilterInfo {
                     catch_all,
                 });
                 Ok(true)
+            }
+            SubError { filter, sub, msg } => {
+                self.link.send_message(msg::Msg::err(format!(
+                    "illegal subfilter in filter #{}: {}",
+                    filter, msg
+                )));
+                self.sub_errors.insert(sub, msg);
+                Ok(true)
             } // UpdateSpecs(specs) => self.update_specs(specs),
         }
     }
@@ -607,18 +710,30 @@ impl Msg {
 pub enum SpecMsg {
     /// Changes the name of a filter.
     ChangeName(ChangeData),
+    /// Changes the description of a filter.
+    ChangeDescription(ChangeData),
     /// Changes the color of a filter.
     ChangeColor(ChangeData),
+    /// Activates an inactive filter, or deactivates an active one.
+    ToggleActive,
 }
 impl SpecMsg {
     /// Changes the name of a filter.
     pub fn change_name(uid: uid::Line, new_name: ChangeData) -> Msg {
         Msg::filter_spec(uid, Self::ChangeName(new_name)).into()
     }
+    /// Changes the description of a filter.
+    pub fn change_description(uid: uid::Line, new_description: ChangeData) -> Msg {
+        Msg::filter_spec(uid, Self::ChangeDescription(new_description)).into()
+    }
     /// Changes the color of a filter.
     pub fn change_color(uid: uid::Line, new_color: ChangeData) -> Msg {
         Msg::filter_spec(uid, Self::ChangeColor(new_color)).into()
     }
+    /// Activates an inactive filter, or deactivates an active one.
+    pub fn toggle_active(uid: uid::Line) -> Msg {
+        Msg::filter_spec(uid, Self::ToggleActive).into()
+    }
 }
 
 /// A message for a specific filter.
@@ -662,7 +777,9 @@ base::implement! {
         Display {
             |&self, fmt| match self {
                 Self::ChangeName(_) => write!(fmt, "change name"),
+                Self::ChangeDescription(_) => write!(fmt, "change description"),
                 Self::ChangeColor(_) => write!(fmt, "change color"),
+                Self::ToggleActive => write!(fmt, "toggle active"),
             }
         }
     }
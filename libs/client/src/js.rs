@@ -47,6 +47,13 @@ extern "C" {
     pub fn alert(s: &str);
 }
 
+#[wasm_bindgen]
+extern "C" {
+    /// Encodes a string so that it can be used in a URI.
+    #[wasm_bindgen(js_name = encodeURIComponent)]
+    fn encode_uri_component(s: &str) -> String;
+}
+
 /// Alias type for `wasm_bindgen`'s `JsValue`.
 pub type Value = JsValue;
 
@@ -67,6 +74,31 @@ pub fn get_element_by_id(id: &str) -> Res<web_sys::Element> {
     Ok(res)
 }
 
+/// Makes the browser download some text as a file.
+pub fn download_text(file_name: &str, content: &str) -> Res<()> {
+    use wasm_bindgen::JsCast;
+    let js_err = |js_val: Value| err::Error::from(format!("{:?}", js_val));
+
+    let document = web_sys::window()
+        .ok_or("could not retrieve window")?
+        .document()
+        .ok_or("could not retrieve document from window")?;
+    let link = document.create_element("a").map_err(js_err)?;
+    link.set_attribute(
+        "href",
+        &format!(
+            "data:text/plain;charset=utf-8,{}",
+            encode_uri_component(content)
+        ),
+    )
+    .map_err(js_err)?;
+    link.set_attribute("download", file_name).map_err(js_err)?;
+    link.dyn_into::<web_sys::HtmlElement>()
+        .map_err(|_| "could not create download link")?
+        .click();
+    Ok(())
+}
+
 /// Server info.
 pub mod server {
     prelude! {}
@@ -94,4 +126,32 @@ pub mod server {
             })
             .chain_err(|| "while retrieving server's address and port")
     }
+
+    /// True if the page was served over HTTPS, in which case the websocket must use `wss`.
+    pub fn is_secure() -> Res<bool> {
+        location()
+            .and_then(|loc| {
+                loc.protocol()
+                    .map_err(|js_val| err::Error::from(format!("{:?}", js_val)))
+            })
+            .map(|protocol| protocol == "https:")
+            .chain_err(|| "while retrieving server's protocol")
+    }
+
+    /// Access token passed as a `token` query parameter to the page, if any.
+    pub fn token() -> Res<Option<String>> {
+        location()
+            .and_then(|loc| {
+                loc.search()
+                    .map_err(|js_val| err::Error::from(format!("{:?}", js_val)))
+            })
+            .map(|search| {
+                search
+                    .trim_start_matches('?')
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("token="))
+                    .map(String::from)
+            })
+            .chain_err(|| "while retrieving access token")
+    }
 }
@@ -36,6 +36,8 @@ pub mod tabs;
 pub const DARK_GREY_BG: &'static str = "#313131";
 /// Light blue foreground.
 pub const LIGHT_BLUE_FG: &'static str = "#8dedff";
+/// Error foreground.
+pub const ERROR_FG: &'static str = "#ff6961";
 
 define_style! {
     SECTION_STYLE = {
@@ -89,9 +91,17 @@ pub fn render(model: &Model) -> Html {
             >
                 {
                     if let Some(load_info) = model.progress.as_ref() {
-                        progress::render(load_info)
+                        progress::render(model, load_info)
                     } else {
-                        model.charts.render(model)
+                        html! {
+                            <>
+                                { model.run_info.render() }
+                                { model.alloc_sites.render() }
+                                { model.search.render(model) }
+                                { model.explain.render(model) }
+                                { model.charts.render(model) }
+                            </>
+                        }
                     }
                 }
             </div>
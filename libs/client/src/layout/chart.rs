@@ -256,11 +256,15 @@ pub mod tiles {
             };
         }
 
-        let mut title = chart.title().to_string();
+        let mut title = chart.title();
         if !chart.settings().display_mode().is_normal() {
             title.push_str(" | ");
             title.push_str(chart.settings().display_mode().desc());
         }
+        if let Some(cursor) = chart.cursor_desc() {
+            title.push_str(" | ");
+            title.push_str(&cursor);
+        }
 
         html! {
             <center
@@ -268,6 +272,7 @@ pub mod tiles {
             >
                 <div
                     style = TITLE_CELL
+                    title = chart.estimate_desc().unwrap_or_default()
                 >
                     {title}
                 </div>
@@ -451,6 +456,10 @@ pub mod settings {
 
                 { title(model, chart) }
                 { options(model, chart) }
+                { y_axis(model, chart) }
+                { lines(model, chart) }
+                { buckets(model, chart) }
+                { export(model, chart) }
             </div>
         }
     }
@@ -462,9 +471,9 @@ pub mod settings {
             .height_px(LINE_HEIGHT_PX);
         title.push_single_value({
             let uid = chart.uid();
-            layout::input::string_input(model, chart.title(), move |new_title_res| {
+            layout::input::string_input(model, &chart.title(), move |new_title_res| {
                 new_title_res
-                    .map(|new_title| msg::ChartSettingsMsg::change_title(uid, new_title))
+                    .map(|new_title| msg::ChartMsg::set_title(uid, Some(new_title)))
                     .into()
             })
         });
@@ -512,6 +521,119 @@ pub mod settings {
             html!()
         }
     }
+
+    /// Renders the y-axis settings of a time chart.
+    pub fn y_axis(model: &Model, chart: &Chart) -> Html {
+        if chart.spec().x_axis().is_histogram() {
+            return html!();
+        }
+        let uid = chart.uid();
+        let y_log = chart.settings().y_log();
+
+        let mut row = layout::table::TableRow::new_menu(false, html! { "y-axis" })
+            .black_sep()
+            .height_px(LINE_HEIGHT_PX);
+        row.push_single_value(layout::input::checkbox(
+            y_log,
+            format!("chart_{}_y_log", uid),
+            "log-scale",
+            model
+                .link
+                .callback(move |_| msg::ChartSettingsMsg::set_y_log::<msg::ChartsMsg>(uid, !y_log)),
+        ));
+        row.render()
+    }
+
+    /// Renders the line settings of a time chart.
+    pub fn lines(model: &Model, chart: &Chart) -> Html {
+        if chart.spec().x_axis().is_histogram() {
+            return html!();
+        }
+        let uid = chart.uid();
+        let split_by_kind = chart.settings().split_by_kind();
+
+        let mut row = layout::table::TableRow::new_menu(false, html! { "lines" })
+            .black_sep()
+            .height_px(LINE_HEIGHT_PX);
+        row.push_single_value(layout::input::checkbox(
+            split_by_kind,
+            format!("chart_{}_split_by_kind", uid),
+            "split by allocation kind",
+            model.link.callback(move |_| {
+                msg::ChartSettingsMsg::set_split_by_kind::<msg::ChartsMsg>(uid, !split_by_kind)
+            }),
+        ));
+        row.render()
+    }
+
+    /// Renders the bucket settings of a histogram chart, or the window settings of a rate chart.
+    pub fn buckets(model: &Model, chart: &Chart) -> Html {
+        let is_rate = chart.spec().y_axis().is_rate();
+        if !chart.spec().x_axis().is_histogram() && !is_rate {
+            return html!();
+        }
+        let settings = chart.settings();
+        let uid = chart.uid();
+
+        let count_label = if is_rate { "windows" } else { "buckets" };
+        let mut count = layout::table::TableRow::new_menu(false, html! { count_label })
+            .black_sep()
+            .height_px(LINE_HEIGHT_PX);
+        count.push_single_value(layout::input::usize_input(
+            model,
+            settings.bucket_count(),
+            move |count_res| {
+                count_res
+                    .map(|count| {
+                        msg::ChartSettingsMsg::set_bucket_count::<msg::ChartsMsg>(uid, count)
+                    })
+                    .into()
+            },
+        ));
+
+        if is_rate {
+            return count.render();
+        }
+
+        let log_buckets = settings.log_buckets();
+        let mut scale = layout::table::TableRow::new_menu(false, html! { "scale" })
+            .black_sep()
+            .height_px(LINE_HEIGHT_PX);
+        scale.push_single_value(layout::input::checkbox(
+            log_buckets,
+            format!("chart_{}_log_buckets", uid),
+            "log-scale buckets",
+            model.link.callback(move |_| {
+                msg::ChartSettingsMsg::set_log_buckets::<msg::ChartsMsg>(uid, !log_buckets)
+            }),
+        ));
+
+        html! {
+            <>
+                { count.render() }
+                { scale.render() }
+            </>
+        }
+    }
+
+    /// Renders the export settings of a chart.
+    pub fn export(model: &Model, chart: &Chart) -> Html {
+        let uid = chart.uid();
+        let mut row = layout::table::TableRow::new_menu(false, html! { "export" })
+            .black_sep()
+            .height_px(LINE_HEIGHT_PX);
+        row.push_single_value(layout::button::text::render_default_button(
+            format!("chart_{}_export_csv", uid),
+            "CSV (full resolution)",
+            Some(
+                model
+                    .link
+                    .callback(move |_| msg::Msg::from(msg::to_server::DataMsg::export_csv(uid))),
+            ),
+            false,
+        ));
+        row.render()
+    }
 }
 
 /// Filter tabs (bottom) rendering.
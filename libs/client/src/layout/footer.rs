@@ -354,7 +354,9 @@ pub mod menu {
                     <br/>
 
                     {render_name_row(model, filter)}
+                    {render_description_row(model, filter)}
                     {render_color_row(model, filter)}
+                    {render_active_row(model, filter)}
                 </>
             }
         }
@@ -374,6 +376,21 @@ pub mod menu {
             table_row.render()
         }
 
+        /// Renders the filter's description row.
+        pub fn render_description_row(model: &Model, filter: &filter::FilterSpec) -> Html {
+            let mut table_row = layout::table::TableRow::new_menu(false, html! { "description" });
+            table_row.push_single_value({
+                let uid = filter.uid();
+                layout::input::text_input(
+                    filter.description().unwrap_or(""),
+                    model
+                        .link
+                        .callback(move |data| msg::filter::SpecMsg::change_description(uid, data)),
+                )
+            });
+            table_row.render()
+        }
+
         /// Renders the filter's color row.
         pub fn render_color_row(model: &Model, filter: &filter::FilterSpec) -> Html {
             let mut table_row = layout::table::TableRow::new_menu(false, html! { "color" });
@@ -388,12 +405,32 @@ pub mod menu {
             });
             table_row.render()
         }
+
+        /// Renders the filter's active row.
+        pub fn render_active_row(model: &Model, filter: &filter::FilterSpec) -> Html {
+            let mut table_row = layout::table::TableRow::new_menu(false, html! { "charts" });
+            table_row.push_single_value({
+                let uid = filter.uid();
+                layout::input::checkbox(
+                    filter.is_active(),
+                    format!("filter_{}_active", uid),
+                    "show in charts",
+                    model
+                        .link
+                        .callback(move |_| msg::filter::SpecMsg::toggle_active(uid)),
+                )
+            });
+            table_row.render()
+        }
     }
 
     /// Sub-filter rendering.
     pub mod subfilters {
         use super::*;
-        use charts::filter::{sub::RawSubFilter, LifetimeFilter, SizeFilter, SubFilter};
+        use charts::filter::{
+            sub::RawSubFilter, AliveAtFilter, KindFilter, LifetimeFilter, SamplesFilter,
+            SizeFilter, SubFilter,
+        };
 
         /// Renders the sub-filters of a filter.
         pub fn render(model: &Model, filter: &filter::Filter) -> Html {
@@ -452,6 +489,16 @@ pub mod menu {
                         }))
                     })
                 }
+                RawSubFilter::Samples(sub) => {
+                    size::render_samples(&mut table_row, model, sub, move |samples_res| {
+                        msg_of_res(samples_res.map(|samples| {
+                            msg::filter::FilterMsg::update_sub(
+                                uid,
+                                filter::SubFilter::new(sub_uid, RawSubFilter::Samples(samples)),
+                            )
+                        }))
+                    })
+                }
                 RawSubFilter::Lifetime(sub) => {
                     lifetime::render(&mut table_row, model, sub, move |lifetime_sub_filter_res| {
                         msg_of_res(lifetime_sub_filter_res.map(|lifetime| {
@@ -462,6 +509,22 @@ pub mod menu {
                         }))
                     })
                 }
+                RawSubFilter::AliveAt(sub) => {
+                    alive_at::render(&mut table_row, model, sub, move |alive_at_res| {
+                        msg_of_res(alive_at_res.map(|alive_at| {
+                            msg::filter::FilterMsg::update_sub(
+                                uid,
+                                filter::SubFilter::new(sub_uid, RawSubFilter::AliveAt(alive_at)),
+                            )
+                        }))
+                    })
+                }
+                RawSubFilter::Kind(sub) => kind::render(&mut table_row, model, sub, move |kind| {
+                    msg::filter::FilterMsg::update_sub(
+                        uid,
+                        filter::SubFilter::new(sub_uid, RawSubFilter::Kind(kind)),
+                    )
+                }),
                 RawSubFilter::Label(sub) => {
                     label::render(&mut table_row, model, sub, move |label_sub_filter_res| {
                         msg_of_res(label_sub_filter_res.map(|label| {
@@ -484,6 +547,23 @@ pub mod menu {
                 }
             };
 
+            if let Some(error) = model.filters.sub_error(sub_uid) {
+                define_style! {
+                    ERROR_STYLE = {
+                        fg({layout::ERROR_FG}),
+                        bold,
+                    };
+                }
+                table_row.push_tiny_value(html! {
+                    <div
+                        style = ERROR_STYLE
+                        title = error
+                    >
+                        {"⚠"}
+                    </div>
+                })
+            }
+
             table_row.render()
         }
 
@@ -539,7 +619,7 @@ pub mod menu {
             }
         }
 
-        /// Size sub-filter rendering.
+        /// Size and number of samples sub-filter rendering.
         pub mod size {
             use super::*;
             use charts::filter::ord::Pred;
@@ -552,6 +632,31 @@ pub mod menu {
                 msg: Update,
             ) where
                 Update: Fn(Res<SizeFilter>) -> Msg + 'static + Clone,
+            {
+                render_with_unit(table_row, model, sub, "byte(s)", msg)
+            }
+
+            /// Renders a number of samples sub-filter.
+            pub fn render_samples<Update>(
+                table_row: &mut layout::table::TableRow,
+                model: &Model,
+                sub: &SamplesFilter,
+                msg: Update,
+            ) where
+                Update: Fn(Res<SamplesFilter>) -> Msg + 'static + Clone,
+            {
+                render_with_unit(table_row, model, sub, "sample(s)", msg)
+            }
+
+            /// Renders a `u32` ordered sub-filter, `unit` is displayed after the value.
+            fn render_with_unit<Update>(
+                table_row: &mut layout::table::TableRow,
+                model: &Model,
+                sub: &SizeFilter,
+                unit: &'static str,
+                msg: Update,
+            ) where
+                Update: Fn(Res<SizeFilter>) -> Msg + 'static + Clone,
             {
                 let selector = {
                     let selected = Some(sub.cmp_kind());
@@ -580,16 +685,16 @@ pub mod menu {
                             move |usize_res| msg(usize_res.map(|val| SizeFilter::Cmp { cmp, val })),
                         ));
                         table_row.push_value(html! {
-                            "machine word(s)"
+                            {unit}
                         })
                     }
                     SizeFilter::In { lb, ub } => {
                         let msg_fn = msg.clone();
                         let lb_html = layout::input::u32_input(model, lb, move |usize_res| {
-                            msg_fn(usize_res.map(|lb| SizeFilter::In { lb, ub }))
+                            msg_fn(usize_res.and_then(|lb| SizeFilter::between(lb, ub)))
                         });
                         let ub_html = layout::input::u32_input(model, ub, move |usize_res| {
-                            msg(usize_res.map(|ub| SizeFilter::In { lb, ub }))
+                            msg(usize_res.and_then(|ub| SizeFilter::between(lb, ub)))
                         });
                         table_row.push_sep(html! {"["});
                         table_row.push_value(lb_html);
@@ -648,10 +753,10 @@ pub mod menu {
                     LifetimeFilter::In { lb, ub } => {
                         let msg_fn = msg.clone();
                         let lb_html = layout::input::lifetime_input(model, lb, move |usize_res| {
-                            msg_fn(usize_res.map(|lb| LifetimeFilter::In { lb, ub }))
+                            msg_fn(usize_res.and_then(|lb| LifetimeFilter::between(lb, ub)))
                         });
                         let ub_html = layout::input::lifetime_input(model, ub, move |usize_res| {
-                            msg(usize_res.map(|ub| LifetimeFilter::In { lb, ub }))
+                            msg(usize_res.and_then(|ub| LifetimeFilter::between(lb, ub)))
                         });
                         table_row.push_sep(html! {"["});
                         table_row.push_value(lb_html);
@@ -663,11 +768,59 @@ pub mod menu {
             }
         }
 
+        /// Alive-at sub-filter rendering.
+        pub mod alive_at {
+            use super::*;
+
+            /// Renders an alive-at sub-filter.
+            pub fn render(
+                table_row: &mut layout::table::TableRow,
+                model: &Model,
+                sub: &AliveAtFilter,
+                msg: impl Fn(Res<AliveAtFilter>) -> Msg + 'static,
+            ) {
+                table_row.push_sep(html! {"@"});
+                table_row.push_value(layout::input::since_start_input(
+                    model,
+                    sub.at(),
+                    move |time_res| msg(time_res.map(AliveAtFilter::new)),
+                ));
+                table_row.push_value(html! {
+                    "second(s)"
+                })
+            }
+        }
+
+        /// Allocation kind sub-filter rendering.
+        pub mod kind {
+            use super::*;
+            use alloc::AllocKind;
+
+            /// Renders an allocation kind sub-filter.
+            pub fn render(
+                table_row: &mut layout::table::TableRow,
+                model: &Model,
+                sub: &KindFilter,
+                msg: impl Fn(KindFilter) -> Msg + 'static,
+            ) {
+                table_row.push_sep(html! {"="});
+                table_row.push_selector(html! {
+                    <Select<AllocKind>
+                        selected = Some(sub.kind())
+                        options = AllocKind::all().to_vec()
+                        on_change = model.link.callback(
+                            move |kind| msg(KindFilter::new(kind))
+                        )
+                    />
+                })
+            }
+        }
+
         /// Label sub-filter rendering.
         pub mod label {
             use super::*;
             use charts::filter::{
-                label::{LabelPred, LabelSpec},
+                label::{self, LabelPred, LabelSpec},
                 LabelFilter,
             };
 
@@ -696,6 +849,7 @@ pub mod menu {
                 let selector = {
                     let selected = Some(sub.pred().clone());
                     let specs = sub.specs().clone();
+                    let namespace = sub.namespace().map(String::from);
                     let msg = msg.clone();
                     html! {
                         <Select<LabelPred>
@@ -704,6 +858,7 @@ pub mod menu {
                             on_change = model.link.callback(
                                 move |new_pred| msg(Ok(
                                     LabelFilter::new(new_pred, specs.clone())
+                                        .with_namespace(namespace.clone())
                                 ))
                             )
                         />
@@ -711,6 +866,16 @@ pub mod menu {
                 };
                 table_row.push_selector(selector);
 
+                table_row.push_tiny_value(html! { "in namespace" });
+                let namespace = label::namespace_to_string(sub.namespace());
+                table_row.push_tiny_value(layout::input::string_input(model, &namespace, {
+                    let msg = msg.clone();
+                    let sub = sub.clone();
+                    move |str_res| {
+                        msg(str_res.map(|s| sub.clone().with_namespace(label::parse_namespace(&s))))
+                    }
+                }));
+
                 for (idx, spec) in sub.specs().iter().enumerate() {
                     push_add_button!(idx);
 
@@ -990,7 +1155,9 @@ pub mod tabs {
 
                     let props = TabProps::new_footer(filter.color().to_string())
                         .set_active(is_active(filter))
-                        .set_edited(edited);
+                        .set_edited(edited)
+                        .set_dimmed(!filter.is_active())
+                        .set_tooltip(filter.description().map(String::from));
                     let props = if let Some((index, f_uid)) = index_uid_opt {
                         props.with_first_last_uid(|| {
                             (
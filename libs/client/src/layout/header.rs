@@ -153,6 +153,90 @@ impl Header {
             },
             "expands the settings menu",
         );
+        let flamegraph = layout::button::text::render_default_button(
+            "flamegraph_download",
+            "flamegraph",
+            Some(
+                self.link
+                    .callback(move |_| msg::Msg::from(msg::to_server::DataMsg::flamegraph(None))),
+            ),
+            false,
+        );
+        let alloc_sites = layout::button::text::render_default_button(
+            "alloc_sites_request",
+            "top sites",
+            Some(
+                self.link
+                    .callback(move |_| alloc_sites::AllocSites::request()),
+            ),
+            false,
+        );
+        let run_info = layout::button::text::render_default_button(
+            "run_info_request",
+            "run info",
+            Some(self.link.callback(move |_| run_info::RunInfo::request())),
+            false,
+        );
+
+        let leaks = layout::button::text::render_default_button(
+            "leaks_request",
+            "show leaks",
+            Some(
+                self.link
+                    .callback(move |_| msg::to_server::FiltersMsg::request_leaks()),
+            ),
+            false,
+        );
+
+        let reparse = layout::button::text::render_default_button(
+            "reparse_request",
+            "reparse",
+            Some(
+                self.link
+                    .callback(move |_| msg::Msg::from(msg::to_server::DataMsg::reparse())),
+            ),
+            false,
+        );
+
+        let pause = {
+            let (label, msg) = if model.ingestion_paused {
+                (
+                    "resume",
+                    msg::to_server::DataMsg::resume_ingestion as fn() -> _,
+                )
+            } else {
+                (
+                    "pause",
+                    msg::to_server::DataMsg::pause_ingestion as fn() -> _,
+                )
+            };
+            layout::button::text::render_default_button(
+                "ingestion_toggle",
+                label,
+                Some(self.link.callback(move |_| msg::Msg::from(msg()))),
+                false,
+            )
+        };
+
+        let explain = layout::button::text::render_default_button(
+            "explain_open",
+            "explain",
+            Some(
+                self.link
+                    .callback(move |_| msg::Msg::from(explain::Msg::Open)),
+            ),
+            false,
+        );
+
+        let search = layout::button::text::render_default_button(
+            "search_open",
+            "search",
+            Some(
+                self.link
+                    .callback(move |_| msg::Msg::from(search::Msg::Open)),
+            ),
+            false,
+        );
 
         html! {
             <>
@@ -166,6 +250,46 @@ impl Header {
                 >
                     {collapse}
                 </div>
+                <div
+                    style = LEFT
+                >
+                    {flamegraph}
+                </div>
+                <div
+                    style = LEFT
+                >
+                    {alloc_sites}
+                </div>
+                <div
+                    style = LEFT
+                >
+                    {run_info}
+                </div>
+                <div
+                    style = LEFT
+                >
+                    {leaks}
+                </div>
+                <div
+                    style = LEFT
+                >
+                    {explain}
+                </div>
+                <div
+                    style = LEFT
+                >
+                    {search}
+                </div>
+                <div
+                    style = LEFT
+                >
+                    {reparse}
+                </div>
+                <div
+                    style = LEFT
+                >
+                    {pause}
+                </div>
                 <div
                     style = RIGHT
                 >
@@ -270,6 +394,17 @@ impl Header {
 
         if let Some(stats) = model.alloc_stats.as_ref() {
             let start = stats.start_date.date().with_timezone(&LOCAL);
+            let skipped = if stats.skipped_files > 0 {
+                html! {
+                    <>
+                        {", "}
+                        {emph(stats.skipped_files)}
+                        {" malformed diff file(s) skipped"}
+                    </>
+                }
+            } else {
+                html! {}
+            };
             html! {
                 <p
                     style = TXT_STYLE
@@ -284,6 +419,7 @@ impl Header {
                     {emph(num_fmt::str_do(stats.alloc_count as f64, identity))}
                     {" allocations, "}
                     {emph(num_fmt::bin_str_do(stats.total_size as f64, |mut s| {s.push('B') ; s}))}
+                    {skipped}
                     {" | "}
                     {code(stats.dump_dir.display())}
                 </p>
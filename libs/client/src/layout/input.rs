@@ -169,6 +169,23 @@ pub fn since_start_opt_input(
     )
 }
 
+/// Generates a text-input field expecting a time-like (SinceStart) value.
+pub fn since_start_input(
+    model: &Model,
+    value: time::SinceStart,
+    msg: impl Fn(Res<time::SinceStart>) -> Msg + 'static,
+) -> Html {
+    text_input(
+        &value.to_string(),
+        model.link.callback(move |data| {
+            let time = parse_text_data(data).and_then(|txt| {
+                time::SinceStart::parse_secs(&txt).chain_err(|| "while parsing time value")
+            });
+            msg(time)
+        }),
+    )
+}
+
 fn parse_u32_data(data: ChangeData) -> Res<u32> {
     use alloc::parser::Parseable;
     parse_text_data(data).and_then(|txt| u32::parse(txt).map_err(|e| e.into()))
@@ -25,8 +25,9 @@ prelude! {}
 
 /// Renders the progress bar.
 ///
-/// This is used when the server is still parsing the dumps.
-pub fn render(info: &LoadInfo) -> Html {
+/// This is used when the server is still parsing the dumps. Reloads can be cancelled, the initial
+/// load cannot as the server only listens to the client once it is done.
+pub fn render(model: &Model, info: &LoadInfo) -> Html {
     define_style! {
         BIG = {
             font_size(180%),
@@ -38,6 +39,21 @@ pub fn render(info: &LoadInfo) -> Html {
 
     let percent = info.percent();
 
+    let cancel = if model.session.is_some() {
+        layout::button::text::render_default_button(
+            "cancel_loading",
+            "cancel",
+            Some(
+                model
+                    .link
+                    .callback(move |_| msg::Msg::from(msg::to_server::DataMsg::cancel_loading())),
+            ),
+            false,
+        )
+    } else {
+        html! {}
+    };
+
     html! {
         <center
             style = BIG
@@ -61,6 +77,8 @@ pub fn render(info: &LoadInfo) -> Html {
             >
                 { format!("{}%", percent) }
             </progress>
+            <br/>
+            {cancel}
         </center>
     }
 }
@@ -50,6 +50,8 @@ pub struct TabProps {
     top: bool,
     /// Makes the whole tab round (*i.e.*, not oriented).
     round: bool,
+    /// Tooltip, shown when hovering over the tab.
+    tooltip: Option<String>,
 }
 impl TabProps {
     /// Creates a tab with some color.
@@ -62,6 +64,7 @@ impl TabProps {
             rev: false,
             top: false,
             round: false,
+            tooltip: None,
         }
     }
 
@@ -75,6 +78,7 @@ impl TabProps {
             rev: false,
             top: true,
             round: false,
+            tooltip: None,
         }
     }
     /// Creates a new gray footer tab.
@@ -88,6 +92,12 @@ impl TabProps {
         self
     }
 
+    /// Sets the tooltip of the tab.
+    pub fn set_tooltip(mut self, tooltip: Option<String>) -> Self {
+        self.tooltip = tooltip;
+        self
+    }
+
     /// Turns itself into button box properties
     pub fn to_box_props(&self) -> layout::button::BoxProps {
         let active = self.active.to_bool();
@@ -319,6 +329,7 @@ impl Tabs {
             <div
                 id = "filter_tab_cell"
                 style = OUTTER_CELL_STYLE
+                title = props.tooltip.clone().unwrap_or_default()
             >
                 <div
                     id = "filter_tab"
@@ -34,6 +34,10 @@ pub mod layout;
 pub mod model;
 pub mod settings;
 
+pub mod alloc_sites;
+pub mod run_info;
+pub mod explain;
+pub mod search;
 pub mod chart;
 pub mod cst;
 pub mod filter;
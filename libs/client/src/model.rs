@@ -27,6 +27,14 @@ pub struct Model {
     pub link: Link,
     /// Socket task for receiving/sending messages from/to the server.
     pub socket_task: Option<WebSocketTask>,
+    /// Token of the session, sent by the server.
+    ///
+    /// Used to resume the session when reconnecting after losing the connection to the server.
+    pub session: Option<msg::SessionToken>,
+    /// Number of reconnection attempts since the connection was lost.
+    pub reconnect_attempts: usize,
+    /// Pending reconnection attempt, if any.
+    pub reconnect_task: Option<TimeoutTask>,
     /// Errors.
     pub errors: Vec<err::Error>,
     /// Collection of charts.
@@ -44,9 +52,19 @@ pub struct Model {
     pub progress: Option<LoadInfo>,
     /// Allocation statistics, for the header.
     pub alloc_stats: Option<AllocStats>,
+    /// True if the server does not register new diffs, see the header's pause button.
+    pub ingestion_paused: bool,
 
     /// Global chart settings.
     pub settings: settings::Settings,
+    /// Top allocation sites report.
+    pub alloc_sites: alloc_sites::AllocSites,
+    /// Run information panel.
+    pub run_info: run_info::RunInfo,
+    /// Allocation explanation report.
+    pub explain: explain::Explain,
+    /// Allocation search.
+    pub search: search::Search,
 }
 
 impl Model {
@@ -78,8 +96,17 @@ impl Model {
     fn activate_ws(link: &mut Link) -> Res<WebSocketTask> {
         log::info!("fetching server's websocket info");
         let (addr, port) = js::server::address()?;
-        let addr = format!("ws://{}:{}", addr, port + 1);
+        let protocol = if js::server::is_secure()? {
+            "wss"
+        } else {
+            "ws"
+        };
+        let mut addr = format!("{}://{}:{}", protocol, addr, port + 1);
         log::info!("websocket: {:?}", addr);
+        if let Some(token) = js::server::token()? {
+            addr.push_str("/?token=");
+            addr.push_str(&token)
+        }
         let callback = link.callback(|msg| Msg::FromServer(msg));
         let notification = link.callback(|status| Msg::ConnectionStatus(status));
         let task = WebSocketService::connect(&addr, callback, notification)?;
@@ -88,8 +115,42 @@ impl Model {
     }
 }
 
+/// Maximum number of reconnection attempts after losing the connection to the server.
+const MAX_RECONNECT_ATTEMPTS: usize = 10;
+/// Delay between two reconnection attempts, in milliseconds.
+const RECONNECT_DELAY_MS: u64 = 2_000;
+
 /// # Communication with the server
 impl Model {
+    /// Starts or resumes the session, called when the connection with the server is established.
+    fn start_session(&mut self) {
+        self.reconnect_attempts = 0;
+        let msg = if let Some(token) = self.session {
+            // The server is going to send all the charts again.
+            self.charts.clear();
+            msg::to_server::Msg::Resume(token)
+        } else {
+            msg::to_server::Msg::Start
+        };
+        self.server_send(msg)
+    }
+
+    /// Schedules a reconnection attempt, if the session can be resumed.
+    ///
+    /// Returns `false` if no reconnection attempt was scheduled.
+    fn schedule_reconnect(&mut self) -> bool {
+        if self.session.is_none() || self.reconnect_attempts >= MAX_RECONNECT_ATTEMPTS {
+            return false;
+        }
+        self.reconnect_attempts += 1;
+        let callback = self.link.callback(|()| Msg::Reconnect);
+        self.reconnect_task = Some(TimeoutService::spawn(
+            std::time::Duration::from_millis(RECONNECT_DELAY_MS),
+            callback,
+        ));
+        true
+    }
+
     /// Sends a message to the server.
     pub fn server_send(&mut self, msg: msg::to_server::Msg) {
         if let Some(socket_task) = self.socket_task.as_mut() {
@@ -111,8 +172,19 @@ impl Model {
                 Ok(false)
             }
             Msg::Charts(msg) => {
-                self.charts
-                    .server_update(self.filters.reference(), self.filters.ref_stats(), msg)
+                if let ChartsMsg::Cursor { time, .. } = &msg {
+                    self.settings.set_cursor(*time)
+                }
+                let stats_changed = msg
+                    .filter_delta()
+                    .map(|delta| self.filters.apply_ref_stats_delta(delta))
+                    .unwrap_or(false);
+                let redraw = self.charts.server_update(
+                    self.filters.reference(),
+                    self.filters.ref_stats(),
+                    msg,
+                )?;
+                Ok(redraw || stats_changed)
             }
             Msg::Filters(msg) => self.filters.server_update(msg),
 
@@ -123,6 +195,7 @@ impl Model {
                     .map(|s| s != &stats)
                     .unwrap_or(true);
                 self.settings.set_run_duration(stats.duration);
+                self.charts.set_start_date(stats.start_date);
                 self.alloc_stats = Some(stats);
                 Ok(redraw)
             }
@@ -142,6 +215,33 @@ impl Model {
                 self.progress = None;
                 Ok(redraw)
             }
+
+            Msg::Data(DataMsg::Flamegraph(folded)) => {
+                js::download_text("memthol.folded", &folded)?;
+                Ok(false)
+            }
+            Msg::Data(DataMsg::TopAllocSites(sites)) => Ok(self.alloc_sites.set(sites)),
+            Msg::Data(DataMsg::RunInfo(info)) => Ok(self.run_info.set(info)),
+            Msg::Data(DataMsg::Errors { errors, dropped }) => {
+                Ok(self.run_info.set_errors(errors, dropped))
+            }
+            Msg::Data(DataMsg::Explain(explanation)) => Ok(self.explain.set(explanation)),
+            Msg::Data(DataMsg::SearchResults { query, allocs }) => {
+                Ok(self.search.set(query, allocs))
+            }
+            Msg::Data(DataMsg::Csv { chart, csv }) => {
+                js::download_text(&format!("memthol_chart_{}.csv", chart), &csv)?;
+                Ok(false)
+            }
+            Msg::Data(DataMsg::IngestionPaused(paused)) => {
+                let redraw = self.ingestion_paused != paused;
+                self.ingestion_paused = paused;
+                Ok(redraw)
+            }
+            Msg::Session(token) => {
+                self.session = Some(token);
+                Ok(false)
+            }
         }
     }
 }
@@ -171,9 +271,16 @@ impl Component for Model {
         let filters = filter::FilterInfo::new(link.clone());
         let settings = settings::Settings::new(link.clone());
         let header = header::Header::new(link.clone());
+        let alloc_sites = alloc_sites::AllocSites::new(link.clone());
+        let run_info = run_info::RunInfo::new(link.clone());
+        let explain = explain::Explain::new(link.clone());
+        let search = search::Search::new(link.clone());
         Model {
             link,
             socket_task,
+            session: None,
+            reconnect_attempts: 0,
+            reconnect_task: None,
             errors,
             charts,
 
@@ -184,7 +291,12 @@ impl Component for Model {
 
             progress: Some(LoadInfo::unknown()),
             alloc_stats: None,
+            ingestion_paused: false,
             settings,
+            alloc_sites,
+            run_info,
+            explain,
+            search,
         }
     }
 
@@ -206,9 +318,36 @@ impl Component for Model {
             Msg::ConnectionStatus(status) => {
                 use WebSocketStatus::*;
                 match status {
-                    Opened => log::debug!("successfully established connection with the server"),
-                    Closed => log::warn!("connection with the server was closed"),
-                    Error => alert!("failed to connect with the server"),
+                    Opened => {
+                        log::debug!("successfully established connection with the server");
+                        self.start_session()
+                    }
+                    Closed => {
+                        log::warn!("connection with the server was closed");
+                        self.schedule_reconnect();
+                    }
+                    Error => {
+                        if !self.schedule_reconnect() {
+                            alert!("failed to connect with the server")
+                        }
+                    }
+                }
+                false
+            }
+            Msg::Reconnect => {
+                log::info!(
+                    "trying to reconnect to the server ({}/{})",
+                    self.reconnect_attempts,
+                    MAX_RECONNECT_ATTEMPTS
+                );
+                self.reconnect_task = None;
+                match Self::activate_ws(&mut self.link) {
+                    Ok(task) => self.socket_task = Some(task),
+                    Err(e) => {
+                        if !self.schedule_reconnect() {
+                            self.link.send_message(e)
+                        }
+                    }
                 }
                 false
             }
@@ -226,6 +365,18 @@ impl Component for Model {
             Msg::Settings(msg) => unwrap_or_send_err!(
                 self.settings.update(msg) => self default false
             ),
+            Msg::AllocSites(msg) => unwrap_or_send_err!(
+                self.alloc_sites.update(msg) => self default false
+            ),
+            Msg::RunInfo(msg) => unwrap_or_send_err!(
+                self.run_info.update(msg) => self default false
+            ),
+            Msg::Explain(msg) => unwrap_or_send_err!(
+                self.explain.update(msg) => self default false
+            ),
+            Msg::Search(msg) => unwrap_or_send_err!(
+                self.search.update(msg) => self default false
+            ),
 
             // Basic communication messages.
             Msg::Msg(s) => {
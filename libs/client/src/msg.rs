@@ -21,7 +21,7 @@
 
 prelude! {}
 
-pub use charts::msg::{to_client as from_server, to_server, ChartSettingsMsg};
+pub use charts::msg::{to_client as from_server, to_server, ChartSettingsMsg, SessionToken};
 
 /// Filter messages.
 pub mod filter {
@@ -31,6 +31,22 @@ pub mod filter {
 pub mod settings {
     pub use crate::settings::Msg;
 }
+/// Allocation sites messages.
+pub mod alloc_sites {
+    pub use crate::alloc_sites::Msg;
+}
+/// Run information messages.
+pub mod run_info {
+    pub use crate::run_info::Msg;
+}
+/// Allocation explanation messages.
+pub mod explain {
+    pub use crate::explain::Msg;
+}
+/// Allocation search messages.
+pub mod search {
+    pub use crate::search::Msg;
+}
 
 /// Internal model messages.
 ///
@@ -44,6 +60,8 @@ pub enum Msg {
     ToServer(to_server::Msg),
     /// Status notification for the connection with the server.
     ConnectionStatus(WebSocketStatus),
+    /// Tries to reconnect to the server.
+    Reconnect,
 
     /// Chart operations.
     Charts(ChartsMsg),
@@ -53,6 +71,14 @@ pub enum Msg {
     Filter(filter::Msg),
     /// Settings operations.
     Settings(settings::Msg),
+    /// Allocation sites report operations.
+    AllocSites(alloc_sites::Msg),
+    /// Run information panel operations.
+    RunInfo(run_info::Msg),
+    /// Allocation explanation report operations.
+    Explain(explain::Msg),
+    /// Allocation search operations.
+    Search(search::Msg),
 
     /// A message to print in the JS console.
     Msg(String),
@@ -105,6 +131,9 @@ pub enum ChartsMsg {
     /// Forces to refresh the filters.
     RefreshFilters,
 
+    /// Sets the format of the time values of all the charts.
+    SetTimeFormat(chart::settings::TimeFormat),
+
     /// Sets the x-axis in the new chart element.
     NewChartSetX(chart::axis::XAxis),
     /// Sets the y-axis in the new chart element.
@@ -129,6 +158,11 @@ impl ChartsMsg {
         Self::RefreshFilters.into()
     }
 
+    /// Sets the format of the time values of all the charts.
+    pub fn set_time_format(time_format: chart::settings::TimeFormat) -> Msg {
+        Self::SetTimeFormat(time_format).into()
+    }
+
     /// Sets the x-axis in the new chart element.
     pub fn new_chart_set_x(x: chart::axis::XAxis) -> Msg {
         Self::NewChartSetX(x).into()
@@ -148,6 +182,8 @@ pub enum ChartMsg {
     FilterToggleVisible(uid::Line),
     /// Updates the chart's settings.
     SettingsUpdate(ChartSettingsMsg),
+    /// Sets the chart's custom title, `None` falls back on its description.
+    SetTitle(Option<String>),
 }
 
 impl ChartMsg {
@@ -159,6 +195,10 @@ impl ChartMsg {
     pub fn filter_toggle_visible(uid: uid::Chart, line: uid::Line) -> ChartsMsg {
         (uid, Self::FilterToggleVisible(line)).into()
     }
+    /// Sets a chart's custom title.
+    pub fn set_title(uid: uid::Chart, title: Option<String>) -> ChartsMsg {
+        (uid, Self::SetTitle(title)).into()
+    }
 }
 
 /// Footer operation.
@@ -187,10 +227,15 @@ base::implement! {
                 Self::FromServer(_) => write!(fmt, "from the server"),
                 Self::ToServer(_) => write!(fmt, "for the server"),
                 Self::ConnectionStatus(_) => write!(fmt, "connection status"),
+                Self::Reconnect => write!(fmt, "reconnect"),
                 Self::Charts(charts_msg) => write!(fmt, "charts, {}", charts_msg),
                 Self::Footer(footer_msg) => write!(fmt, "footer, {}", footer_msg),
                 Self::Filter(filter_msg) => write!(fmt, "filter, {}", filter_msg),
                 Self::Settings(settings_msg) => write!(fmt, "settings, {}", settings_msg),
+                Self::AllocSites(sites_msg) => write!(fmt, "alloc sites, {}", sites_msg),
+                Self::RunInfo(info_msg) => write!(fmt, "run info, {}", info_msg),
+                Self::Explain(explain_msg) => write!(fmt, "explain, {}", explain_msg),
+                Self::Search(search_msg) => write!(fmt, "search, {}", search_msg),
                 Self::Msg(_) => write!(fmt, "info"),
                 Self::Warn(_) => write!(fmt, "warning"),
                 Self::Err(_) => write!(fmt, "error"),
@@ -209,6 +254,10 @@ base::implement! {
             },
             from FooterMsg => |msg| Self::Footer(msg),
             from settings::Msg => |msg| Self::Settings(msg),
+            from alloc_sites::Msg => |msg| Self::AllocSites(msg),
+            from run_info::Msg => |msg| Self::RunInfo(msg),
+            from explain::Msg => |msg| Self::Explain(msg),
+            from search::Msg => |msg| Self::Search(msg),
         }
     }
 
@@ -218,6 +267,7 @@ base::implement! {
                 Self::Move { uid, up } => write!(fmt, "move {}/{}", uid, up),
                 Self::Destroy(c_uid) => write!(fmt, "destroy {}", c_uid),
                 Self::RefreshFilters => write!(fmt, "refresh filters"),
+                Self::SetTimeFormat(time_format) => write!(fmt, "set time format {}", time_format),
                 Self::NewChartSetX(_) => write!(fmt, "new-chart-set-x"),
                 Self::NewChartSetY(_) => write!(fmt, "new-chart-set-y"),
                 Self::ChartMsg { uid, msg } => write!(fmt, "chart[{}]: {}", uid, msg),
@@ -239,6 +289,7 @@ base::implement! {
                 Self::SettingsToggleVisible => write!(fmt, "settings toggle visible"),
                 Self::FilterToggleVisible(l_uid) => write!(fmt, "filter toggle visible {}", l_uid),
                 Self::SettingsUpdate(msg) => write!(fmt, "{}", msg),
+                Self::SetTitle(_) => write!(fmt, "set title"),
             }
         }
 
@@ -21,7 +21,10 @@
 pub use yew::{
     html,
     html::ChangeData,
-    services::websocket::{WebSocketService, WebSocketStatus, WebSocketTask},
+    services::{
+        timeout::{TimeoutService, TimeoutTask},
+        websocket::{WebSocketService, WebSocketStatus, WebSocketTask},
+    },
     Callback, Component, Renderable, ShouldRender,
 };
 pub use yew_components::Select;
@@ -78,12 +81,13 @@ pub mod point {
 pub use point::Point;
 
 pub use crate::{
+    alloc_sites,
     chart::{self, Chart, Charts},
-    cst, filter, js,
+    cst, explain, filter, js,
     layout::{self, footer, header},
     model::Model,
     msg::{self, Msg},
-    settings,
+    run_info, search, settings,
 };
 
 /// Component link to the model, can send messages to the model.
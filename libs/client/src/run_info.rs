@@ -0,0 +1,224 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Run information panel.
+
+prelude! {}
+
+/// Run information messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// Collapses the panel if it is expanded, expands it otherwise.
+    Toggle,
+    /// Closes the panel.
+    Close,
+}
+
+base::implement! {
+    impl Msg {
+        Display {
+            |&self, fmt| match self {
+                Self::Toggle => write!(fmt, "toggle"),
+                Self::Close => write!(fmt, "close"),
+            }
+        }
+    }
+}
+
+/// Run information panel, the information is sent by the server.
+pub struct RunInfo {
+    /// Model link.
+    link: Link,
+    /// Run information, if any.
+    ///
+    /// The inner option is `None` if the server has no run loaded yet.
+    info: Option<Option<charts::prelude::RunInfo>>,
+    /// Recent errors of the run, oldest first.
+    errors: Vec<String>,
+    /// Number of older errors dropped by the server.
+    dropped: usize,
+    /// True if the panel is collapsed.
+    collapsed: bool,
+}
+
+impl RunInfo {
+    /// Constructor.
+    pub fn new(link: Link) -> Self {
+        Self {
+            link,
+            info: None,
+            errors: vec![],
+            dropped: 0,
+            collapsed: false,
+        }
+    }
+
+    /// Message requesting the run information to the server.
+    pub fn request() -> msg::Msg {
+        msg::to_server::DataMsg::run_info().into()
+    }
+
+    /// Sets the run information, as sent by the server.
+    pub fn set(&mut self, info: Option<charts::prelude::RunInfo>) -> ShouldRender {
+        self.info = Some(info);
+        self.collapsed = false;
+        true
+    }
+
+    /// Sets the recent errors of the run, as sent by the server along with the run information.
+    pub fn set_errors(&mut self, errors: Vec<String>, dropped: usize) -> ShouldRender {
+        self.errors = errors;
+        self.dropped = dropped;
+        self.info.is_some()
+    }
+
+    /// Handles a message.
+    pub fn update(&mut self, msg: Msg) -> Res<ShouldRender> {
+        match msg {
+            Msg::Toggle => self.collapsed = !self.collapsed,
+            Msg::Close => self.info = None,
+        }
+        Ok(true)
+    }
+
+    /// Renders the panel, if any.
+    pub fn render(&self) -> Html {
+        define_style! {
+            CONTAINER = {
+                padding(0 px, 0 px, 30 px, 0 px),
+            };
+            TOGGLE = {
+                pointer,
+            };
+            TABLE = {
+                width(100%),
+            };
+            KEY = {
+                bold,
+                text_align(right),
+                padding(0 px, 10 px),
+            };
+            VAL = {
+                text_align(left),
+                padding(0 px, 10 px),
+            };
+            ERROR = {
+                text_align(left),
+                padding(0 px, 10 px),
+                white_space(pre wrap),
+            };
+        }
+
+        let info = if let Some(info) = self.info.as_ref() {
+            info
+        } else {
+            return html! {};
+        };
+
+        let title = if self.collapsed {
+            "run info ▸"
+        } else {
+            "run info ▾"
+        };
+        let row = |key: &str, val: String| {
+            html! {
+                <tr>
+                    <td style = KEY>{key}</td>
+                    <td style = VAL>{val}</td>
+                </tr>
+            }
+        };
+        let content = match info {
+            _ if self.collapsed => html! {},
+            None => html! { {"no run loaded yet"} },
+            Some(info) => {
+                let mut rows = vec![
+                    row("start", info.start_time.to_string()),
+                    row(
+                        "end",
+                        info.end_time
+                            .map(|time| time.to_string())
+                            .unwrap_or_else(|| "unknown".into()),
+                    ),
+                    row("word size", format!("{} bits", info.word_size)),
+                    row("sampling rate", info.sample_rate.to_string()),
+                ];
+                match info.exe_info.as_ref() {
+                    Some(exe) => {
+                        rows.push(row("executable", exe.name.clone()));
+                        rows.push(row("parameters", exe.params.clone()));
+                        rows.push(row("host", exe.host_name.clone()));
+                        rows.push(row("pid", exe.pid.to_string()));
+                        if let Some(context) = exe.context.as_ref() {
+                            rows.push(row("context", context.clone()))
+                        }
+                    }
+                    None => rows.push(row("executable", "unknown".into())),
+                }
+                let errors = if self.errors.is_empty() {
+                    html! {}
+                } else {
+                    let title = if self.dropped > 0 {
+                        format!("recent errors ({} older error(s) dropped)", self.dropped)
+                    } else {
+                        "recent errors".into()
+                    };
+                    html! {
+                        <>
+                            {layout::section_title(&title)}
+                            {for self.errors.iter().map(|err| html! {
+                                <div style = ERROR>{err}</div>
+                            })}
+                        </>
+                    }
+                };
+                html! {
+                    <>
+                        <table
+                            style = TABLE
+                        >
+                            {for rows.into_iter()}
+                        </table>
+                        {errors}
+                    </>
+                }
+            }
+        };
+
+        html! {
+            <div
+                style = CONTAINER
+            >
+                <div
+                    style = TOGGLE
+                    onclick = self.link.callback(|_| msg::Msg::from(Msg::Toggle))
+                >
+                    {layout::section_title(title)}
+                </div>
+                {layout::button::text::render_default_button(
+                    "run_info_close",
+                    "close",
+                    Some(self.link.callback(|_| msg::Msg::from(Msg::Close))),
+                    false,
+                )}
+                {content}
+            </div>
+        }
+    }
+}
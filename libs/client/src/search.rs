@@ -0,0 +1,194 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Allocation search.
+
+prelude! {}
+
+/// Maximum number of search results requested to the server.
+pub const SEARCH_LIMIT: usize = 50;
+
+/// Search messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// Opens the search box.
+    Open,
+    /// Sends a search query to the server.
+    Query(String),
+    /// Closes the search box.
+    Close,
+}
+
+base::implement! {
+    impl Msg {
+        Display {
+            |&self, fmt| match self {
+                Self::Open => write!(fmt, "open"),
+                Self::Query(query) => write!(fmt, "query `{}`", query),
+                Self::Close => write!(fmt, "close"),
+            }
+        }
+    }
+}
+
+/// Searches for allocations by UID or by trace.
+pub struct Search {
+    /// Model link.
+    link: Link,
+    /// True if the search box is open.
+    open: bool,
+    /// Current query.
+    query: String,
+    /// Results sent by the server for the current query, if any.
+    results: Option<Vec<uid::Alloc>>,
+}
+
+impl Search {
+    /// Constructor.
+    pub fn new(link: Link) -> Self {
+        Self {
+            link,
+            open: false,
+            query: String::new(),
+            results: None,
+        }
+    }
+
+    /// Sets the results for some query, as sent by the server.
+    ///
+    /// Results for a query that is not the current one are ignored.
+    pub fn set(&mut self, query: String, allocs: Vec<uid::Alloc>) -> ShouldRender {
+        if query != self.query {
+            return false;
+        }
+        self.results = Some(allocs);
+        true
+    }
+
+    /// Handles a message.
+    pub fn update(&mut self, msg: Msg) -> Res<ShouldRender> {
+        match msg {
+            Msg::Open => self.open = true,
+            Msg::Query(query) => {
+                self.query = query;
+                self.results = None;
+                self.link.send_message(msg::to_server::DataMsg::search(
+                    self.query.clone(),
+                    SEARCH_LIMIT,
+                ))
+            }
+            Msg::Close => {
+                self.open = false;
+                self.results = None
+            }
+        }
+        Ok(true)
+    }
+
+    /// Renders the search box, if open.
+    pub fn render(&self, model: &Model) -> Html {
+        define_style! {
+            CONTAINER = {
+                padding(0 px, 0 px, 30 px, 0 px),
+            };
+            INPUT = {
+                width(20%),
+                height(30 px),
+                padding(10 px, 0 px),
+            };
+            TABLE = {
+                width(100%),
+            };
+            HEADER = {
+                bold,
+                text_align(center),
+                border(bottom, 2 px, black),
+            };
+            CELL = {
+                text_align(center),
+                padding(0 px, 10 px),
+            };
+        }
+
+        if !self.open {
+            return html! {};
+        }
+
+        let input = layout::input::string_input(model, &self.query, |res| match res {
+            Ok(query) => Msg::Query(query).into(),
+            Err(e) => msg::Msg::err(e),
+        });
+
+        let results = match self.results.as_ref() {
+            Some(allocs) if allocs.is_empty() => html! {
+                <div style = CELL>{"no allocation matches"}</div>
+            },
+            Some(allocs) => html! {
+                <table
+                    style = TABLE
+                >
+                    <tr>
+                        <th style = HEADER>{"allocation"}</th>
+                        <th style = HEADER></th>
+                    </tr>
+                    {for allocs.iter().map(|uid| {
+                        let uid = uid.clone();
+                        html! {
+                            <tr>
+                                <td style = CELL>{format!("#{}", uid)}</td>
+                                <td style = CELL>
+                                    {layout::button::text::render_default_button(
+                                        &format!("search_explain_{}", uid),
+                                        "explain",
+                                        Some(self.link.callback(move |_| {
+                                            msg::Msg::from(explain::Msg::Request(uid.clone()))
+                                        })),
+                                        false,
+                                    )}
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </table>
+            },
+            None => html! {},
+        };
+
+        html! {
+            <div
+                style = CONTAINER
+            >
+                {layout::section_title("search allocations")}
+                {layout::button::text::render_default_button(
+                    "search_close",
+                    "close",
+                    Some(self.link.callback(|_| msg::Msg::from(Msg::Close))),
+                    false,
+                )}
+                <div
+                    style = INPUT
+                >
+                    {"allocation UID or file "}
+                    {input}
+                </div>
+                {results}
+            </div>
+        }
+    }
+}
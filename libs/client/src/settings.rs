@@ -112,6 +112,8 @@ pub struct Settings {
     link: Link,
     /// Duration of the run.
     run_duration: time::SinceStart,
+    /// Time cursor shared by the charts, as last sent by the server.
+    cursor: Option<time::SinceStart>,
 
     /// Global charts settings.
     charts_settings: Memory<charts::chart::settings::Charts>,
@@ -125,6 +127,7 @@ impl Settings {
             charts_settings: Memory::default(),
             link,
             run_duration: time::SinceStart::zero(),
+            cursor: None,
         }
     }
 
@@ -147,6 +150,11 @@ impl Settings {
         self.run_duration = run_duration
     }
 
+    /// Updates the time cursor, does not notify the server.
+    pub fn set_cursor(&mut self, cursor: Option<time::SinceStart>) {
+        self.cursor = cursor
+    }
+
     /// Renders the settings menu.
     pub fn render(&self, model: &Model) -> Html {
         match self.display_mode {
@@ -173,6 +181,7 @@ impl Settings {
             display_mode: _,
             link: _,
             run_duration: _,
+            cursor: _,
 
             charts_settings,
         } = self;
@@ -188,6 +197,7 @@ impl Settings {
             display_mode: _,
             link: _,
             run_duration: _,
+            cursor: _,
 
             charts_settings,
         } = self;
@@ -271,7 +281,7 @@ impl Settings {
 
         header::Header::three_part_line_with(
             &*SETTINGS_LINE,
-            html! {},
+            self.cursor_input(model),
             header::Header::center(html! {
                 <div>
                     <div
@@ -321,10 +331,81 @@ impl Settings {
                     </div>
                 </div>
             }),
-            html! {},
+            self.palette_selector(model),
         )
     }
 
+    /// Generates the time cursor input, an empty input removes the cursor.
+    pub fn cursor_input(&self, model: &Model) -> Html {
+        define_style! {
+            LEFT = {
+                float(left),
+            };
+            INPUT_CONTAINER = {
+                extends_style(&*LEFT),
+                width(30%),
+                height(80%),
+            };
+        }
+
+        header::Header::center(html! {
+            <div>
+                <div
+                    style = LEFT
+                >
+                    { layout::header::emph("cursor") }
+                    { " (seconds) " }
+                </div>
+                <div
+                    style = INPUT_CONTAINER
+                >
+                    { layout::input::since_start_opt_input(
+                        model,
+                        self.run_duration / 10,
+                        self.cursor,
+                        |since_start_opt| msg_of_res(
+                            since_start_opt.map(|cursor| Msg::Cursor(cursor).into())
+                        )
+                    ) }
+                </div>
+            </div>
+        })
+    }
+
+    /// Generates the palette selector.
+    pub fn palette_selector(&self, model: &Model) -> Html {
+        let selected = Some(self.charts_settings.get().palette());
+        header::Header::center(html! {
+            <div>
+                { layout::header::emph("palette") }
+                { " " }
+                <Select<charts::color::Palette>
+                    options = charts::color::Palette::all()
+                    selected = selected
+                    on_change = model.link.callback(
+                        |palette| msg::Msg::from(Msg::Palette(palette))
+                    )
+                />
+                { layout::input::checkbox(
+                    self.charts_settings.get().name_colors(),
+                    "settings_name_colors",
+                    "colors from names",
+                    model.link.callback(|_| msg::Msg::from(Msg::ToggleNameColors)),
+                ) }
+                { " " }
+                { layout::header::emph("time format") }
+                { " " }
+                <Select<charts::chart::settings::TimeFormat>
+                    options = charts::chart::settings::TimeFormat::all()
+                    selected = Some(self.charts_settings.get().time_format())
+                    on_change = model.link.callback(
+                        |time_format| msg::Msg::from(Msg::TimeFormat(time_format))
+                    )
+                />
+            </div>
+        })
+    }
+
     /// Updates itself given a settings message.
     pub fn update(&mut self, msg: Msg) -> Res<ShouldRender> {
         let res = match msg {
@@ -357,6 +438,34 @@ impl Settings {
                     Ok(false)
                 }
             }
+            Msg::Palette(palette) => {
+                if self.charts_settings.get().palette() != palette {
+                    self.charts_settings.get_mut().set_palette(palette);
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Msg::ToggleNameColors => {
+                let name_colors = self.charts_settings.get().name_colors();
+                self.charts_settings.get_mut().set_name_colors(!name_colors);
+                Ok(true)
+            }
+            Msg::TimeFormat(time_format) => {
+                if self.charts_settings.get().time_format() != time_format {
+                    self.charts_settings.get_mut().set_time_format(time_format);
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Msg::Cursor(cursor) => {
+                self.link
+                    .send_message(msg::Msg::ToServer(msg::to_server::ChartsMsg::set_cursor(
+                        cursor,
+                    )));
+                Ok(false)
+            }
             Msg::Expand => {
                 let changed = self.display_mode.inc();
                 Ok(changed)
@@ -380,6 +489,9 @@ impl Settings {
                             msg::to_server::ChartsMsg::settings(self.charts_settings.get().clone())
                                 .into(),
                         ));
+                        self.link.send_message(msg::ChartsMsg::set_time_format(
+                            self.charts_settings.get().time_format(),
+                        ));
                         self.charts_settings.overwrite_reference();
                         Ok(true)
                     }
@@ -399,6 +511,14 @@ pub enum Msg {
     TimeWindowLb(Option<time::SinceStart>),
     /// Updates the time window's upper bound.
     TimeWindowUb(Option<time::SinceStart>),
+    /// Updates the palette used for new filters.
+    Palette(charts::color::Palette),
+    /// Toggles name-based coloring of new filters.
+    ToggleNameColors,
+    /// Updates the format of the time values on time axes.
+    TimeFormat(charts::chart::settings::TimeFormat),
+    /// Sets the time cursor shared by the charts, applied immediately.
+    Cursor(Option<time::SinceStart>),
     /// Reverts the settings.
     Revert,
     /// Saves the current settings.
@@ -426,6 +546,16 @@ base::implement! {
                         .map(|ub| ub.to_string())
                         .unwrap_or("_".into()),
                 ),
+                Self::Palette(palette) => write!(fmt, "palette: {}", palette),
+                Self::ToggleNameColors => write!(fmt, "toggle name colors"),
+                Self::TimeFormat(time_format) => write!(fmt, "time format: {}", time_format),
+                Self::Cursor(cursor) => write!(
+                    fmt,
+                    "cursor: {}",
+                    cursor
+                        .map(|cursor| cursor.to_string())
+                        .unwrap_or("_".into()),
+                ),
                 Self::Revert => write!(fmt, "revert"),
                 Self::Save => write!(fmt, "save"),
                 Self::Expand => write!(fmt, "expand"),
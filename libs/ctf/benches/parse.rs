@@ -0,0 +1,174 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Parser benchmarks.
+//!
+//! Run with `cargo bench -p ctf`. The dump benchmarks run on the `rsc/synthetic.ctf` fixture, see
+//! the `synthetic` example to regenerate it.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+use alloc_data::prelude::*;
+use ctf::parse::{CanParse, LeParser, RawParser};
+
+/// Path to the synthetic dump.
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/rsc/synthetic.ctf");
+
+/// Parses a dump, yields the number of allocations.
+fn parse(bytes: &[u8]) -> usize {
+    let mut factory = Box::new(mem::Factory::new(false));
+    let stats = ctf::parse(
+        bytes,
+        &mut factory,
+        ctf::ParseOptions::default(),
+        |_, _| (),
+        || true,
+        |_, _| (),
+        |_, builder| {
+            black_box(builder);
+        },
+        |_, _, _| (),
+        |_, _| (),
+    )
+    .expect("failed to parse synthetic dump");
+    stats.allocs
+}
+
+/// Whole-dump benchmarks, in MB/second and allocations/second.
+fn dump(c: &mut Criterion) {
+    let bytes = std::fs::read(FIXTURE).expect("failed to read synthetic dump");
+    let allocs = parse(&bytes);
+
+    let mut group = c.benchmark_group("dump");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("bytes", |b| b.iter(|| parse(black_box(&bytes))));
+    group.throughput(Throughput::Elements(allocs as u64));
+    group.bench_function("allocs", |b| b.iter(|| parse(black_box(&bytes))));
+    group.finish()
+}
+
+/// Parsing primitives.
+fn primitives(c: &mut Criterion) {
+    const COUNT: usize = 1_000;
+
+    // Variable-length integers of all sizes.
+    let mut v_usizes = vec![];
+    for n in 0..COUNT as u64 {
+        match n % 4 {
+            0 => v_usizes.push(n as u8 % 253),
+            1 => {
+                v_usizes.push(253);
+                v_usizes.extend_from_slice(&(n as u16).to_le_bytes())
+            }
+            2 => {
+                v_usizes.push(254);
+                v_usizes.extend_from_slice(&(n as u32).to_le_bytes())
+            }
+            _ => {
+                v_usizes.push(255);
+                v_usizes.extend_from_slice(&n.to_le_bytes())
+            }
+        }
+    }
+    let u64s: Vec<u8> = (0..COUNT as u64).flat_map(u64::to_le_bytes).collect();
+
+    let mut group = c.benchmark_group("primitives");
+    group.throughput(Throughput::Elements(COUNT as u64));
+    group.bench_function("v_usize", |b| {
+        b.iter(|| {
+            let mut parser = LeParser::new(black_box(&v_usizes), 0);
+            while !parser.is_eof() {
+                black_box(parser.v_usize().unwrap());
+            }
+        })
+    });
+    group.bench_function("u64_le", |b| {
+        b.iter(|| {
+            let mut parser = RawParser::new(black_box(&u64s), 0);
+            while !parser.is_eof() {
+                black_box(parser.u64_le().unwrap());
+            }
+        })
+    });
+    group.bench_function("u64_be", |b| {
+        b.iter(|| {
+            let mut parser = RawParser::new(black_box(&u64s), 0);
+            while !parser.is_eof() {
+                black_box(parser.u64_be().unwrap());
+            }
+        })
+    });
+    group.finish()
+}
+
+/// Trace building, for backtraces sharing half of their prefix with the previous one.
+fn build_trace(c: &mut Criterion) {
+    const SITES: u64 = 64;
+    const DEPTH: usize = 16;
+    const COUNT: usize = 1_000;
+
+    let mut factory = mem::Factory::new(false);
+    let mut loc_map = ctf::LocMap::new();
+    for code in 0..SITES {
+        let loc = factory.register_loc("build_trace.ml", code as usize, (0, 10));
+        loc_map.insert(code, vec![loc]);
+    }
+    let backtraces: Vec<Vec<usize>> = (0..COUNT)
+        .map(|n| {
+            (0..DEPTH)
+                .map(|frame| {
+                    let n = if frame < DEPTH / 2 { n / 8 } else { n };
+                    (n * 7 + frame) % SITES as usize
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("trace");
+    group.throughput(Throughput::Elements(COUNT as u64));
+    group.bench_function("build_trace", |b| {
+        b.iter_batched(
+            || backtraces.clone(),
+            |backtraces| {
+                let mut builder = ctf::TraceBuilder::new();
+                let mut last: Option<&Vec<usize>> = None;
+                for backtrace in &backtraces {
+                    let common_pref_len = last
+                        .map(|last| {
+                            last.iter()
+                                .zip(backtrace.iter())
+                                .take_while(|(l, r)| l == r)
+                                .count()
+                        })
+                        .unwrap_or(0);
+                    let trace = builder
+                        .build_trace(&mut factory, &loc_map, common_pref_len, backtrace.clone())
+                        .unwrap();
+                    black_box(trace);
+                    last = Some(backtrace);
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish()
+}
+
+criterion_group!(benches, dump, primitives, build_trace);
+criterion_main!(benches);
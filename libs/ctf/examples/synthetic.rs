@@ -0,0 +1,34 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Generates the `rsc/synthetic.ctf` fixture used by the benchmarks, see `ctf::synth`.
+//!
+//! An optional argument overrides the output path.
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/rsc/synthetic.ctf").into());
+    let bytes = ctf::synth::Synthetic::default().to_bytes();
+    if let Err(e) = std::fs::write(&path, &bytes) {
+        eprintln!("failed to write `{}`: {}", path, e);
+        std::process::exit(2)
+    }
+    println!("wrote {} bytes to `{}`", bytes.len(), path)
+}
@@ -0,0 +1,31 @@
+//! Feeds arbitrary bytes to the event-level parsers.
+//!
+//! Each step reads a byte from the input that decides whether to parse a variable-length usize,
+//! an allocation, a collection UID delta or some locations. Parsing stops on the first error:
+//! parsers must never panic, only fail cleanly.
+
+#![no_main]
+
+use ctf::parse::{CanParse, Cxt, LeParser};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut cxt = Cxt::new();
+    let mut parser = LeParser::new(data, 0);
+
+    while !parser.is_eof() {
+        let res = match parser.u8() {
+            Ok(selector) => match selector % 5 {
+                0 => parser.v_usize().map(|_| ()),
+                1 => parser.alloc(0, &mut cxt, None).map(|_| ()),
+                2 => parser.alloc(0, &mut cxt, Some(1)).map(|_| ()),
+                3 => parser.alloc_uid_from_delta(&cxt).map(|_| ()),
+                _ => parser.locs(&mut cxt).map(|_| ()),
+            },
+            Err(e) => Err(e),
+        };
+        if res.is_err() {
+            break;
+        }
+    }
+});
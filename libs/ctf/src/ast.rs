@@ -113,15 +113,7 @@ pub mod header {
         /// Associated PID.
         pub pid: Pid,
         /// Memtrace version in use.
-        pub version: u16,
-    }
-    impl Header {
-        /// True if the element this header is for has a context.
-        ///
-        /// Only true in memtrace `v2.*` and above.
-        pub fn has_context(&self) -> bool {
-            self.version >= 2
-        }
+        pub version: Version,
     }
 
     /// CTF header, top-level header of a memtrace dump.
@@ -387,14 +379,25 @@ pub mod event {
         }
 
         /// Turns itself into an `Init`.
-        pub fn to_init(&self, start_time: time::Date) -> alloc_data::Init {
+        pub fn to_init(
+            &self,
+            start_time: time::Date,
+            end_time: Option<time::SinceStart>,
+        ) -> alloc_data::Init {
             alloc_data::Init::new(
                 start_time,
-                None,
+                end_time,
                 convert(self.word_size, "ctf parser: word_size"),
                 false,
             )
             .sample_rate(self.sample_rate)
+            .exe_info(alloc_data::ExeInfo {
+                name: self.exe_name.clone(),
+                host_name: self.host_name.clone(),
+                params: self.exe_params.clone(),
+                pid: self.pid,
+                context: self.context.map(String::from),
+            })
         }
     }
 
@@ -491,10 +494,14 @@ pub struct Locs<'data> {
 /// Cache-check data.
 #[derive(Debug, Clone)]
 pub struct CacheCheck {
-    /// Currently unused.
+    /// Index of the backtrace cache entry to check, [`Self::NONE`] if there is nothing to check.
     pub ix: u16,
-    /// Currently unused.
+    /// Prediction for the cache entry, currently unused.
     pub pred: u16,
-    /// Currently unused.
+    /// Expected value of the cache entry.
     pub value: u64,
 }
+impl CacheCheck {
+    /// Index used by packets that do not check the backtrace cache.
+    pub const NONE: u16 = u16::MAX;
+}
@@ -96,13 +96,40 @@ impl Cxt {
     }
 
     /// Retrieves a backtrace at the current position in the input parser.
+    ///
+    /// Fails if `common_pref_len` is greater than the length of the last backtrace retrieved.
+    ///
+    /// ```rust
+    /// use ctf::{btrace::Cxt, parse::LeParser};
+    ///
+    /// // Two cache misses: bucket `0` for location `7`, bucket `1` for location `8`.
+    /// let mut bytes = vec![];
+    /// bytes.extend_from_slice(&3u16.to_le_bytes());
+    /// bytes.extend_from_slice(&7u64.to_le_bytes());
+    /// bytes.extend_from_slice(&((1u16 << 2) | 3).to_le_bytes());
+    /// bytes.extend_from_slice(&8u64.to_le_bytes());
+    ///
+    /// let mut cxt = Cxt::new();
+    /// let mut parser = LeParser::new(&bytes, 0);
+    /// assert_eq!(cxt.get_backtrace(&mut parser, 2, 0).unwrap(), vec![7, 8]);
+    /// // Only sharing a prefix with the last backtrace.
+    /// assert_eq!(cxt.get_backtrace(&mut parser, 0, 1).unwrap(), vec![7]);
+    /// // Last backtrace has length `1` now.
+    /// assert!(cxt.get_backtrace(&mut parser, 0, 2).is_err());
+    /// ```
     pub fn get_backtrace<'data>(
         &mut self,
         parser: &mut impl CanParse<'data>,
         nencoded: usize,
         common_pref_len: usize,
     ) -> Res<Vec<usize>> {
-        assert!(common_pref_len <= self.last_backtrace.len());
+        if common_pref_len > self.last_backtrace.len() {
+            bail!(
+                "illegal backtrace common prefix length {}, last backtrace has length {}",
+                common_pref_len,
+                self.last_backtrace.len(),
+            )
+        }
 
         let Self {
             cache_loc,
@@ -119,7 +146,10 @@ impl Cxt {
 
         let res = 'decode: loop {
             if decode_current == 0 {
-                break 'decode buf[0..pos].iter().cloned().collect();
+                // Forget about the stale part of the buffer, so that the next common prefix length
+                // is checked against the actual length of this backtrace.
+                buf.truncate(pos);
+                break 'decode buf.clone();
             }
 
             let codeword = parser.u16()?;
@@ -144,7 +174,7 @@ impl Cxt {
                 }
                 // Cache miss.
                 _ => {
-                    let lit = convert(parser.u64()?, "get_backtrace: lit");
+                    let lit = try_convert(parser.u64()?, "get_backtrace: lit")?;
                     cache_loc[bucket] = lit;
                     Self::put(buf, pos, lit);
                     pos += 1;
@@ -220,7 +250,7 @@ impl Cxt {
                 ix, self.cache_pred[ix], pred,
             )
         }
-        if self.cache_loc[ix] != convert(value, "check_cache_verifier: value") {
+        if self.cache_loc[ix] != try_convert(value, "check_cache_verifier: value")? {
             error!(
                 "expected cache_loc[ix] == value, got cache_loc[{}] = {} != {}",
                 ix, self.cache_loc[ix], value,
@@ -230,6 +260,20 @@ impl Cxt {
         Ok(())
     }
 
+    /// True if the cache is consistent with the cache check of a packet header.
+    ///
+    /// Only compares the value of the cache entry at index `ix`, not the prediction list. Packets
+    /// without cache check have index [`CacheCheck::NONE`], and always match.
+    pub fn cache_check_matches(&self, check: &CacheCheck) -> bool {
+        if check.ix == CacheCheck::NONE {
+            return true;
+        }
+        self.cache_loc
+            .get(convert::<u16, usize>(check.ix, "cache_check_matches: ix"))
+            .map(|loc| convert::<usize, u64>(*loc, "cache_check_matches: loc") == check.value)
+            .unwrap_or(false)
+    }
+
     /// Parses cache-verification data and ignores it.
     pub fn skip_cache_verifier<'data>(&self, parser: &mut impl CanParse<'data>) -> Res<()> {
         let _ix = parser.u16()?;
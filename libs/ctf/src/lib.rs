@@ -18,14 +18,32 @@
 */
 
 //! Frontend for memtrace's CTF format.
+//!
+//! # Fuzzing
+//!
+//! The `fuzz` directory of this crate contains a [`cargo-fuzz`] target feeding arbitrary bytes to
+//! the event parsers (variable-length integers, allocations, locations...). Parsers should never
+//! panic, only fail. Fuzzing requires nightly; from this crate's directory:
+//!
+//! ```text
+//! cargo install cargo-fuzz
+//! cargo +nightly fuzz run parse_events
+//! ```
+//!
+//! Inputs causing a panic end up in `fuzz/artifacts/parse_events`, and can be replayed with
+//! `cargo +nightly fuzz run parse_events <artifact>`.
+//!
+//! [`cargo-fuzz`]: https://github.com/rust-fuzz/cargo-fuzz
 
 #![deny(missing_docs)]
 
 #[macro_use]
 mod macros;
 
-/// Memtrace version of the parser realized by this crate.
-pub const VERSION: u16 = 2;
+/// Most recent memtrace version supported by the parser realized by this crate.
+///
+/// See [`version`] for the list of supported versions.
+pub const VERSION: u16 = <version::V2 as version::TraceVersion>::VERSION;
 
 pub use base::err;
 
@@ -36,6 +54,8 @@ pub mod ast;
 pub mod btrace;
 pub mod loc;
 pub mod parse;
+pub mod synth;
+pub mod version;
 
 prelude! {}
 
@@ -58,7 +78,389 @@ impl<'data, T> EventAction<'data> for T where
 {
 }
 
-pub use diff_parse::parse;
+pub use diff_parse::{parse, parse_with_sink, LocMap, TraceBuilder};
+pub use parse::EventStream;
+
+/// Iterator over the events of a CTF dump, alternative to the callback-based [`parse`] function.
+///
+/// Fails if the CTF header or the trace info of the dump cannot be parsed. Events are yielded with
+/// the header of the packet they belong to, and their timestamp.
+///
+/// ```rust
+/// let bytes = std::fs::read("rsc/lists_and_sets.ctf").unwrap();
+/// let (mut allocs, mut collections) = (0, 0);
+/// for res in ctf::events(&bytes).unwrap() {
+///     let (_packet, _clock, event) = res.unwrap();
+///     match event {
+///         ctf::ast::event::Event::Alloc(_) => allocs += 1,
+///         ctf::ast::event::Event::Collection(_) => collections += 1,
+///         _ => (),
+///     }
+/// }
+/// assert!(allocs > 0);
+/// assert!(collections <= allocs);
+/// ```
+///
+/// [`parse`]: crate::parse (parse function)
+pub fn events(bytes: &[u8]) -> err::Res<EventStream<'_>> {
+    EventStream::new(bytes)
+}
+
+/// Receives the events of a memtrace CTF dump, see [`parse_with_sink`].
+///
+/// Alternative to the closures of [`parse`] for callers that cannot, or do not want to, be generic
+/// over them, typically FFI bindings or plugins picked at runtime.
+///
+/// [`parse`]: crate::parse (parse function)
+/// [`parse_with_sink`]: crate::parse_with_sink (parse_with_sink function)
+pub trait EventSink {
+    /// Called once on the trace info of the dump, before any other event.
+    fn on_init(&mut self, init: alloc_data::Init);
+    /// Called on each allocation.
+    fn on_alloc(&mut self, alloc: alloc_data::Alloc);
+    /// Called on each collection of a previous allocation.
+    fn on_dead(&mut self, timestamp: time::SinceStart, uid: uid::Alloc);
+}
+
+/// Statistics about a run of the [`parse`] function.
+///
+/// ```rust
+/// let bytes = std::fs::read("rsc/lists_and_sets.ctf").unwrap();
+/// let mut factory = Box::new(alloc_data::mem::Factory::new(false));
+/// let (mut allocs, mut deaths) = (0, 0);
+/// let stats = ctf::parse(
+///     &bytes,
+///     &mut factory,
+///     ctf::ParseOptions::default(),
+///     |_, _| (),
+///     || true,
+///     |_, _| (),
+///     |_, _| allocs += 1,
+///     |_, _, _| deaths += 1,
+///     |_, _| (),
+/// )
+/// .unwrap();
+/// assert_eq!(stats.allocs, allocs);
+/// assert_eq!(stats.deaths, deaths);
+/// assert!(stats.packets > 0);
+/// assert!(stats.locs > 0);
+/// ```
+///
+/// [`parse`]: crate::parse (parse function)
+#[derive(Debug, Clone, Default)]
+pub struct ParseStats {
+    /// Time spent parsing, regardless of time-stats.
+    pub elapsed: std::time::Duration,
+    /// Description and elapsed time of the time-stats stopwatches.
+    ///
+    /// Empty unless the `time_stats` feature is active.
+    pub durations: Vec<(&'static str, std::time::Duration)>,
+    /// Number of packets parsed.
+    pub packets: usize,
+    /// Number of allocations parsed.
+    pub allocs: usize,
+    /// Number of deaths parsed, *i.e.* collections of allocations that were not skipped.
+    pub deaths: usize,
+    /// Number of locations parsed.
+    pub locs: usize,
+    /// Number of packets whose cache check did not match the backtrace cache.
+    ///
+    /// `None` unless [`ParseOptions::check_cache`] is set. Mismatches indicate the parser
+    /// decoded some backtraces differently from the program that generated the dump, which is a
+    /// sign of a corrupted dump or of a parser bug.
+    ///
+    /// ```rust
+    /// let bytes = ctf::synth::Synthetic::default().to_bytes();
+    /// let parse = |check_cache| {
+    ///     let mut factory = Box::new(alloc_data::mem::Factory::new(false));
+    ///     ctf::parse(
+    ///         &bytes,
+    ///         &mut factory,
+    ///         ctf::ParseOptions {
+    ///             check_cache,
+    ///             ..ctf::ParseOptions::default()
+    ///         },
+    ///         |_, _| (),
+    ///         || true,
+    ///         |_, _| (),
+    ///         |_, _| (),
+    ///         |_, _, _| (),
+    ///         |_, _| (),
+    ///     )
+    ///     .unwrap()
+    /// };
+    /// assert_eq!(parse(false).cache_mismatches, None);
+    /// assert_eq!(parse(true).cache_mismatches, Some(0));
+    /// ```
+    pub cache_mismatches: Option<usize>,
+}
+impl fmt::Display for ParseStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "parsed {} allocations, {} deaths, {} locations and {} packets in {}.{:0>3}s",
+            self.allocs,
+            self.deaths,
+            self.locs,
+            self.packets,
+            self.elapsed.as_secs(),
+            self.elapsed.subsec_millis(),
+        )?;
+        if let Some(count) = self.cache_mismatches {
+            write!(fmt, ", {} cache check mismatch(es)", count)?
+        }
+        Ok(())
+    }
+}
+
+/// Sanity bound on the size of the allocations of a dump, see [`parse`].
+///
+/// Corrupted dumps can decode absurd allocation sizes, this bound catches such desynchronizations
+/// early. Sizes are in machine words, as decoded from the dump.
+///
+/// ```rust
+/// use ctf::MaxAllocSize;
+/// let bytes = std::fs::read("rsc/lists_and_sets.ctf").unwrap();
+/// let parse = |max_alloc_size| {
+///     let mut factory = Box::new(alloc_data::mem::Factory::new(false));
+///     let (mut allocs, mut collections) = (0, 0);
+///     let stats = ctf::parse(
+///         &bytes,
+///         &mut factory,
+///         ctf::ParseOptions {
+///             max_alloc_size: Some(max_alloc_size),
+///             ..ctf::ParseOptions::default()
+///         },
+///         |_, _| (),
+///         || true,
+///         |_, _| (),
+///         |_, _| allocs += 1,
+///         |_, _, _| collections += 1,
+///         |_, _| (),
+///     );
+///     stats.map(|stats| (stats.allocs, allocs, collections))
+/// };
+///
+/// assert!(parse(MaxAllocSize::Fail(0)).is_err());
+/// assert_eq!(parse(MaxAllocSize::Skip(0)).unwrap(), (0, 0, 0));
+/// let (_, allocs, _) = parse(MaxAllocSize::Fail(u64::MAX)).unwrap();
+/// assert!(allocs > 0);
+/// ```
+///
+/// [`parse`]: crate::parse (parse function)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxAllocSize {
+    /// Parsing fails on allocations bigger than the bound.
+    Fail(u64),
+    /// Allocations bigger than the bound, and their collection, are skipped with a warning.
+    ///
+    /// The allocations that are not skipped are renumbered so that their UIDs stay contiguous, and
+    /// so are their collections.
+    Skip(u64),
+}
+impl MaxAllocSize {
+    /// Maximum allocation size, in machine words.
+    pub fn bound(self) -> u64 {
+        match self {
+            Self::Fail(bound) | Self::Skip(bound) => bound,
+        }
+    }
+}
+
+/// Handling of the location events redefining an already declared location identifier.
+///
+/// Memtrace declares the locations of each location identifier once. Some dumps declare them again
+/// though, which is fine when the locations are the same. This policy decides what happens when
+/// they are not.
+///
+/// ```rust
+/// use alloc_data::prelude::*;
+/// use ctf::DupLocPolicy;
+/// let synth = ctf::synth::Synthetic {
+///     allocs: 17,
+///     sites: 4,
+///     depth: 1,
+///     allocs_per_packet: 10,
+///     redefined_sites: 1,
+/// };
+/// let bytes = synth.to_bytes();
+/// // Line of the allocation site of the last allocation, which is on site `0`.
+/// let parse = |dup_locs| {
+///     let mut factory = Box::new(alloc_data::mem::Factory::new(false));
+///     let mut last = None;
+///     let res = ctf::parse(
+///         &bytes,
+///         &mut factory,
+///         ctf::ParseOptions {
+///             dup_locs,
+///             ..ctf::ParseOptions::default()
+///         },
+///         |_, _| (),
+///         || true,
+///         |_, _| (),
+///         |_, alloc| last = Some(alloc),
+///         |_, _, _| (),
+///         |_, _| (),
+///     );
+///     drop(factory);
+///     res.map(|_| last.unwrap().trace()[0].loc.line)
+/// };
+///
+/// assert!(parse(DupLocPolicy::Error).is_err());
+/// assert_eq!(parse(DupLocPolicy::KeepFirst).unwrap(), 0);
+/// assert_eq!(parse(DupLocPolicy::Overwrite).unwrap(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DupLocPolicy {
+    /// Parsing fails.
+    Error,
+    /// The new locations are ignored, with a warning.
+    KeepFirst,
+    /// The new locations replace the previous ones, with a warning.
+    Overwrite,
+}
+impl Default for DupLocPolicy {
+    fn default() -> Self {
+        Self::Error
+    }
+}
+
+/// Granularity of the progress notifications issued while parsing.
+///
+/// The progress callback of [`parse`] receives the current byte position and the total number of
+/// bytes, it is called at most once per packet.
+///
+/// [`parse`]: crate::parse (parse function)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// Notifies progress once every `n` bytes parsed.
+    Bytes(usize),
+    /// Notifies progress once every `n` packets parsed.
+    Packets(usize),
+}
+impl Default for Progress {
+    fn default() -> Self {
+        Self::Packets(10)
+    }
+}
+impl Progress {
+    /// Tracks the progress notifications of a parser.
+    fn tracker(self) -> ProgressTracker {
+        ProgressTracker {
+            granularity: self,
+            packets: 0,
+            last_pos: 0,
+        }
+    }
+}
+
+/// Decides when to notify progress, see [`Progress`].
+///
+/// [`Progress`]: crate::Progress (Progress enum)
+struct ProgressTracker {
+    /// Notification granularity.
+    granularity: Progress,
+    /// Number of packets seen so far.
+    packets: usize,
+    /// Byte position of the last notification.
+    last_pos: usize,
+}
+impl ProgressTracker {
+    /// Registers a new packet starting at `pos`, true if progress should be notified.
+    fn new_packet(&mut self, pos: usize) -> bool {
+        self.packets += 1;
+        let notify = match self.granularity {
+            Progress::Packets(n) => self.packets % n.max(1) == 0,
+            Progress::Bytes(n) => pos.saturating_sub(self.last_pos) >= n.max(1),
+        };
+        if notify {
+            self.last_pos = pos
+        }
+        notify
+    }
+}
+
+/// Options of the [`parse`] function.
+///
+/// ```rust
+/// let options = ctf::ParseOptions {
+///     word_size_override: Some(32),
+///     suppress_deprecated_warning: true,
+///     ..ctf::ParseOptions::default()
+/// };
+/// assert_eq!(options.progress, ctf::Progress::default());
+/// assert_eq!(options.max_alloc_size, None);
+/// assert_eq!(options.dup_locs, ctf::DupLocPolicy::Error);
+/// ```
+///
+/// [`parse`]: crate::parse (parse function)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Granularity of the progress notifications.
+    pub progress: Progress,
+    /// Word size **in bits** used instead of the one found in the dump's trace info, if any.
+    ///
+    /// Typically useful when the dump was generated on a machine with a different word size, or
+    /// when the dump's value is wrong. It affects the [`Init`] passed to the init action of
+    /// [`parse`], and thus the size in bytes and the real size of all allocations. A warning is
+    /// logged when the override differs from the dump's value.
+    ///
+    /// [`Init`]: alloc_data::Init (Init struct)
+    /// [`parse`]: crate::parse (parse function)
+    pub word_size_override: Option<u8>,
+    /// Sanity bound on the size of the allocations, `None` accepts all sizes.
+    pub max_alloc_size: Option<MaxAllocSize>,
+    /// If true, no warning is logged when the dump uses a deprecated version of the memtrace
+    /// format.
+    ///
+    /// See [`Version::is_deprecated`]. The warning is on by default, scripts and batch processing
+    /// typically want to suppress it.
+    ///
+    /// [`Version::is_deprecated`]: crate::version::Version::is_deprecated (is_deprecated method)
+    pub suppress_deprecated_warning: bool,
+    /// Maximal number of frames in the traces of the allocations, `None` keeps all of them.
+    ///
+    /// Deeper traces only keep the frames closest to the allocation site, and are marked as
+    /// [truncated](alloc_data::prelude::Trace::is_truncated). Trades fidelity for memory on dumps
+    /// with very deep backtraces. See [`TraceBuilder::max_depth`].
+    ///
+    /// ```rust
+    /// use alloc_data::prelude::*;
+    /// let synth = ctf::synth::Synthetic { depth: 8, ..ctf::synth::Synthetic::default() };
+    /// let options = ctf::ParseOptions {
+    ///     max_trace_depth: Some(3),
+    ///     ..ctf::ParseOptions::default()
+    /// };
+    /// let mut factory = Box::new(alloc_data::mem::Factory::new(false));
+    /// let mut allocs = vec![];
+    /// ctf::parse(
+    ///     &synth.to_bytes(),
+    ///     &mut factory,
+    ///     options,
+    ///     |_, _| (),
+    ///     || true,
+    ///     |_, _| (),
+    ///     |_, alloc| allocs.push(alloc),
+    ///     |_, _, _| (),
+    ///     |_, _| (),
+    /// )
+    /// .unwrap();
+    /// drop(factory);
+    /// for alloc in allocs {
+    ///     let trace = alloc.trace();
+    ///     // Truncation marker and the three frames closest to the allocation site.
+    ///     assert_eq!(trace.iter().map(|cloc| cloc.cnt).sum::<usize>(), 4);
+    ///     assert!(trace[0].loc.is_truncation_marker());
+    /// }
+    /// ```
+    pub max_trace_depth: Option<usize>,
+    /// Handling of the locations declared twice with different values.
+    pub dup_locs: DupLocPolicy,
+    /// If true, the cache check of each packet header is compared with the backtrace cache.
+    ///
+    /// Mismatches do not fail parsing, they are counted in [`ParseStats::cache_mismatches`].
+    pub check_cache: bool,
+}
 
 mod diff_parse {
     use alloc_data::prelude::*;
@@ -66,33 +468,93 @@ mod diff_parse {
     /// Type of an encoded location.
     type EncodedLoc = u64;
     /// Maps encoded locations to vectors of locations.
-    type LocMap = HMap<EncodedLoc, Vec<Loc>>;
+    ///
+    /// Locations are interned by the factory, identical locations share the same storage.
+    pub type LocMap = HMap<EncodedLoc, Vec<Arc<Loc>>>;
 
+    /// Builds traces from the backtraces of allocation events.
+    ///
+    /// Backtraces are sequences of location codes, see [`LocMap`], and share a prefix with the
+    /// backtrace of the previous allocation event. The builder remembers the previous trace to
+    /// only decode the part that changed.
+    ///
+    /// ```rust
+    /// use alloc_data::prelude::*;
+    /// let mut factory = mem::Factory::new(false);
+    /// let mut loc_map = ctf::LocMap::new();
+    /// for code in 0..3 {
+    ///     let loc = factory.register_loc("trace_builder.ml", code as usize, (0, 1));
+    ///     loc_map.insert(code, vec![loc]);
+    /// }
+    /// let mut builder = ctf::TraceBuilder::new();
+    /// let trace = builder.build_trace(&mut factory, &loc_map, 0, vec![0, 1]).unwrap();
+    /// // Shares its first location with the previous trace.
+    /// let other = builder.build_trace(&mut factory, &loc_map, 1, vec![0, 2]).unwrap();
+    /// drop(factory);
+    /// let lines = |trace: &Trace| -> Vec<usize> { trace.get().iter().map(|c| c.loc.line).collect() };
+    /// assert_eq!(lines(&trace), vec![0, 1]);
+    /// assert_eq!(lines(&other), vec![0, 2]);
+    /// ```
     pub struct TraceBuilder {
         last_trace: Vec<CLoc>,
         last_trace_len: usize,
         last_trace_cached: Option<Trace>,
         cursor: usize,
         cursor_count_minus: usize,
+        max_depth: Option<usize>,
     }
     impl TraceBuilder {
-        fn new() -> Self {
+        /// Constructor.
+        pub fn new() -> Self {
             Self {
                 last_trace: Vec::with_capacity(32),
                 last_trace_cached: None,
                 last_trace_len: 0,
                 cursor: 0,
                 cursor_count_minus: 0,
+                max_depth: None,
             }
         }
+
+        /// Sets the maximal number of frames of the traces built, `None` keeps all of them.
+        ///
+        /// Deeper traces only keep the `max_depth` frames closest to the allocation site, and
+        /// start with a [truncation marker](Loc::is_truncation_marker).
+        ///
+        /// ```rust
+        /// use alloc_data::prelude::*;
+        /// let mut factory = mem::Factory::new(false);
+        /// let mut loc_map = ctf::LocMap::new();
+        /// for code in 0..4 {
+        ///     let loc = factory.register_loc("max_depth.ml", code as usize, (0, 1));
+        ///     loc_map.insert(code, vec![loc]);
+        /// }
+        /// let mut builder = ctf::TraceBuilder::new().max_depth(Some(2));
+        /// let short = builder.build_trace(&mut factory, &loc_map, 0, vec![0, 1]).unwrap();
+        /// let deep = builder.build_trace(&mut factory, &loc_map, 1, vec![0, 1, 2, 3]).unwrap();
+        /// drop(factory);
+        /// assert!(!short.is_truncated());
+        /// assert!(deep.is_truncated());
+        /// // Allocation site is the last frame.
+        /// let frames: Vec<String> = deep.get().iter().map(|c| c.loc.to_string()).collect();
+        /// assert_eq!(frames[0], "… (truncated)");
+        /// assert_eq!(&frames[1..], &["`max_depth.ml`:2:0-1", "`max_depth.ml`:3:0-1"]);
+        /// ```
+        pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+            self.max_depth = max_depth;
+            self
+        }
         #[inline]
         fn reset(&mut self) {
             self.cursor = 0;
             self.cursor_count_minus = 0;
         }
 
+        /// Builds the trace of a backtrace sharing `common_pref_len` codes with the previous one.
+        ///
+        /// `trace` contains all the location codes of the backtrace, including the common prefix.
         #[inline]
-        fn build_trace(
+        pub fn build_trace(
             &mut self,
             factory: &mut mem::Factory,
             loc_map: &LocMap,
@@ -106,13 +568,10 @@ mod diff_parse {
 
             let trace = if common_pref_len == trace_len && trace_len == self.last_trace_len {
                 if let Some(trace) = self.last_trace_cached.clone() {
+                    factory.trace_cache_hit();
                     trace
                 } else if common_pref_len == 0 {
-                    let mut trace = self.last_trace.clone();
-                    trace.shrink_to_fit();
-                    let trace = factory.register_trace(trace);
-                    self.last_trace_cached = Some(trace.clone());
-                    trace
+                    self.register_last_trace(factory)
                 } else {
                     bail!("[build_trace] illegal internal state: no previous trace exists")
                 }
@@ -164,7 +623,7 @@ mod diff_parse {
 
                     for loc in sub_trace {
                         if let Some(cloc) = self.last_trace.last_mut() {
-                            if loc == &cloc.loc {
+                            if *loc == cloc.loc {
                                 cloc.cnt += 1
                             } else {
                                 self.last_trace.push(CLoc::new(loc.clone(), 1));
@@ -178,31 +637,141 @@ mod diff_parse {
                 self.reset();
 
                 self.last_trace_len = trace_len;
-                let mut trace = self.last_trace.clone();
-                trace.shrink_to_fit();
-                let trace = factory.register_trace(trace);
-                self.last_trace_cached = Some(trace.clone());
-                trace
+                self.register_last_trace(factory)
             };
 
             Ok(trace)
         }
+
+        /// Registers the last trace built, truncated if needed, and caches it.
+        fn register_last_trace(&mut self, factory: &mut mem::Factory) -> Trace {
+            let mut trace = self.last_trace.clone();
+            if let Some(max_depth) = self.max_depth {
+                Self::truncate(factory, &mut trace, max_depth)
+            }
+            trace.shrink_to_fit();
+            let trace = factory.register_trace(trace);
+            self.last_trace_cached = Some(trace.clone());
+            trace
+        }
+
+        /// Only keeps the `max_depth` frames of `trace` closest to the allocation site.
+        ///
+        /// The dropped frames are replaced by a truncation marker.
+        fn truncate(factory: &mut mem::Factory, trace: &mut Vec<CLoc>, max_depth: usize) {
+            let depth: usize = trace.iter().map(|cloc| cloc.cnt).sum();
+            if depth <= max_depth {
+                return;
+            }
+
+            // The factory reverses reversed callstacks, the allocation site is the first frame in
+            // this case. Work on the root-to-site order.
+            let rev = factory.callstack_is_rev();
+            if rev {
+                trace.reverse()
+            }
+
+            let mut to_drop = depth - max_depth;
+            let mut dropped = 0;
+            for cloc in trace.iter_mut() {
+                if cloc.cnt <= to_drop {
+                    to_drop -= cloc.cnt;
+                    dropped += 1;
+                } else {
+                    cloc.cnt -= to_drop;
+                    break;
+                }
+            }
+            trace.drain(0..dropped);
+            trace.insert(0, CLoc::new(factory.register_truncation_marker(), 1));
+
+            if rev {
+                trace.reverse()
+            }
+        }
     }
 
     fn date_from_microsecs(date: crate::prelude::Clock) -> time::Date {
         time::Date::from_micros(convert(date, "date_from_microsecs"))
     }
 
+    /// Duration of a run from the timestamp range of its CTF header.
+    ///
+    /// Memtrace writes the CTF header when the run starts, at which point its upper bound is
+    /// usually equal to its lower bound: in this case, and if the range is inconsistent, the end
+    /// time is unknown.
+    fn end_time(timestamp: &Range<crate::prelude::Clock>) -> Option<time::SinceStart> {
+        if timestamp.ubound > timestamp.lbound {
+            Some(date_from_microsecs(timestamp.ubound) - date_from_microsecs(timestamp.lbound))
+        } else {
+            if timestamp.ubound < timestamp.lbound {
+                base::log::warn!(
+                    "ignoring inconsistent CTF header timestamp range {}, end time is unknown",
+                    timestamp
+                )
+            }
+            None
+        }
+    }
+
     /// Parses a CTF file (memtrace format).
+    ///
+    /// See [`ParseOptions`] for the options.
+    ///
+    /// `bytes_progress` receives the current byte position and the total number of bytes, at the
+    /// granularity specified by the options.
+    ///
+    /// `should_continue` is polled after each packet: when it returns `false`, parsing stops and
+    /// this function returns the statistics of the packets processed so far.
+    ///
+    /// The dump stores allocation sizes in machine words, the allocations passed to `new_action`
+    /// have their size converted to bytes using the word size of the [`Init`].
+    ///
+    /// Returns statistics about the parsing, see [`ParseStats`].
+    ///
+    /// ```rust
+    /// let bytes = std::fs::read("rsc/lists_and_sets.ctf").unwrap();
+    /// let parse = |max_packets| {
+    ///     let mut factory = Box::new(alloc_data::mem::Factory::new(false));
+    ///     let packets = std::cell::Cell::new(0);
+    ///     ctf::parse(
+    ///         &bytes,
+    ///         &mut factory,
+    ///         ctf::ParseOptions::default(),
+    ///         |_, _| (),
+    ///         || {
+    ///             packets.set(packets.get() + 1);
+    ///             packets.get() < max_packets
+    ///         },
+    ///         |_, _| (),
+    ///         |_, _| (),
+    ///         |_, _, _| (),
+    ///         |_, _| (),
+    ///     )
+    ///     .unwrap()
+    /// };
+    ///
+    /// let all = parse(usize::MAX);
+    /// assert!(all.packets > 1);
+    /// // Stops after the first packet.
+    /// let first = parse(1);
+    /// assert_eq!(first.packets, 1);
+    /// assert!(first.allocs < all.allocs);
+    /// ```
+    ///
+    /// [`ParseOptions`]: crate::ParseOptions (ParseOptions struct)
+    /// [`ParseStats`]: crate::ParseStats (ParseStats struct)
     pub fn parse<'a, F>(
         bytes: &[u8],
         mut factory: &mut F,
-        mut bytes_progress: impl FnMut(usize),
+        options: crate::ParseOptions,
+        mut bytes_progress: impl FnMut(usize, usize),
+        should_continue: impl Fn() -> bool,
         init_action: impl FnOnce(&mut F, Init),
         mut new_action: impl FnMut(&mut F, alloc_data::Builder),
         mut dead_action: impl FnMut(&mut F, time::SinceStart, uid::Alloc),
         mut mark_timestamp: impl FnMut(&mut F, time::SinceStart),
-    ) -> Res<()>
+    ) -> Res<crate::ParseStats>
     where
         F: std::ops::DerefMut<Target = mem::Factory<'a>>,
     {
@@ -219,37 +788,84 @@ mod diff_parse {
                 pub alloc_action => "allocation action",
             }
         }
+        let crate::ParseOptions {
+            progress,
+            word_size_override,
+            max_alloc_size,
+            suppress_deprecated_warning,
+            max_trace_depth,
+            dup_locs,
+            check_cache,
+        } = options;
         let mut prof = Prof::new();
         prof.total.start();
+        let start = std::time::Instant::now();
+        let mut stats = crate::ParseStats::default();
 
-        let mut trace_builder = TraceBuilder::new();
+        let mut trace_builder = TraceBuilder::new().max_depth(max_trace_depth);
+        let mut progress = progress.tracker();
 
         // Maps location encoded identifiers to actual locations.
         let mut loc_id_to_loc = LocMap::with_capacity(1001);
+        // Allocations skipped because of `max_alloc_size`, their collection must be skipped too.
+        let mut skipped: HSet<crate::prelude::AllocUid> = HSet::new();
+        // All the UIDs of the allocations skipped so far, in increasing order. Used to renumber the
+        // allocations that come after them.
+        let mut skipped_uids: Vec<crate::prelude::AllocUid> = vec![];
 
         parse! {
             bytes => |mut parser| {
+                if check_cache {
+                    parser.check_cache()
+                }
                 prof.basic_parsing.start();
 
                 let header = parser.header();
+                let total_bytes = parser.data().len();
+
+                if !suppress_deprecated_warning && header.version.is_deprecated() {
+                    base::log::warn!(
+                        "this dump uses the deprecated memtrace format {}, \
+                        it is supported but might not be parsed exactly right, \
+                        consider regenerating it with a newer version of memtrace (format {})",
+                        header.version,
+                        crate::version::Version::LATEST,
+                    )
+                }
 
                 // Start time of the run, used for init and to compute the time-since-start of all
                 // events.
                 let start_time = date_from_microsecs(header.timestamp.lbound);
-                // let end_time = date_from_microsecs(header.header.timestamp.end).sub(start_time)?;
+                let end_time = end_time(&header.timestamp);
 
-                // Init info.
-                let init = parser.trace_info().to_init(start_time);
+                let mut trace_info = parser.trace_info().clone();
+                if let Some(word_size) = word_size_override {
+                    if word_size != trace_info.word_size {
+                        base::log::warn!(
+                            "overriding the word size of the dump ({} bits) with {} bits",
+                            trace_info.word_size,
+                            word_size,
+                        );
+                        trace_info.word_size = word_size
+                    }
+                }
+
+                // Init info, comes with a fresh run identifier. Also used to convert allocation
+                // sizes from machine words to bytes.
+                let init = trace_info.to_init(start_time, end_time);
 
-                init_action(factory, init);
+                init_action(factory, init.clone());
                 prof.basic_parsing.stop();
 
                 // Iterate over the packet of the trace.
                 while let Some(mut packet_parser) = prof.packet_parsing.time(
                     || parser.next_packet()
                 )? {
-                    if packet_parser.header().id() % 10 == 9 {
-                        bytes_progress(packet_parser.real_position().0);
+                    stats.packets += 1;
+                    let pid = packet_parser.header().pid;
+                    let pos = packet_parser.real_position().0;
+                    if progress.new_packet(pos) {
+                        bytes_progress(pos, total_bytes);
                     }
 
                     // Iterate over the events of the packet.
@@ -258,81 +874,132 @@ mod diff_parse {
                     )? {
                         use crate::ast::event::Event;
 
-                        match event {
-                            Event::Alloc(crate::ast::event::Alloc {
-                                id: uid, backtrace, len, common_pref_len, nsamples, ..
-                            }) => {
-                                let trace = {
-                                    prof.trace_building.time(|| trace_builder.build_trace(
-                                        factory,
-                                        &loc_id_to_loc,
-                                        common_pref_len,
-                                        backtrace,
-                                    ))?
-                                };
-
-                                prof.alloc.start();
-
-                                // Build the allocation.
-                                let alloc = {
-                                    let time_since_start =
-                                        date_from_microsecs(clock) - start_time;
-                                    let labels = factory.empty_labels();
-                                    let alloc = alloc_data::Builder::new(
-                                        Some(uid.into()),
-                                        AllocKind::Minor,
-                                        convert(len, "ctf parser: alloc size"),
-                                        trace,
-                                        labels,
-                                        time_since_start,
-                                        None
-                                    ).nsamples(nsamples as u32);
-                                    alloc
-                                };
-
-                                prof.alloc.stop();
-
-                                prof.alloc_action.time(|| new_action(factory, alloc))
-                            },
-
-                            Event::Collection(alloc_uid) => {
-                                prof.dead.start();
-
-                                let uid = uid::Alloc::from(alloc_uid);
-                                let timestamp = date_from_microsecs(clock) - start_time;
-
-                                dead_action(&mut factory, timestamp, uid);
-
-                                prof.dead.stop();
-                            },
-                            Event::Locs(crate::ast::Locs { id, locs }) => {
-                                prof.locations.start();
-
-                                let locs = locs.into_iter().map(|loc| {
-                                    let file = factory.register_str(loc.file_path);
-                                    let line = loc.line;
-                                    let col = loc.col;
-
-                                    Loc::new(
-                                        file,
-                                        line,
-                                        Span {
-                                            lbound: col.lbound,
-                                            ubound: col.ubound,
-                                        },
-                                    )
-                                }).collect();
-
-                                let prev = loc_id_to_loc.insert(id, locs);
-                                prof.locations.stop();
-                                if prev.is_some() && prev.as_ref() != loc_id_to_loc.get(&id) {
-                                    bail!("[ctf parser] trying to register locations #{} twice", id)
-                                }
-                            },
-                            Event::Promotion(_) => {
-                                ()
-                            },
-                        }
+                        let handle_event = || -> Res<()> {
+                            match event {
+                                Event::Alloc(crate::ast::event::Alloc {
+                                    id: uid, backtrace, len, common_pref_len, nsamples, ..
+                                }) => {
+                                    let trace = {
+                                        prof.trace_building.time(|| trace_builder.build_trace(
+                                            factory,
+                                            &loc_id_to_loc,
+                                            common_pref_len,
+                                            backtrace,
+                                        ))?
+                                    };
+
+                                    if let Some(max) = max_alloc_size {
+                                        let bound = max.bound();
+                                        if try_convert::<usize, u64>(len, "ctf parser: alloc size")? > bound {
+                                            let msg = format!(
+                                                "allocation #{} has size {}, \
+                                                which exceeds the maximum allocation size {}",
+                                                uid, len, bound,
+                                            );
+                                            match max {
+                                                crate::MaxAllocSize::Fail(_) => bail!(msg),
+                                                crate::MaxAllocSize::Skip(_) => {
+                                                    base::log::warn!("{}, skipping it", msg);
+                                                    skipped.insert(uid);
+                                                    skipped_uids.push(uid);
+                                                    return Ok(())
+                                                }
+                                            }
+                                        }
+                                    }
+
+                                    prof.alloc.start();
+                                    stats.allocs += 1;
+
+                                    // Allocation UIDs are increasing, all skipped UIDs come before
+                                    // this one.
+                                    let uid = uid - skipped_uids.len() as crate::prelude::AllocUid;
+
+                                    // Build the allocation.
+                                    let alloc = {
+                                        let time_since_start =
+                                            date_from_microsecs(clock) - start_time;
+                                        let labels = factory.empty_labels();
+                                        let size = init.words_to_bytes(
+                                            try_convert(len, "ctf parser: alloc size")?
+                                        ).chain_err(|| format!("while handling allocation #{}", uid))?;
+                                        let alloc = alloc_data::Builder::new(
+                                            Some(uid.into()),
+                                            AllocKind::Minor,
+                                            size,
+                                            trace,
+                                            labels,
+                                            time_since_start,
+                                            None
+                                        ).nsamples(nsamples as u32).pid(pid);
+                                        alloc
+                                    };
+
+                                    prof.alloc.stop();
+
+                                    prof.alloc_action.time(|| new_action(factory, alloc))
+                                },
+
+                                Event::Collection(alloc_uid) => {
+                                    if skipped.remove(&alloc_uid) {
+                                        return Ok(())
+                                    }
+                                    prof.dead.start();
+                                    stats.deaths += 1;
+
+                                    let shift = skipped_uids.partition_point(|skipped| *skipped < alloc_uid);
+                                    let uid = uid::Alloc::from(alloc_uid - shift as crate::prelude::AllocUid);
+                                    let timestamp = date_from_microsecs(clock) - start_time;
+
+                                    dead_action(&mut factory, timestamp, uid);
+
+                                    prof.dead.stop();
+                                },
+                                Event::Locs(crate::ast::Locs { id, locs }) => {
+                                    prof.locations.start();
+                                    stats.locs += locs.len();
+
+                                    let locs = locs.into_iter().map(|loc| {
+                                        factory.register_loc(
+                                            loc.file_path,
+                                            loc.line,
+                                            Span {
+                                                lbound: loc.col.lbound,
+                                                ubound: loc.col.ubound,
+                                            },
+                                        )
+                                    }).collect();
+
+                                    let prev = loc_id_to_loc.insert(id, locs);
+                                    prof.locations.stop();
+                                    if let Some(prev) = prev {
+                                        if Some(&prev) != loc_id_to_loc.get(&id) {
+                                            let msg = format!(
+                                                "[ctf parser] trying to register locations #{} twice",
+                                                id,
+                                            );
+                                            match dup_locs {
+                                                crate::DupLocPolicy::Error => bail!(msg),
+                                                crate::DupLocPolicy::KeepFirst => {
+                                                    base::log::warn!("{}, keeping the first ones", msg);
+                                                    loc_id_to_loc.insert(id, prev);
+                                                }
+                                                crate::DupLocPolicy::Overwrite => {
+                                                    base::log::warn!("{}, overwriting them", msg)
+                                                }
+                                            }
+                                        }
+                                    }
+                                },
+                                Event::Promotion(_) => {
+                                    ()
+                                },
+                            }
+                            Ok(())
+                        };
+                        handle_event().chain_err(
+                            || format!("while handling {}", packet_parser.current_event_desc())
+                        )?
                     }
 
                     let packet_end = date_from_microsecs(
@@ -341,16 +1008,153 @@ mod diff_parse {
                     mark_timestamp(
                         factory,
                         packet_end,
-                    )
+                    );
+
+                    if !should_continue() {
+                        base::log::info!("parsing cancelled");
+                        break
+                    }
                 }
 
+                prof.total.stop();
+                stats.elapsed = start.elapsed();
+                stats.cache_mismatches = parser.cache_mismatches();
+                stats.durations = prof.durations();
+
                 prof.all_do(
-                    || base::log::info!("done parsing"),
+                    || {
+                        base::log::info!("done parsing");
+                        base::log::info!("| {:>25}: {}", "trace interning", factory.trace_stats());
+                        base::log::info!("| {:>25}: {}", "location interning", factory.loc_stats());
+                        base::log::info!("| {:>25}: {}", "string interning", factory.str_stats());
+                    },
                     |desc, sw| base::log::info!("| {:>25}: {}", desc, sw),
                 );
+                base::log::info!("{}", stats);
 
-                Ok(())
+                Ok(stats)
             }
         }
     }
+
+    /// Parses a CTF file (memtrace format), feeding the events to a sink.
+    ///
+    /// Dynamic-dispatch version of [`parse`], built on top of it. Each event costs a virtual call
+    /// that the compiler cannot inline, and allocations are built before being handed to the
+    /// sink instead of being passed as builders. This is negligible compared to parsing itself for
+    /// most sinks, but [`parse`] remains the way to go for Rust callers on hot paths.
+    ///
+    /// Fails if the dump has an allocation before its trace info. The interning factories are
+    /// locked while parsing, so the sink must not resolve the traces or labels of allocations
+    /// before this function returns.
+    ///
+    /// ```rust
+    /// use alloc_data::prelude::*;
+    /// #[derive(Default)]
+    /// struct Counter {
+    ///     init: bool,
+    ///     allocs: usize,
+    ///     deaths: usize,
+    /// }
+    /// impl ctf::EventSink for Counter {
+    ///     fn on_init(&mut self, _: Init) {
+    ///         self.init = true
+    ///     }
+    ///     fn on_alloc(&mut self, _: Alloc) {
+    ///         self.allocs += 1
+    ///     }
+    ///     fn on_dead(&mut self, _: time::SinceStart, _: uid::Alloc) {
+    ///         self.deaths += 1
+    ///     }
+    /// }
+    ///
+    /// let bytes = std::fs::read("rsc/lists_and_sets.ctf").unwrap();
+    /// let mut counter = Counter::default();
+    /// let sink: &mut dyn ctf::EventSink = &mut counter;
+    /// let stats = ctf::parse_with_sink(&bytes, ctf::ParseOptions::default(), sink).unwrap();
+    /// assert!(counter.init);
+    /// assert_eq!(counter.allocs, stats.allocs);
+    /// assert_eq!(counter.deaths, stats.deaths);
+    /// ```
+    ///
+    /// [`parse`]: crate::parse (parse function)
+    pub fn parse_with_sink(
+        bytes: &[u8],
+        options: crate::ParseOptions,
+        sink: &mut dyn crate::EventSink,
+    ) -> Res<crate::ParseStats> {
+        /// Factory forwarding the events to the sink.
+        struct SinkFactory<'a, 'sink> {
+            factory: mem::Factory<'a>,
+            sink: &'sink mut dyn crate::EventSink,
+            sample_rate: Option<SampleRate>,
+            error: Option<err::Error>,
+        }
+        impl<'a, 'sink> std::ops::Deref for SinkFactory<'a, 'sink> {
+            type Target = mem::Factory<'a>;
+            fn deref(&self) -> &Self::Target {
+                &self.factory
+            }
+        }
+        impl<'a, 'sink> std::ops::DerefMut for SinkFactory<'a, 'sink> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.factory
+            }
+        }
+        impl<'a, 'sink> SinkFactory<'a, 'sink> {
+            fn build(&self, builder: Builder) -> Res<Alloc> {
+                let sample_rate = self
+                    .sample_rate
+                    .as_ref()
+                    .ok_or("ctf parser produced an allocation before the trace info")?;
+                let uid = builder
+                    .uid_hint
+                    .ok_or("ctf parser produced an allocation without a UID")?;
+                builder.build(sample_rate, uid)
+            }
+        }
+
+        // Stops parsing as soon as building an allocation fails.
+        let failed = std::cell::Cell::new(false);
+        let mut factory = SinkFactory {
+            factory: mem::Factory::new(false),
+            sink,
+            sample_rate: None,
+            error: None,
+        };
+        let stats = parse(
+            bytes,
+            &mut factory,
+            options,
+            |_, _| (),
+            || !failed.get(),
+            |factory, init| {
+                factory.sample_rate = Some(init.sample_rate.clone());
+                factory.sink.on_init(init)
+            },
+            |factory, builder| {
+                if factory.error.is_some() {
+                    return;
+                }
+                match factory.build(builder) {
+                    Ok(alloc) => factory.sink.on_alloc(alloc),
+                    Err(e) => {
+                        factory.error = Some(e);
+                        failed.set(true)
+                    }
+                }
+            },
+            |factory, timestamp, uid| {
+                if factory.error.is_none() {
+                    factory.sink.on_dead(timestamp, uid)
+                }
+            },
+            |_, _| (),
+        )?;
+
+        match factory.error {
+            Some(e) => Err(e),
+            None => Ok(stats),
+        }
+    }
 }
@@ -739,10 +739,10 @@ decl_impl_trait! {
                 .chain_err(parse_error!(|| expected "variable-length usize"))?;
 
             let res = match variant {
-                0..=252 => convert(variant, "v_usize: u8"),
-                253 => convert(self.u16()?, "v_usize: u16"),
-                254 => convert(self.u32()?, "v_usize: u32"),
-                255 => convert(self.u64()?, "v_usize: u64"),
+                0..=252 => try_convert(variant, "v_usize: u8")?,
+                253 => try_convert(self.u16()?, "v_usize: u16")?,
+                254 => try_convert(self.u32()?, "v_usize: u32")?,
+                255 => try_convert(self.u64()?, "v_usize: u64")?,
             };
 
             Ok(res)
@@ -810,20 +810,45 @@ decl_impl_trait! {
         ///
         /// Used when retrieving the UID of a promotion/collection.
         ///
-        /// # Panics
+        /// Fails if the delta does not refer to an allocation UID generated previously, which
+        /// cannot happen on coherent CTF files.
         ///
-        /// Calling this function before any allocation UID is generated is a logical error. As long
-        /// as this function is used only for promotion/collection, it will not panic on coherent
-        /// CTF files. This is because promoting/collecting necessarily talks about an allocation
-        /// that was created previously, meaning at least one allocation UID was generated.
+        /// ```rust
+        /// use ctf::parse::{CanParse, Cxt, LeParser};
+        /// let mut cxt = Cxt::new();
+        /// // No allocation yet, no delta is legal.
+        /// let err = LeParser::new(&[0], 0).alloc_uid_from_delta(&cxt).unwrap_err();
+        /// assert!(err.to_string().contains("with 0 allocation(s) so far"));
         ///
-        /// > In debug, the code actually `debug_assert`s this. In release, the panic will be an
-        /// > arithmetic underflow.
+        /// for _ in 0..3 {
+        ///     cxt.next_alloc_id();
+        /// }
+        /// let mut bytes = vec![0, 2, 3];
+        /// // Largest delta possible, encoded on 8 bytes.
+        /// bytes.push(255);
+        /// bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        /// let mut parser = LeParser::new(&bytes, 0);
+        /// assert_eq!(parser.alloc_uid_from_delta(&cxt).unwrap(), 2);
+        /// assert_eq!(parser.alloc_uid_from_delta(&cxt).unwrap(), 0);
+        /// for delta in &[3, u64::MAX] {
+        ///     let err = parser.alloc_uid_from_delta(&cxt).unwrap_err();
+        ///     let found = format!("{} with 3 allocation(s) so far", delta);
+        ///     assert!(err.to_string().contains(&found), "{}", err);
+        /// }
+        /// ```
         fn alloc_uid_from_delta(&mut self, cxt: &Cxt<'data>) -> Res<u64> {
             let next_alloc_id = cxt.peek_next_alloc_id();
-            debug_assert!(next_alloc_id > 0);
             let id_delta = self.v_usize()? as u64;
-            Ok(next_alloc_id - 1 - id_delta)
+            next_alloc_id
+                .checked_sub(1)
+                .and_then(|last| last.checked_sub(id_delta))
+                .ok_or_else(|| {
+                    parse_error!(
+                        expected "allocation UID delta referring to a previous allocation",
+                        found format!("{} with {} allocation(s) so far", id_delta, next_alloc_id)
+                    )
+                    .into()
+                })
         }
 
         /// Parses some new locations.
@@ -831,8 +856,8 @@ decl_impl_trait! {
         /// Context-sensitive.
         fn locs(&mut self, cxt: &mut Cxt<'data>) -> Res<ast::Locs<'data>> {
             pinfo!(self, "    parsing locations");
-            let id = convert(self.u64()?, "locs: id");
-            let len = convert(self.u8()?, "locs: len");
+            let id = try_convert(self.u64()?, "locs: id")?;
+            let len = try_convert(self.u8()?, "locs: len")?;
             pinfo!(self, "    -> parsing {} location(s)", len);
             let mut locs = Vec::with_capacity(len);
             for _ in 0..len {
@@ -910,10 +935,73 @@ decl_impl_trait! {
         fn ctf_header(&mut self) -> Res<header::Ctf> {
             pinfo!(self, "parsing ctf header");
             self.raw_package_header(false)
-                .map(|(header, _)| header::Ctf::new(header, self.big_endian))
+                .map(|(header, _)| header::Ctf::new(header, self.is_big_endian()))
                 .chain_err(|| "while parsing ctf header")
         }
 
+        /// Checks the first header of a dump is plausible, fails with a targeted error otherwise.
+        ///
+        /// The endianness of the dump comes from its magic number alone. When the magic number is
+        /// corrupted, the endianness guess can be wrong and all multi-byte values are garbage. This
+        /// check catches such situations on the first header instead of failing on some absurd
+        /// size later on. A version is plausible if it is supported, a packet size is plausible if
+        /// it is a whole number of bytes that fits in the input.
+        ///
+        /// ```rust
+        /// use ctf::parse::{CanParse, LeParser};
+        /// let parser = LeParser::new(&[0; 64], 0);
+        /// let version = ctf::VERSION;
+        /// assert!(parser.check_ctf_header(64 * 8, version).is_ok());
+        ///
+        /// // Byte-swapped values, as decoded with the wrong endianness.
+        /// let err = parser
+        ///     .check_ctf_header((64u32 * 8).swap_bytes(), version.swap_bytes())
+        ///     .unwrap_err()
+        ///     .to_string();
+        /// assert!(err.contains("endianness"));
+        /// assert!(err.contains("parsing as big-endian"));
+        /// ```
+        fn check_ctf_header(&self, packet_size_bits: u32, version: u16) -> Res<()> {
+            let max_bits = (self.data().len() as u64) * 8;
+            let plausible = |packet_size_bits: u32, version: u16| {
+                Version::new(version).is_ok()
+                    && packet_size_bits % 8 == 0
+                    && (packet_size_bits as u64) <= max_bits
+            };
+            if plausible(packet_size_bits, version) {
+                return Ok(());
+            }
+
+            let (current, other) = if self.is_big_endian() {
+                ("big", "low")
+            } else {
+                ("low", "big")
+            };
+            let mut msg = format!(
+                "implausible ctf header parsed as {}-endian: \
+                version {}, packet size of {} bits for an input of {} bytes",
+                current,
+                version,
+                packet_size_bits,
+                self.data().len(),
+            );
+            let (swapped_size, swapped_version) =
+                (packet_size_bits.swap_bytes(), version.swap_bytes());
+            if plausible(swapped_size, swapped_version) {
+                msg.push_str(&format!(
+                    ", parsing as {}-endian yields a legal header (version {}, packet size of {} \
+                    bits): the magic number is probably corrupted, causing a wrong endianness guess",
+                    other, swapped_version, swapped_size,
+                ))
+            } else {
+                msg.push_str(
+                    ", the dump is probably corrupted, or its magic number is and \
+                    the endianness guess is wrong",
+                )
+            }
+            bail!(msg)
+        }
+
         /// Raw package header parser.
         ///
         /// Returns internal errors as they are, should only be called by [`ctf_header`] and
@@ -955,23 +1043,31 @@ decl_impl_trait! {
             );
             let alloc_id = Range::new(alloc_begin, alloc_end);
 
-            if VERSION == version {
-                ()
-            } else {
-                match (VERSION, version) {
-                    (2, 1) => (),
-                    _ => bail!("found trace format v{}, expected v{}", version, VERSION),
-                }
+            // The first header is where a wrong endianness guess shows, see `check_ctf_header`.
+            if !parse_magic {
+                self.check_ctf_header(packet_size_bits, version)?;
             }
 
-            let header_size: u32 = convert(self.pos() - start, "raw_package_header: header_size");
+            let version = Version::new(version)?;
+
+            let header_size: u32 =
+                try_convert(self.pos() - start, "raw_package_header: header_size")?;
 
             if packet_size_bits % 8 != 0 {
-                bail!("illegal packet size {}, not a legal number of bits")
+                bail!(
+                    "illegal packet size {}, not a legal number of bits",
+                    packet_size_bits
+                )
             }
 
             let total_content_size = packet_size_bits / 8;
-            let content_size = total_content_size - header_size;
+            let content_size = match total_content_size.checked_sub(header_size) {
+                Some(content_size) => content_size,
+                None => bail!(parse_error!(
+                    expected format!("packet size of at least {} bytes", header_size),
+                    found total_content_size
+                )),
+            };
             pinfo!(
                 self,
                 "    content size in bytes {} = ({} / 8) - {}",
@@ -999,8 +1095,17 @@ decl_impl_trait! {
         /// However, **currently** the trace info needs to be unique and appear between the CTF
         /// (top-level) header and the first package of the trace.
         fn trace_info(&mut self, header: &header::Ctf) -> Res<event::Info<'data>> {
+            header.version.trace_info(self)
+        }
+
+        /// Parses the fields of a trace info, version-agnostic.
+        ///
+        /// Should only be called by the [`TraceVersion`] implementations, `context` specifies
+        /// whether the trace info ends with a context string.
+        ///
+        /// [`TraceVersion`]: crate::version::TraceVersion (TraceVersion trait)
+        fn raw_trace_info(&mut self, context: bool) -> Res<event::Info<'data>> {
             pinfo!(self, "parsing trace info");
-            // let start_time = header.timestamp.begin;
             let sample_rate = self.f64()?;
             pinfo!(self, "    sample rate {}", sample_rate);
 
@@ -1019,7 +1124,7 @@ decl_impl_trait! {
             let pid = self.u64()?;
             pinfo!(self, "    pid {}", pid);
 
-            let context = if header.has_context() {
+            let context = if context {
                 Some(self.string()?)
             } else {
                 None
@@ -1056,6 +1161,8 @@ pub struct CtfParser<'data, Endian> {
     trace_info: ast::event::Info<'data>,
     cxt: Cxt<'data>,
     packet_count: usize,
+    /// Number of packets whose cache check does not match the backtrace cache, if checking.
+    cache_mismatches: Option<usize>,
 }
 impl<'data> CtfParser<'data, ()> {
     /// Constructor.
@@ -1082,6 +1189,7 @@ impl<'data> CtfParser<'data, ()> {
                 let trace_info = parser.trace_info(&header)?;
                 CtfParser {
                     parser, header, trace_info, cxt: Cxt::new(), packet_count: 0,
+                    cache_mismatches: None,
                 }
             }
         };
@@ -1116,6 +1224,20 @@ impl<'data, Endian> CtfParser<'data, Endian> {
     pub fn trace_info(&self) -> &ast::event::Info<'data> {
         &self.trace_info
     }
+
+    /// Activates the comparison of the cache check of the packet headers with the backtrace cache.
+    ///
+    /// Mismatches are only counted, see [`Self::cache_mismatches`].
+    pub fn check_cache(&mut self) {
+        self.cache_mismatches.get_or_insert(0);
+    }
+
+    /// Number of packets whose cache check did not match the backtrace cache so far.
+    ///
+    /// `None` unless [`Self::check_cache`] was called.
+    pub fn cache_mismatches(&self) -> Option<usize> {
+        self.cache_mismatches
+    }
 }
 
 /// Pseudo-parsers: parses a very tiny amout of data to produce a subparser.
@@ -1125,9 +1247,22 @@ where
 {
     /// Yields a [`PacketParser`] for the next packet, if any.
     pub fn next_packet<'me>(&'me mut self) -> Res<Option<PacketParser<'me, 'data, Endian>>> {
+        Ok(self
+            .next_packet_bytes()?
+            .map(move |(event_bytes, offset, packet_header)| {
+                PacketParser::<Endian>::new(event_bytes, offset, packet_header, &mut self.cxt)
+            }))
+    }
+
+    /// Parses the header of the next packet, if any.
+    ///
+    /// Yields the bytes of the events of the packet, their offset from the start of the input, and
+    /// the packet header.
+    fn next_packet_bytes(&mut self) -> Res<Option<(&'data [u8], usize, header::Packet)>> {
         let parser = &mut self.parser;
         let cxt = &mut self.cxt;
         let packet_count = &mut self.packet_count;
+        let cache_mismatches = &mut self.cache_mismatches;
 
         if parser.is_eof() {
             cxt.prof.all_do(
@@ -1139,7 +1274,13 @@ where
         pinfo!(parser, "parsing packet header");
 
         let packet_header = parser.packet_header(*packet_count)?;
-        let content_len: usize = convert(packet_header.content_size, "next_packet: content_len");
+        if let Some(count) = cache_mismatches {
+            if !cxt.btrace.cache_check_matches(packet_header.cache_check()) {
+                *count += 1
+            }
+        }
+        let content_len: usize =
+            try_convert(packet_header.content_size, "next_packet: content_len")?;
         pinfo!(
             parser,
             "next packet: {} bytes -> {}/{}",
@@ -1154,11 +1295,11 @@ where
             )))
         }
 
+        let offset = parser.real_position().0;
         let event_bytes = parser.take(content_len);
-        let next = PacketParser::<Endian>::new(event_bytes, *parser.pos(), packet_header, cxt);
         *packet_count += 1;
 
-        Ok(Some(next))
+        Ok(Some((event_bytes, offset, packet_header)))
     }
 }
 
@@ -1176,6 +1317,8 @@ pub struct PacketParser<'cxt, 'data, Endian> {
     header: header::Packet,
     /// Event counter.
     event_cnt: usize,
+    /// Index in the packet and position in the input of the current event, for error-reporting.
+    current_event: (usize, usize),
     /// Parsing context.
     cxt: &'cxt mut Cxt<'data>,
 }
@@ -1218,6 +1361,7 @@ where
             parser: Parser::new(input, offset),
             header,
             event_cnt: 0,
+            current_event: (0, offset),
             cxt,
         }
     }
@@ -1227,65 +1371,217 @@ where
         &self.header
     }
 
+    /// Number of events parsed so far.
+    pub fn event_count(&self) -> usize {
+        self.event_cnt
+    }
+
+    /// Describes the current event, *i.e.* the last one [`Self::next_event`] was called for.
+    ///
+    /// Used as error context, of the form `event 5 of packet 37 at byte 1234`.
+    pub fn current_event_desc(&self) -> String {
+        let (index, pos) = self.current_event;
+        format!(
+            "event {} of packet {} at byte {}",
+            index,
+            self.header.id(),
+            pos
+        )
+    }
+
     /// Returns the next event of the packet, if any.
+    ///
+    /// Errors mention the index of the event and its position, see
+    /// [`Self::current_event_desc`].
     pub fn next_event(&mut self) -> Res<Option<(Clock, Event<'data>)>> {
-        if self.is_eof() {
-            return Ok(None);
+        self.current_event = (self.event_cnt, self.parser.real_position().0);
+        let res = next_event(&mut self.parser, &self.header, self.cxt)
+            .chain_err(|| format!("while parsing {}", self.current_event_desc()))?;
+        if res.is_some() {
+            self.event_cnt += 1;
         }
+        Ok(res)
+    }
+}
 
-        self.cxt.prof.basic_parsing.start();
-        let (event_kind, event_timestamp) = self.parser.event_kind(&self.header)?;
-        self.cxt.prof.basic_parsing.stop();
+/// Parses the next event of a packet, if any.
+///
+/// - `parser`: parser over the bytes of the events of the packet;
+/// - `header`: header of the packet;
+/// - `cxt`: parsing context.
+fn next_event<'data, Endian>(
+    parser: &mut Parser<'data, Endian>,
+    header: &header::Packet,
+    cxt: &mut Cxt<'data>,
+) -> Res<Option<(Clock, Event<'data>)>>
+where
+    Parser<'data, Endian>: CanParse<'data>,
+{
+    if parser.is_eof() {
+        return Ok(None);
+    }
 
-        pinfo!(self, "event: {:?} ({})", event_kind, event_timestamp);
+    cxt.prof.basic_parsing.start();
+    let (event_kind, event_timestamp) = parser.event_kind(header)?;
+    cxt.prof.basic_parsing.stop();
 
-        let parser = &mut self.parser;
-        let cxt = &mut self.cxt;
+    pinfo!(parser, "event: {:?} ({})", event_kind, event_timestamp);
 
-        let event = match event_kind {
-            event::Kind::Alloc => {
-                cxt.prof.alloc.start();
-                let alloc = parser.alloc(event_timestamp, cxt, None)?;
-                cxt.prof.alloc.stop();
-                Event::Alloc(alloc)
-            }
-            event::Kind::SmallAlloc(n) => {
-                cxt.prof.alloc.start();
-                let alloc = parser.alloc(
-                    event_timestamp,
-                    cxt,
-                    Some(convert(n, "event: SmallAlloc(n)")),
-                )?;
-                cxt.prof.alloc.stop();
-                Event::Alloc(alloc)
-            }
-            event::Kind::Promotion => {
-                let alloc_id = parser.alloc_uid_from_delta(cxt)?;
-                Event::Promotion(alloc_id)
-            }
-            event::Kind::Collection => {
-                cxt.prof.collection.start();
-                let alloc_id = parser.alloc_uid_from_delta(cxt)?;
-                cxt.prof.collection.stop();
-                Event::Collection(alloc_id)
+    let event = match event_kind {
+        event::Kind::Alloc => {
+            cxt.prof.alloc.start();
+            let alloc = header.version.alloc(parser, event_timestamp, cxt, None)?;
+            cxt.prof.alloc.stop();
+            Event::Alloc(alloc)
+        }
+        event::Kind::SmallAlloc(n) => {
+            cxt.prof.alloc.start();
+            let alloc = header.version.alloc(
+                parser,
+                event_timestamp,
+                cxt,
+                Some(try_convert(n, "event: SmallAlloc(n)")?),
+            )?;
+            cxt.prof.alloc.stop();
+            Event::Alloc(alloc)
+        }
+        event::Kind::Promotion => {
+            let alloc_id = parser.alloc_uid_from_delta(cxt)?;
+            Event::Promotion(alloc_id)
+        }
+        event::Kind::Collection => {
+            cxt.prof.collection.start();
+            let alloc_id = parser.alloc_uid_from_delta(cxt)?;
+            cxt.prof.collection.stop();
+            Event::Collection(alloc_id)
+        }
+        event::Kind::Locs => {
+            cxt.prof.locs.start();
+            let locs = header.version.locs(parser, cxt)?;
+            cxt.prof.locs.stop();
+            Event::Locs(locs)
+        }
+
+        // Can't have more than two info events.
+        event::Kind::Info => bail!(
+            parse_error!(expected "non-info event: having more than two info events is illegal")
+        ),
+    };
+
+    pinfo!(parser, "    {:?}", event);
+
+    Ok(Some((event_timestamp, event)))
+}
+
+/// Iterator over the events of a CTF dump, for a given endian convention.
+///
+/// Used internally by [`EventStream`], which hides the endian convention.
+struct EndianEventStream<'data, Endian> {
+    /// Top-level parser.
+    parser: CtfParser<'data, Endian>,
+    /// Parser over the events of the current packet, and the header of this packet.
+    packet: Option<(Parser<'data, Endian>, Arc<header::Packet>)>,
+}
+impl<'data, Endian> EndianEventStream<'data, Endian>
+where
+    Parser<'data, Endian>: CanParse<'data>,
+{
+    /// Constructor.
+    fn new(parser: CtfParser<'data, Endian>) -> Self {
+        Self {
+            parser,
+            packet: None,
+        }
+    }
+
+    /// Yields the next event of the dump, if any.
+    fn next_event(&mut self) -> Res<Option<(Arc<header::Packet>, Clock, Event<'data>)>> {
+        loop {
+            if let Some((parser, header)) = self.packet.as_mut() {
+                if let Some((clock, event)) = next_event(parser, header, &mut self.parser.cxt)? {
+                    return Ok(Some((header.clone(), clock, event)));
+                }
             }
-            event::Kind::Locs => {
-                cxt.prof.locs.start();
-                let locs = parser.locs(cxt)?;
-                cxt.prof.locs.stop();
-                Event::Locs(locs)
+
+            match self.parser.next_packet_bytes()? {
+                Some((event_bytes, offset, header)) => {
+                    self.packet = Some((Parser::new(event_bytes, offset), Arc::new(header)))
+                }
+                None => {
+                    self.packet = None;
+                    return Ok(None);
+                }
             }
+        }
+    }
+}
 
-            // Can't have more than two info events.
-            event::Kind::Info => bail!(
-                parse_error!(expected "non-info event: having more than two info events is illegal")
+/// Iterator over the events of a CTF dump.
+///
+/// Alternative to the callback-based [`parse`][crate::parse] function, constructed by
+/// [`events`][crate::events]. Yields each event along with its timestamp and the header of the
+/// packet it belongs to. Packet headers are shared by all the events of a packet, hence the `Arc`.
+///
+/// The iterator is fused: it yields `None` forever after the last event or after the first error.
+pub struct EventStream<'data> {
+    /// Actual stream, `None` once the stream is exhausted or has failed.
+    stream:
+        Option<Either<EndianEventStream<'data, BigEndian>, EndianEventStream<'data, LowEndian>>>,
+    /// CTF header of the dump.
+    header: header::Ctf,
+    /// Trace info of the dump.
+    trace_info: ast::event::Info<'data>,
+}
+impl<'data> EventStream<'data> {
+    /// Constructor, parses the CTF header and the trace info of the dump.
+    pub fn new(bytes: &'data [u8]) -> Res<Self> {
+        let (header, trace_info, stream) = match CtfParser::new(bytes)? {
+            Either::Left(parser) => (
+                parser.header().clone(),
+                parser.trace_info().clone(),
+                Either::Left(EndianEventStream::new(parser)),
+            ),
+            Either::Right(parser) => (
+                parser.header().clone(),
+                parser.trace_info().clone(),
+                Either::Right(EndianEventStream::new(parser)),
             ),
         };
+        Ok(Self {
+            stream: Some(stream),
+            header,
+            trace_info,
+        })
+    }
 
-        pinfo!(parser, "    {:?}", event);
-
-        self.event_cnt += 1;
+    /// CTF header of the dump.
+    pub fn header(&self) -> &header::Ctf {
+        &self.header
+    }
+    /// Trace info of the dump.
+    pub fn trace_info(&self) -> &ast::event::Info<'data> {
+        &self.trace_info
+    }
+}
 
-        Ok(Some((event_timestamp, event)))
+impl<'data> Iterator for EventStream<'data> {
+    type Item = Res<(Arc<header::Packet>, Clock, Event<'data>)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = match self.stream.as_mut()? {
+            Either::Left(stream) => stream.next_event(),
+            Either::Right(stream) => stream.next_event(),
+        };
+        match res {
+            Ok(Some(next)) => Some(Ok(next)),
+            Ok(None) => {
+                self.stream = None;
+                None
+            }
+            Err(e) => {
+                self.stream = None;
+                Some(Err(e))
+            }
+        }
     }
 }
+impl<'data> std::iter::FusedIterator for EventStream<'data> {}
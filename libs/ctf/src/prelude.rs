@@ -21,7 +21,11 @@
 
 pub use base::prelude::*;
 
-pub use crate::{parse::CanParse, *};
+pub use crate::{
+    parse::CanParse,
+    version::{TraceVersion, Version},
+    *,
+};
 
 /// A duration since the start of the run as microseconds.
 pub type Clock = u64;
@@ -0,0 +1,311 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Synthetic memtrace CTF dumps.
+//!
+//! Generates small, deterministic, low-endian dumps in the most recent memtrace format. Used by the
+//! benchmarks of this crate, which run on the `rsc/synthetic.ctf` fixture. The fixture is generated
+//! by the `synthetic` example:
+//!
+//! ```text
+//! cargo run -p ctf --example synthetic
+//! ```
+
+prelude! {}
+
+/// Memtrace CTF magic number.
+const MAGIC: u32 = 0xc1fc1fc1;
+/// Length of the timestamp part of an event header, in bits.
+const EVENT_HEADER_TIME_LEN: u32 = 25;
+/// Mask for the timestamp part of an event header.
+const EVENT_HEADER_TIME_MASK: u64 = (1 << EVENT_HEADER_TIME_LEN) - 1;
+/// Location index meaning *not in the MTF table*, forces the location to carry its strings.
+const LOC_NOT_FOUND: u64 = 31;
+
+/// Start of the run, in microseconds.
+const START: Clock = 1_600_000_000_000_000;
+/// Time between two allocations, in microseconds.
+const ALLOC_DELTA: Clock = 10;
+
+/// Description of a synthetic dump.
+///
+/// The dump starts with a location event for each allocation site, then allocation `i` happens at
+/// `10 * i` microseconds with a backtrace of `depth` sites. Each packet ends with the collection of
+/// one third of the allocations of the packet. The second packet starts by declaring the locations
+/// of the first `redefined_sites` sites again, on a different line.
+///
+/// ```rust
+/// let synth = ctf::synth::Synthetic {
+///     allocs: 1_000,
+///     sites: 16,
+///     depth: 5,
+///     allocs_per_packet: 300,
+///     redefined_sites: 0,
+/// };
+/// let bytes = synth.to_bytes();
+///
+/// let mut factory = Box::new(alloc_data::mem::Factory::new(false));
+/// let mut dead = 0;
+/// let stats = ctf::parse(
+///     &bytes,
+///     &mut factory,
+///     ctf::ParseOptions::default(),
+///     |_, _| (),
+///     || true,
+///     |_, _| (),
+///     |_, _| (),
+///     |_, _, _| dead += 1,
+///     |_, _| (),
+/// )
+/// .unwrap();
+/// assert_eq!(stats.allocs, 1_000);
+/// assert_eq!(stats.locs, 16);
+/// assert_eq!(stats.packets, 4);
+/// assert_eq!(dead, 334);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Synthetic {
+    /// Number of allocations.
+    pub allocs: usize,
+    /// Number of allocation sites, at most `2^14`.
+    pub sites: usize,
+    /// Depth of the backtraces.
+    pub depth: usize,
+    /// Number of allocations in each packet.
+    pub allocs_per_packet: usize,
+    /// Number of sites whose location is declared a second time, with a different line.
+    ///
+    /// Not legal in memtrace, see [`DupLocPolicy`](crate::DupLocPolicy).
+    pub redefined_sites: usize,
+}
+
+impl Default for Synthetic {
+    /// Parameters of the `rsc/synthetic.ctf` fixture.
+    fn default() -> Self {
+        Self {
+            allocs: 5_000,
+            sites: 64,
+            depth: 8,
+            allocs_per_packet: 500,
+            redefined_sites: 0,
+        }
+    }
+}
+
+impl Synthetic {
+    /// Generates the bytes of the dump.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        assert!(self.sites > 0 && self.sites <= 1 << 14);
+        assert!(self.allocs_per_packet > 0);
+
+        let end = START + ALLOC_DELTA * (self.allocs as Clock + 1);
+        let mut bytes = vec![];
+
+        // CTF header and trace info.
+        let mut info = Writer::new();
+        info.event_header(0, START);
+        info.f64(1.0);
+        info.u8(64);
+        info.string("synthetic");
+        info.string("localhost");
+        info.string("");
+        info.u64(1);
+        info.string("");
+        Writer::header(&mut bytes, false, (START, end), (0, 0), &info.bytes);
+
+        // Backtrace cache, bucket `n` is used for site `n` only.
+        let mut cached = vec![false; self.sites];
+        let mut first = 0;
+
+        loop {
+            let last = (first + self.allocs_per_packet).min(self.allocs);
+            let begin = START + ALLOC_DELTA * first as Clock;
+            let mut events = Writer::new();
+
+            if first == 0 {
+                for site in 0..self.sites {
+                    events.locs(begin, site, site as u64);
+                }
+            } else if first == self.allocs_per_packet {
+                for site in 0..self.redefined_sites.min(self.sites) {
+                    events.locs(begin, site, site as u64 + 1);
+                }
+            }
+
+            for alloc in first..last {
+                let time = START + ALLOC_DELTA * alloc as Clock;
+                let sites = (0..self.depth).map(|frame| (alloc + frame * 7) % self.sites);
+                events.alloc(time, 1 + (alloc * 7) % 100, sites, &mut cached);
+            }
+            let end = START + ALLOC_DELTA * last as Clock;
+            for alloc in (first..last).filter(|alloc| alloc % 3 == 0) {
+                events.collection(end, last - 1 - alloc);
+            }
+
+            Writer::header(
+                &mut bytes,
+                true,
+                (begin, end),
+                (first as u64, last as u64),
+                &events.bytes,
+            );
+            if last >= self.allocs {
+                break;
+            }
+            first = last;
+        }
+
+        bytes
+    }
+}
+
+/// Low-endian writer.
+struct Writer {
+    bytes: Vec<u8>,
+}
+impl Writer {
+    fn new() -> Self {
+        Self { bytes: vec![] }
+    }
+
+    fn u8(&mut self, n: u8) {
+        self.bytes.push(n)
+    }
+    fn u16(&mut self, n: u16) {
+        self.bytes.extend_from_slice(&n.to_le_bytes())
+    }
+    fn u32(&mut self, n: u32) {
+        self.bytes.extend_from_slice(&n.to_le_bytes())
+    }
+    fn u64(&mut self, n: u64) {
+        self.bytes.extend_from_slice(&n.to_le_bytes())
+    }
+    fn f64(&mut self, n: f64) {
+        self.bytes.extend_from_slice(&n.to_le_bytes())
+    }
+    fn string(&mut self, s: &str) {
+        self.bytes.extend_from_slice(s.as_bytes());
+        self.bytes.push(0)
+    }
+    /// Memtrace's variable-length format, see [`CanParse::v_usize`].
+    fn v_usize(&mut self, n: usize) {
+        if n < 253 {
+            self.u8(n as u8)
+        } else if n <= u16::MAX as usize {
+            self.u8(253);
+            self.u16(n as u16)
+        } else if n <= u32::MAX as usize {
+            self.u8(254);
+            self.u32(n as u32)
+        } else {
+            self.u8(255);
+            self.u64(n as u64)
+        }
+    }
+
+    fn event_header(&mut self, code: u32, time: Clock) {
+        self.u32((code << EVENT_HEADER_TIME_LEN) | (time & EVENT_HEADER_TIME_MASK) as u32)
+    }
+
+    /// A location event declaring a single location for `site`.
+    fn locs(&mut self, time: Clock, site: usize, line: u64) {
+        self.event_header(1, time);
+        self.u64(site as u64);
+        self.u8(1);
+        let (line, start, end) = (line % 0xfffff, 0u64, 10u64);
+        let encoded =
+            line | (start << 20) | (end << 28) | (LOC_NOT_FOUND << 38) | (LOC_NOT_FOUND << 43);
+        self.u32(encoded as u32);
+        self.u16((encoded >> 32) as u16);
+        self.string(&format!("synthetic_{}.ml", site % 8));
+        self.string(&format!("f_{}", site))
+    }
+
+    /// An allocation event, backtraces do not use prefix-sharing nor prediction.
+    fn alloc(
+        &mut self,
+        time: Clock,
+        len: usize,
+        sites: impl Iterator<Item = usize> + Clone,
+        cached: &mut [bool],
+    ) {
+        self.event_header(2, time);
+        self.v_usize(len);
+        self.v_usize(1);
+        self.u8(0);
+        self.v_usize(0);
+        self.u16(sites.clone().count() as u16);
+        for site in sites {
+            let bucket = (site as u16) << 2;
+            if cached[site] {
+                self.u16(bucket)
+            } else {
+                cached[site] = true;
+                self.u16(bucket | 3);
+                self.u64(site as u64)
+            }
+        }
+    }
+
+    /// A collection event, `delta` is relative to the most recent allocation.
+    fn collection(&mut self, time: Clock, delta: usize) {
+        self.event_header(4, time);
+        self.v_usize(delta)
+    }
+
+    /// Writes a header and its content.
+    ///
+    /// Both the CTF header and packet headers start with the magic number, but it is only part of
+    /// the size of packet headers.
+    fn header(
+        bytes: &mut Vec<u8>,
+        is_packet: bool,
+        (begin, end): (Clock, Clock),
+        (alloc_begin, alloc_end): (u64, u64),
+        content: &[u8],
+    ) {
+        let mut header = Self::new();
+        header.u32(MAGIC);
+        let start = header.bytes.len();
+        // Packet size, set below.
+        header.u32(0);
+        header.u64(begin);
+        header.u64(end);
+        header.u32(0);
+        header.u16(crate::VERSION);
+        header.u64(1);
+        // Cache check.
+        header.u16(0);
+        header.u16(0);
+        header.u64(0);
+        header.u64(alloc_begin);
+        header.u64(alloc_end);
+
+        let header_len = if is_packet {
+            header.bytes.len()
+        } else {
+            header.bytes.len() - start
+        };
+        let size_bits = ((header_len + content.len()) * 8) as u32;
+        header.bytes[start..start + 4].copy_from_slice(&size_bits.to_le_bytes());
+
+        bytes.extend(header.bytes);
+        bytes.extend_from_slice(content)
+    }
+}
@@ -0,0 +1,204 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Memtrace format versions.
+//!
+//! Each version of the memtrace format is a type implementing [`TraceVersion`], which specifies how
+//! the version-dependent parts of a dump are parsed. The default implementations of the functions
+//! of the trait correspond to the most recent format; older versions only override what changed.
+//!
+//! The parser dispatches on the version of each header through [`Version`], which is generated by
+//! the `versions!` macro below. Supporting a new version (say v3) boils down to
+//!
+//! - declaring a `V3` type implementing [`TraceVersion`], and
+//! - adding `V3` to the `versions!` invocation.
+//!
+//! ```rust
+//! use ctf::version::Version;
+//! assert_eq!(Version::new(1).unwrap(), Version::V1);
+//! assert_eq!(Version::new(2).unwrap().to_string(), "v2");
+//! assert_eq!(Version::LATEST.number(), ctf::VERSION);
+//!
+//! let err = Version::new(7).unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "unsupported trace format v7, supported versions: v1, v2",
+//! );
+//! ```
+
+prelude! {}
+
+use ast::event;
+use parse::Cxt;
+
+/// Version-dependent parsing functions.
+pub trait TraceVersion {
+    /// Version number, as it appears in memtrace headers.
+    const VERSION: u16;
+
+    /// Parses a trace info.
+    fn trace_info<'data, P: CanParse<'data>>(parser: &mut P) -> Res<event::Info<'data>> {
+        parser.raw_trace_info(true)
+    }
+
+    /// Parses an allocation.
+    ///
+    /// Context-sensitive.
+    fn alloc<'data, P: CanParse<'data>>(
+        parser: &mut P,
+        timestamp: u64,
+        cxt: &mut Cxt<'data>,
+        short: Option<usize>,
+    ) -> Res<event::Alloc> {
+        parser.alloc(timestamp, cxt, short)
+    }
+
+    /// Parses some new locations.
+    ///
+    /// Context-sensitive.
+    fn locs<'data, P: CanParse<'data>>(
+        parser: &mut P,
+        cxt: &mut Cxt<'data>,
+    ) -> Res<ast::Locs<'data>> {
+        parser.locs(cxt)
+    }
+}
+
+/// Memtrace format v1.
+///
+/// Same as [`V2`], except that trace infos have no context.
+#[derive(Debug, Clone, Copy)]
+pub struct V1;
+impl TraceVersion for V1 {
+    const VERSION: u16 = 1;
+    fn trace_info<'data, P: CanParse<'data>>(parser: &mut P) -> Res<event::Info<'data>> {
+        parser.raw_trace_info(false)
+    }
+}
+
+/// Memtrace format v2.
+#[derive(Debug, Clone, Copy)]
+pub struct V2;
+impl TraceVersion for V2 {
+    const VERSION: u16 = 2;
+}
+
+/// Generates the [`Version`] enum and its dispatch functions from a list of [`TraceVersion`]s.
+macro_rules! versions {
+    ($($version:ident),* $(,)?) => {
+        /// Memtrace format versions supported by the parser.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum Version {
+            $(
+                #[doc = "See [`"]
+                #[doc = stringify!($version)]
+                #[doc = "`]."]
+                $version,
+            )*
+        }
+
+        impl Version {
+            /// All the supported versions, in increasing order.
+            pub const ALL: &'static [Self] = &[$(Self::$version),*];
+
+            /// Constructor from a version number, fails on unsupported versions.
+            pub fn new(version: u16) -> Res<Self> {
+                $(
+                    if version == $version::VERSION {
+                        return Ok(Self::$version);
+                    }
+                )*
+                bail!(
+                    "unsupported trace format v{}, supported versions: {}",
+                    version,
+                    Self::ALL
+                        .iter()
+                        .map(Self::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            }
+
+            /// Version number, as it appears in memtrace headers.
+            pub fn number(self) -> u16 {
+                match self {
+                    $(Self::$version => $version::VERSION,)*
+                }
+            }
+
+            /// Parses a trace info.
+            pub fn trace_info<'data, P: CanParse<'data>>(
+                self,
+                parser: &mut P,
+            ) -> Res<event::Info<'data>> {
+                match self {
+                    $(Self::$version => $version::trace_info(parser),)*
+                }
+            }
+
+            /// Parses an allocation.
+            pub fn alloc<'data, P: CanParse<'data>>(
+                self,
+                parser: &mut P,
+                timestamp: u64,
+                cxt: &mut Cxt<'data>,
+                short: Option<usize>,
+            ) -> Res<event::Alloc> {
+                match self {
+                    $(Self::$version => $version::alloc(parser, timestamp, cxt, short),)*
+                }
+            }
+
+            /// Parses some new locations.
+            pub fn locs<'data, P: CanParse<'data>>(
+                self,
+                parser: &mut P,
+                cxt: &mut Cxt<'data>,
+            ) -> Res<ast::Locs<'data>> {
+                match self {
+                    $(Self::$version => $version::locs(parser, cxt),)*
+                }
+            }
+        }
+    };
+}
+
+versions! { V1, V2 }
+
+impl Version {
+    /// Most recent version supported.
+    pub const LATEST: Self = Self::V2;
+
+    /// True if the version is deprecated, *i.e.* not the most recent one.
+    ///
+    /// ```rust
+    /// use ctf::version::Version;
+    /// assert!(Version::V1.is_deprecated());
+    /// assert!(!Version::LATEST.is_deprecated());
+    /// ```
+    pub fn is_deprecated(self) -> bool {
+        self < Self::LATEST
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "v{}", self.number())
+    }
+}
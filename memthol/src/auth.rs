@@ -0,0 +1,297 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Optional shared-secret token gating the UI.
+//!
+//! When a token is set, the HTTP server and the websocket listener reject (with a `401`) the
+//! requests that do not provide it, either
+//!
+//! - as a `token` query parameter, *e.g.* `http://localhost:7878/?token=<token>`;
+//! - in an `Authorization: Bearer <token>` header; or
+//! - in the [`COOKIE`] cookie, which the server sets when serving the index page. The cookie is
+//!   restricted to HTTPS when TLS is on, see [`set_secure_cookie`].
+//!
+//! This is only a minimal access control: without TLS (see [`crate::tls`]), the token travels in
+//! clear text and can be sniffed by anyone on the network. It should always be combined with TLS.
+//!
+//! The `/healthz` and `/metrics` probes are gated by the token too, unless [`set_public_probes`]
+//! exempts them so that monitoring tools can query them without knowing the token.
+
+use crate::prelude::*;
+
+/// Name of the cookie holding the token.
+pub const COOKIE: &str = "memthol_token";
+
+lazy_static! {
+    /// Token required to access the UI, if any.
+    static ref TOKEN: sync::RwLock<Option<String>> = sync::RwLock::new(None);
+}
+
+/// True if the `/healthz` and `/metrics` probes do not require the token.
+static PUBLIC_PROBES: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// True if the cookie holding the token is restricted to HTTPS.
+static SECURE_COOKIE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Exempts (or not) the `/healthz` and `/metrics` probes from access control.
+pub fn set_public_probes(public: bool) {
+    PUBLIC_PROBES.store(public, std::sync::atomic::Ordering::SeqCst)
+}
+
+/// True if the `/healthz` and `/metrics` probes are exempted from access control.
+pub fn public_probes() -> bool {
+    PUBLIC_PROBES.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Restricts (or not) the cookie holding the token to HTTPS, should be set when TLS is on.
+pub fn set_secure_cookie(secure: bool) {
+    SECURE_COOKIE.store(secure, std::sync::atomic::Ordering::SeqCst)
+}
+
+/// True if the cookie holding the token is restricted to HTTPS.
+pub fn secure_cookie() -> bool {
+    SECURE_COOKIE.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Checks that a token is legal: non-empty and only made of URL-safe characters.
+pub fn check_token(token: &str) -> Res<()> {
+    if token.is_empty() {
+        bail!("illegal empty token")
+    }
+    if let Some(c) = token
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || "-._~".contains(*c)))
+    {
+        bail!(
+            "illegal character `{}` in token, expected ASCII alphanumerics, `-`, `.`, `_` or `~`",
+            c
+        )
+    }
+    Ok(())
+}
+
+/// Sets the token required to access the UI, `None` to deactivate access control.
+pub fn set_token(token: Option<String>) -> Res<()> {
+    if let Some(token) = token.as_ref() {
+        check_token(token)?
+    }
+    let mut current = TOKEN
+        .write()
+        .map_err(|e| err::Error::from(e.to_string()))
+        .chain_err(|| "while setting the access token")?;
+    *current = token;
+    Ok(())
+}
+
+/// Token required to access the UI, if any.
+fn token() -> Option<String> {
+    match TOKEN.read() {
+        Ok(token) => token.clone(),
+        Err(e) => {
+            // Fail closed.
+            log::error!("failed to access the access token: {}", e);
+            Some(String::new())
+        }
+    }
+}
+
+/// Compares two strings in constant time, *w.r.t.* the content of the strings.
+fn secure_eq(s1: &str, s2: &str) -> bool {
+    s1.len() == s2.len()
+        && s1
+            .bytes()
+            .zip(s2.bytes())
+            .fold(0, |acc, (b1, b2)| acc | (b1 ^ b2))
+            == 0
+}
+
+/// Value of the `Set-Cookie` header storing the token client-side, if a token is set.
+///
+/// The cookie has the `Secure` attribute if [`set_secure_cookie`] activated it.
+pub fn set_cookie() -> Option<String> {
+    token().map(|token| cookie(&token, secure_cookie()))
+}
+
+/// Value of the `Set-Cookie` header storing some token client-side.
+fn cookie(token: &str, secure: bool) -> String {
+    format!(
+        "{}={}; HttpOnly; SameSite=Strict{}",
+        COOKIE,
+        token,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+/// True if a request is authorized.
+///
+/// The inputs are the query of the request's URI, and the values of its `Authorization` and
+/// `Cookie` headers. Always true if no token is set.
+pub fn is_authorized(
+    query: Option<&str>,
+    authorization: Option<&str>,
+    cookie: Option<&str>,
+) -> bool {
+    match token() {
+        Some(token) => authorizes(&token, query, authorization, cookie),
+        None => true,
+    }
+}
+
+/// True if a request provides some token, see [`is_authorized`].
+fn authorizes(
+    token: &str,
+    query: Option<&str>,
+    authorization: Option<&str>,
+    cookie: Option<&str>,
+) -> bool {
+    let from_query = query.into_iter().flat_map(|query| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.strip_prefix("token="))
+    });
+    let from_authorization = authorization
+        .and_then(|auth| auth.trim().strip_prefix("Bearer "))
+        .map(str::trim);
+    let from_cookie = cookie.into_iter().flat_map(|cookie| {
+        cookie
+            .split(';')
+            .filter_map(|pair| pair.trim().strip_prefix(COOKIE)?.strip_prefix('='))
+    });
+    let mut candidates = from_query.chain(from_authorization).chain(from_cookie);
+    candidates.any(|candidate| secure_eq(candidate, token))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TOKEN: &str = "s3cr3t-T0k3n";
+
+    fn query(query: &str) -> bool {
+        authorizes(TOKEN, Some(query), None, None)
+    }
+    fn bearer(authorization: &str) -> bool {
+        authorizes(TOKEN, None, Some(authorization), None)
+    }
+    fn cookie_header(cookie: &str) -> bool {
+        authorizes(TOKEN, None, None, Some(cookie))
+    }
+
+    #[test]
+    fn no_token() {
+        // Only this test touches the global token.
+        set_token(None).unwrap();
+        assert!(is_authorized(None, None, None));
+        assert!(is_authorized(Some("token=whatever"), None, None));
+        assert_eq!(set_cookie(), None);
+
+        set_token(Some(TOKEN.into())).unwrap();
+        assert!(!is_authorized(None, None, None));
+        assert!(is_authorized(Some(&format!("token={}", TOKEN)), None, None));
+        set_token(None).unwrap();
+    }
+
+    #[test]
+    fn missing() {
+        assert!(!authorizes(TOKEN, None, None, None));
+        assert!(!query(""));
+        assert!(!bearer(""));
+        assert!(!cookie_header(""));
+    }
+
+    #[test]
+    fn in_query() {
+        assert!(query(&format!("token={}", TOKEN)));
+        assert!(query(&format!("a=b&token={}&c=d", TOKEN)));
+        assert!(!query("token=wrong"));
+        assert!(!query("token="));
+        assert!(!query(TOKEN));
+    }
+
+    #[test]
+    fn in_authorization() {
+        assert!(bearer(&format!("Bearer {}", TOKEN)));
+        assert!(bearer(&format!("  Bearer  {}  ", TOKEN)));
+        assert!(!bearer("Bearer wrong"));
+        assert!(!bearer(&format!("Basic {}", TOKEN)));
+        assert!(!bearer(&format!("bearer {}", TOKEN)));
+        assert!(!bearer(TOKEN));
+    }
+
+    #[test]
+    fn in_cookie() {
+        assert!(cookie_header(&format!("{}={}", COOKIE, TOKEN)));
+        assert!(cookie_header(&format!("a=b; {}={}; c=d", COOKIE, TOKEN)));
+        assert!(!cookie_header(&format!("{}=wrong", COOKIE)));
+        assert!(!cookie_header(&format!("token={}", TOKEN)));
+    }
+
+    #[test]
+    fn prefix_and_suffix() {
+        let (prefix, suffix) = (&TOKEN[..TOKEN.len() - 1], &TOKEN[1..]);
+        let longer = format!("{}x", TOKEN);
+        for candidate in &[prefix, suffix, longer.as_str()] {
+            assert!(!query(&format!("token={}", candidate)));
+            assert!(!bearer(&format!("Bearer {}", candidate)));
+            assert!(!cookie_header(&format!("{}={}", COOKIE, candidate)));
+        }
+    }
+
+    #[test]
+    fn look_alike_keys() {
+        assert!(!query(&format!("xtoken={}", TOKEN)));
+        assert!(!query(&format!("tokenx={}", TOKEN)));
+        assert!(!query(&format!("a=token={}", TOKEN)));
+        assert!(!cookie_header(&format!("x{}={}", COOKIE, TOKEN)));
+        assert!(!cookie_header(&format!("{}x={}", COOKIE, TOKEN)));
+        assert!(!cookie_header(&format!("a={}={}", COOKIE, TOKEN)));
+    }
+
+    #[test]
+    fn legal_tokens() {
+        check_token(TOKEN).unwrap();
+        check_token("a.b_c~d-e").unwrap();
+        assert!(check_token("").is_err());
+        for illegal in &["a b", "a&b", "a;b", "a=b", "tökén", "a\nb"] {
+            assert!(check_token(illegal).is_err(), "{}", illegal)
+        }
+        assert!(set_token(Some(String::new())).is_err());
+    }
+
+    #[test]
+    fn constant_time_eq() {
+        assert!(secure_eq(TOKEN, TOKEN));
+        assert!(secure_eq("", ""));
+        assert!(!secure_eq(TOKEN, ""));
+        assert!(!secure_eq("abc", "abd"));
+        assert!(!secure_eq("abc", "abcd"));
+    }
+
+    #[test]
+    fn cookie_attributes() {
+        assert_eq!(
+            cookie(TOKEN, false),
+            format!("{}={}; HttpOnly; SameSite=Strict", COOKIE, TOKEN),
+        );
+        assert_eq!(
+            cookie(TOKEN, true),
+            format!("{}={}; HttpOnly; SameSite=Strict; Secure", COOKIE, TOKEN),
+        );
+    }
+}
@@ -0,0 +1,182 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Dumps the events of a memtrace CTF file as newline-delimited JSON, see [`allocs`].
+//!
+//! Each line of the output is a JSON object describing one event, in the order the parser
+//! produced them. New allocations look like
+//!
+//! ```text
+//! {"event":"new","kind":"Minor","labels":[],"nsamples":3,"pid":4242,"real_size":24,"size":24,
+//!  "toc":0.25,"trace":[{"cols":[2,9],"count":1,"file":"main.ml","line":7}],"uid":0}
+//! ```
+//!
+//! (on a single line) where the trace goes from the root of the stack to the allocation site,
+//! `toc` is the time of creation in seconds since the start of the run, `size` and `real_size` are
+//! in bytes, and `pid` is the PID of the process that made the allocation. Deaths look like
+//!
+//! ```text
+//! {"event":"dead","tod":1.5,"uid":0}
+//! ```
+
+use std::io::Write;
+
+use charts::alloc_data::{self, Builder, CLoc};
+use serde_json::json;
+
+use crate::prelude::{time::DurationExt, *};
+
+/// An event of the dump.
+enum Event {
+    /// A new allocation.
+    New(Builder),
+    /// The death of an allocation.
+    Dead(time::SinceStart, uid::Alloc),
+}
+
+/// Factory used when dumping, stores the events until parsing is over.
+///
+/// Events cannot be serialized while parsing, as the locks on the allocation-data factories are
+/// needed to retrieve the traces and labels.
+struct Factory<'a> {
+    /// Lock over the allocation-data factories.
+    factory: alloc_data::mem::Factory<'a>,
+    /// Dump init, available after the parser read the trace info.
+    init: Option<AllocInit>,
+    /// Events, in the order they were parsed.
+    events: Vec<Event>,
+}
+
+impl<'a> std::ops::Deref for Factory<'a> {
+    type Target = alloc_data::mem::Factory<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.factory
+    }
+}
+impl<'a> std::ops::DerefMut for Factory<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.factory
+    }
+}
+
+/// Seconds since the start of the run, as a float.
+fn secs(time: time::SinceStart) -> f64 {
+    time.as_duration().as_secs_f64()
+}
+
+/// JSON value for a counted location.
+fn cloc_to_json(cloc: &CLoc) -> serde_json::Value {
+    json!({
+        "file": cloc.loc.file.to_string(),
+        "line": cloc.loc.line,
+        "cols": [cloc.loc.span.lbound, cloc.loc.span.ubound],
+        "count": cloc.cnt,
+    })
+}
+
+/// Parses a memtrace CTF file and writes its events to `output` as newline-delimited JSON.
+///
+/// See the [module-level documentation](self) for the format of the events. Returns the number of
+/// events written.
+pub fn allocs(
+    input: impl AsRef<std::path::Path>,
+    output: impl AsRef<std::path::Path>,
+    word_size_override: Option<u8>,
+) -> Res<usize> {
+    let (input, output) = (input.as_ref(), output.as_ref());
+    if input.is_dir() {
+        bail!(
+            "cannot dump the allocations of `{}`: only memtrace CTF files are supported",
+            input.display()
+        )
+    }
+
+    let bytes = std::fs::read(input)
+        .chain_err(|| format!("while reading ctf file `{}`", input.display()))?;
+
+    let mut factory = Factory {
+        factory: alloc_data::mem::Factory::new(false),
+        init: None,
+        events: vec![],
+    };
+    ctf::parse(
+        &bytes,
+        &mut factory,
+        ctf::ParseOptions {
+            word_size_override,
+            ..ctf::ParseOptions::default()
+        },
+        |_, _| (),
+        || true,
+        |factory, init| factory.init = Some(init),
+        |factory, builder| factory.events.push(Event::New(builder)),
+        |factory, tod, uid| factory.events.push(Event::Dead(tod, uid)),
+        |_, _| (),
+    )
+    .chain_err(|| format!("while parsing ctf file `{}`", input.display()))?;
+
+    // Releases the locks on the allocation-data factories.
+    let Factory {
+        factory,
+        init,
+        events,
+    } = factory;
+    drop(factory);
+    let init = init.ok_or("ctf file has no trace info")?;
+
+    let file = std::fs::File::create(output)
+        .chain_err(|| format!("while creating dump file `{}`", output.display()))?;
+    let mut writer = std::io::BufWriter::new(file);
+
+    let count = events.len();
+    for event in events {
+        let json = match event {
+            Event::New(builder) => {
+                let uid = builder
+                    .uid_hint
+                    .ok_or("ctf parser produced an allocation without a UID")?;
+                let alloc = builder.build(&init.sample_rate, uid)?;
+                json!({
+                    "event": "new",
+                    "uid": alloc.uid.get(),
+                    "kind": alloc.kind.as_str(),
+                    "size": alloc.size,
+                    "real_size": alloc.real_size,
+                    "nsamples": alloc.nsamples,
+                    "trace": alloc.trace().iter().map(cloc_to_json).collect::<Vec<_>>(),
+                    "labels": alloc.iter_labels().map(|label| label.to_string()).collect::<Vec<_>>(),
+                    "toc": secs(alloc.toc),
+                    "pid": alloc.pid,
+                })
+            }
+            Event::Dead(tod, uid) => json!({
+                "event": "dead",
+                "uid": uid.get(),
+                "tod": secs(tod),
+            }),
+        };
+        writeln!(writer, "{}", json)
+            .chain_err(|| format!("while writing to dump file `{}`", output.display()))?
+    }
+    writer
+        .flush()
+        .chain_err(|| format!("while writing to dump file `{}`", output.display()))?;
+
+    Ok(count)
+}
@@ -51,13 +51,20 @@
 pub mod prelude;
 
 pub mod assets;
+pub mod auth;
+pub mod dump;
 pub mod msg;
 pub mod router;
+pub mod session;
 pub mod socket;
+pub mod tls;
 
 use prelude::*;
 
 /// Top-level error handler.
+///
+/// Errors are registered as fatal or not depending on their severity, see [`err::Severity`]: the
+/// watcher reports and skips malformed diffs, while I/O and configuration errors are fatal.
 pub struct ErrorHandler {
     /// Error context.
     cxt: err::ErrorCxt,
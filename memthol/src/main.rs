@@ -33,6 +33,17 @@ mod default {
     pub const ADDR: &str = "localhost";
     /// Default port.
     pub const PORT: &str = "7878";
+    /// Default compression threshold, in bytes.
+    pub const COMPRESS_THRESHOLD: &str = "4096";
+
+    /// Default polling interval of the dump directory, in milliseconds.
+    pub const POLL_INTERVAL: &str = "100";
+    /// Default debounce window for new diff files, in milliseconds.
+    pub const DEBOUNCE: &str = "0";
+    /// Default malformed diff policy, see [`charts::data::MalformedPolicy`].
+    pub const MALFORMED: &str = "skip";
+    /// Default capacity of the error log, see [`charts::data::ErrorLog::DEFAULT_CAPACITY`].
+    pub const ERROR_CAPACITY: &str = "1000";
 
     /// Default directory.
     pub const INPUT: &str = ".";
@@ -48,6 +59,37 @@ fn usize_validator(s: String) -> Result<(), String> {
     }
 }
 
+/// Fails if the input string is not a legal address, see [`memthol::socket::check_addr`].
+fn addr_validator(s: String) -> Result<(), String> {
+    memthol::socket::check_addr(&s).map_err(|e| e.to_pretty())
+}
+
+/// Fails if the input string is not a legal access token, see [`memthol::auth::check_token`].
+fn token_validator(s: String) -> Result<(), String> {
+    memthol::auth::check_token(&s).map_err(|e| e.to_pretty())
+}
+
+/// Fails if the input string is not a legal malformed diff policy, see
+/// [`charts::data::MalformedPolicy`].
+fn malformed_validator(s: String) -> Result<(), String> {
+    use std::str::FromStr;
+    charts::data::MalformedPolicy::from_str(&s)
+        .map(|_| ())
+        .map_err(|e| e.to_pretty())
+}
+
+/// Fails if the input string is not a legal word size in bits.
+fn word_size_validator(s: String) -> Result<(), String> {
+    use std::str::FromStr;
+    match u8::from_str(&s) {
+        Ok(word_size) if word_size > 0 && word_size % 8 == 0 => Ok(()),
+        _ => Err(format!(
+            "expected a positive multiple of 8 smaller than 256, found `{}`",
+            s
+        )),
+    }
+}
+
 /// Initializes the logger.
 fn init_logger(verb: u64) {
     let mut builder = pretty_env_logger::formatted_timed_builder();
@@ -88,6 +130,49 @@ pub fn main() {
             -l --log !required
             "activates (separate) socket logging"
         )
+        (@arg LENIENT:
+            --lenient !required
+            "drops allocations with an already used UID instead of failing"
+        )
+        (@arg WORD_SIZE:
+            --word_size +takes_value !required
+            { word_size_validator }
+            "forces the word size (in bits) of memtrace CTF dumps, \
+            overriding the one stored in the dump"
+        )
+
+        (@arg SNAPSHOT:
+            --snapshot +takes_value !required
+            "loads memtrace CTF files from the snapshot at this path if it is up to date, \
+            parses them and writes the snapshot otherwise"
+        )
+
+        (@arg POLL_INTERVAL:
+            --poll_interval +takes_value !required
+            default_value(default::POLL_INTERVAL)
+            { usize_validator }
+            "time (in milliseconds) between two polls of the dump directory"
+        )
+        (@arg DEBOUNCE:
+            --debounce +takes_value !required
+            default_value(default::DEBOUNCE)
+            { usize_validator }
+            "time (in milliseconds) to wait for more diff files once new ones show up, \
+            so that they are registered together"
+        )
+        (@arg MALFORMED:
+            --malformed +takes_value !required
+            default_value(default::MALFORMED)
+            { malformed_validator }
+            "what to do with malformed diff files: `skip` them, `move` them to a `malformed` \
+            sub-directory of the dump directory, or `abort`"
+        )
+        (@arg ERROR_CAPACITY:
+            --error_capacity +takes_value !required
+            default_value(default::ERROR_CAPACITY)
+            { usize_validator }
+            "maximum number of recent errors kept for the UI, older errors are dropped"
+        )
 
         // Filter-gen stuff.
         (@arg FILTER_GEN:
@@ -101,7 +186,10 @@ pub fn main() {
         (@arg ADDR:
             -a --addr +takes_value !required
             default_value(default::ADDR)
-            "the address to serve the UI at"
+            { addr_validator }
+            "the address to serve the UI at: an IPv4 address, an IPv6 address or a hostname; \
+            binding a non-loopback address such as `0.0.0.0` or `::` makes the UI, and hence the \
+            profiling data, reachable by anyone who can reach this machine"
         )
         (@arg PORT:
             -p --port +takes_value !required
@@ -109,6 +197,43 @@ pub fn main() {
             { usize_validator }
             "the port to serve the UI at"
         )
+        (@arg COMPRESS_THRESHOLD:
+            --compress_threshold +takes_value !required
+            default_value(default::COMPRESS_THRESHOLD)
+            { usize_validator }
+            "messages sent to the client that are at least this many bytes long are compressed"
+        )
+        (@arg AUTH_TOKEN:
+            --("auth-token") +takes_value !required
+            { token_validator }
+            "shared secret required to access the UI, as a `token` query parameter (`/?token=...`) \
+            or an `Authorization: Bearer ...` header; this is not a substitute for TLS, without \
+            which the token is sent in clear text"
+        )
+        (@arg PUBLIC_PROBES:
+            --("public-probes") !required
+            "exempts the `/healthz` and `/metrics` endpoints from `--auth-token`, so that \
+            monitoring tools can query them without the token"
+        )
+        (@arg TLS_CERT:
+            --("tls-cert") +takes_value !required
+            requires[TLS_KEY]
+            "path to a PEM file containing the certificate chain to serve the UI over HTTPS with, \
+            requires the `tls` feature"
+        )
+        (@arg TLS_KEY:
+            --("tls-key") +takes_value !required
+            requires[TLS_CERT]
+            "path to a PEM file containing the private key of the TLS certificate (PKCS#8, RSA or \
+            EC), requires the `tls` feature"
+        )
+
+        // Diagnostics.
+        (@arg DUMP_ALLOCS:
+            --dump_allocs +takes_value !required
+            "parses the input memtrace CTF file, writes its allocation and death events to the \
+            file at this path as newline-delimited JSON, and exits without starting the UI"
+        )
 
         // Directory or CTF file.
 
@@ -126,56 +251,189 @@ pub fn main() {
         let port = matches.value_of("PORT").expect("argument with default");
         usize::from_str(port).expect("argument with validator")
     };
+    let compress_threshold = {
+        use std::str::FromStr;
+        let threshold = matches
+            .value_of("COMPRESS_THRESHOLD")
+            .expect("argument with default");
+        usize::from_str(threshold).expect("argument with validator")
+    };
     let log = matches.occurrences_of("LOG") > 0;
     let open = matches.occurrences_of("OPEN") > 0;
+    let lenient = matches.occurrences_of("LENIENT") > 0;
+    let word_size = matches.value_of("WORD_SIZE").map(|word_size| {
+        use std::str::FromStr;
+        u8::from_str(word_size).expect("argument with validator")
+    });
+    let snapshot = matches.value_of("SNAPSHOT").map(std::path::PathBuf::from);
+
+    let watcher_config = {
+        use std::{str::FromStr, time::Duration};
+        let millis = |key| {
+            let millis = matches.value_of(key).expect("argument with default");
+            let millis = usize::from_str(millis).expect("argument with validator");
+            Duration::from_millis(base::convert(millis, "milliseconds"))
+        };
+        let malformed = matches
+            .value_of("MALFORMED")
+            .expect("argument with default");
+        let malformed =
+            charts::data::MalformedPolicy::from_str(malformed).expect("argument with validator");
+        charts::data::WatcherConfig::new(millis("POLL_INTERVAL"), millis("DEBOUNCE"))
+            .with_malformed(malformed)
+    };
 
     let verb = matches.occurrences_of("VERB");
     init_logger(verb);
 
+    base::unwrap_or! {
+        memthol::auth::set_token(matches.value_of("AUTH_TOKEN").map(String::from)), exit
+    }
+    memthol::auth::set_public_probes(matches.occurrences_of("PUBLIC_PROBES") > 0);
+
+    let tls = match (matches.value_of("TLS_CERT"), matches.value_of("TLS_KEY")) {
+        (Some(cert), Some(key)) => Some(base::unwrap_or! {
+            memthol::tls::Config::load(cert, key), exit
+        }),
+        _ => None,
+    };
+    memthol::auth::set_secure_cookie(tls.is_some());
+
     let target = matches.value_of("INPUT").expect("argument with default");
 
+    if let Some(output) = matches.value_of("DUMP_ALLOCS") {
+        let count = base::unwrap_or! {
+            memthol::dump::allocs(target, output, word_size), exit
+        };
+        println!("wrote {} events to `{}`", count, output);
+        std::process::exit(0)
+    }
+
     let filter_gen_args = matches
         .value_of("FILTER_GEN")
         .expect("argument with default");
     memthol::clap::filter_gen(filter_gen_args);
 
-    let path = format!("{}:{}", addr, port);
+    let path = memthol::socket::host_port(addr, port);
+    let url = format!(
+        "{}://{}",
+        if tls.is_some() { "https" } else { "http" },
+        path
+    );
     println!("|===| Starting");
-    println!("| url: http://{}", path);
+    println!("| url: {}", url);
     println!("| target: `{}`", target);
+    if matches.is_present("AUTH_TOKEN") {
+        println!("| access token required, use `{}/?token=<token>`", url);
+    }
     println!("|===|");
     println!();
 
-    error_handler.handle_new_errors();
+    if memthol::socket::is_public_addr(addr) {
+        log::warn!("serving the UI at non-loopback address `{}`", addr);
+        if !matches.is_present("AUTH_TOKEN") {
+            log::warn!(
+                "anyone able to reach this machine on ports {} and {} can access the profiling data",
+                port,
+                port + 1
+            );
+            log::warn!("consider restricting access to the UI with `--auth-token`")
+        }
+        if tls.is_none() {
+            log::warn!("the data is sent unencrypted, see `--tls-cert` and `--tls-key`")
+        }
+    }
 
-    let router = memthol::router::new();
+    error_handler.handle_new_errors();
 
     log::info!("starting data monitoring");
     base::unwrap_or! {
-        charts::data::start(target), exit
+        charts::data::set_strict(!lenient), exit
+    }
+    base::unwrap_or! {
+        charts::data::set_word_size_override(word_size), exit
+    }
+    base::unwrap_or! {
+        charts::data::set_snapshot_path(snapshot), exit
+    }
+    let error_capacity = {
+        use std::str::FromStr;
+        let capacity = matches
+            .value_of("ERROR_CAPACITY")
+            .expect("argument with default");
+        usize::from_str(capacity).expect("argument with validator")
+    };
+    base::unwrap_or! {
+        charts::data::set_error_capacity(error_capacity), exit
     }
+    base::unwrap_or! {
+        charts::data::start(target, watcher_config), exit
+    }
+    print_parse_summary_in_background();
 
     error_handler.handle_new_errors();
 
     log::info!("starting socket listeners");
     base::unwrap_or! {
-        memthol::socket::spawn_server(addr, port + 1, log), exit
+        memthol::socket::spawn_server(addr, port + 1, log, compress_threshold, tls.clone()), exit
     }
 
     error_handler.handle_new_errors();
 
     if open {
-        open_in_background(&path)
+        match matches.value_of("AUTH_TOKEN") {
+            Some(token) => open_in_background(format!("{}/?token={}", url, token)),
+            None => open_in_background(url),
+        }
     }
 
     log::info!("starting gotham server");
-    std::thread::spawn(move || gotham::start(path, router));
+    std::thread::spawn(move || {
+        if let Err(e) = memthol::router::serve(path, tls) {
+            base::err::register_fatal(e)
+        }
+    });
 
     error_handler.error_watch_loop()
 }
 
-fn open_in_background(path: &str) {
-    let path = format!("http://{}", path);
+/// Prints a summary of the first parse of the target once it is over, see [`charts::data::parse_stats`].
+///
+/// Prints nothing for dump directories, or when the data is loaded from a snapshot.
+fn print_parse_summary_in_background() {
+    std::thread::spawn(|| {
+        loop {
+            match charts::data::progress::get() {
+                Ok(Some(_)) => std::thread::sleep(std::time::Duration::from_millis(100)),
+                Ok(None) => break,
+                Err(e) => {
+                    log::error!("{}", e.to_pretty());
+                    return;
+                }
+            }
+        }
+        match charts::data::parse_stats() {
+            Ok(Some(stats)) => {
+                println!("|===| Parsed");
+                println!("| packets: {}", stats.packets);
+                println!("| allocations: {}", stats.allocs);
+                println!("| deaths: {}", stats.deaths);
+                println!("| locations: {}", stats.locs);
+                println!(
+                    "| time: {}.{:0>3}s",
+                    stats.elapsed.as_secs(),
+                    stats.elapsed.subsec_millis()
+                );
+                println!("|===|");
+                println!();
+            }
+            Ok(None) => (),
+            Err(e) => log::error!("{}", e.to_pretty()),
+        }
+    });
+}
+
+fn open_in_background(path: String) {
     std::thread::spawn(move || match open::that(&path) {
         Ok(status) => {
             if !status.success() {
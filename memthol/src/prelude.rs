@@ -39,8 +39,8 @@ pub mod net {
 
     pub use tungstenite::{protocol::CloseFrame, Message as Msg};
 
-    /// Type alias for a tungstenite websocket for a TCP stream.
-    pub type WebSocket = tungstenite::WebSocket<TcpStream>;
+    /// Type alias for a tungstenite websocket for a (possibly encrypted) TCP stream.
+    pub type WebSocket = tungstenite::WebSocket<crate::socket::Stream>;
 }
 
 /// Type of the result of receiving messages from the client.
@@ -21,27 +21,71 @@
 
 use gotham::router::Router;
 
+use crate::prelude::*;
+
 /// Functions that load assets.
 pub mod handlers {
     use gotham::{
         hyper::{
             header::{self, HeaderValue},
-            Body, Response,
+            Body, HeaderMap, Response, StatusCode, Uri,
         },
-        state::State,
+        state::{FromState, State},
     };
 
+    /// True if a request is authorized, see [`crate::auth`].
+    fn is_authorized(state: &State) -> bool {
+        let headers = HeaderMap::borrow_from(state);
+        let header = |name: header::HeaderName| headers.get(name).and_then(|val| val.to_str().ok());
+        crate::auth::is_authorized(
+            Uri::borrow_from(state).query(),
+            header(header::AUTHORIZATION),
+            header(header::COOKIE),
+        )
+    }
+
+    /// Answers `401` to unauthorized requests.
+    ///
+    /// The `probe` variant lets the requests through if the probes are public, see
+    /// [`crate::auth::public_probes`].
+    macro_rules! check_authorized {
+        (probe $state:expr) => {
+            if !crate::auth::public_probes() {
+                check_authorized!($state)
+            }
+        };
+        ($state:expr) => {
+            if !is_authorized(&$state) {
+                let mut response =
+                    Response::new(Body::from("unauthorized: missing or invalid token"));
+                *response.status_mut() = StatusCode::UNAUTHORIZED;
+                return ($state, response);
+            }
+        };
+    }
+
     /// Loads the index page.
+    ///
+    /// Also sets the access token cookie, if any, so that the requests for the client code are
+    /// authorized.
     pub fn index_handler(state: State) -> (State, Response<Body>) {
-        (state, Response::new(Body::from(crate::assets::INDEX)))
+        check_authorized!(state);
+        let mut response = Response::new(Body::from(crate::assets::INDEX));
+        if let Some(cookie) = crate::auth::set_cookie().and_then(|c| HeaderValue::from_str(&c).ok())
+        {
+            response.headers_mut().insert(header::SET_COOKIE, cookie);
+        }
+        (state, response)
     }
     /// Loads the index page's favicon.
     pub fn favicon(state: State) -> (State, Response<Body>) {
+        check_authorized!(state);
         (state, Response::new(Body::from(crate::assets::FAVICON)))
     }
 
     /// Loads the wasm client, *i.e.* the actual client code.
     pub fn client_wasm(state: State) -> (State, Response<Body>) {
+        check_authorized!(state);
         let mut response = Response::new(Body::from(crate::assets::CLIENT_WASM));
         // Need to set the MIME-type to `application/wasm`.
         response.headers_mut().insert(
@@ -52,6 +96,7 @@ pub mod handlers {
     }
     /// Loads the JS part of the client.
     pub fn client_js(state: State) -> (State, Response<Body>) {
+        check_authorized!(state);
         let mut response = Response::new(Body::from(crate::assets::CLIENT_JS));
         // Need to set the MIME-type to `text/javascript`.
         response.headers_mut().insert(
@@ -60,6 +105,107 @@ pub mod handlers {
         );
         (state, response)
     }
+
+    /// Health probe.
+    ///
+    /// Answers `200` if the watcher thread is running and the global data lock is not poisoned,
+    /// `503` otherwise.
+    pub fn healthz(state: State) -> (State, Response<Body>) {
+        check_authorized!(probe state);
+        let (status, body) = if !charts::data::watcher_is_running() {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "unhealthy: the watcher is not running",
+            )
+        } else if charts::data::is_poisoned() {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "unhealthy: the data lock is poisoned",
+            )
+        } else {
+            (StatusCode::OK, "ok")
+        };
+        let mut response = Response::new(Body::from(body));
+        *response.status_mut() = status;
+        (state, response)
+    }
+
+    /// Metrics in the Prometheus text exposition format.
+    ///
+    /// Answers `503` if the global data cannot be read.
+    pub fn metrics(state: State) -> (State, Response<Body>) {
+        check_authorized!(probe state);
+        let mut response = match render_metrics() {
+            Ok(metrics) => {
+                let mut response = Response::new(Body::from(metrics));
+                response.headers_mut().insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("text/plain; version=0.0.4"),
+                );
+                response
+            }
+            Err(e) => {
+                let mut response = Response::new(Body::from(e.to_pretty()));
+                *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+                response
+            }
+        };
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        (state, response)
+    }
+
+    /// Renders the metrics served by [`metrics`].
+    ///
+    /// The error count covers all the errors registered so far, including the parse errors.
+    fn render_metrics() -> base::prelude::Res<String> {
+        use std::fmt::Write;
+
+        let (alloc_count, diff_count, live_bytes) = {
+            let data = charts::data::get()?;
+            (data.alloc_count(), data.diff_count(), data.live_now().1)
+        };
+        let (error_count, fatal_count) = base::err::count();
+
+        let mut metrics = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, values: &[(&str, String)]| {
+            // Writing to a string cannot fail.
+            let _ = writeln!(metrics, "# HELP {} {}", name, help);
+            let _ = writeln!(metrics, "# TYPE {} {}", name, kind);
+            for (labels, value) in values {
+                let _ = writeln!(metrics, "{}{} {}", name, labels, value);
+            }
+        };
+        metric(
+            "memthol_allocations_total",
+            "counter",
+            "Number of allocations parsed.",
+            &[("", alloc_count.to_string())],
+        );
+        metric(
+            "memthol_diffs_applied_total",
+            "counter",
+            "Number of diffs applied.",
+            &[("", diff_count.to_string())],
+        );
+        metric(
+            "memthol_live_bytes",
+            "gauge",
+            "Total size of the live allocations, in bytes.",
+            &[("", live_bytes.to_string())],
+        );
+        metric(
+            "memthol_errors_total",
+            "counter",
+            "Number of errors registered, including parse errors.",
+            &[
+                ("{fatal=\"false\"}", (error_count - fatal_count).to_string()),
+                ("{fatal=\"true\"}", fatal_count.to_string()),
+            ],
+        );
+        Ok(metrics)
+    }
 }
 
 /// Creates the router.
@@ -72,5 +218,21 @@ pub fn new() -> Router {
         route.get("favicon.png").to(handlers::favicon);
         route.get("client_bg.wasm").to(handlers::client_wasm);
         route.get("client.js").to(handlers::client_js);
+
+        route.get("healthz").to(handlers::healthz);
+        route.get("metrics").to(handlers::metrics);
     })
 }
+
+/// Serves the UI at some address, blocking.
+///
+/// The UI is served over HTTPS if a TLS configuration is provided.
+pub fn serve(addr: String, tls: Option<crate::tls::Config>) -> Res<()> {
+    let router = new();
+    match tls {
+        Some(tls) => tls.serve(addr, router),
+        None => gotham::start(addr, router)
+            .map_err(|e| err::Error::from(e.to_string()))
+            .chain_err(|| "while running HTTP server"),
+    }
+}
@@ -0,0 +1,132 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Client sessions, kept alive for a while after a client loses its connection.
+//!
+//! When a client first connects, it sends a [`Start`] message and the server mints a fresh random
+//! [`SessionToken`] that it sends to the client. When the connection is lost, the handler *parks*
+//! its session: its charts and filters are stored in a global map, keyed by the session's token.
+//! If the client reconnects and sends a [`Resume`] message with its token within
+//! [`GRACE_PERIOD`], the new handler takes the session over.
+//!
+//! Sessions do not store the points sent to the client. The charts of a session know how far they
+//! went in the data, which is all the server needs: on resume, the handler re-extracts the points
+//! of the session's charts from the shared data.
+//!
+//! At most [`MAX_PARKED`] sessions are parked at the same time, parking a session when there are
+//! already that many parked drops the oldest one. Stale sessions, *i.e.* parked for longer than
+//! [`GRACE_PERIOD`], are dropped periodically by the thread spawned by [`spawn_collector`], and
+//! whenever a session is parked or resumed.
+//!
+//! [`Start`]: msg::from_client::Msg::Start (The Start message)
+//! [`Resume`]: msg::from_client::Msg::Resume (The Resume message)
+
+use crate::prelude::*;
+
+pub use charts::msg::SessionToken;
+
+/// Time during which a parked session can be resumed.
+pub const GRACE_PERIOD: time::Duration = time::Duration::from_secs(5 * 60);
+
+/// Maximum number of sessions parked at the same time.
+pub const MAX_PARKED: usize = 32;
+
+/// Time between two garbage collections of the stale sessions.
+pub const COLLECT_PERIOD: time::Duration = time::Duration::from_secs(60);
+
+/// A client session.
+pub struct Session {
+    /// Token of the session.
+    pub token: SessionToken,
+    /// Charts and filters of the client.
+    pub charts: Charts,
+}
+
+impl Session {
+    /// Creates a session with a fresh token.
+    pub fn new(charts: Charts) -> Self {
+        Self {
+            token: base::rand::random(),
+            charts,
+        }
+    }
+}
+
+/// Parked sessions, with the time at which they were parked.
+type Parked = BTMap<SessionToken, (time::Instant, Session)>;
+
+lazy_static! {
+    /// Parked sessions.
+    static ref PARKED: sync::Mutex<Parked> = sync::Mutex::new(BTMap::new());
+}
+
+/// Lock over the parked sessions, garbage-collects stale sessions.
+fn parked<'a>() -> Res<sync::MutexGuard<'a, Parked>> {
+    let mut parked = PARKED
+        .lock()
+        .map_err(|e| err::Error::from(e.to_string()))
+        .chain_err(|| "while accessing the parked sessions")?;
+    let now = time::Instant::now();
+    parked.retain(|token, (since, _)| {
+        let keep = now.duration_since(*since) < GRACE_PERIOD;
+        if !keep {
+            log::info!("dropping stale session {:x}", token)
+        }
+        keep
+    });
+    Ok(parked)
+}
+
+/// Parks a session so that it can be resumed later.
+///
+/// Drops the oldest parked session(s) if there are already [`MAX_PARKED`] parked sessions.
+pub fn park(session: Session) -> Res<()> {
+    log::info!("parking session {:x}", session.token);
+    let mut parked = parked()?;
+    while parked.len() >= MAX_PARKED {
+        let oldest = parked
+            .iter()
+            .min_by_key(|(_, (since, _))| *since)
+            .map(|(token, _)| *token);
+        if let Some(token) = oldest {
+            log::info!("too many parked sessions, dropping session {:x}", token);
+            parked.remove(&token);
+        } else {
+            break;
+        }
+    }
+    parked.insert(session.token, (time::Instant::now(), session));
+    Ok(())
+}
+
+/// Retrieves a parked session, if it exists and is not stale.
+pub fn resume(token: SessionToken) -> Res<Option<Session>> {
+    Ok(parked()?.remove(&token).map(|(_, session)| session))
+}
+
+/// Spawns a thread dropping stale sessions every [`COLLECT_PERIOD`].
+pub fn spawn_collector() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(COLLECT_PERIOD);
+        if let Err(e) = parked() {
+            log::error!("{}", e);
+            break;
+        }
+    });
+}
@@ -19,34 +19,213 @@
 
 //! Websockets used by the server to communicate with the clients.
 
-use crate::prelude::*;
+use crate::{prelude::*, session, session::Session, tls};
+
+/// Strips the brackets around an IPv6 literal, if any.
+fn unbracket(addr: &str) -> &str {
+    addr.strip_prefix('[')
+        .and_then(|addr| addr.strip_suffix(']'))
+        .unwrap_or(addr)
+}
+
+/// Checks that an address is legal: an IPv4 address, a (bracketed or not) IPv6 address, or a
+/// hostname.
+pub fn check_addr(addr: &str) -> Res<()> {
+    let bracketed = addr.starts_with('[') || addr.ends_with(']');
+    let unbracketed = unbracket(addr);
+    if bracketed {
+        if unbracketed == addr || unbracketed.parse::<std::net::Ipv6Addr>().is_err() {
+            bail!(
+                "illegal address `{}`, expected an IPv6 address between brackets",
+                addr
+            )
+        }
+    } else if addr.parse::<std::net::IpAddr>().is_err() {
+        let legal_hostname = !addr.is_empty()
+            && addr.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+        if !legal_hostname {
+            bail!(
+                "illegal address `{}`, expected an IPv4 address, an IPv6 address or a hostname",
+                addr
+            )
+        }
+    }
+    Ok(())
+}
+
+/// True if an address is not a loopback address, *i.e.* if it is potentially reachable from
+/// other machines.
+///
+/// Hostnames other than `localhost` are considered public.
+pub fn is_public_addr(addr: &str) -> bool {
+    let addr = unbracket(addr);
+    match addr.parse::<std::net::IpAddr>() {
+        Ok(ip) => !ip.is_loopback(),
+        Err(_) => addr != "localhost",
+    }
+}
+
+/// Formats an address and a port as `<addr>:<port>`, adding brackets around IPv6 literals.
+pub fn host_port(addr: &str, port: usize) -> String {
+    let addr = unbracket(addr);
+    if addr.parse::<std::net::Ipv6Addr>().is_ok() {
+        format!("[{}]:{}", addr, port)
+    } else {
+        format!("{}:{}", addr, port)
+    }
+}
+
+/// A stream to a client, encrypted or not.
+pub enum Stream {
+    /// Plain TCP stream.
+    Plain(net::TcpStream),
+    /// TLS stream.
+    #[cfg(feature = "tls")]
+    Tls(Box<rustls::StreamOwned<rustls::ServerConnection, net::TcpStream>>),
+}
+impl Stream {
+    /// Underlying TCP stream.
+    pub fn tcp(&self) -> &net::TcpStream {
+        match self {
+            Self::Plain(stream) => stream,
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.get_ref(),
+        }
+    }
+}
+impl std::io::Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+impl std::io::Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.write(buf),
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Rejects websocket upgrade requests that are not authorized, see [`crate::auth`].
+///
+/// The error response is boxed as it is much larger than the response.
+fn check_authorized(
+    request: &tungstenite::handshake::server::Request,
+    response: tungstenite::handshake::server::Response,
+) -> Result<
+    tungstenite::handshake::server::Response,
+    Box<tungstenite::handshake::server::ErrorResponse>,
+> {
+    use tungstenite::http::{header, StatusCode};
+    let header = |name: header::HeaderName| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|val| val.to_str().ok())
+    };
+    if crate::auth::is_authorized(
+        request.uri().query(),
+        header(header::AUTHORIZATION),
+        header(header::COOKIE),
+    ) {
+        Ok(response)
+    } else {
+        log::warn!("rejecting unauthorized websocket connection");
+        let mut response = tungstenite::handshake::server::ErrorResponse::new(Some(
+            "unauthorized: missing or invalid token".into(),
+        ));
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+        Err(Box::new(response))
+    }
+}
+
+/// Websocket handshake callback, see [`check_authorized`].
+struct CheckAuthorized;
+impl tungstenite::handshake::server::Callback for CheckAuthorized {
+    fn on_request(
+        self,
+        request: &tungstenite::handshake::server::Request,
+        response: tungstenite::handshake::server::Response,
+    ) -> Result<tungstenite::handshake::server::Response, tungstenite::handshake::server::ErrorResponse>
+    {
+        check_authorized(request, response).map_err(|response| *response)
+    }
+}
 
 /// Creates a websocket server at some address.
 fn new_server(addr: &str, port: usize) -> Res<net::TcpListener> {
-    let server = net::TcpListener::bind(&format!("{}:{}", addr, port))
-        .chain_err(|| format!("while binding websocket server at `{}:{}`", addr, port))?;
+    let host_port = host_port(addr, port);
+    let server = net::TcpListener::bind(&host_port)
+        .chain_err(|| format!("while binding websocket server at `{}`", host_port))?;
     Ok(server)
 }
 
 /// Spawns a `Handler` for each incoming connection request.
-fn handle_requests(log: bool, server: net::TcpListener) {
+fn handle_requests(
+    log: bool,
+    compress_threshold: usize,
+    tls: Option<tls::Config>,
+    server: net::TcpListener,
+) {
     for stream in server.incoming().filter_map(Result::ok) {
-        let mut handler = base::unwrap_or! {
-            Handler::new(log, stream).chain_err(|| "while creating request handler"),
-            {
-                log::error!("failed to start request handler");
-                return ()
-            }
-        };
-        std::thread::spawn(move || handler.run());
-        ()
+        let tls = tls.clone();
+        // Handler creation waits for the client's first message, so it must not block the loop.
+        std::thread::spawn(move || {
+            let stream = match tls {
+                Some(tls) => tls.accept(stream),
+                None => Ok(Stream::Plain(stream)),
+            };
+            let handler = base::unwrap_or! {
+                stream
+                    .and_then(|stream| Handler::new(log, compress_threshold, stream))
+                    .chain_err(|| "while creating request handler"),
+                {
+                    log::error!("failed to start request handler");
+                    return ()
+                }
+            };
+            handler.run()
+        });
     }
 }
 
 /// Spawns the server that listens for connection requests.
-pub fn spawn_server(addr: &str, port: usize, log: bool) -> Res<()> {
+///
+/// The address can be an IPv4 address, an IPv6 address (bracketed or not) or a hostname, see
+/// [`check_addr`].
+///
+/// Messages to the clients that are at least `compress_threshold` bytes long are compressed, if
+/// the `compression` feature is active.
+///
+/// If a TLS configuration is provided, clients must connect with `wss`.
+pub fn spawn_server(
+    addr: &str,
+    port: usize,
+    log: bool,
+    compress_threshold: usize,
+    tls: Option<tls::Config>,
+) -> Res<()> {
     let server = new_server(addr, port)?;
-    std::thread::spawn(move || handle_requests(log, server));
+    session::spawn_collector();
+    std::thread::spawn(move || handle_requests(log, compress_threshold, tls, server));
     Ok(())
 }
 
@@ -69,6 +248,9 @@ pub struct Com {
     log: Option<std::fs::File>,
     /// Ping message use for acknowledgments.
     ping_msg: tungstenite::Message,
+    /// Messages at least this long (in bytes) are compressed.
+    #[cfg_attr(not(feature = "compression"), allow(dead_code))]
+    compress_threshold: usize,
     /// Time statistics.
     prof: Prof,
     /// Error context.
@@ -79,11 +261,17 @@ impl Com {
     ///
     /// The `log` flag, if `true`, makes the constructor create a log file in the current directory.
     /// It will contain a log of all the interactions with this client.
-    pub fn new(log: bool, ping_label: Vec<u8>, socket: net::WebSocket) -> Res<Self> {
+    pub fn new(
+        log: bool,
+        compress_threshold: usize,
+        ping_label: Vec<u8>,
+        socket: net::WebSocket,
+    ) -> Res<Self> {
         let ping_msg = tungstenite::Message::Ping(ping_label);
 
         let ip = socket
             .get_ref()
+            .tcp()
             .peer_addr()
             .map_err(|e| format!("failed to retrieve client IP: {}", e))?;
 
@@ -106,6 +294,7 @@ impl Com {
             ip,
             socket,
             ping_msg,
+            compress_threshold,
             prof: Prof::new(),
             err_cxt: err::ErrorCxt::new(),
         })
@@ -149,7 +338,13 @@ impl Com {
 
         let bytes = time! {
             > self.prof.bytes,
-            msg.to_bytes()
+            {
+                #[cfg(feature = "compression")]
+                let bytes = msg.to_compressed_bytes(self.compress_threshold);
+                #[cfg(not(feature = "compression"))]
+                let bytes = msg.to_bytes();
+                bytes
+            }
         }?;
         log::trace!("sending binary message ({} bytes)", bytes.len());
         let msg = Message::Binary(bytes);
@@ -192,7 +387,10 @@ impl Com {
     }
 
     /// Sends chart statistics to the client.
-    fn send_stats(&mut self, charts: &Charts) -> Res<()> {
+    ///
+    /// Filter statistics are only sent if `filter_stats`, the client otherwise maintains them from
+    /// the filter deltas of the points messages.
+    fn send_stats(&mut self, charts: &Charts, filter_stats: bool) -> Res<()> {
         if let Some(stats) = charts::prelude::AllocStats::get()? {
             if let Some(log) = self.log.as_mut() {
                 use std::io::Write;
@@ -205,9 +403,11 @@ impl Com {
             }
 
             self.send(msg::to_client::Msg::alloc_stats(stats))?;
-            self.send(msg::to_client::Msg::filter_stats(
-                charts.filters().filter_stats()?,
-            ))?;
+            if filter_stats {
+                self.send(msg::to_client::Msg::filter_stats(
+                    charts.filters().filter_stats()?,
+                ))?;
+            }
         }
 
         Ok(())
@@ -265,8 +465,8 @@ base::new_time_stats! {
 pub struct Handler {
     /// Sends/receives messages to/from the client.
     com: Com,
-    /// The charts of the client.
-    charts: Charts,
+    /// The session of the client: charts and filters.
+    session: Session,
     /// Stores the result of receiving messages from the client.
     from_client: FromClient,
     /// Time at which we last sent points to render.
@@ -279,12 +479,15 @@ pub struct Handler {
     instance_prof: HandlerProf,
     total_prof: HandlerProf,
     msgs: Vec<msg::to_client::Msg>,
+    /// Last loading progress sent to the client, if the data is being reloaded.
+    load_info: Option<charts::prelude::LoadInfo>,
 }
 
 impl Handler {
     /// Constructor from a request and a dump directory.
-    pub fn new(log: bool, stream: std::net::TcpStream) -> Res<Self> {
-        let socket = tungstenite::accept(stream).map_err(|e| e.to_string())?;
+    pub fn new(log: bool, compress_threshold: usize, stream: Stream) -> Res<Self> {
+        let socket =
+            tungstenite::accept_hdr(stream, CheckAuthorized).map_err(|e| e.to_string())?;
 
         let instance_prof = HandlerProf::new();
         let total_prof = HandlerProf::new();
@@ -294,7 +497,7 @@ impl Handler {
         //     .chain_err(|| "while splitting the client into receive/send pair")?;
 
         let ping_label = vec![6u8, 6u8, 6u8];
-        let mut com = Com::new(log, ping_label.clone(), socket)
+        let mut com = Com::new(log, compress_threshold, ping_label.clone(), socket)
             .chain_err(|| "during communicator construction")?;
 
         com.send_errors()?;
@@ -321,16 +524,23 @@ impl Handler {
 
         com.send(msg::to_client::Msg::DoneLoading)?;
 
-        let charts = time! {
-            Charts
-                ::auto_gen()
-                .chain_err(|| "during default filter generation")?,
-            |time| log::info!("done with filter generation in {}", time)
+        let session = match Self::handshake(&mut com)? {
+            Some(session) => session,
+            None => {
+                let charts = time! {
+                    Charts
+                        ::auto_gen()
+                        .chain_err(|| "during default filter generation")?,
+                    |time| log::info!("done with filter generation in {}", time)
+                };
+                Session::new(charts)
+            }
         };
+        com.send(msg::to_client::Msg::Session(session.token))?;
 
         let slf = Handler {
             com,
-            charts,
+            session,
             from_client: FromClient::new(),
             last_frame: time::Instant::now(),
             frame_span: time::Duration::from_millis(500),
@@ -339,6 +549,7 @@ impl Handler {
             instance_prof,
             total_prof,
             msgs: Vec::with_capacity(7),
+            load_info: None,
         };
 
         log::info!("successfully connected to {}", slf.ip());
@@ -346,6 +557,49 @@ impl Handler {
         Ok(slf)
     }
 
+    /// Waits for the first message of the client, retrieves its session if it wants to resume it.
+    ///
+    /// Returns `None` if the client wants to start a new session, or if the session it wants to
+    /// resume does not exist anymore.
+    fn handshake(com: &mut Com) -> Res<Option<Session>> {
+        let msg = loop {
+            match com.incoming_message()? {
+                net::Msg::Binary(data) => {
+                    let msg = msg::from_client::Msg::from_bytes(&data)
+                        .chain_err(|| "while parsing message from client")?;
+                    com.log_receive_msg(Either::Left(&msg))?;
+                    break msg;
+                }
+                net::Msg::Close(_) => bail!("client closed the connection during handshake"),
+                net::Msg::Text(_) => bail!(
+                    "trying to receive a message in text format, \
+                        only binary format is supported"
+                ),
+                _ => continue,
+            }
+        };
+        match msg {
+            msg::from_client::Msg::Start => Ok(None),
+            msg::from_client::Msg::Resume(token) => {
+                let session = session::resume(token)?;
+                if session.is_some() {
+                    log::info!("resuming session {:x}", token)
+                } else {
+                    log::info!("cannot resume unknown or stale session {:x}", token);
+                    com.send(msg::to_client::Msg::alert(
+                        "your previous session expired, starting a new one",
+                        false,
+                    ))?
+                }
+                Ok(session)
+            }
+            msg => bail!(
+                "expected a start or resume message from the client, got `{}`",
+                msg
+            ),
+        }
+    }
+
     /// The client's IP address.
     pub fn ip(&self) -> &net::IpAddr {
         self.com.ip()
@@ -374,12 +628,13 @@ impl Handler {
         );
     }
 
-    /// Runs the handler.
-    pub fn run(&mut self) {
+    /// Runs the handler, parks the session when done.
+    pub fn run(mut self) {
         base::unwrap_or!(
             self.internal_run(),
             log::info!("lost connection with {}", self.ip())
-        )
+        );
+        base::unwrap_or!(session::park(self.session), ())
     }
 
     /// Sets the time of the last frame to now.
@@ -432,7 +687,7 @@ impl Handler {
                 log::debug!("handling message from client: {}", msg);
                 time! {
                     {
-                        let (msgs, reloaded) = self.charts.handle_msg(msg)?;
+                        let (msgs, reloaded) = self.session.charts.handle_msg(msg)?;
                         self.msgs.extend(msgs);
                         if reloaded {
                             send_stats = true
@@ -454,8 +709,10 @@ impl Handler {
                 std::thread::sleep((self.last_frame + self.frame_span) - now)
             }
 
-            // Render.
-            self.send_points(false)?
+            // Render, unless the data is being reloaded.
+            if !self.forward_load_progress()? {
+                self.send_points(false)?
+            }
         }
 
         Ok(())
@@ -463,12 +720,34 @@ impl Handler {
 
     /// Sends chart-related statistics to the client.
     fn send_stats(&mut self) -> Res<()> {
-        self.com.send_stats(&self.charts)
+        self.com.send_stats(&self.session.charts, true)
+    }
+
+    /// Forwards the loading progress to the client while the data is being reloaded.
+    ///
+    /// Returns `true` if the data is being reloaded.
+    fn forward_load_progress(&mut self) -> Res<bool> {
+        match charts::data::progress::get()? {
+            Some(info) => {
+                if self.load_info.as_ref() != Some(&info) {
+                    self.com
+                        .send(msg::to_client::Msg::load_progress(info.clone()))?;
+                    self.load_info = Some(info)
+                }
+                Ok(true)
+            }
+            None => {
+                if self.load_info.take().is_some() {
+                    self.com.send(msg::to_client::Msg::DoneLoading)?
+                }
+                Ok(false)
+            }
+        }
     }
 
     /// Sends all charts to the client.
     fn send_all_charts(&mut self) -> Res<()> {
-        for chart in self.charts.charts() {
+        for chart in self.session.charts.charts() {
             let msg = msg::to_client::ChartsMsg::new_chart(
                 chart.spec().clone(),
                 chart.settings().clone(),
@@ -480,9 +759,9 @@ impl Handler {
     /// Sends all the filters to the client.
     fn send_filters(&mut self) -> Res<()> {
         let msg = msg::to_client::FiltersMsg::revert(
-            self.charts.filters().everything().clone(),
-            self.charts.filters().filters().clone(),
-            self.charts.filters().catch_all().clone(),
+            self.session.charts.filters().everything().clone(),
+            self.session.charts.filters().filters().clone(),
+            self.session.charts.filters().catch_all().clone(),
         );
         self.send(msg)
     }
@@ -492,20 +771,29 @@ impl Handler {
             > self.instance_prof.point_extraction,
             > self.total_prof.point_extraction,
 
-            self.charts.new_points(init)?
+            self.session.charts.new_points(init)?
         };
 
+        // Errors of charts that failed to produce their points, if any.
+        let chart_errors: Vec<_> = self.session.charts.drain_to_client_msgs().collect();
+        for msg in chart_errors {
+            self.send(msg)?
+        }
+
         if !points.is_empty() {
+            // Allocation counts of the filters, the client maintains its filter statistics from
+            // them.
+            let delta = self.session.charts.take_filter_delta();
             time! {
                 > self.instance_prof.point_sending,
                 > self.total_prof.point_sending,
 
-                self.send(msg::to_client::ChartsMsg::points(points, overwrite))?
+                self.send(msg::to_client::ChartsMsg::points(points, overwrite, delta))?
             }
 
             self.show_time_stats("done extracting/sending points");
 
-            self.send_stats()?
+            self.com.send_stats(&self.session.charts, false)?
         }
 
         self.instance_prof.reset();
@@ -514,6 +802,8 @@ impl Handler {
     }
 
     /// Initializes a client.
+    ///
+    /// When resuming a session, the client dropped its charts: all the points are extracted again.
     pub fn init(&mut self) -> Res<()> {
         self.send_stats()?;
 
@@ -536,7 +826,7 @@ impl Handler {
 
     /// Sends a message to the client.
     pub fn send(&mut self, msg: impl Into<msg::to_client::Msg>) -> Res<()> {
-        self.com.send(msg)
+        self.com.send(msg.into())
     }
     /// Sends all its internal messages to the client.
     pub fn send_all(&mut self) -> Res<()> {
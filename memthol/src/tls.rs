@@ -0,0 +1,145 @@
+/*<LICENSE>
+    This file is part of Memthol.
+
+    Copyright (C) 2020 OCamlPro.
+
+    Memthol is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    Memthol is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with Memthol.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! TLS support for the HTTP server and the websocket listener.
+//!
+//! TLS is only available with the `tls` feature. Without it, [`Config::load`] always fails.
+//!
+//! # Certificate formats
+//!
+//! Both the certificate and the key are read from PEM files.
+//!
+//! - the certificate file contains the certificate chain, starting with the server's certificate
+//!   and followed by the intermediate certificates, if any;
+//! - the key file contains the private key of the server's certificate, as a PKCS#8 key (`BEGIN
+//!   PRIVATE KEY`), a PKCS#1 RSA key (`BEGIN RSA PRIVATE KEY`), or a SEC1 EC key (`BEGIN EC
+//!   PRIVATE KEY`). If the file contains several keys, the first one is used.
+
+use std::path::Path;
+
+use crate::prelude::*;
+
+/// A TLS configuration, shared by the HTTP server and the websocket listener.
+#[derive(Clone)]
+pub struct Config {
+    /// Actual configuration.
+    #[cfg(feature = "tls")]
+    config: std::sync::Arc<rustls::ServerConfig>,
+    /// Cannot be constructed without the `tls` feature.
+    #[cfg(not(feature = "tls"))]
+    never: std::convert::Infallible,
+}
+
+#[cfg(not(feature = "tls"))]
+impl Config {
+    /// Loads a certificate chain and a private key from PEM files.
+    ///
+    /// Always fails, memthol was compiled without the `tls` feature.
+    pub fn load(cert: impl AsRef<Path>, key: impl AsRef<Path>) -> Res<Self> {
+        let _ = (cert, key);
+        bail!("TLS is not supported, memthol must be compiled with the `tls` feature")
+    }
+
+    /// Wraps a TCP stream in a server-side TLS stream.
+    pub fn accept(&self, _stream: net::TcpStream) -> Res<crate::socket::Stream> {
+        match self.never {}
+    }
+
+    /// Serves the UI over HTTPS, blocking.
+    pub fn serve(self, _addr: String, _router: gotham::router::Router) -> Res<()> {
+        match self.never {}
+    }
+}
+
+#[cfg(feature = "tls")]
+impl Config {
+    /// Loads a certificate chain and a private key from PEM files.
+    ///
+    /// See the [module-level documentation](self) for the formats accepted.
+    pub fn load(cert: impl AsRef<Path>, key: impl AsRef<Path>) -> Res<Self> {
+        let (cert, key) = (cert.as_ref(), key.as_ref());
+        let certs = Self::load_certs(cert)
+            .chain_err(|| format!("while loading TLS certificate `{}`", cert.display()))?;
+        let key = Self::load_key(key)
+            .chain_err(|| format!("while loading TLS key `{}`", key.display()))?;
+        let config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| err::Error::from(e.to_string()))
+            .chain_err(|| "while building TLS configuration")?;
+        Ok(Self {
+            config: std::sync::Arc::new(config),
+        })
+    }
+
+    /// Reads the PEM items of a file.
+    fn read_pem(path: &Path) -> Res<Vec<rustls_pemfile::Item>> {
+        let file = std::fs::File::open(path)?;
+        let items = rustls_pemfile::read_all(&mut std::io::BufReader::new(file))?;
+        Ok(items)
+    }
+
+    /// Loads a certificate chain.
+    fn load_certs(path: &Path) -> Res<Vec<rustls::Certificate>> {
+        let certs: Vec<_> = Self::read_pem(path)?
+            .into_iter()
+            .filter_map(|item| match item {
+                rustls_pemfile::Item::X509Certificate(cert) => Some(rustls::Certificate(cert)),
+                _ => None,
+            })
+            .collect();
+        if certs.is_empty() {
+            bail!("no PEM-encoded certificate found")
+        }
+        Ok(certs)
+    }
+
+    /// Loads the first private key of a file.
+    fn load_key(path: &Path) -> Res<rustls::PrivateKey> {
+        Self::read_pem(path)?
+            .into_iter()
+            .find_map(|item| match item {
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key) => Some(rustls::PrivateKey(key)),
+                _ => None,
+            })
+            .ok_or_else(|| "no PEM-encoded PKCS#8, RSA or EC private key found".into())
+    }
+
+    /// Wraps a TCP stream in a server-side TLS stream.
+    ///
+    /// The handshake happens lazily, on the first read or write.
+    pub fn accept(&self, stream: net::TcpStream) -> Res<crate::socket::Stream> {
+        let connection = rustls::ServerConnection::new(self.config.clone())
+            .map_err(|e| err::Error::from(e.to_string()))
+            .chain_err(|| "while creating TLS connection")?;
+        Ok(crate::socket::Stream::Tls(Box::new(
+            rustls::StreamOwned::new(connection, stream),
+        )))
+    }
+
+    /// Serves the UI over HTTPS, blocking.
+    pub fn serve(self, addr: String, router: gotham::router::Router) -> Res<()> {
+        gotham::tls::start(addr, router, (*self.config).clone())
+            .map_err(|e| err::Error::from(e.to_string()))
+            .chain_err(|| "while running HTTPS server")
+    }
+}